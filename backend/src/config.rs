@@ -1,9 +1,11 @@
 //! Application configuration loaded from environment variables.
 //!
-//! Required: `HMAC_SECRET`, `PROGRAM_ID`
+//! Required: `PROGRAM_ID`, `HMAC_SECRET` (ed25519 proof scheme only)
 //! Optional: `RPC_URL`, `WS_URL`, `AUTHORITY_KEYPAIR_PATH`, `CLUSTER`,
 //!           `HTTP_PORT`, `MAX_RETRIES`, `INITIAL_RETRY_DELAY_MS`,
-//!           `PRIORITY_FEE_MICRO_LAMPORTS`, `FULFILLMENT_CONCURRENCY`
+//!           `PRIORITY_FEE_MICRO_LAMPORTS`, `FULFILLMENT_CONCURRENCY`,
+//!           `PROOF_SCHEME`, `ORACLE_PEERS`, `ORACLE_PEER_KEYPAIRS`,
+//!           `CALLBACK_RETRY_DELAY_MS`, `FULFILLMENT_BATCH_SIZE`
 
 use anyhow::{Context, Result};
 use solana_sdk::pubkey::Pubkey;
@@ -11,6 +13,34 @@ use solana_sdk::signature::{read_keypair_file, Keypair};
 use std::str::FromStr;
 use std::sync::Arc;
 
+/// How fulfillment proofs are produced. Must match the on-chain
+/// `CoordinatorConfig.proof_scheme`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProofScheme {
+    /// HMAC-SHA256 output authenticated by an Ed25519 signature.
+    Ed25519,
+    /// ECVRF-EDWARDS25519-SHA512-TAI proof (RFC 9381) verified on-chain.
+    Ecvrf,
+    /// `SHA256(signature)` over a request-bound message, checked on-chain.
+    SignatureHash,
+    /// ECVRF proofs from a request's oracle committee, combined on-chain.
+    MultiOracle,
+}
+
+impl FromStr for ProofScheme {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "ed25519" => Ok(Self::Ed25519),
+            "ecvrf" => Ok(Self::Ecvrf),
+            "signature-hash" => Ok(Self::SignatureHash),
+            "multi-oracle" => Ok(Self::MultiOracle),
+            other => anyhow::bail!("unknown proof scheme: {other}"),
+        }
+    }
+}
+
 /// Application configuration for the VRF oracle backend.
 #[derive(Clone)]
 pub struct AppConfig {
@@ -20,8 +50,10 @@ pub struct AppConfig {
     pub ws_url: String,
     /// Ed25519 keypair used to sign fulfillment proofs.
     pub authority_keypair: Arc<Keypair>,
-    /// Secret key for HMAC-SHA256 randomness generation.
-    pub hmac_secret: Vec<u8>,
+    /// Proof scheme used for fulfillments.
+    pub proof_scheme: ProofScheme,
+    /// Secret key for HMAC-SHA256 randomness generation (ed25519 scheme only).
+    pub hmac_secret: Option<Vec<u8>>,
     /// The deployed VRF coordinator program ID.
     pub program_id: Pubkey,
     /// Cluster name for explorer URLs.
@@ -36,6 +68,15 @@ pub struct AppConfig {
     pub priority_fee_micro_lamports: u64,
     /// Maximum concurrent fulfillment tasks.
     pub fulfillment_concurrency: usize,
+    /// Base URLs of peer oracle backends serving `/partial-proof/{request_id}`.
+    pub oracle_peers: Vec<String>,
+    /// Local peer keypairs that prove in-process (testing stand-in for remote peers).
+    pub oracle_peer_keypairs: Vec<Arc<Keypair>>,
+    /// Delay in milliseconds between callback retries for stored results.
+    pub callback_retry_delay_ms: u64,
+    /// Most queued requests packed into one `fulfill_random_words_batch`
+    /// transaction (ed25519 and signature-hash schemes); 1 disables batching.
+    pub fulfillment_batch_size: usize,
 }
 
 impl AppConfig {
@@ -51,9 +92,15 @@ impl AppConfig {
             .map_err(|e| anyhow::anyhow!("{e}"))
             .with_context(|| format!("failed to read keypair from {keypair_path}"))?;
 
-        let hmac_secret = std::env::var("HMAC_SECRET")
-            .context("HMAC_SECRET env var must be set")?
-            .into_bytes();
+        let proof_scheme = match std::env::var("PROOF_SCHEME") {
+            Ok(v) => v.parse()?,
+            Err(_) => ProofScheme::Ed25519,
+        };
+
+        let hmac_secret = std::env::var("HMAC_SECRET").ok().map(String::into_bytes);
+        if proof_scheme == ProofScheme::Ed25519 && hmac_secret.is_none() {
+            anyhow::bail!("HMAC_SECRET env var must be set for the ed25519 proof scheme");
+        }
 
         let program_id_str = std::env::var("PROGRAM_ID").context("PROGRAM_ID env var must be set")?;
         let program_id = Pubkey::from_str(&program_id_str)
@@ -77,6 +124,11 @@ impl AppConfig {
             .and_then(|v| v.parse().ok())
             .unwrap_or(500);
 
+        let callback_retry_delay_ms = std::env::var("CALLBACK_RETRY_DELAY_MS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(30_000);
+
         let priority_fee_micro_lamports = std::env::var("PRIORITY_FEE_MICRO_LAMPORTS")
             .ok()
             .and_then(|v| v.parse().ok())
@@ -87,10 +139,30 @@ impl AppConfig {
             .and_then(|v| v.parse().ok())
             .unwrap_or(4);
 
+        let fulfillment_batch_size = std::env::var("FULFILLMENT_BATCH_SIZE")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(4usize)
+            .max(1);
+
+        let oracle_peers = env_list("ORACLE_PEERS");
+
+        let oracle_peer_keypairs = env_list("ORACLE_PEER_KEYPAIRS")
+            .into_iter()
+            .map(|path| {
+                let path = shellexpand::tilde(&path).to_string();
+                read_keypair_file(&path)
+                    .map(Arc::new)
+                    .map_err(|e| anyhow::anyhow!("{e}"))
+                    .with_context(|| format!("failed to read peer keypair from {path}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
         Ok(Self {
             rpc_url,
             ws_url,
             authority_keypair: Arc::new(authority_keypair),
+            proof_scheme,
             hmac_secret,
             program_id,
             cluster,
@@ -99,6 +171,10 @@ impl AppConfig {
             initial_retry_delay_ms,
             priority_fee_micro_lamports,
             fulfillment_concurrency,
+            oracle_peers,
+            oracle_peer_keypairs,
+            callback_retry_delay_ms,
+            fulfillment_batch_size,
         })
     }
 
@@ -110,3 +186,16 @@ impl AppConfig {
         }
     }
 }
+
+/// Read a comma-separated env var, skipping empty entries.
+fn env_list(name: &str) -> Vec<String> {
+    std::env::var(name)
+        .map(|v| {
+            v.split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
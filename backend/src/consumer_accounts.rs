@@ -4,20 +4,29 @@
 //! `fulfill_random_words` instruction. The backend must provide the correct
 //! remaining_accounts for the callback.
 //!
-//! **Generic approach**: The consumer stores its callback accounts in the VRF
-//! request PDA at request time. The backend reads them from the request account
-//! data and includes them as remaining_accounts in the fulfillment transaction.
+//! **Generic approach**: The consumer stores its callback accounts on-chain at
+//! request time — up to 4 inline in the VRF request PDA, or any number up to
+//! 32 in a `CallbackAccounts` sidecar PDA. The backend reads them and includes
+//! them as remaining_accounts in the fulfillment transaction. A sidecar may
+//! name an address lookup table, which the fulfiller uses to compile a v0
+//! transaction.
 
 use anyhow::{Context, Result};
+use solana_address_lookup_table_interface::state::AddressLookupTable;
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_commitment_config::CommitmentConfig;
 use solana_sdk::instruction::AccountMeta;
+use solana_sdk::message::AddressLookupTableAccount;
 use solana_sdk::pubkey::Pubkey;
-use tracing::{debug, warn};
+use tracing::debug;
 
 /// Maximum callback accounts stored in the request PDA (must match on-chain constant).
 const MAX_CALLBACK_ACCOUNTS: usize = 4;
 
+/// Maximum callback accounts stored in a sidecar (must match on-chain
+/// `MAX_SIDECAR_CALLBACK_ACCOUNTS`).
+const MAX_SIDECAR_CALLBACK_ACCOUNTS: usize = 32;
+
 /// Offset of `callback_account_count` in the request account body (after 8-byte discriminator).
 ///
 /// Layout:
@@ -36,7 +45,33 @@ const CALLBACK_BITMAP_OFFSET: usize = CALLBACK_KEYS_OFFSET + 32 * MAX_CALLBACK_A
 /// Minimum account data length to contain callback fields.
 const MIN_DATA_LEN_WITH_CALLBACKS: usize = CALLBACK_BITMAP_OFFSET + 1;
 
-/// Read callback accounts from the VRF request PDA on-chain.
+/// Offset of the `callback_sidecar` flag:
+/// bitmap(1) + fee_paid(8) + callback_attempts(1) + store_only(1) after the bitmap.
+const CALLBACK_SIDECAR_OFFSET: usize = CALLBACK_BITMAP_OFFSET + 1 + 8 + 1 + 1;
+
+/// Offset of `lookup_table` in the sidecar: discriminator(8) + request_id(8) + requester(32).
+const SIDECAR_LOOKUP_TABLE_OFFSET: usize = 8 + 8 + 32;
+
+/// Offset of the accounts vector length: lookup_table(32) + bump(1).
+const SIDECAR_ACCOUNTS_OFFSET: usize = SIDECAR_LOOKUP_TABLE_OFFSET + 32 + 1;
+
+/// Size of one serialized `CallbackAccountMeta`: pubkey(32) + is_writable(1).
+const SIDECAR_ENTRY_LEN: usize = 33;
+
+/// Callback accounts for one request and what the fulfillment transaction
+/// needs to carry them.
+#[derive(Debug, Default)]
+pub struct CallbackAccounts {
+    /// Remaining accounts for the fulfillment instruction, in callback order.
+    pub accounts: Vec<AccountMeta>,
+    /// Lookup tables to compile the v0 transaction against.
+    pub lookup_tables: Vec<AddressLookupTableAccount>,
+    /// The sidecar PDA holding the list, to close once the request is closed.
+    pub sidecar: Option<Pubkey>,
+}
+
+/// Read callback accounts for a request from the VRF request PDA on-chain,
+/// following it to the `CallbackAccounts` sidecar and lookup table if used.
 ///
 /// Returns the remaining_accounts that should be appended to the
 /// `fulfill_random_words` transaction for the consumer's callback CPI.
@@ -44,7 +79,7 @@ pub async fn read_callback_accounts_from_request(
     rpc_client: &RpcClient,
     vrf_program_id: &Pubkey,
     request_id: u64,
-) -> Result<Vec<AccountMeta>> {
+) -> Result<CallbackAccounts> {
     let (request_pda, _) = Pubkey::find_program_address(
         &[b"vrf-request", &request_id.to_le_bytes()],
         vrf_program_id,
@@ -59,27 +94,71 @@ pub async fn read_callback_accounts_from_request(
 
     let data = &account.data;
 
-    if data.len() < MIN_DATA_LEN_WITH_CALLBACKS {
-        // Old request format without callback accounts — return empty
-        debug!(
-            request_id,
-            data_len = data.len(),
-            "Request PDA too short for callback accounts, returning empty"
-        );
-        return Ok(vec![]);
-    }
+    // Requests from before the callback fields were added predate the last
+    // upgrade; the program cannot deserialize them, so retrying is pointless.
+    anyhow::ensure!(
+        data.len() >= MIN_DATA_LEN_WITH_CALLBACKS,
+        "request PDA predates the current layout ({} bytes): AccountDidNotDeserialize",
+        data.len()
+    );
 
-    let count = data[CALLBACK_COUNT_OFFSET] as usize;
-    if count == 0 {
-        return Ok(vec![]);
+    if data.get(CALLBACK_SIDECAR_OFFSET) != Some(&1) {
+        let accounts = parse_inline_callback_accounts(data)?;
+        debug!(request_id, count = accounts.len(), "Read inline callback accounts");
+        return Ok(CallbackAccounts {
+            accounts,
+            ..Default::default()
+        });
     }
-    if count > MAX_CALLBACK_ACCOUNTS {
-        warn!(
-            request_id,
-            count, "callback_account_count exceeds max, clamping"
-        );
+
+    let (sidecar_pda, _) = Pubkey::find_program_address(
+        &[b"callback-accounts", &request_id.to_le_bytes()],
+        vrf_program_id,
+    );
+    let sidecar = rpc_client
+        .get_account_with_commitment(&sidecar_pda, CommitmentConfig::confirmed())
+        .await
+        .context("failed to fetch callback accounts sidecar")?
+        .value
+        .context("callback accounts sidecar not found")?;
+    let (accounts, lookup_table) = parse_sidecar(&sidecar.data)?;
+
+    let mut lookup_tables = Vec::new();
+    if let Some(key) = lookup_table {
+        let table = rpc_client
+            .get_account_with_commitment(&key, CommitmentConfig::confirmed())
+            .await
+            .context("failed to fetch callback lookup table")?
+            .value
+            .context("callback lookup table not found")?;
+        let addresses = AddressLookupTable::deserialize(&table.data)
+            .map_err(|e| anyhow::anyhow!("invalid lookup table {key}: {e}"))?
+            .addresses
+            .to_vec();
+        lookup_tables.push(AddressLookupTableAccount { key, addresses });
     }
-    let count = count.min(MAX_CALLBACK_ACCOUNTS);
+
+    debug!(
+        request_id,
+        count = accounts.len(),
+        lookup_tables = lookup_tables.len(),
+        "Read callback accounts from sidecar"
+    );
+
+    Ok(CallbackAccounts {
+        accounts,
+        lookup_tables,
+        sidecar: Some(sidecar_pda),
+    })
+}
+
+/// Parse the callback accounts stored inline in a request account.
+fn parse_inline_callback_accounts(data: &[u8]) -> Result<Vec<AccountMeta>> {
+    let count = data[CALLBACK_COUNT_OFFSET] as usize;
+    anyhow::ensure!(
+        count <= MAX_CALLBACK_ACCOUNTS,
+        "callback_account_count {count} exceeds the inline maximum of {MAX_CALLBACK_ACCOUNTS}"
+    );
 
     let bitmap = data[CALLBACK_BITMAP_OFFSET];
 
@@ -98,12 +177,85 @@ pub async fn read_callback_accounts_from_request(
         }
     }
 
-    debug!(
-        request_id,
-        count,
-        "Read {} callback accounts from request PDA",
-        count
+    Ok(accounts)
+}
+
+/// Parse a `CallbackAccounts` sidecar into account metas and its lookup table.
+fn parse_sidecar(data: &[u8]) -> Result<(Vec<AccountMeta>, Option<Pubkey>)> {
+    let header = data
+        .get(..SIDECAR_ACCOUNTS_OFFSET + 4)
+        .context("callback accounts sidecar too short")?;
+    let lookup_table = Pubkey::try_from(&header[SIDECAR_LOOKUP_TABLE_OFFSET..SIDECAR_LOOKUP_TABLE_OFFSET + 32])
+        .map_err(|_| anyhow::anyhow!("invalid lookup table pubkey"))?;
+    let count = u32::from_le_bytes(header[SIDECAR_ACCOUNTS_OFFSET..].try_into()?) as usize;
+    anyhow::ensure!(
+        count <= MAX_SIDECAR_CALLBACK_ACCOUNTS,
+        "sidecar holds {count} callback accounts, more than the maximum of {MAX_SIDECAR_CALLBACK_ACCOUNTS}"
     );
 
-    Ok(accounts)
+    let entries = data
+        .get(SIDECAR_ACCOUNTS_OFFSET + 4..SIDECAR_ACCOUNTS_OFFSET + 4 + count * SIDECAR_ENTRY_LEN)
+        .context("callback accounts sidecar truncated")?;
+    let accounts = entries
+        .chunks_exact(SIDECAR_ENTRY_LEN)
+        .map(|entry| {
+            let key = Pubkey::try_from(&entry[..32]).expect("32-byte slice");
+            if entry[32] != 0 {
+                AccountMeta::new(key, false)
+            } else {
+                AccountMeta::new_readonly(key, false)
+            }
+        })
+        .collect();
+
+    let lookup_table = (lookup_table != Pubkey::default()).then_some(lookup_table);
+    Ok((accounts, lookup_table))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sidecar_data(lookup_table: Pubkey, entries: &[(Pubkey, bool)]) -> Vec<u8> {
+        let mut data = vec![0u8; 8 + 8];
+        data.extend_from_slice(Pubkey::new_unique().as_ref()); // requester
+        data.extend_from_slice(lookup_table.as_ref());
+        data.push(255); // bump
+        data.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+        for (key, writable) in entries {
+            data.extend_from_slice(key.as_ref());
+            data.push(*writable as u8);
+        }
+        data
+    }
+
+    #[test]
+    fn parses_sidecar_accounts_in_order() {
+        let table = Pubkey::new_unique();
+        let entries: Vec<_> = (0..6).map(|i| (Pubkey::new_unique(), i % 2 == 0)).collect();
+
+        let (accounts, lookup_table) = parse_sidecar(&sidecar_data(table, &entries)).unwrap();
+        assert_eq!(lookup_table, Some(table));
+        assert_eq!(accounts.len(), entries.len());
+        for (meta, (key, writable)) in accounts.iter().zip(&entries) {
+            assert_eq!(meta.pubkey, *key);
+            assert_eq!(meta.is_writable, *writable);
+            assert!(!meta.is_signer);
+        }
+
+        let (_, lookup_table) = parse_sidecar(&sidecar_data(Pubkey::default(), &entries)).unwrap();
+        assert_eq!(lookup_table, None);
+    }
+
+    #[test]
+    fn rejects_oversized_or_truncated_sidecar() {
+        let entries: Vec<_> = (0..MAX_SIDECAR_CALLBACK_ACCOUNTS + 1)
+            .map(|_| (Pubkey::new_unique(), false))
+            .collect();
+        assert!(parse_sidecar(&sidecar_data(Pubkey::default(), &entries)).is_err());
+
+        let mut data = sidecar_data(Pubkey::default(), &entries[..3]);
+        data.pop();
+        assert!(parse_sidecar(&data).is_err());
+    }
 }
@@ -0,0 +1,211 @@
+//! ECVRF-EDWARDS25519-SHA512-TAI proof generation and verification (RFC 9381).
+//!
+//! Produces a proof `pi = Gamma || c || s` that the coordinator verifies
+//! on-chain against `CoordinatorConfig.authority`. The VRF output is derived
+//! from `Gamma`, so exactly one output exists per (key, input) pair and anyone
+//! can check it without trusting the oracle.
+
+use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+use curve25519_dalek::scalar::{clamp_integer, Scalar};
+use sha2::{Digest, Sha512};
+
+/// Serialized proof length: Gamma (32) + c (16) + s (32).
+pub const PROOF_LEN: usize = 80;
+
+/// Suite identifier for ECVRF-EDWARDS25519-SHA512-TAI.
+const SUITE_STRING: u8 = 0x03;
+
+/// Build the VRF input for a request (must match the on-chain derivation).
+///
+/// ```text
+/// alpha = seed || request_slot_le || request_id_le
+/// ```
+pub fn vrf_input(seed: &[u8; 32], request_slot: u64, request_id: u64) -> [u8; 48] {
+    let mut alpha = [0u8; 48];
+    alpha[..32].copy_from_slice(seed);
+    alpha[32..40].copy_from_slice(&request_slot.to_le_bytes());
+    alpha[40..].copy_from_slice(&request_id.to_le_bytes());
+    alpha
+}
+
+/// Generate an ECVRF proof for `alpha` with an Ed25519 secret key.
+///
+/// Returns the 80-byte proof and the 32-byte randomness (the first half of
+/// the 64-byte VRF output `beta`).
+pub fn prove(secret_key: &[u8; 32], alpha: &[u8]) -> ([u8; PROOF_LEN], [u8; 32]) {
+    let hashed_sk: [u8; 64] = Sha512::digest(secret_key).into();
+    let mut scalar_bytes = [0u8; 32];
+    scalar_bytes.copy_from_slice(&hashed_sk[..32]);
+    let x = Scalar::from_bytes_mod_order(clamp_integer(scalar_bytes));
+    let public_key = EdwardsPoint::mul_base(&x).compress();
+
+    let h = encode_to_curve(&public_key, alpha);
+    let h_string = h.compress();
+    let gamma = x * h;
+
+    let k = Scalar::from_hash(
+        Sha512::new()
+            .chain_update(&hashed_sk[32..])
+            .chain_update(h_string.as_bytes()),
+    );
+    let c_bytes = challenge(&[
+        public_key,
+        h_string,
+        gamma.compress(),
+        EdwardsPoint::mul_base(&k).compress(),
+        (k * h).compress(),
+    ]);
+    let c = challenge_scalar(&c_bytes);
+    let s = k + c * x;
+
+    let mut proof = [0u8; PROOF_LEN];
+    proof[..32].copy_from_slice(gamma.compress().as_bytes());
+    proof[32..48].copy_from_slice(&c_bytes);
+    proof[48..].copy_from_slice(s.as_bytes());
+
+    (proof, proof_to_randomness(&gamma))
+}
+
+/// Verify a proof by `public_key` over `alpha` and return its randomness,
+/// mirroring the on-chain verifier. Used to check peer proofs before they are
+/// submitted.
+pub fn verify(public_key: &[u8; 32], alpha: &[u8], proof: &[u8; PROOF_LEN]) -> Option<[u8; 32]> {
+    let y_string = CompressedEdwardsY(*public_key);
+    let y = y_string.decompress()?;
+    let gamma = CompressedEdwardsY(proof[..32].try_into().unwrap()).decompress()?;
+    let c_bytes: [u8; 16] = proof[32..48].try_into().unwrap();
+    let s = Option::<Scalar>::from(Scalar::from_canonical_bytes(
+        proof[48..].try_into().unwrap(),
+    ))?;
+    let c = challenge_scalar(&c_bytes);
+
+    let h = encode_to_curve(&y_string, alpha);
+    let u = EdwardsPoint::mul_base(&s) - c * y;
+    let v = s * h - c * gamma;
+    let expected = challenge(&[
+        y_string,
+        h.compress(),
+        gamma.compress(),
+        u.compress(),
+        v.compress(),
+    ]);
+    (expected == c_bytes).then(|| proof_to_randomness(&gamma))
+}
+
+/// Try-and-increment hash to curve, salted with the public key.
+fn encode_to_curve(public_key: &CompressedEdwardsY, alpha: &[u8]) -> EdwardsPoint {
+    for ctr in 0..=u8::MAX {
+        let hash = Sha512::new()
+            .chain_update([SUITE_STRING, 0x01])
+            .chain_update(public_key.as_bytes())
+            .chain_update(alpha)
+            .chain_update([ctr, 0x00])
+            .finalize();
+        let mut candidate = [0u8; 32];
+        candidate.copy_from_slice(&hash[..32]);
+        if let Some(point) = CompressedEdwardsY(candidate).decompress() {
+            let point = point.mul_by_cofactor();
+            if point != EdwardsPoint::default() {
+                return point;
+            }
+        }
+    }
+    unreachable!("try-and-increment exhausted 256 candidates")
+}
+
+/// Challenge generation: the first 16 bytes of a SHA-512 over the points.
+fn challenge(points: &[CompressedEdwardsY; 5]) -> [u8; 16] {
+    let mut hasher = Sha512::new();
+    hasher.update([SUITE_STRING, 0x02]);
+    for point in points {
+        hasher.update(point.as_bytes());
+    }
+    hasher.update([0x00]);
+    let hash = hasher.finalize();
+    let mut c = [0u8; 16];
+    c.copy_from_slice(&hash[..16]);
+    c
+}
+
+fn challenge_scalar(c: &[u8; 16]) -> Scalar {
+    let mut bytes = [0u8; 32];
+    bytes[..16].copy_from_slice(c);
+    Scalar::from_bytes_mod_order(bytes)
+}
+
+/// `beta = SHA512(suite || 0x03 || cofactor * Gamma || 0x00)`, truncated to 32 bytes.
+fn proof_to_randomness(gamma: &EdwardsPoint) -> [u8; 32] {
+    let hash = Sha512::new()
+        .chain_update([SUITE_STRING, 0x03])
+        .chain_update(gamma.mul_by_cofactor().compress().as_bytes())
+        .chain_update([0x00])
+        .finalize();
+    let mut output = [0u8; 32];
+    output.copy_from_slice(&hash[..32]);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn public_key_of(secret: &[u8; 32]) -> [u8; 32] {
+        let hashed: [u8; 64] = Sha512::digest(secret).into();
+        let x = Scalar::from_bytes_mod_order(clamp_integer(hashed[..32].try_into().unwrap()));
+        EdwardsPoint::mul_base(&x).compress().to_bytes()
+    }
+
+    #[test]
+    fn matches_rfc9381_test_vector() {
+        // RFC 9381, Appendix B.3, Example 16.
+        let secret: [u8; 32] =
+            hex("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60")
+                .try_into()
+                .unwrap();
+        let (proof, output) = prove(&secret, b"");
+
+        assert_eq!(
+            proof.to_vec(),
+            hex(concat!(
+                "8657106690b5526245a92b003bb079ccd1a92130477671f6fc01ad16f26f723f",
+                "26f8a57ccaed74ee1b190bed1f479d97",
+                "27d2d0f9b005a6e456a35d4fb0daab1268a1b0db10836d9826a528ca76567805",
+            ))
+        );
+        assert_eq!(
+            output.to_vec(),
+            hex("90cf1df3b703cce59e2a35b925d411164068269d7b2d29f3301c03dd757876ff")
+        );
+    }
+
+    #[test]
+    fn proof_verifies_and_binds_output() {
+        let secret = [7u8; 32];
+        let public_key = public_key_of(&secret);
+        let alpha = vrf_input(&[1u8; 32], 100, 0);
+
+        let (proof, output) = prove(&secret, &alpha);
+        assert_eq!(verify(&public_key, &alpha, &proof), Some(output));
+
+        let other_alpha = vrf_input(&[1u8; 32], 100, 1);
+        assert_eq!(verify(&public_key, &other_alpha, &proof), None);
+    }
+
+    #[test]
+    fn tampered_proof_is_rejected() {
+        let secret = [7u8; 32];
+        let public_key = public_key_of(&secret);
+        let alpha = vrf_input(&[1u8; 32], 100, 0);
+
+        let (mut proof, _) = prove(&secret, &alpha);
+        proof[40] ^= 1;
+        assert_eq!(verify(&public_key, &alpha, &proof), None);
+    }
+}
@@ -0,0 +1,171 @@
+//! Derives the token accounts a fulfillment needs to collect a token fee.
+//!
+//! Requests charged to a token-billed subscription record their `fee_mint`.
+//! The coordinator then moves the fee from the subscription's associated token
+//! account to the fee vault's, and needs the approved mint PDA, the mint, both
+//! token accounts and the token program. SOL requests pass the program ID in
+//! each of those optional account slots.
+
+use anyhow::{Context, Result};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::pubkey::Pubkey;
+
+/// Offset of `fee_mint` in the request account: discriminator(8) + the 311
+/// bytes of fields before it.
+const FEE_MINT_OFFSET: usize = 8 + 311;
+
+/// The associated token account program.
+const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// Token accounts for collecting a request's fee, if it was charged in a mint.
+#[derive(Debug, Default, Clone)]
+pub struct FeeAccounts {
+    token: Option<TokenFeeAccounts>,
+}
+
+#[derive(Debug, Clone)]
+struct TokenFeeAccounts {
+    approved_mint: Pubkey,
+    mint: Pubkey,
+    subscription_vault: Pubkey,
+    fee_vault_token_account: Pubkey,
+    token_program: Pubkey,
+}
+
+impl FeeAccounts {
+    /// Accounts for a fee paid in `mint`, owned by `token_program`.
+    pub fn token(
+        program_id: &Pubkey,
+        subscription_id: u64,
+        mint: Pubkey,
+        token_program: Pubkey,
+    ) -> Self {
+        let (approved_mint, _) =
+            Pubkey::find_program_address(&[b"approved-mint", mint.as_ref()], program_id);
+        let (subscription, _) = Pubkey::find_program_address(
+            &[b"subscription", &subscription_id.to_le_bytes()],
+            program_id,
+        );
+        let (fee_vault, _) = Pubkey::find_program_address(&[b"fee-vault"], program_id);
+        Self {
+            token: Some(TokenFeeAccounts {
+                approved_mint,
+                mint,
+                subscription_vault: associated_token_address(&subscription, &mint, &token_program),
+                fee_vault_token_account: associated_token_address(&fee_vault, &mint, &token_program),
+                token_program,
+            }),
+        }
+    }
+
+    /// The five optional fee account metas of `fulfill_random_words`, in order.
+    pub fn metas(&self, program_id: &Pubkey) -> [AccountMeta; 5] {
+        match &self.token {
+            Some(t) => [
+                AccountMeta::new(t.approved_mint, false),
+                AccountMeta::new_readonly(t.mint, false),
+                AccountMeta::new(t.subscription_vault, false),
+                AccountMeta::new(t.fee_vault_token_account, false),
+                AccountMeta::new_readonly(t.token_program, false),
+            ],
+            None => std::array::from_fn(|_| AccountMeta::new_readonly(*program_id, false)),
+        }
+    }
+}
+
+/// Read the request's `fee_mint` and, for a token fee, derive the accounts
+/// needed to collect it. The mint's owner tells SPL Token from Token-2022.
+pub async fn read_fee_accounts(
+    rpc_client: &RpcClient,
+    vrf_program_id: &Pubkey,
+    request_id: u64,
+    subscription_id: u64,
+) -> Result<FeeAccounts> {
+    let (request_pda, _) = Pubkey::find_program_address(
+        &[b"vrf-request", &request_id.to_le_bytes()],
+        vrf_program_id,
+    );
+    let account = rpc_client
+        .get_account_with_commitment(&request_pda, CommitmentConfig::confirmed())
+        .await
+        .context("failed to fetch request PDA")?
+        .value
+        .context("request PDA not found")?;
+
+    let Some(mint) = parse_fee_mint(&account.data)? else {
+        return Ok(FeeAccounts::default());
+    };
+
+    let mint_account = rpc_client
+        .get_account_with_commitment(&mint, CommitmentConfig::confirmed())
+        .await
+        .context("failed to fetch fee mint")?
+        .value
+        .context("fee mint not found")?;
+
+    Ok(FeeAccounts::token(
+        vrf_program_id,
+        subscription_id,
+        mint,
+        mint_account.owner,
+    ))
+}
+
+/// The request's fee mint, or `None` for a lamport fee.
+fn parse_fee_mint(data: &[u8]) -> Result<Option<Pubkey>> {
+    let Some(bytes) = data.get(FEE_MINT_OFFSET..FEE_MINT_OFFSET + 32) else {
+        // Request created before token billing: always lamports.
+        return Ok(None);
+    };
+    let mint = Pubkey::try_from(bytes).map_err(|_| anyhow::anyhow!("invalid fee mint"))?;
+    Ok((mint != Pubkey::default()).then_some(mint))
+}
+
+/// Associated token account of `owner` for `mint` under `token_program`.
+fn associated_token_address(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_fee_mint() {
+        let mut data = vec![0u8; FEE_MINT_OFFSET + 32];
+        assert_eq!(parse_fee_mint(&data).unwrap(), None);
+        assert_eq!(parse_fee_mint(&data[..FEE_MINT_OFFSET]).unwrap(), None);
+
+        let mint = Pubkey::new_unique();
+        data[FEE_MINT_OFFSET..].copy_from_slice(mint.as_ref());
+        assert_eq!(parse_fee_mint(&data).unwrap(), Some(mint));
+    }
+
+    #[test]
+    fn derives_token_fee_accounts() {
+        let program_id = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let token_program = Pubkey::new_unique();
+
+        let metas = FeeAccounts::default().metas(&program_id);
+        assert!(metas.iter().all(|m| m.pubkey == program_id && !m.is_writable));
+
+        let metas = FeeAccounts::token(&program_id, 3, mint, token_program).metas(&program_id);
+        let (subscription, _) =
+            Pubkey::find_program_address(&[b"subscription", &3u64.to_le_bytes()], &program_id);
+        assert_eq!(metas[1], AccountMeta::new_readonly(mint, false));
+        assert_eq!(
+            metas[2].pubkey,
+            associated_token_address(&subscription, &mint, &token_program)
+        );
+        assert!(metas[2].is_writable && metas[3].is_writable);
+        assert_eq!(metas[4], AccountMeta::new_readonly(token_program, false));
+    }
+}
@@ -4,29 +4,62 @@
 //!
 //! Each fulfillment transaction contains:
 //! 1. (Optional) A `set_compute_unit_price` instruction for priority fees.
-//! 2. A native Ed25519 signature-verify instruction (proof of VRF output).
-//! 3. The `fulfill_random_words` coordinator instruction (verifies proof,
-//!    expands randomness, CPIs callback into consumer, closes request PDA).
+//! 2. For the ed25519 and signature-hash schemes, a native Ed25519
+//!    signature-verify instruction (proof of VRF output). The ECVRF and
+//!    multi-oracle schemes carry their proofs in the coordinator instruction,
+//!    one per committee oracle under multi-oracle.
+//! 3. The `fulfill_random_words` (ed25519, signature-hash),
+//!    `fulfill_random_words_ecvrf` or `fulfill_random_words_multi` coordinator
+//!    instruction (verifies proof, expands randomness, CPIs callback into
+//!    consumer, closes request PDA).
+//!
+//! Transactions are compiled as v0 messages so that a request whose callback
+//! accounts live in a sidecar can reference them through its address lookup
+//! table. Once such a request is delivered, the sidecar is closed.
+//!
+//! A reverted consumer callback aborts the whole transaction, so each
+//! fulfillment is simulated first. If the failure comes from the consumer
+//! program, the proof is submitted with the failure as `callback_failure`
+//! instead: the output is stored on-chain (`STATUS_CALLBACK_FAILED`), the
+//! failure is emitted in `RandomWordsCallbackFailed` and delivery is retried
+//! via `retry_callback` up to the on-chain attempt cap.
+//!
+//! Requests that must wait `min_confirmation_slots` are held in a queue
+//! ordered by their fulfillable slot and only take a concurrency permit once
+//! that slot is reached, so a long wait never holds up requests that are
+//! ready.
+//!
+//! When several requests are queued under the ed25519 or signature-hash
+//! scheme, they are packed into `fulfill_random_words_batch` transactions
+//! whose single Ed25519 instruction carries one signature per request. A batch
+//! that fails simulation falls back to the per-request path above.
 
 use anyhow::{Context, Result};
 use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
 use solana_commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
 use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::message::{v0, AddressLookupTableAccount, VersionedMessage};
 use solana_sdk::pubkey::Pubkey;
-use solana_sdk::signature::Signer;
+use solana_sdk::signature::{Signature, Signer};
 use solana_sdk::sysvar;
-use solana_sdk::transaction::Transaction;
+use solana_sdk::transaction::VersionedTransaction;
+use std::collections::{BTreeMap, VecDeque};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, Semaphore};
 use tracing::{error, info, instrument, warn};
 
-use crate::config::AppConfig;
-use crate::consumer_accounts::read_callback_accounts_from_request;
-use crate::listener::RandomWordsRequestedEvent;
+use crate::config::{AppConfig, ProofScheme};
+use crate::consumer_accounts::{read_callback_accounts_from_request, CallbackAccounts};
+use crate::fee_accounts::{read_fee_accounts, FeeAccounts};
+use crate::ecvrf;
+use crate::listener::{CancelledRequests, RandomWordsRequestedEvent};
 use crate::metrics::Metrics;
-use crate::vrf::compute_randomness;
+use crate::peers::{self, OracleSet};
+use crate::vrf::{compute_randomness, randomness_from_signature, signature_randomness_message};
 
 /// Known non-retryable Anchor error codes.
 const ERROR_REQUEST_NOT_PENDING: u32 = 6000;
@@ -35,14 +68,80 @@ const ERROR_UNAUTHORIZED: u32 = 6007;
 const ERROR_ACCOUNT_NOT_INITIALIZED: u32 = 3012;
 /// Anchor framework error: AccountDidNotDeserialize (stale request from old program version).
 const ERROR_ACCOUNT_DID_NOT_DESERIALIZE: u32 = 3003;
-/// Default compute unit limit for fulfillment + callback transactions.
-const DEFAULT_COMPUTE_UNIT_LIMIT: u32 = 400_000;
+/// Compute units reserved for proof verification, fee collection and the
+/// coordinator's own work, on top of the request's `callback_compute_limit`.
+const FULFILL_COMPUTE_UNITS: u32 = 200_000;
+/// Most compute units a transaction can request.
+const MAX_TRANSACTION_COMPUTE_UNITS: u32 = 1_400_000;
+/// Offset of `CoordinatorConfig.oracle_count`: 8 (discriminator) + 94
+/// (preceding fields). `oracle_threshold` and the oracle keys follow it.
+const CONFIG_ORACLE_COUNT_OFFSET: usize = 8 + 94;
+/// Compute units for verifying each multi-oracle ECVRF proof beyond the first,
+/// which `FULFILL_COMPUTE_UNITS` covers.
+const ORACLE_PROOF_COMPUTE_UNITS: u32 = 50_000;
+/// Slots after a request becomes fulfillable during which only its
+/// multi-oracle committee may prove it (must match on-chain
+/// `CoordinatorConfig::MULTI_ORACLE_FALLBACK_SLOTS`).
+const MULTI_ORACLE_FALLBACK_SLOTS: u64 = 150;
+/// Approximate slot time, used to pace waits for a request's confirmation slot.
+const SLOT_DURATION: Duration = Duration::from_millis(400);
+/// How often to re-check a paused coordinator.
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Offset of `CoordinatorConfig.paused`: 8 (discriminator) + 422 (preceding fields).
+const CONFIG_PAUSED_OFFSET: usize = 8 + 422;
+/// Maximum recorded callback failures per request (must match on-chain
+/// `RandomnessRequest::MAX_CALLBACK_ATTEMPTS`).
+const MAX_CALLBACK_ATTEMPTS: u8 = 3;
+/// Maximum bytes of a reported callback failure (must match on-chain
+/// `MAX_CALLBACK_FAILURE_LEN`).
+const MAX_CALLBACK_FAILURE_LEN: usize = 128;
+/// Maximum requests per `fulfill_random_words_batch` (must match on-chain
+/// `MAX_BATCH_FULFILLMENTS`).
+const MAX_BATCH_FULFILLMENTS: usize = 8;
+/// Compute units reserved per request in a batch for its account checks, fee
+/// collection and signature check, on top of its `callback_compute_limit`.
+const BATCH_ENTRY_COMPUTE_UNITS: u32 = 50_000;
+/// Most bytes a serialized transaction may take (the packet data size).
+const MAX_TRANSACTION_SIZE: usize = 1232;
+/// Subscription ID of direct-funded requests, which have no subscription
+/// account (must match on-chain `Subscription::DIRECT_SUBSCRIPTION_ID`).
+const DIRECT_SUBSCRIPTION_ID: u64 = u32::MAX as u64;
+
+/// Outcome of a submitted fulfillment.
+enum Fulfillment {
+    /// Randomness verified and delivered; the request PDA is closed.
+    Delivered(String),
+    /// Randomness verified and stored, but the consumer callback reverts.
+    Stored(String),
+}
 
-/// Compute the Anchor instruction discriminator for `fulfill_random_words`.
-fn fulfill_discriminator() -> [u8; 8] {
+/// A request prepared for a batch fulfillment: its signed output and the
+/// accounts needed to deliver it.
+struct BatchEntry<'a> {
+    event: &'a RandomWordsRequestedEvent,
+    randomness: [u8; 32],
+    message: Vec<u8>,
+    signature: Signature,
+    callback: CallbackAccounts,
+    fee: FeeAccounts,
+}
+
+/// Result of simulating a transaction that CPIs a consumer callback.
+#[derive(Debug, PartialEq, Eq)]
+enum CallbackSimulation {
+    /// The transaction would succeed.
+    Succeeds,
+    /// The consumer program itself fails, with the failure from its log.
+    Reverts(String),
+    /// The transaction fails for another reason, or simulation was unavailable.
+    Fails,
+}
+
+/// Compute the Anchor instruction discriminator: `sha256("global:<name>")[..8]`.
+fn instruction_discriminator(instruction_name: &str) -> [u8; 8] {
     use sha2::{Digest, Sha256};
     let mut hasher = Sha256::new();
-    hasher.update(b"global:fulfill_random_words");
+    hasher.update(format!("global:{instruction_name}"));
     let hash = hasher.finalize();
     let mut disc = [0u8; 8];
     disc.copy_from_slice(&hash[..8]);
@@ -76,6 +175,7 @@ fn is_non_retryable(err_str: &str) -> bool {
 pub async fn run_fulfiller(
     config: AppConfig,
     mut rx: mpsc::Receiver<RandomWordsRequestedEvent>,
+    cancelled: Arc<CancelledRequests>,
     pending_count: Arc<AtomicU64>,
     metrics: Arc<Metrics>,
 ) {
@@ -86,8 +186,68 @@ pub async fn run_fulfiller(
 
     let semaphore = Arc::new(Semaphore::new(config.fulfillment_concurrency));
 
-    while let Some(event) = rx.recv().await {
-        pending_count.fetch_add(1, Ordering::Relaxed);
+    let batch_size = if supports_batching(config.proof_scheme) {
+        config.fulfillment_batch_size.min(MAX_BATCH_FULFILLMENTS)
+    } else {
+        1
+    };
+
+    let mut queue = ConfirmationQueue::default();
+    let mut current_slot = 0;
+    let mut channel_open = true;
+
+    loop {
+        // Take whatever else is already queued without waiting for more:
+        // batching only kicks in when requests back up.
+        while let Ok(event) = rx.try_recv() {
+            pending_count.fetch_add(1, Ordering::Relaxed);
+            queue.push(event);
+        }
+
+        let mut events = queue.pop_fulfillable(current_slot, batch_size);
+        if events.is_empty() {
+            let Some(target) = queue.next_fulfillable_slot() else {
+                if !channel_open {
+                    break;
+                }
+                match rx.recv().await {
+                    Some(event) => {
+                        pending_count.fetch_add(1, Ordering::Relaxed);
+                        queue.push(event);
+                    }
+                    None => channel_open = false,
+                }
+                continue;
+            };
+
+            // Requests still waiting for their confirmation slot hold no
+            // permit, so they never delay requests that are ready.
+            match rpc_client.get_slot().await {
+                Ok(slot) => current_slot = current_slot.max(slot),
+                Err(e) => warn!(error = %format!("{e:#}"), "Failed to fetch current slot"),
+            }
+            if current_slot < target {
+                let wait = SLOT_DURATION.saturating_mul((target - current_slot).min(32) as u32);
+                tokio::select! {
+                    event = rx.recv(), if channel_open => match event {
+                        Some(event) => {
+                            pending_count.fetch_add(1, Ordering::Relaxed);
+                            queue.push(event);
+                        }
+                        None => channel_open = false,
+                    },
+                    _ = tokio::time::sleep(wait) => {}
+                }
+            }
+            continue;
+        }
+
+        let queued = events.len();
+        events.retain(|event| !skip_cancelled(&cancelled, event));
+        pending_count.fetch_sub((queued - events.len()) as u64, Ordering::Relaxed);
+        if events.is_empty() {
+            continue;
+        }
 
         let permit = match semaphore.clone().acquire_owned().await {
             Ok(p) => p,
@@ -100,44 +260,145 @@ pub async fn run_fulfiller(
         let cfg = config.clone();
         let pending = pending_count.clone();
         let met = metrics.clone();
+        let cancelled = cancelled.clone();
 
         tokio::spawn(async move {
             let _permit = permit;
             let start = Instant::now();
 
-            info!(
-                request_id = event.request_id,
-                subscription_id = event.subscription_id,
-                requester = %event.requester,
-                consumer = %event.consumer_program,
-                num_words = event.num_words,
-                callback_compute_limit = event.callback_compute_limit,
-                slot = event.request_slot,
-                "Fulfilling randomness request"
-            );
+            // Requests may have been cancelled while waiting for a permit.
+            let queued = events.len();
+            events.retain(|event| !skip_cancelled(&cancelled, event));
+            pending.fetch_sub((queued - events.len()) as u64, Ordering::Relaxed);
+
+            for event in &events {
+                info!(
+                    request_id = event.request_id,
+                    subscription_id = event.subscription_id,
+                    requester = %event.requester,
+                    consumer = %event.consumer_program,
+                    num_words = event.num_words,
+                    callback_compute_limit = event.callback_compute_limit,
+                    slot = event.request_slot,
+                    "Fulfilling randomness request"
+                );
+            }
 
-            match fulfill_request(&rpc, &cfg, &event).await {
-                Ok(sig) => {
-                    let latency_ms = start.elapsed().as_millis() as u64;
+            // Whatever a batch did not deliver is fulfilled on its own, which
+            // also handles reverting callbacks.
+            let events = if events.len() > 1 {
+                let delivered = fulfill_batches(&rpc, &cfg, &events).await;
+                let latency_ms = start.elapsed().as_millis() as u64;
+                for _ in &delivered {
                     met.record_fulfillment(latency_ms);
-                    info!(
-                        request_id = event.request_id,
-                        signature = %sig,
-                        latency_ms,
-                        explorer = %cfg.explorer_url(&sig),
-                        "Fulfilled successfully"
-                    );
                 }
-                Err(e) => handle_fulfillment_error(event.request_id, e, &met),
-            }
+                pending.fetch_sub(delivered.len() as u64, Ordering::Relaxed);
+                events
+                    .into_iter()
+                    .filter(|event| !delivered.contains(&event.request_id))
+                    .collect()
+            } else {
+                events
+            };
+
+            for event in events {
+                match fulfill_request(&rpc, &cfg, &event).await {
+                    Ok(Fulfillment::Stored(sig)) => {
+                        met.record_callback_failure();
+                        warn!(
+                            request_id = event.request_id,
+                            signature = %sig,
+                            explorer = %cfg.explorer_url(&sig),
+                            "Stored randomness; consumer callback failed, scheduling retries"
+                        );
+                        tokio::spawn(retry_stored_callback(rpc.clone(), cfg.clone(), event.clone()));
+                    }
+                    Ok(Fulfillment::Delivered(sig)) => {
+                        let latency_ms = start.elapsed().as_millis() as u64;
+                        met.record_fulfillment(latency_ms);
+                        info!(
+                            request_id = event.request_id,
+                            signature = %sig,
+                            latency_ms,
+                            explorer = %cfg.explorer_url(&sig),
+                            "Fulfilled successfully"
+                        );
+                    }
+                    Err(e) => handle_fulfillment_error(event.request_id, e, &met),
+                }
 
-            pending.fetch_sub(1, Ordering::Relaxed);
+                pending.fetch_sub(1, Ordering::Relaxed);
+            }
         });
     }
 
     info!("Fulfiller channel closed, shutting down");
 }
 
+/// Requests waiting for their confirmation slot, ordered by the first slot at
+/// which the coordinator accepts their fulfillment.
+#[derive(Default)]
+struct ConfirmationQueue {
+    by_slot: BTreeMap<u64, VecDeque<RandomWordsRequestedEvent>>,
+}
+
+impl ConfirmationQueue {
+    fn push(&mut self, event: RandomWordsRequestedEvent) {
+        self.by_slot
+            .entry(fulfillable_slot(&event))
+            .or_default()
+            .push_back(event);
+    }
+
+    /// Earliest slot at which a queued request becomes fulfillable.
+    fn next_fulfillable_slot(&self) -> Option<u64> {
+        self.by_slot.keys().next().copied()
+    }
+
+    /// Remove up to `max` requests fulfillable at `slot`, earliest first.
+    fn pop_fulfillable(&mut self, slot: u64, max: usize) -> Vec<RandomWordsRequestedEvent> {
+        let mut events = Vec::new();
+        while events.len() < max {
+            let Some(mut entry) = self.by_slot.first_entry() else {
+                break;
+            };
+            if *entry.key() > slot {
+                break;
+            }
+            let queued = entry.get_mut();
+            while events.len() < max {
+                match queued.pop_front() {
+                    Some(event) => events.push(event),
+                    None => break,
+                }
+            }
+            if queued.is_empty() {
+                entry.remove();
+            }
+        }
+        events
+    }
+}
+
+/// First slot at which the coordinator accepts `event`'s fulfillment, or 0 if
+/// it does not wait for confirmations.
+fn fulfillable_slot(event: &RandomWordsRequestedEvent) -> u64 {
+    if event.min_confirmation_slots == 0 {
+        0
+    } else {
+        event.request_slot.saturating_add(event.min_confirmation_slots)
+    }
+}
+
+/// Whether `event`'s request was cancelled on-chain, logging the skip.
+fn skip_cancelled(cancelled: &CancelledRequests, event: &RandomWordsRequestedEvent) -> bool {
+    let skip = cancelled.contains(event.request_id);
+    if skip {
+        info!(request_id = event.request_id, "Request cancelled on-chain, skipping");
+    }
+    skip
+}
+
 fn handle_fulfillment_error(request_id: u64, error: anyhow::Error, metrics: &Metrics) {
     let err_str = format!("{error:#}");
     if is_non_retryable(&err_str) {
@@ -156,60 +417,725 @@ fn handle_fulfillment_error(request_id: u64, error: anyhow::Error, metrics: &Met
     }
 }
 
-/// Build, sign, and submit a fulfillment transaction with callback.
+/// Wait while the coordinator is paused, so queued requests are fulfilled
+/// once the admin unpauses instead of failing with `CoordinatorPaused`.
+async fn wait_while_paused(rpc_client: &RpcClient, program_id: &Pubkey) -> Result<()> {
+    let (config_pda, _) = Pubkey::find_program_address(&[b"coordinator-config"], program_id);
+    loop {
+        let data = rpc_client
+            .get_account_data(&config_pda)
+            .await
+            .context("failed to fetch coordinator config")?;
+        if !is_paused(&data) {
+            return Ok(());
+        }
+        info!("Coordinator is paused, waiting");
+        tokio::time::sleep(PAUSE_POLL_INTERVAL).await;
+    }
+}
+
+/// Whether raw `CoordinatorConfig` account data has `paused` set. Configs
+/// created before the pause switch existed are never paused.
+fn is_paused(config_data: &[u8]) -> bool {
+    config_data.get(CONFIG_PAUSED_OFFSET) == Some(&1)
+}
+
+/// Build, sign, and submit a fulfillment transaction with callback. `event`
+/// must already be past its confirmation slot.
 #[instrument(skip_all, fields(request_id = event.request_id))]
 async fn fulfill_request(
     rpc_client: &RpcClient,
     config: &AppConfig,
     event: &RandomWordsRequestedEvent,
-) -> Result<String> {
-    let randomness = compute_randomness(
-        &config.hmac_secret,
-        &event.seed,
-        event.request_slot,
+) -> Result<Fulfillment> {
+    wait_while_paused(rpc_client, &config.program_id).await?;
+
+    let mut proof_instructions = Vec::with_capacity(1);
+    let mut proof_compute_units = 0;
+
+    // Instruction data: discriminator + request_id + proof payload
+    let mut data = Vec::with_capacity(8 + 8 + ecvrf::PROOF_LEN);
+    match config.proof_scheme {
+        ProofScheme::Ed25519 => {
+            let hmac_secret = config
+                .hmac_secret
+                .as_deref()
+                .context("HMAC_SECRET is required for the ed25519 proof scheme")?;
+            let randomness = compute_randomness(
+                hmac_secret,
+                &event.seed,
+                event.request_slot,
+                event.request_id,
+            );
+
+            // Signed message layout: request_id (8 bytes LE) || randomness (32 bytes)
+            let mut message = Vec::with_capacity(40);
+            message.extend_from_slice(&event.request_id.to_le_bytes());
+            message.extend_from_slice(&randomness);
+            proof_instructions.push(build_ed25519_instruction(
+                config.authority_keypair.as_ref(),
+                &message,
+            ));
+
+            data.extend_from_slice(&instruction_discriminator("fulfill_random_words"));
+            data.extend_from_slice(&event.request_id.to_le_bytes());
+            data.extend_from_slice(&randomness);
+        }
+        ProofScheme::SignatureHash => {
+            let message = signature_randomness_message(
+                &config.program_id,
+                event.request_id,
+                &event.seed,
+                event.request_slot,
+            );
+            let signature = config.authority_keypair.sign_message(&message);
+            let randomness = randomness_from_signature(signature.as_ref());
+            proof_instructions.push(build_ed25519_instruction(
+                config.authority_keypair.as_ref(),
+                &message,
+            ));
+
+            data.extend_from_slice(&instruction_discriminator("fulfill_random_words"));
+            data.extend_from_slice(&event.request_id.to_le_bytes());
+            data.extend_from_slice(&randomness);
+        }
+        ProofScheme::MultiOracle => {
+            let oracle_set = read_oracle_set(rpc_client, &config.program_id).await?;
+            let alpha = ecvrf::vrf_input(&event.seed, event.request_slot, event.request_id);
+            let committee = oracle_set.committee(event.request_id);
+            let proofs = match peers::collect_proofs(
+                config,
+                &oracle_set,
+                &committee,
+                event.request_id,
+                &alpha,
+            )
+            .await
+            {
+                Ok(proofs) => proofs,
+                Err(e) => {
+                    // Past the fallback slot any registered oracles may prove
+                    let fallback_slot = event
+                        .request_slot
+                        .saturating_add(event.min_confirmation_slots)
+                        .saturating_add(MULTI_ORACLE_FALLBACK_SLOTS);
+                    warn!(
+                        error = %format!("{e:#}"),
+                        fallback_slot,
+                        "Committee proofs incomplete, waiting for the fallback slot"
+                    );
+                    wait_for_slot(rpc_client, fallback_slot).await?;
+                    peers::collect_proofs(
+                        config,
+                        &oracle_set,
+                        &oracle_set.everyone(),
+                        event.request_id,
+                        &alpha,
+                    )
+                    .await?
+                }
+            };
+            proof_compute_units = ORACLE_PROOF_COMPUTE_UNITS * (proofs.len() as u32 - 1);
+
+            // proofs: Vec<OracleProof { oracle_index: u8, proof: [u8; 80] }>
+            data.extend_from_slice(&instruction_discriminator("fulfill_random_words_multi"));
+            data.extend_from_slice(&event.request_id.to_le_bytes());
+            data.extend_from_slice(&(proofs.len() as u32).to_le_bytes());
+            for (oracle_index, proof) in &proofs {
+                data.push(*oracle_index);
+                data.extend_from_slice(proof);
+            }
+        }
+        ProofScheme::Ecvrf => {
+            let alpha = ecvrf::vrf_input(&event.seed, event.request_slot, event.request_id);
+            let (proof, _) = ecvrf::prove(config.authority_keypair.secret_bytes(), &alpha);
+
+            data.extend_from_slice(&instruction_discriminator("fulfill_random_words_ecvrf"));
+            data.extend_from_slice(&event.request_id.to_le_bytes());
+            data.extend_from_slice(&proof);
+        }
+    }
+
+    // Read consumer callback accounts from the request PDA (or its sidecar)
+    // on-chain. The coordinator rejects any other list, so there is no
+    // fallback.
+    let callback = read_callback_accounts_from_request(
+        rpc_client,
+        &config.program_id,
+        event.request_id,
+    )
+    .await
+    .context("failed to read callback accounts")?;
+    let fee = read_fee_accounts(
+        rpc_client,
+        &config.program_id,
         event.request_id,
+        event.subscription_id,
+    )
+    .await
+    .context("failed to read fee accounts")?;
+
+    // Trailing `callback_failure` (borsh `Option<String>`).
+    let fulfill_ix = |callback_failure: Option<&str>| {
+        let mut data = data.clone();
+        encode_callback_failure(&mut data, callback_failure);
+        build_fulfill_instruction(
+            &config.program_id,
+            &config.authority_keypair.pubkey(),
+            event,
+            data,
+            &callback,
+            &fee,
+        )
+    };
+    let lookup_tables = &callback.lookup_tables;
+
+    let mut instructions = build_compute_budget_instructions(
+        config,
+        transaction_compute_limit(event.callback_compute_limit)
+            .saturating_add(proof_compute_units)
+            .min(MAX_TRANSACTION_COMPUTE_UNITS),
     );
+    instructions.extend(proof_instructions);
+    instructions.push(fulfill_ix(None));
+
+    if let CallbackSimulation::Reverts(reason) =
+        simulate_callback(rpc_client, config, &instructions, lookup_tables, &event.consumer_program)
+            .await
+    {
+        instructions.pop();
+        instructions.push(fulfill_ix(Some(&reason)));
+        return send_with_retries(rpc_client, config, &instructions, lookup_tables, event.request_id)
+            .await
+            .map(Fulfillment::Stored);
+    }
+
+    let signature =
+        send_with_retries(rpc_client, config, &instructions, lookup_tables, event.request_id)
+            .await?;
+    if let Some(sidecar) = callback.sidecar {
+        close_callback_sidecar(rpc_client, config, event, &sidecar).await;
+    }
+    Ok(Fulfillment::Delivered(signature))
+}
+
+/// Whether `fulfill_random_words_batch` accepts proofs of `scheme`. It needs
+/// one Ed25519 signature per request, which the ECVRF and multi-oracle
+/// schemes do not produce.
+fn supports_batching(scheme: ProofScheme) -> bool {
+    matches!(scheme, ProofScheme::Ed25519 | ProofScheme::SignatureHash)
+}
+
+/// Fulfill `events` with as few `fulfill_random_words_batch` transactions as
+/// the transaction size and compute limits allow, and return the IDs of the
+/// requests delivered.
+///
+/// `events` must all be past their confirmation slot: the confirmation queue
+/// only releases requests that are fulfillable, and keeps the rest queued for
+/// a later batch, so no batch waits on its slowest request.
+///
+/// A reverting callback fails the whole batch, so each batch is simulated
+/// first. Requests in a batch that fails, and requests that could not be
+/// batched, are left for individual fulfillment.
+async fn fulfill_batches(
+    rpc_client: &RpcClient,
+    config: &AppConfig,
+    events: &[RandomWordsRequestedEvent],
+) -> Vec<u64> {
+    if let Err(e) = wait_while_paused(rpc_client, &config.program_id).await {
+        warn!(error = %format!("{e:#}"), "Failed to read coordinator config, not batching");
+        return Vec::new();
+    }
+
+    let mut entries = Vec::with_capacity(events.len());
+    for event in events {
+        match prepare_batch_entry(rpc_client, config, event).await {
+            Ok(entry) => entries.push(entry),
+            Err(e) => warn!(
+                request_id = event.request_id,
+                error = %format!("{e:#}"),
+                "Cannot batch request, fulfilling it alone"
+            ),
+        }
+    }
+
+    let mut delivered = Vec::new();
+    for batch in pack_batches(config, entries) {
+        if batch.len() < 2 {
+            continue;
+        }
+        let request_ids: Vec<u64> = batch.iter().map(|entry| entry.event.request_id).collect();
+        let (instructions, lookup_tables) = build_batch_instructions(config, &batch);
 
-    // Signed message layout: request_id (8 bytes LE) || randomness (32 bytes)
-    let mut message = Vec::with_capacity(40);
-    message.extend_from_slice(&event.request_id.to_le_bytes());
-    message.extend_from_slice(&randomness);
+        // Any failure counts: the requests are retried one by one, where a
+        // reverting callback is handled with a `callback_failure`.
+        if simulate_callback(
+            rpc_client,
+            config,
+            &instructions,
+            &lookup_tables,
+            &batch[0].event.consumer_program,
+        )
+        .await
+            != CallbackSimulation::Succeeds
+        {
+            warn!(?request_ids, "Batch simulation failed, fulfilling requests individually");
+            continue;
+        }
 
-    let ed25519_ix = build_ed25519_instruction(config.authority_keypair.as_ref(), &message);
+        match send_with_retries(rpc_client, config, &instructions, &lookup_tables, request_ids[0])
+            .await
+        {
+            Ok(sig) => {
+                info!(
+                    ?request_ids,
+                    signature = %sig,
+                    explorer = %config.explorer_url(&sig),
+                    "Fulfilled batch successfully"
+                );
+                for entry in &batch {
+                    if let Some(sidecar) = entry.callback.sidecar {
+                        close_callback_sidecar(rpc_client, config, entry.event, &sidecar).await;
+                    }
+                }
+                delivered.extend(request_ids);
+            }
+            Err(e) => warn!(
+                ?request_ids,
+                error = %format!("{e:#}"),
+                "Batch fulfillment failed, fulfilling requests individually"
+            ),
+        }
+    }
+    delivered
+}
 
-    // Read consumer callback accounts from the request PDA on-chain.
-    let callback_remaining = read_callback_accounts_from_request(
+/// Sign `event`'s output and read the accounts needed to deliver it.
+async fn prepare_batch_entry<'a>(
+    rpc_client: &RpcClient,
+    config: &AppConfig,
+    event: &'a RandomWordsRequestedEvent,
+) -> Result<BatchEntry<'a>> {
+    let (randomness, message, signature) = match config.proof_scheme {
+        ProofScheme::Ed25519 => {
+            let hmac_secret = config
+                .hmac_secret
+                .as_deref()
+                .context("HMAC_SECRET is required for the ed25519 proof scheme")?;
+            let randomness = compute_randomness(
+                hmac_secret,
+                &event.seed,
+                event.request_slot,
+                event.request_id,
+            );
+            let mut message = Vec::with_capacity(40);
+            message.extend_from_slice(&event.request_id.to_le_bytes());
+            message.extend_from_slice(&randomness);
+            let signature = config.authority_keypair.sign_message(&message);
+            (randomness, message, signature)
+        }
+        ProofScheme::SignatureHash => {
+            let message = signature_randomness_message(
+                &config.program_id,
+                event.request_id,
+                &event.seed,
+                event.request_slot,
+            );
+            let signature = config.authority_keypair.sign_message(&message);
+            (randomness_from_signature(signature.as_ref()), message.to_vec(), signature)
+        }
+        ProofScheme::MultiOracle | ProofScheme::Ecvrf => {
+            anyhow::bail!("proof scheme does not support batch fulfillment")
+        }
+    };
+
+    let callback = read_callback_accounts_from_request(
         rpc_client,
         &config.program_id,
         event.request_id,
     )
     .await
-    .unwrap_or_else(|e| {
-        warn!(request_id = event.request_id, error = %e, "Failed to read callback accounts, using empty");
-        vec![]
-    });
+    .context("failed to read callback accounts")?;
+    let fee = read_fee_accounts(
+        rpc_client,
+        &config.program_id,
+        event.request_id,
+        event.subscription_id,
+    )
+    .await
+    .context("failed to read fee accounts")?;
 
-    let fulfill_ix = build_fulfill_instruction(
+    Ok(BatchEntry {
+        event,
+        randomness,
+        message,
+        signature,
+        callback,
+        fee,
+    })
+}
+
+/// Split `entries`, in order, into batches that each fit one transaction. An
+/// entry too large to share a transaction ends up in a batch of its own.
+fn pack_batches<'a>(config: &AppConfig, entries: Vec<BatchEntry<'a>>) -> Vec<Vec<BatchEntry<'a>>> {
+    let mut batches = Vec::new();
+    let mut current: Vec<BatchEntry> = Vec::new();
+    for entry in entries {
+        current.push(entry);
+        if current.len() > 1 && !batch_fits(config, &current) {
+            let entry = current.pop().expect("batch has at least two entries");
+            batches.push(std::mem::take(&mut current));
+            current.push(entry);
+        }
+    }
+    if !current.is_empty() {
+        batches.push(current);
+    }
+    batches
+}
+
+/// Whether `batch` stays within one transaction's compute budget and size.
+fn batch_fits(config: &AppConfig, batch: &[BatchEntry]) -> bool {
+    if batch.len() > MAX_BATCH_FULFILLMENTS
+        || batch_compute_limit(batch.iter().map(|entry| entry.event.callback_compute_limit)).is_none()
+    {
+        return false;
+    }
+    let (instructions, lookup_tables) = build_batch_instructions(config, batch);
+    build_transaction(config, &instructions, &lookup_tables, Hash::default())
+        .map(|tx| transaction_size(&tx) <= MAX_TRANSACTION_SIZE)
+        .unwrap_or(false)
+}
+
+/// Compute unit limit for a batch: the coordinator's own work once, plus each
+/// request's callback limit and per-request overhead. `None` if that exceeds
+/// a transaction's budget.
+fn batch_compute_limit(callback_compute_limits: impl IntoIterator<Item = u32>) -> Option<u32> {
+    callback_compute_limits
+        .into_iter()
+        .try_fold(FULFILL_COMPUTE_UNITS, |total, limit| {
+            total.checked_add(limit)?.checked_add(BATCH_ENTRY_COMPUTE_UNITS)
+        })
+        .filter(|&total| total <= MAX_TRANSACTION_COMPUTE_UNITS)
+}
+
+/// Size of a serialized transaction with fewer than 128 signatures.
+fn transaction_size(tx: &VersionedTransaction) -> usize {
+    1 + 64 * tx.signatures.len() + tx.message.serialize().len()
+}
+
+/// The instructions of a batch fulfillment transaction, and the lookup tables
+/// of all its requests to compile it against.
+fn build_batch_instructions(
+    config: &AppConfig,
+    batch: &[BatchEntry],
+) -> (Vec<Instruction>, Vec<AddressLookupTableAccount>) {
+    let compute_limit =
+        batch_compute_limit(batch.iter().map(|entry| entry.event.callback_compute_limit))
+            .unwrap_or(MAX_TRANSACTION_COMPUTE_UNITS);
+    let mut instructions = build_compute_budget_instructions(config, compute_limit);
+
+    let signatures: Vec<_> = batch
+        .iter()
+        .map(|entry| {
+            (
+                config.authority_keypair.pubkey(),
+                entry.signature,
+                entry.message.as_slice(),
+            )
+        })
+        .collect();
+    instructions.push(build_batch_ed25519_instruction(&signatures));
+    instructions.push(build_fulfill_batch_instruction(
         &config.program_id,
         &config.authority_keypair.pubkey(),
-        event,
-        &randomness,
-        &callback_remaining,
-    );
+        batch,
+    ));
+
+    let mut lookup_tables: Vec<AddressLookupTableAccount> = Vec::new();
+    for table in batch.iter().flat_map(|entry| &entry.callback.lookup_tables) {
+        if !lookup_tables.iter().any(|t| t.key == table.key) {
+            lookup_tables.push(table.clone());
+        }
+    }
+    (instructions, lookup_tables)
+}
+
+/// Close the request's callback accounts sidecar after delivery, refunding its
+/// rent to the requester. Best-effort: anyone can close it later.
+async fn close_callback_sidecar(
+    rpc_client: &RpcClient,
+    config: &AppConfig,
+    event: &RandomWordsRequestedEvent,
+    sidecar: &Pubkey,
+) {
+    let instruction = build_close_callback_accounts_instruction(&config.program_id, event, sidecar);
+    match send_with_retries(rpc_client, config, &[instruction], &[], event.request_id).await {
+        Ok(sig) => info!(signature = %sig, "Closed callback accounts sidecar"),
+        Err(e) => warn!(error = %format!("{e:#}"), "Failed to close callback accounts sidecar"),
+    }
+}
+
+/// Retry delivery of a stored result until it succeeds or the on-chain
+/// attempt cap is reached. Each failed attempt is recorded with
+/// `retry_callback(record_failure = Some(reason))`, since a reverted retry
+/// leaves no trace on-chain.
+#[instrument(skip_all, fields(request_id = event.request_id))]
+async fn retry_stored_callback(
+    rpc_client: Arc<RpcClient>,
+    config: AppConfig,
+    event: RandomWordsRequestedEvent,
+) {
+    let delay = Duration::from_millis(config.callback_retry_delay_ms);
+
+    // The fulfillment itself recorded the first failed attempt.
+    for attempt in 2..=MAX_CALLBACK_ATTEMPTS {
+        tokio::time::sleep(delay).await;
+        if let Err(e) = wait_while_paused(&rpc_client, &config.program_id).await {
+            warn!(error = %format!("{e:#}"), "Failed to read coordinator config, stopping retries");
+            return;
+        }
+
+        let callback = match read_callback_accounts_from_request(
+            &rpc_client,
+            &config.program_id,
+            event.request_id,
+        )
+        .await
+        {
+            Ok(callback) => callback,
+            Err(e) => {
+                warn!(error = %format!("{e:#}"), "Failed to read callback accounts, stopping retries");
+                return;
+            }
+        };
+        let lookup_tables = &callback.lookup_tables;
+        let retry_ix = |record_failure: Option<&str>| {
+            build_retry_callback_instruction(
+                &config.program_id,
+                &config.authority_keypair.pubkey(),
+                &event,
+                record_failure,
+                &callback,
+            )
+        };
+
+        let mut instructions = build_compute_budget_instructions(
+            &config,
+            transaction_compute_limit(event.callback_compute_limit),
+        );
+        instructions.push(retry_ix(None));
+
+        match simulate_callback(
+            &rpc_client,
+            &config,
+            &instructions,
+            lookup_tables,
+            &event.consumer_program,
+        )
+        .await
+        {
+            CallbackSimulation::Succeeds => {
+                match send_with_retries(
+                    &rpc_client,
+                    &config,
+                    &instructions,
+                    lookup_tables,
+                    event.request_id,
+                )
+                .await
+                {
+                    Ok(sig) => {
+                        info!(signature = %sig, attempt, "Delivered stored randomness");
+                        if let Some(sidecar) = callback.sidecar {
+                            close_callback_sidecar(&rpc_client, &config, &event, &sidecar).await;
+                        }
+                    }
+                    Err(e) => warn!(error = %format!("{e:#}"), "Callback retry failed"),
+                }
+                return;
+            }
+            CallbackSimulation::Reverts(reason) => {
+                instructions.pop();
+                instructions.push(retry_ix(Some(&reason)));
+                if let Err(e) = send_with_retries(
+                    &rpc_client,
+                    &config,
+                    &instructions,
+                    lookup_tables,
+                    event.request_id,
+                )
+                .await
+                {
+                    warn!(error = %format!("{e:#}"), "Failed to record callback failure");
+                    return;
+                }
+                warn!(attempt, "Consumer callback still reverts");
+            }
+            CallbackSimulation::Fails => {
+                // Delivered by someone else, closed, or otherwise not retryable.
+                warn!(attempt, "Callback retry not possible, stopping");
+                return;
+            }
+        }
+    }
+
+    warn!("Callback retries exhausted; randomness remains readable on the request PDA");
+}
+
+/// Simulate `instructions` and classify the outcome.
+///
+/// A failing consumer program is recognised from the simulation logs, which
+/// cover both explicit errors and compute-budget exhaustion inside the CPI.
+async fn simulate_callback(
+    rpc_client: &RpcClient,
+    config: &AppConfig,
+    instructions: &[Instruction],
+    lookup_tables: &[AddressLookupTableAccount],
+    consumer_program: &Pubkey,
+) -> CallbackSimulation {
+    // The blockhash is replaced by the RPC node.
+    let tx = match build_transaction(config, instructions, lookup_tables, Hash::default()) {
+        Ok(tx) => tx,
+        Err(e) => {
+            warn!(error = %format!("{e:#}"), "Failed to build simulation transaction");
+            return CallbackSimulation::Fails;
+        }
+    };
+    let sim_config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        replace_recent_blockhash: true,
+        commitment: Some(CommitmentConfig::confirmed()),
+        ..Default::default()
+    };
+
+    match rpc_client
+        .simulate_transaction_with_config(&tx, sim_config)
+        .await
+    {
+        Ok(response) if response.value.err.is_none() => CallbackSimulation::Succeeds,
+        Ok(response) => {
+            let logs = response.value.logs.unwrap_or_default();
+            match consumer_failure(&logs, consumer_program) {
+                Some(reason) => CallbackSimulation::Reverts(reason),
+                None => CallbackSimulation::Fails,
+            }
+        }
+        Err(e) => {
+            warn!(error = %e, "Simulation request failed");
+            CallbackSimulation::Fails
+        }
+    }
+}
+
+/// The failure of `consumer_program` itself in the logs, if it failed, e.g.
+/// `custom program error: 0x1770`.
+fn consumer_failure(logs: &[String], consumer_program: &Pubkey) -> Option<String> {
+    let prefix = format!("Program {consumer_program} failed: ");
+    logs.iter()
+        .find_map(|line| line.strip_prefix(&prefix))
+        .map(str::to_string)
+}
+
+/// Append `reason` as a borsh `Option<String>`, cut to
+/// `MAX_CALLBACK_FAILURE_LEN` bytes on a character boundary.
+fn encode_callback_failure(data: &mut Vec<u8>, reason: Option<&str>) {
+    let Some(reason) = reason else {
+        data.push(0);
+        return;
+    };
+    let mut len = reason.len().min(MAX_CALLBACK_FAILURE_LEN);
+    while !reason.is_char_boundary(len) {
+        len -= 1;
+    }
+    data.push(1);
+    data.extend_from_slice(&(len as u32).to_le_bytes());
+    data.extend_from_slice(&reason.as_bytes()[..len]);
+}
 
+/// Compute budget instructions shared by fulfillment, batch and retry
+/// transactions.
+fn build_compute_budget_instructions(config: &AppConfig, compute_unit_limit: u32) -> Vec<Instruction> {
     let mut instructions = Vec::with_capacity(4);
-    // Set compute unit limit to ensure enough CU for Ed25519 verify + fulfill + CPI callback
-    let compute_limit = event.callback_compute_limit.max(DEFAULT_COMPUTE_UNIT_LIMIT);
-    instructions.push(build_set_compute_unit_limit_instruction(compute_limit));
+    instructions.push(build_set_compute_unit_limit_instruction(compute_unit_limit));
     if config.priority_fee_micro_lamports > 0 {
         instructions.push(build_set_compute_unit_price_instruction(
             config.priority_fee_micro_lamports,
         ));
     }
-    instructions.push(ed25519_ix);
-    instructions.push(fulfill_ix);
+    instructions
+}
+
+/// Compute unit limit for a fulfillment or retry transaction: the callback's
+/// paid-for limit plus the coordinator's own work. The coordinator caps
+/// `callback_compute_limit`, so the cap here only guards against a
+/// misconfigured maximum.
+fn transaction_compute_limit(callback_compute_limit: u32) -> u32 {
+    callback_compute_limit
+        .saturating_add(FULFILL_COMPUTE_UNITS)
+        .min(MAX_TRANSACTION_COMPUTE_UNITS)
+}
+
+/// Read the registered multi-oracle set and threshold from the coordinator
+/// config.
+pub async fn read_oracle_set(rpc_client: &RpcClient, program_id: &Pubkey) -> Result<OracleSet> {
+    let (config_pda, _) = Pubkey::find_program_address(&[b"coordinator-config"], program_id);
+    let data = rpc_client
+        .get_account_data(&config_pda)
+        .await
+        .context("failed to fetch coordinator config")?;
+    let oracle_set = oracle_set(&data).context("invalid coordinator config")?;
+    anyhow::ensure!(oracle_set.threshold > 0, "no oracle set is registered");
+    Ok(oracle_set)
+}
 
-    send_with_retries(rpc_client, config, &instructions, event.request_id).await
+/// Parse the oracle set from raw `CoordinatorConfig` account data.
+fn oracle_set(config_data: &[u8]) -> Option<OracleSet> {
+    let count = *config_data.get(CONFIG_ORACLE_COUNT_OFFSET)? as usize;
+    let threshold = *config_data.get(CONFIG_ORACLE_COUNT_OFFSET + 1)? as usize;
+    let start = CONFIG_ORACLE_COUNT_OFFSET + 2;
+    let oracles = config_data
+        .get(start..start + 32 * count)?
+        .chunks_exact(32)
+        .map(|key| Pubkey::try_from(key).ok())
+        .collect::<Option<Vec<_>>>()?;
+    (threshold <= count).then_some(OracleSet { oracles, threshold })
+}
+
+/// Wait until the cluster reaches `slot`.
+async fn wait_for_slot(rpc_client: &RpcClient, slot: u64) -> Result<()> {
+    loop {
+        let current = rpc_client
+            .get_slot()
+            .await
+            .context("failed to fetch current slot")?;
+        if current >= slot {
+            return Ok(());
+        }
+        tokio::time::sleep(SLOT_DURATION.saturating_mul((slot - current).min(32) as u32)).await;
+    }
+}
+
+/// Compile `instructions` into a v0 transaction signed by the oracle authority,
+/// resolving accounts through `lookup_tables` where possible.
+fn build_transaction(
+    config: &AppConfig,
+    instructions: &[Instruction],
+    lookup_tables: &[AddressLookupTableAccount],
+    blockhash: Hash,
+) -> Result<VersionedTransaction> {
+    let message = v0::Message::try_compile(
+        &config.authority_keypair.pubkey(),
+        instructions,
+        lookup_tables,
+        blockhash,
+    )
+    .context("failed to compile v0 message")?;
+    VersionedTransaction::try_new(
+        VersionedMessage::V0(message),
+        &[config.authority_keypair.as_ref()],
+    )
+    .context("failed to sign transaction")
 }
 
 /// Send a transaction with exponential backoff on BlockhashNotFound.
@@ -217,6 +1143,7 @@ async fn send_with_retries(
     rpc_client: &RpcClient,
     config: &AppConfig,
     instructions: &[Instruction],
+    lookup_tables: &[AddressLookupTableAccount],
     request_id: u64,
 ) -> Result<String> {
     let mut retry_delay = Duration::from_millis(config.initial_retry_delay_ms);
@@ -227,12 +1154,7 @@ async fn send_with_retries(
             .await
             .context("failed to fetch latest blockhash")?;
 
-        let tx = Transaction::new_signed_with_payer(
-            instructions,
-            Some(&config.authority_keypair.pubkey()),
-            &[config.authority_keypair.as_ref()],
-            blockhash,
-        );
+        let tx = build_transaction(config, instructions, lookup_tables, blockhash)?;
 
         match rpc_client.send_and_confirm_transaction(&tx).await {
             Ok(sig) => return Ok(sig.to_string()),
@@ -296,6 +1218,47 @@ fn build_ed25519_instruction(
     }
 }
 
+/// Construct a native Ed25519 signature-verify instruction carrying one
+/// signature per batched request, each over its own message, in batch order.
+///
+/// Layout: header (2) + one offsets entry (14) per signature, then
+/// `public_key (32) || signature (64) || message` per signature.
+fn build_batch_ed25519_instruction(signatures: &[(Pubkey, Signature, &[u8])]) -> Instruction {
+    use solana_sdk::ed25519_program;
+
+    let mut offsets = Vec::with_capacity(14 * signatures.len());
+    let mut payload = Vec::new();
+    let data_start = 2 + 14 * signatures.len();
+    for (pubkey, signature, message) in signatures {
+        let public_key_offset = (data_start + payload.len()) as u16;
+        let signature_offset = public_key_offset + 32;
+        let message_data_offset = signature_offset + 64;
+        offsets.extend_from_slice(&signature_offset.to_le_bytes());
+        offsets.extend_from_slice(&u16::MAX.to_le_bytes());
+        offsets.extend_from_slice(&public_key_offset.to_le_bytes());
+        offsets.extend_from_slice(&u16::MAX.to_le_bytes());
+        offsets.extend_from_slice(&message_data_offset.to_le_bytes());
+        offsets.extend_from_slice(&(message.len() as u16).to_le_bytes());
+        offsets.extend_from_slice(&u16::MAX.to_le_bytes());
+
+        payload.extend_from_slice(&pubkey.to_bytes());
+        payload.extend_from_slice(signature.as_ref());
+        payload.extend_from_slice(message);
+    }
+
+    let mut data = Vec::with_capacity(data_start + payload.len());
+    data.push(signatures.len() as u8); // num_signatures
+    data.push(0u8); // padding
+    data.extend_from_slice(&offsets);
+    data.extend_from_slice(&payload);
+
+    Instruction {
+        program_id: ed25519_program::id(),
+        accounts: vec![],
+        data,
+    }
+}
+
 /// Build a `SetComputeUnitLimit` instruction.
 fn build_set_compute_unit_limit_instruction(units: u32) -> Instruction {
     let compute_budget_id: Pubkey = "ComputeBudget111111111111111111111111111111"
@@ -326,23 +1289,19 @@ fn build_set_compute_unit_price_instruction(micro_lamports: u64) -> Instruction
     }
 }
 
-/// Build the `fulfill_random_words` coordinator instruction.
+/// Build a fulfillment coordinator instruction from pre-encoded instruction data.
 fn build_fulfill_instruction(
     program_id: &Pubkey,
     authority: &Pubkey,
     event: &RandomWordsRequestedEvent,
-    randomness: &[u8; 32],
-    callback_remaining: &[AccountMeta],
+    data: Vec<u8>,
+    callback: &CallbackAccounts,
+    fee: &FeeAccounts,
 ) -> Instruction {
     let (config_pda, _) = Pubkey::find_program_address(&[b"coordinator-config"], program_id);
     let (request_pda, _) =
         Pubkey::find_program_address(&[b"vrf-request", &event.request_id.to_le_bytes()], program_id);
-
-    // Instruction data: discriminator + request_id + randomness
-    let mut data = Vec::with_capacity(8 + 8 + 32);
-    data.extend_from_slice(&fulfill_discriminator());
-    data.extend_from_slice(&event.request_id.to_le_bytes());
-    data.extend_from_slice(randomness);
+    let (fee_vault_pda, _) = Pubkey::find_program_address(&[b"fee-vault"], program_id);
 
     // Core accounts
     let mut accounts = vec![
@@ -352,10 +1311,95 @@ fn build_fulfill_instruction(
         AccountMeta::new(event.requester, false),                   // requester (rent refund)
         AccountMeta::new_readonly(event.consumer_program, false),   // consumer program
         AccountMeta::new_readonly(sysvar::instructions::ID, false), // instructions sysvar
+        callback_sidecar_meta(program_id, callback),                // callback accounts sidecar
+        subscription_meta(program_id, event.subscription_id),       // subscription (pending fee)
+        AccountMeta::new(fee_vault_pda, false),                     // fee vault
     ];
+    // Token fee accounts: approved mint, mint, both token accounts, token program
+    accounts.extend(fee.metas(program_id));
+    accounts.push(randomness_result_meta(program_id, event)); // wallet request result
+
 
     // Append consumer callback remaining_accounts
-    accounts.extend_from_slice(callback_remaining);
+    accounts.extend_from_slice(&callback.accounts);
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Build the `fulfill_random_words_batch` instruction. Each request adds the
+/// accounts of its `fulfill_random_words` instruction, callback accounts
+/// last, in batch order.
+fn build_fulfill_batch_instruction(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    batch: &[BatchEntry],
+) -> Instruction {
+    let (config_pda, _) = Pubkey::find_program_address(&[b"coordinator-config"], program_id);
+
+    let mut accounts = vec![
+        AccountMeta::new(*authority, true),                         // authority (signer, payer)
+        AccountMeta::new_readonly(config_pda, false),               // coordinator config PDA
+        AccountMeta::new_readonly(sysvar::instructions::ID, false), // instructions sysvar
+    ];
+
+    // Borsh Vec<BatchFulfillment>: length as u32 LE, then request_id || randomness each
+    let mut data = Vec::with_capacity(8 + 4 + 40 * batch.len());
+    data.extend_from_slice(&instruction_discriminator("fulfill_random_words_batch"));
+    data.extend_from_slice(&(batch.len() as u32).to_le_bytes());
+    for entry in batch {
+        accounts.extend(
+            build_fulfill_instruction(
+                program_id,
+                authority,
+                entry.event,
+                Vec::new(),
+                &entry.callback,
+                &entry.fee,
+            )
+            .accounts,
+        );
+        data.extend_from_slice(&entry.event.request_id.to_le_bytes());
+        data.extend_from_slice(&entry.randomness);
+    }
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Build the `retry_callback` instruction for a request stored with
+/// `STATUS_CALLBACK_FAILED`.
+fn build_retry_callback_instruction(
+    program_id: &Pubkey,
+    caller: &Pubkey,
+    event: &RandomWordsRequestedEvent,
+    record_failure: Option<&str>,
+    callback: &CallbackAccounts,
+) -> Instruction {
+    let (config_pda, _) = Pubkey::find_program_address(&[b"coordinator-config"], program_id);
+    let (request_pda, _) =
+        Pubkey::find_program_address(&[b"vrf-request", &event.request_id.to_le_bytes()], program_id);
+
+    let mut accounts = vec![
+        AccountMeta::new(*caller, true),                          // caller (signer, payer)
+        AccountMeta::new_readonly(config_pda, false),             // coordinator config PDA
+        AccountMeta::new(request_pda, false),                     // randomness request PDA
+        AccountMeta::new(event.requester, false),                 // requester (rent refund)
+        AccountMeta::new_readonly(event.consumer_program, false), // consumer program
+        callback_sidecar_meta(program_id, callback),              // callback accounts sidecar
+    ];
+    accounts.extend_from_slice(&callback.accounts);
+
+    let mut data = Vec::with_capacity(8 + 8 + 1);
+    data.extend_from_slice(&instruction_discriminator("retry_callback"));
+    data.extend_from_slice(&event.request_id.to_le_bytes());
+    encode_callback_failure(&mut data, record_failure);
 
     Instruction {
         program_id: *program_id,
@@ -363,3 +1407,325 @@ fn build_fulfill_instruction(
         data,
     }
 }
+
+/// The optional `callback_accounts` sidecar account. Anchor encodes an absent
+/// optional account as the program ID.
+fn callback_sidecar_meta(program_id: &Pubkey, callback: &CallbackAccounts) -> AccountMeta {
+    AccountMeta::new_readonly(callback.sidecar.unwrap_or(*program_id), false)
+}
+
+/// The optional subscription account: the subscription PDA, or the program
+/// ID for direct-funded requests, whose fee is held in the request PDA.
+fn subscription_meta(program_id: &Pubkey, subscription_id: u64) -> AccountMeta {
+    if subscription_id == DIRECT_SUBSCRIPTION_ID {
+        return AccountMeta::new_readonly(*program_id, false);
+    }
+    let (subscription_pda, _) =
+        Pubkey::find_program_address(&[b"subscription", &subscription_id.to_le_bytes()], program_id);
+    AccountMeta::new(subscription_pda, false)
+}
+
+/// The optional `randomness_result` account: the result PDA of a wallet
+/// request, which has no consumer program, or the program ID otherwise.
+fn randomness_result_meta(program_id: &Pubkey, event: &RandomWordsRequestedEvent) -> AccountMeta {
+    if event.consumer_program != Pubkey::default() {
+        return AccountMeta::new_readonly(*program_id, false);
+    }
+    let (result_pda, _) = Pubkey::find_program_address(
+        &[b"randomness-result", &event.request_id.to_le_bytes()],
+        program_id,
+    );
+    AccountMeta::new(result_pda, false)
+}
+
+/// Build the `close_callback_accounts` instruction for a request's sidecar.
+fn build_close_callback_accounts_instruction(
+    program_id: &Pubkey,
+    event: &RandomWordsRequestedEvent,
+    sidecar: &Pubkey,
+) -> Instruction {
+    let (request_pda, _) =
+        Pubkey::find_program_address(&[b"vrf-request", &event.request_id.to_le_bytes()], program_id);
+
+    let mut data = Vec::with_capacity(8 + 8);
+    data.extend_from_slice(&instruction_discriminator("close_callback_accounts"));
+    data.extend_from_slice(&event.request_id.to_le_bytes());
+
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*sidecar, false),                 // callback accounts sidecar
+            AccountMeta::new_readonly(request_pda, false),     // closed request PDA
+            AccountMeta::new(event.requester, false),          // requester (rent refund)
+        ],
+        data,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request_event(request_id: u64) -> RandomWordsRequestedEvent {
+        RandomWordsRequestedEvent {
+            request_id,
+            subscription_id: 1,
+            consumer_program: Pubkey::new_unique(),
+            requester: Pubkey::new_unique(),
+            num_words: 1,
+            seed: [0u8; 32],
+            request_slot: 0,
+            callback_compute_limit: 0,
+            min_confirmation_slots: 0,
+        }
+    }
+
+    fn confirming_event(
+        request_id: u64,
+        request_slot: u64,
+        min_confirmation_slots: u64,
+    ) -> RandomWordsRequestedEvent {
+        RandomWordsRequestedEvent {
+            request_slot,
+            min_confirmation_slots,
+            ..request_event(request_id)
+        }
+    }
+
+    fn request_ids(events: &[RandomWordsRequestedEvent]) -> Vec<u64> {
+        events.iter().map(|event| event.request_id).collect()
+    }
+
+    #[test]
+    fn confirmation_queue_releases_requests_once_fulfillable() {
+        let mut queue = ConfirmationQueue::default();
+        queue.push(confirming_event(1, 100, 50));
+        queue.push(confirming_event(2, 100, 0));
+        queue.push(confirming_event(3, 100, 10));
+
+        // Requests without confirmations never wait
+        assert_eq!(request_ids(&queue.pop_fulfillable(0, 8)), vec![2]);
+        assert_eq!(queue.next_fulfillable_slot(), Some(110));
+
+        assert!(queue.pop_fulfillable(109, 8).is_empty());
+        assert_eq!(request_ids(&queue.pop_fulfillable(110, 8)), vec![3]);
+        assert_eq!(request_ids(&queue.pop_fulfillable(200, 8)), vec![1]);
+        assert_eq!(queue.next_fulfillable_slot(), None);
+    }
+
+    #[test]
+    fn confirmation_queue_pops_at_most_max_in_slot_order() {
+        let mut queue = ConfirmationQueue::default();
+        for request_id in 0..3 {
+            queue.push(confirming_event(request_id, 100, 5));
+        }
+        queue.push(confirming_event(3, 90, 5));
+
+        assert_eq!(request_ids(&queue.pop_fulfillable(105, 2)), vec![3, 0]);
+        assert_eq!(request_ids(&queue.pop_fulfillable(105, 2)), vec![1, 2]);
+        assert!(queue.pop_fulfillable(105, 2).is_empty());
+    }
+
+    #[test]
+    fn confirmation_queue_batches_only_fulfillable_requests() {
+        let mut queue = ConfirmationQueue::default();
+        queue.push(confirming_event(0, 100, 5));
+        queue.push(confirming_event(1, 100, 500));
+        queue.push(confirming_event(2, 101, 5));
+
+        // The long wait stays queued instead of holding up the batch
+        assert_eq!(request_ids(&queue.pop_fulfillable(110, 4)), vec![0, 2]);
+        assert_eq!(queue.next_fulfillable_slot(), Some(600));
+    }
+
+    #[test]
+    fn fulfill_instruction_places_sidecar_before_callback_accounts() {
+        let program_id = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let event = request_event(7);
+        let callback_account = AccountMeta::new(Pubkey::new_unique(), false);
+
+        // Without a sidecar the optional account slot holds the program ID.
+        let mut callback = CallbackAccounts {
+            accounts: vec![callback_account.clone()],
+            ..Default::default()
+        };
+        let fee = FeeAccounts::default();
+        let ix = build_fulfill_instruction(&program_id, &authority, &event, vec![], &callback, &fee);
+        assert_eq!(ix.accounts.len(), 16);
+        assert_eq!(ix.accounts[6].pubkey, program_id);
+        let (fee_vault, _) = Pubkey::find_program_address(&[b"fee-vault"], &program_id);
+        assert_eq!(ix.accounts[8], AccountMeta::new(fee_vault, false));
+        assert_eq!(ix.accounts[14], AccountMeta::new_readonly(program_id, false));
+        assert_eq!(ix.accounts[15], callback_account);
+
+        // Direct-funded requests have no subscription account.
+        let direct = RandomWordsRequestedEvent {
+            subscription_id: DIRECT_SUBSCRIPTION_ID,
+            ..request_event(8)
+        };
+        let ix = build_fulfill_instruction(&program_id, &authority, &direct, vec![], &callback, &fee);
+        assert_eq!(ix.accounts[7], AccountMeta::new_readonly(program_id, false));
+
+        // Wallet requests have no consumer program and receive a result account.
+        let wallet = RandomWordsRequestedEvent {
+            subscription_id: DIRECT_SUBSCRIPTION_ID,
+            consumer_program: Pubkey::default(),
+            ..request_event(9)
+        };
+        let ix = build_fulfill_instruction(&program_id, &authority, &wallet, vec![], &callback, &fee);
+        let (result, _) =
+            Pubkey::find_program_address(&[b"randomness-result", &9u64.to_le_bytes()], &program_id);
+        assert_eq!(ix.accounts[14], AccountMeta::new(result, false));
+
+        let sidecar = Pubkey::new_unique();
+        callback.sidecar = Some(sidecar);
+        let ix = build_retry_callback_instruction(&program_id, &authority, &event, None, &callback);
+        assert_eq!(ix.accounts[5].pubkey, sidecar);
+        assert_eq!(ix.accounts[6], callback_account);
+    }
+
+    #[test]
+    fn batch_instruction_appends_each_request_after_the_shared_accounts() {
+        let program_id = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let events = [request_event(1), request_event(2)];
+        let callback_account = AccountMeta::new(Pubkey::new_unique(), false);
+        let entry = |event, accounts| BatchEntry {
+            event,
+            randomness: [0xAB; 32],
+            message: Vec::new(),
+            signature: Signature::default(),
+            callback: CallbackAccounts {
+                accounts,
+                ..Default::default()
+            },
+            fee: FeeAccounts::default(),
+        };
+        let batch = [
+            entry(&events[0], vec![callback_account.clone()]),
+            entry(&events[1], Vec::new()),
+        ];
+
+        let ix = build_fulfill_batch_instruction(&program_id, &authority, &batch);
+        // Shared accounts, then 15 fulfillment accounts per request plus its callback accounts.
+        assert_eq!(ix.accounts.len(), 3 + 16 + 15);
+        assert_eq!(ix.accounts[3], AccountMeta::new(authority, true));
+        assert_eq!(ix.accounts[18], callback_account);
+        assert_eq!(ix.accounts[19], AccountMeta::new(authority, true));
+        let (second_request, _) =
+            Pubkey::find_program_address(&[b"vrf-request", &2u64.to_le_bytes()], &program_id);
+        assert_eq!(ix.accounts[21].pubkey, second_request);
+
+        assert_eq!(ix.data.len(), 8 + 4 + 2 * 40);
+        assert_eq!(&ix.data[8..12], &2u32.to_le_bytes());
+        assert_eq!(&ix.data[52..60], &2u64.to_le_bytes());
+        assert_eq!(&ix.data[60..92], &[0xAB; 32]);
+    }
+
+    #[test]
+    fn batch_ed25519_instruction_points_each_signature_at_its_message() {
+        let signatures = [
+            (Pubkey::new_unique(), Signature::from([1u8; 64]), &b"first"[..]),
+            (Pubkey::new_unique(), Signature::from([2u8; 64]), &b"second message"[..]),
+        ];
+        let ix = build_batch_ed25519_instruction(&signatures);
+        assert_eq!(ix.data[0], 2);
+
+        for (i, (pubkey, signature, message)) in signatures.iter().enumerate() {
+            let offsets = &ix.data[2 + 14 * i..16 + 14 * i];
+            let read = |at: usize| u16::from_le_bytes([offsets[at], offsets[at + 1]]) as usize;
+            assert_eq!(&ix.data[read(0)..read(0) + 64], signature.as_ref());
+            assert_eq!(&ix.data[read(4)..read(4) + 32], pubkey.as_ref());
+            assert_eq!(&ix.data[read(8)..read(8) + read(10)], *message);
+        }
+    }
+
+    #[test]
+    fn batch_compute_limit_charges_overhead_per_request_up_to_the_budget() {
+        assert_eq!(
+            batch_compute_limit([100_000, 200_000]),
+            Some(FULFILL_COMPUTE_UNITS + 300_000 + 2 * BATCH_ENTRY_COMPUTE_UNITS)
+        );
+        assert_eq!(batch_compute_limit([1_000_000, 200_000]), None);
+    }
+
+    #[test]
+    fn compute_limit_adds_fulfillment_overhead_to_callback_limit() {
+        assert_eq!(transaction_compute_limit(0), FULFILL_COMPUTE_UNITS);
+        assert_eq!(transaction_compute_limit(200_000), 400_000);
+        assert_eq!(transaction_compute_limit(1_300_000), MAX_TRANSACTION_COMPUTE_UNITS);
+    }
+
+    #[test]
+    fn detects_consumer_failure_in_logs() {
+        let consumer = Pubkey::new_unique();
+        let coordinator = Pubkey::new_unique();
+        let logs = vec![
+            format!("Program {coordinator} invoke [1]"),
+            format!("Program {consumer} invoke [2]"),
+            format!("Program {consumer} failed: custom program error: 0x1770"),
+            format!("Program {coordinator} failed: custom program error: 0x1770"),
+        ];
+        assert_eq!(
+            consumer_failure(&logs, &consumer).as_deref(),
+            Some("custom program error: 0x1770")
+        );
+        assert_eq!(consumer_failure(&logs[..2], &consumer), None);
+
+        let coordinator_only = vec![format!(
+            "Program {coordinator} failed: custom program error: 0x1770"
+        )];
+        assert_eq!(consumer_failure(&coordinator_only, &consumer), None);
+    }
+
+    #[test]
+    fn encodes_callback_failure_as_a_bounded_borsh_option() {
+        let mut data = Vec::new();
+        encode_callback_failure(&mut data, None);
+        assert_eq!(data, [0]);
+
+        let mut data = Vec::new();
+        encode_callback_failure(&mut data, Some("reverted"));
+        assert_eq!(data, [&[1, 8, 0, 0, 0][..], b"reverted"].concat());
+
+        // Cut on a character boundary below the on-chain limit
+        let long = format!("a{}", "é".repeat(MAX_CALLBACK_FAILURE_LEN));
+        let mut data = Vec::new();
+        encode_callback_failure(&mut data, Some(&long));
+        let len = u32::from_le_bytes(data[1..5].try_into().unwrap()) as usize;
+        assert_eq!(len, MAX_CALLBACK_FAILURE_LEN - 1);
+        assert!(std::str::from_utf8(&data[5..]).is_ok());
+    }
+
+    #[test]
+    fn reads_oracle_set_from_config_data() {
+        let oracles = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut data = vec![0u8; CONFIG_ORACLE_COUNT_OFFSET + 2 + 32 * 8];
+        data[CONFIG_ORACLE_COUNT_OFFSET] = 3;
+        data[CONFIG_ORACLE_COUNT_OFFSET + 1] = 2;
+        for (i, oracle) in oracles.iter().enumerate() {
+            let start = CONFIG_ORACLE_COUNT_OFFSET + 2 + 32 * i;
+            data[start..start + 32].copy_from_slice(oracle.as_ref());
+        }
+
+        assert_eq!(
+            oracle_set(&data),
+            Some(OracleSet {
+                oracles: oracles.to_vec(),
+                threshold: 2,
+            })
+        );
+        assert_eq!(oracle_set(&data[..CONFIG_ORACLE_COUNT_OFFSET + 40]), None);
+    }
+
+    #[test]
+    fn reads_paused_flag_from_config_data() {
+        let mut data = vec![0u8; CONFIG_PAUSED_OFFSET + 1];
+        assert!(!is_paused(&data));
+        data[CONFIG_PAUSED_OFFSET] = 1;
+        assert!(is_paused(&data));
+        // A config from before the pause switch is shorter than the flag offset.
+        assert!(!is_paused(&data[..CONFIG_PAUSED_OFFSET]));
+    }
+}
@@ -37,6 +37,8 @@ pub struct RandomWordsRequestedEvent {
     pub seed: [u8; 32],
     pub request_slot: u64,
     pub callback_compute_limit: u32,
+    /// Slots after `request_slot` before the coordinator accepts fulfillment.
+    pub min_confirmation_slots: u64,
 }
 
 /// Compute the Anchor event discriminator: `sha256("event:<Name>")[..8]`.
@@ -66,24 +68,50 @@ const WS_RECONNECT_MIN: Duration = Duration::from_secs(1);
 /// Maximum WebSocket reconnect delay.
 const WS_RECONNECT_MAX: Duration = Duration::from_secs(60);
 
-/// Minimum expected account data length for a `RandomnessRequest`.
+/// Offset of `min_confirmation_slots` in the RandomnessRequest account body
+/// (after the discriminator), past the callback, fee and `fee_mint` fields.
+const MIN_CONFIRMATION_SLOTS_BODY_OFFSET: usize = 343;
+
+/// Minimum account data length of a `RandomnessRequest` in the current layout.
 ///
-/// Layout: discriminator (8) + request_id (8) + subscription_id (8) +
-/// consumer_program (32) + requester (32) + num_words (4) + seed (32) +
-/// request_slot (8) + callback_compute_limit (4) + status (1) +
-/// randomness (32) + fulfilled_slot (8) + bump (1) +
-/// callback_account_count (1) + callback_account_keys (128) +
-/// callback_writable_bitmap (1) = 308 bytes.
+/// Layout: discriminator (8) + fixed fields up to and including
+/// min_confirmation_slots (351) + callback_version (1) + user_data length
+/// prefix (4), followed by the variable-length user data and callback prefix.
 ///
-/// For backward compatibility with older requests that lack callback fields,
-/// we only require the first 137 bytes for parsing core fields.
-const MIN_ACCOUNT_DATA_LEN: usize = 137;
+/// Requests written before the last program upgrade are shorter. The program
+/// cannot deserialize them until the admin grows them with `migrate_request`,
+/// so they are skipped here until then (see "Upgrading an Existing
+/// Deployment" in `docs/deployment.md`).
+const MIN_ACCOUNT_DATA_LEN: usize = 8 + MIN_CONFIRMATION_SLOTS_BODY_OFFSET + 8 + 1 + 4;
 
 /// Offset of the status byte in the RandomnessRequest account data.
 /// discriminator(8) + request_id(8) + subscription_id(8) + consumer_program(32) +
 /// requester(32) + num_words(4) + seed(32) + request_slot(8) + callback_compute_limit(4) = 136
 const STATUS_OFFSET: usize = 136;
 
+/// Request IDs cancelled on-chain, shared with the fulfiller so it can drop
+/// queued work for them.
+#[derive(Default)]
+pub struct CancelledRequests {
+    ids: Mutex<HashSet<u64>>,
+}
+
+impl CancelledRequests {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a cancellation. Returns `false` if it was already known.
+    pub fn insert(&self, request_id: u64) -> bool {
+        self.ids.lock().unwrap().insert(request_id)
+    }
+
+    /// Whether the request has been cancelled.
+    pub fn contains(&self, request_id: u64) -> bool {
+        self.ids.lock().unwrap().contains(&request_id)
+    }
+}
+
 /// Tracks request IDs that have already been dispatched.
 struct Deduplicator {
     seen: Mutex<HashSet<u64>>,
@@ -147,7 +175,7 @@ pub async fn catch_up_pending_requests(
                     warn!(
                         account = %pubkey,
                         len = data.len(),
-                        "Account data too short, skipping"
+                        "Request account predates the current layout and needs migrate_request, skipping"
                     );
                     continue;
                 }
@@ -212,9 +240,11 @@ pub async fn catch_up_pending_requests(
 pub async fn listen_for_events(
     config: AppConfig,
     tx: mpsc::Sender<RandomWordsRequestedEvent>,
+    cancelled: Arc<CancelledRequests>,
     metrics: Arc<Metrics>,
 ) {
     let event_disc = event_discriminator("RandomWordsRequested");
+    let cancelled_disc = event_discriminator("RandomWordsCancelled");
     let dedup = Deduplicator::new();
     let mut reconnect_delay = WS_RECONNECT_MIN;
 
@@ -239,7 +269,9 @@ pub async fn listen_for_events(
                             process_log_lines(
                                 &log_result.value.logs,
                                 &event_disc,
+                                &cancelled_disc,
                                 &tx,
+                                &cancelled,
                                 &dedup,
                                 &metrics,
                             )
@@ -263,11 +295,14 @@ pub async fn listen_for_events(
     }
 }
 
-/// Scan transaction log lines for `RandomWordsRequested` events.
+/// Scan transaction log lines for `RandomWordsRequested` and
+/// `RandomWordsCancelled` events.
 async fn process_log_lines(
     logs: &[String],
     event_disc: &[u8; 8],
+    cancelled_disc: &[u8; 8],
     tx: &mpsc::Sender<RandomWordsRequestedEvent>,
+    cancelled: &CancelledRequests,
     dedup: &Deduplicator,
     metrics: &Arc<Metrics>,
 ) {
@@ -291,6 +326,23 @@ async fn process_log_lines(
         let disc = &decoded[..8];
         let payload = &decoded[8..];
 
+        if disc == cancelled_disc {
+            // Layout: request_id(8) + subscription_id(8) + cancelled_by(32) + refunded_fee(8)
+            let Some(request_id) = payload
+                .get(..8)
+                .and_then(|b| b.try_into().ok())
+                .map(u64::from_le_bytes)
+            else {
+                warn!("Failed to parse RandomWordsCancelled event payload");
+                continue;
+            };
+            if cancelled.insert(request_id) {
+                metrics.record_cancellation();
+                info!(request_id, "Received RandomWordsCancelled event");
+            }
+            continue;
+        }
+
         if disc == event_disc {
             let Some(event) = parse_random_words_requested_event(payload) else {
                 warn!("Failed to parse RandomWordsRequested event payload");
@@ -326,7 +378,8 @@ async fn process_log_lines(
 /// Parse a `RandomWordsRequested` event from its body (after discriminator).
 ///
 /// Layout: request_id(8) + subscription_id(8) + consumer_program(32) +
-/// requester(32) + num_words(4) + seed(32) + request_slot(8) + callback_compute_limit(4) = 128 bytes.
+/// requester(32) + num_words(4) + seed(32) + request_slot(8) + callback_compute_limit(4) = 128 bytes,
+/// followed by min_confirmation_slots(8), absent from older program versions.
 fn parse_random_words_requested_event(data: &[u8]) -> Option<RandomWordsRequestedEvent> {
     if data.len() < 128 {
         return None;
@@ -341,6 +394,7 @@ fn parse_random_words_requested_event(data: &[u8]) -> Option<RandomWordsRequeste
     seed.copy_from_slice(&data[84..116]);
     let request_slot = u64::from_le_bytes(data[116..124].try_into().ok()?);
     let callback_compute_limit = u32::from_le_bytes(data[124..128].try_into().ok()?);
+    let min_confirmation_slots = read_u64(data, 128).unwrap_or(0);
 
     Some(RandomWordsRequestedEvent {
         request_id,
@@ -351,14 +405,47 @@ fn parse_random_words_requested_event(data: &[u8]) -> Option<RandomWordsRequeste
         seed,
         request_slot,
         callback_compute_limit,
+        min_confirmation_slots,
     })
 }
 
+/// Fetch a request PDA and return its parsed fields if it is still pending.
+///
+/// Used by the partial-proof endpoint so peer oracles prove over the
+/// on-chain request rather than data supplied by the caller.
+pub async fn fetch_pending_request(
+    client: &solana_client::nonblocking::rpc_client::RpcClient,
+    program_id: &Pubkey,
+    request_id: u64,
+) -> anyhow::Result<RandomWordsRequestedEvent> {
+    use anyhow::Context;
+
+    let (request_pda, _) =
+        Pubkey::find_program_address(&[b"vrf-request", &request_id.to_le_bytes()], program_id);
+    let account = client
+        .get_account_with_commitment(&request_pda, CommitmentConfig::confirmed())
+        .await
+        .context("failed to fetch request PDA")?
+        .value
+        .context("request PDA not found")?;
+
+    let data = &account.data;
+    anyhow::ensure!(
+        account.owner == *program_id
+            && data.len() >= MIN_ACCOUNT_DATA_LEN
+            && data[..8] == account_discriminator("RandomnessRequest"),
+        "account is not a randomness request"
+    );
+    anyhow::ensure!(data[STATUS_OFFSET] == 0, "request is not pending");
+    parse_request_account_data(&data[8..]).context("failed to parse request account data")
+}
+
 /// Parse a RandomnessRequest account body (after discriminator).
 ///
 /// Layout: request_id(8) + subscription_id(8) + consumer_program(32) +
 /// requester(32) + num_words(4) + seed(32) + request_slot(8) + callback_compute_limit(4) +
-/// status(1) + randomness(32) + fulfilled_slot(8) + bump(1)
+/// status(1) + randomness(32) + fulfilled_slot(8) + bump(1) + ... +
+/// min_confirmation_slots(8) at `MIN_CONFIRMATION_SLOTS_BODY_OFFSET`
 fn parse_request_account_data(body: &[u8]) -> Option<RandomWordsRequestedEvent> {
     if body.len() < MIN_ACCOUNT_DATA_LEN - 8 {
         return None;
@@ -373,6 +460,7 @@ fn parse_request_account_data(body: &[u8]) -> Option<RandomWordsRequestedEvent>
     seed.copy_from_slice(&body[84..116]);
     let request_slot = u64::from_le_bytes(body[116..124].try_into().ok()?);
     let callback_compute_limit = u32::from_le_bytes(body[124..128].try_into().ok()?);
+    let min_confirmation_slots = read_u64(body, MIN_CONFIRMATION_SLOTS_BODY_OFFSET)?;
 
     Some(RandomWordsRequestedEvent {
         request_id,
@@ -383,5 +471,63 @@ fn parse_request_account_data(body: &[u8]) -> Option<RandomWordsRequestedEvent>
         seed,
         request_slot,
         callback_compute_limit,
+        min_confirmation_slots,
     })
 }
+
+/// Read a little-endian u64 at `offset`, or `None` if `data` is too short.
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(data.get(offset..offset + 8)?.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event_body(min_confirmation_slots: Option<u64>) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&7u64.to_le_bytes());
+        data.extend_from_slice(&1u64.to_le_bytes());
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&[9u8; 32]);
+        data.extend_from_slice(&100u64.to_le_bytes());
+        data.extend_from_slice(&200_000u32.to_le_bytes());
+        if let Some(slots) = min_confirmation_slots {
+            data.extend_from_slice(&slots.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn parses_min_confirmation_slots_from_event() {
+        let event = parse_random_words_requested_event(&event_body(Some(32))).unwrap();
+        assert_eq!(event.request_slot, 100);
+        assert_eq!(event.callback_compute_limit, 200_000);
+        assert_eq!(event.min_confirmation_slots, 32);
+
+        // Events from before the field existed need no confirmations.
+        let event = parse_random_words_requested_event(&event_body(None)).unwrap();
+        assert_eq!(event.min_confirmation_slots, 0);
+    }
+
+    #[test]
+    fn parses_min_confirmation_slots_from_request_account() {
+        let mut body = event_body(None);
+        body.resize(MIN_CONFIRMATION_SLOTS_BODY_OFFSET, 0);
+        body.extend_from_slice(&12u64.to_le_bytes());
+        body.extend_from_slice(&[0u8; 5]);
+        let event = parse_request_account_data(&body).unwrap();
+        assert_eq!(event.min_confirmation_slots, 12);
+    }
+
+    #[test]
+    fn rejects_request_accounts_from_before_the_upgrade() {
+        // Old-layout requests end before min_confirmation_slots; the program
+        // can no longer deserialize them.
+        let mut body = event_body(None);
+        body.resize(MIN_CONFIRMATION_SLOTS_BODY_OFFSET, 0);
+        assert!(parse_request_account_data(&body).is_none());
+    }
+}
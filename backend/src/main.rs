@@ -7,9 +7,12 @@
 //!
 //! - **Listener** — WebSocket subscription to on-chain events + startup catch-up scan.
 //! - **Fulfiller** — Consumes request events and submits fulfillment transactions.
-//! - **HTTP server** — Liveness (`/health`), readiness (`/status`), and `/metrics` probes.
+//! - **HTTP server** — Liveness (`/health`), readiness (`/status`), and `/metrics` probes,
+//!   plus `/partial-proof/{request_id}` for registered peer oracles under the multi-oracle scheme.
 
 use actix_web::{web, App, HttpResponse, HttpServer};
+use serde::Deserialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::signature::Signer;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
@@ -19,12 +22,15 @@ use tracing_subscriber::{fmt, EnvFilter};
 
 mod config;
 mod consumer_accounts;
+mod ecvrf;
+mod fee_accounts;
 mod fulfiller;
 mod listener;
 mod metrics;
+mod peers;
 mod vrf;
 
-use config::AppConfig;
+use config::{AppConfig, ProofScheme};
 use metrics::Metrics;
 
 /// Shared application state accessible from HTTP handlers.
@@ -33,6 +39,10 @@ struct AppState {
     pending_count: Arc<AtomicU64>,
     /// Aggregated metrics.
     metrics: Arc<Metrics>,
+    /// Backend configuration (program ID and oracle keypair).
+    config: AppConfig,
+    /// RPC client used to read request PDAs for partial proofs.
+    rpc_client: Arc<RpcClient>,
 }
 
 /// Liveness probe — returns 200 if the process is running.
@@ -62,6 +72,79 @@ async fn metrics_handler(data: web::Data<AppState>) -> HttpResponse {
     HttpResponse::Ok().json(json)
 }
 
+/// Caller identification of a partial-proof request.
+#[derive(Deserialize)]
+struct PartialProofQuery {
+    /// Base58 public key of the requesting oracle.
+    oracle: String,
+    /// Base58 Ed25519 signature by `oracle` over `peers::proof_request_message`.
+    signature: String,
+}
+
+/// Partial-proof endpoint — returns this oracle's ECVRF proof for a pending
+/// request so the submitting oracle can include this oracle's output.
+///
+/// Only registered oracles are answered, and only once the request is past
+/// its confirmation slot, so the output cannot be learned before anyone may
+/// fulfill it. The VRF input is rebuilt from the on-chain request PDA, never
+/// from caller input.
+async fn partial_proof(
+    data: web::Data<AppState>,
+    path: web::Path<u64>,
+    query: web::Query<PartialProofQuery>,
+) -> HttpResponse {
+    if data.config.proof_scheme != ProofScheme::MultiOracle {
+        return HttpResponse::NotFound().finish();
+    }
+    let request_id = path.into_inner();
+    let program_id = &data.config.program_id;
+
+    let oracle_set = match fulfiller::read_oracle_set(&data.rpc_client, program_id).await {
+        Ok(oracle_set) => oracle_set,
+        Err(e) => {
+            return HttpResponse::ServiceUnavailable()
+                .json(serde_json::json!({"error": format!("{e:#}")}))
+        }
+    };
+    if let Err(e) = peers::authorize_proof_request(
+        &oracle_set,
+        program_id,
+        request_id,
+        &query.oracle,
+        &query.signature,
+    ) {
+        return HttpResponse::Unauthorized().json(serde_json::json!({"error": e.to_string()}));
+    }
+
+    let request =
+        match listener::fetch_pending_request(&data.rpc_client, program_id, request_id).await {
+            Ok(request) => request,
+            Err(e) => {
+                return HttpResponse::NotFound().json(serde_json::json!({"error": e.to_string()}))
+            }
+        };
+    let fulfillable_slot = request
+        .request_slot
+        .saturating_add(request.min_confirmation_slots);
+    match data.rpc_client.get_slot().await {
+        Ok(slot) if slot >= fulfillable_slot => {}
+        Ok(_) => {
+            return HttpResponse::Forbidden()
+                .json(serde_json::json!({"error": "request is not fulfillable yet"}))
+        }
+        Err(e) => {
+            return HttpResponse::ServiceUnavailable()
+                .json(serde_json::json!({"error": e.to_string()}))
+        }
+    }
+
+    let alpha = ecvrf::vrf_input(&request.seed, request.request_slot, request.request_id);
+    HttpResponse::Ok().json(peers::PartialProof::prove(
+        data.config.authority_keypair.as_ref(),
+        &alpha,
+    ))
+}
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     dotenvy::dotenv().ok();
@@ -92,6 +175,7 @@ async fn main() -> std::io::Result<()> {
 
     let pending_count = Arc::new(AtomicU64::new(0));
     let metrics = Arc::new(Metrics::new());
+    let cancelled = Arc::new(listener::CancelledRequests::new());
     let (tx, rx) = mpsc::channel(256);
 
     // Scan for any requests that arrived while the backend was offline.
@@ -100,9 +184,16 @@ async fn main() -> std::io::Result<()> {
     // Background: stream on-chain events and forward to the fulfiller.
     let listener_config = config.clone();
     let listener_tx = tx.clone();
+    let listener_cancelled = cancelled.clone();
     let listener_metrics = metrics.clone();
     let listener_handle = tokio::spawn(async move {
-        listener::listen_for_events(listener_config, listener_tx, listener_metrics).await;
+        listener::listen_for_events(
+            listener_config,
+            listener_tx,
+            listener_cancelled,
+            listener_metrics,
+        )
+        .await;
     });
 
     // Background: consume events and submit fulfillment transactions.
@@ -113,6 +204,7 @@ async fn main() -> std::io::Result<()> {
         fulfiller::run_fulfiller(
             fulfiller_config,
             rx,
+            cancelled,
             fulfiller_pending,
             fulfiller_metrics,
         )
@@ -122,6 +214,8 @@ async fn main() -> std::io::Result<()> {
     let state = web::Data::new(AppState {
         pending_count: pending_count.clone(),
         metrics: metrics.clone(),
+        config: config.clone(),
+        rpc_client: Arc::new(RpcClient::new(config.rpc_url.clone())),
     });
 
     let bind_addr = format!("0.0.0.0:{}", config.http_port);
@@ -133,6 +227,10 @@ async fn main() -> std::io::Result<()> {
             .route("/health", web::get().to(health))
             .route("/status", web::get().to(status))
             .route("/metrics", web::get().to(metrics_handler))
+            .route(
+                "/partial-proof/{request_id}",
+                web::get().to(partial_proof),
+            )
     })
     .bind(&bind_addr)?
     .run();
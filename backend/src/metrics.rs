@@ -10,6 +10,10 @@ pub struct Metrics {
     pub requests_fulfilled: AtomicU64,
     /// Total number of fulfillment attempts that failed permanently.
     pub requests_failed: AtomicU64,
+    /// Total number of requests cancelled on-chain after expiring.
+    pub requests_cancelled: AtomicU64,
+    /// Total number of fulfillments stored because the consumer callback reverted.
+    pub callbacks_failed: AtomicU64,
     /// Sum of fulfillment latencies in milliseconds.
     pub fulfillment_latency_sum_ms: AtomicU64,
     /// Number of fulfilled requests contributing to latency sum.
@@ -22,6 +26,8 @@ impl Metrics {
             requests_received: AtomicU64::new(0),
             requests_fulfilled: AtomicU64::new(0),
             requests_failed: AtomicU64::new(0),
+            requests_cancelled: AtomicU64::new(0),
+            callbacks_failed: AtomicU64::new(0),
             fulfillment_latency_sum_ms: AtomicU64::new(0),
             fulfillment_count: AtomicU64::new(0),
         }
@@ -38,6 +44,14 @@ impl Metrics {
         self.requests_failed.fetch_add(1, Ordering::Relaxed);
     }
 
+    pub fn record_cancellation(&self) {
+        self.requests_cancelled.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_callback_failure(&self) {
+        self.callbacks_failed.fetch_add(1, Ordering::Relaxed);
+    }
+
     pub fn record_request(&self) {
         self.requests_received.fetch_add(1, Ordering::Relaxed);
     }
@@ -55,6 +69,8 @@ impl Metrics {
             "requests_received": self.requests_received.load(Ordering::Relaxed),
             "requests_fulfilled": self.requests_fulfilled.load(Ordering::Relaxed),
             "requests_failed": self.requests_failed.load(Ordering::Relaxed),
+            "requests_cancelled": self.requests_cancelled.load(Ordering::Relaxed),
+            "callbacks_failed": self.callbacks_failed.load(Ordering::Relaxed),
             "avg_fulfillment_latency_ms": self.avg_latency_ms(),
             "total_fulfillment_latency_ms": self.fulfillment_latency_sum_ms.load(Ordering::Relaxed),
             "fulfillment_count": self.fulfillment_count.load(Ordering::Relaxed),
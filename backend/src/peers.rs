@@ -0,0 +1,310 @@
+//! Partial-proof collection for the multi-oracle proof scheme.
+//!
+//! The coordinator requires ECVRF proofs over the request's VRF input from
+//! `oracle_threshold` distinct registered oracles, named by index in
+//! increasing order. Until the request's fallback slot they must be its
+//! committee — `oracle_threshold` oracles chosen by the request ID — and
+//! after it any registered oracles. Each oracle has exactly one valid proof
+//! per request, so a peer cannot grind its contribution.
+//!
+//! Proofs come from three places, in order:
+//!
+//! 1. This oracle's own authority keypair.
+//! 2. `ORACLE_PEER_KEYPAIRS` — keypairs proving in-process (local testing).
+//! 3. `ORACLE_PEERS` — remote backends serving `/partial-proof/{request_id}`.
+//!
+//! A peer only answers registered oracles: the request carries this
+//! oracle's Ed25519 signature over [`proof_request_message`]. Remote proofs
+//! are verified against the locally built VRF input before use, so a peer
+//! that proves different data is skipped rather than causing an on-chain
+//! failure.
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tracing::warn;
+
+use crate::config::AppConfig;
+use crate::ecvrf;
+
+/// Timeout for a single peer request.
+const PEER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// One oracle's ECVRF proof over the request's VRF input.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartialProof {
+    /// Base58 oracle public key.
+    pub oracle: String,
+    /// Base64 80-byte ECVRF proof.
+    pub proof: String,
+}
+
+impl PartialProof {
+    /// Prove `alpha` with `keypair`.
+    pub fn prove(keypair: &Keypair, alpha: &[u8]) -> Self {
+        let (proof, _) = ecvrf::prove(keypair.secret_bytes(), alpha);
+        Self {
+            oracle: keypair.pubkey().to_string(),
+            proof: base64::engine::general_purpose::STANDARD.encode(proof),
+        }
+    }
+
+    /// Decode the proof and verify it over `alpha`.
+    pub fn verify(&self, alpha: &[u8]) -> Result<(Pubkey, [u8; ecvrf::PROOF_LEN])> {
+        let oracle: Pubkey = self.oracle.parse().context("invalid oracle pubkey")?;
+        let proof: [u8; ecvrf::PROOF_LEN] = base64::engine::general_purpose::STANDARD
+            .decode(&self.proof)
+            .context("invalid proof encoding")?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("proof must be {} bytes", ecvrf::PROOF_LEN))?;
+        anyhow::ensure!(
+            ecvrf::verify(&oracle.to_bytes(), alpha, &proof).is_some(),
+            "proof does not verify against the request's VRF input"
+        );
+        Ok((oracle, proof))
+    }
+}
+
+/// The registered multi-oracle set and its threshold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OracleSet {
+    /// Registered oracle keys, in registry order.
+    pub oracles: Vec<Pubkey>,
+    /// Number of proofs a fulfillment must carry.
+    pub threshold: usize,
+}
+
+impl OracleSet {
+    /// Indices of `request_id`'s committee, in increasing order: the
+    /// `threshold` oracles from `request_id % oracles.len()` on, wrapping
+    /// around (must match on-chain `CoordinatorConfig::is_on_committee`).
+    pub fn committee(&self, request_id: u64) -> Vec<usize> {
+        let count = self.oracles.len();
+        let start = (request_id % count as u64) as usize;
+        let mut committee: Vec<usize> = (0..self.threshold).map(|k| (start + k) % count).collect();
+        committee.sort_unstable();
+        committee
+    }
+
+    /// Indices of every registered oracle.
+    pub fn everyone(&self) -> Vec<usize> {
+        (0..self.oracles.len()).collect()
+    }
+}
+
+/// Message an oracle signs to request a peer's partial proof:
+/// `"vrf-partial-proof" || program_id || request_id (u64 LE)`.
+pub fn proof_request_message(program_id: &Pubkey, request_id: u64) -> Vec<u8> {
+    let mut message = Vec::with_capacity(17 + 32 + 8);
+    message.extend_from_slice(b"vrf-partial-proof");
+    message.extend_from_slice(program_id.as_ref());
+    message.extend_from_slice(&request_id.to_le_bytes());
+    message
+}
+
+/// Check that a partial-proof request for `request_id` is signed by a
+/// registered oracle. `oracle` and `signature` are base58.
+pub fn authorize_proof_request(
+    oracle_set: &OracleSet,
+    program_id: &Pubkey,
+    request_id: u64,
+    oracle: &str,
+    signature: &str,
+) -> Result<Pubkey> {
+    let oracle: Pubkey = oracle.parse().context("invalid oracle pubkey")?;
+    let signature: Signature = signature.parse().context("invalid signature")?;
+    anyhow::ensure!(
+        oracle_set.oracles.contains(&oracle),
+        "caller is not a registered oracle"
+    );
+    anyhow::ensure!(
+        signature.verify(oracle.as_ref(), &proof_request_message(program_id, request_id)),
+        "signature does not verify"
+    );
+    Ok(oracle)
+}
+
+/// Collect proofs over `alpha` from `oracle_set.threshold` of the oracles
+/// at the `eligible` indices, returned with their oracle index in
+/// increasing index order.
+///
+/// Peers are queried sequentially and collection stops as soon as enough
+/// eligible oracles have a proof. Fails if too few of them answer.
+pub async fn collect_proofs(
+    config: &AppConfig,
+    oracle_set: &OracleSet,
+    eligible: &[usize],
+    request_id: u64,
+    alpha: &[u8],
+) -> Result<Vec<(u8, [u8; ecvrf::PROOF_LEN])>> {
+    let mut proofs = BTreeMap::new();
+
+    let local = std::iter::once(config.authority_keypair.as_ref())
+        .chain(config.oracle_peer_keypairs.iter().map(|k| k.as_ref()));
+    for keypair in local {
+        let (oracle, proof) = PartialProof::prove(keypair, alpha).verify(alpha)?;
+        insert_proof(&mut proofs, oracle_set, eligible, &oracle, proof);
+    }
+
+    if proofs.len() < oracle_set.threshold && !config.oracle_peers.is_empty() {
+        let client = reqwest::Client::builder()
+            .timeout(PEER_TIMEOUT)
+            .build()
+            .context("failed to build HTTP client")?;
+        let signature = config
+            .authority_keypair
+            .sign_message(&proof_request_message(&config.program_id, request_id));
+        for peer in &config.oracle_peers {
+            if proofs.len() >= oracle_set.threshold {
+                break;
+            }
+            match fetch_partial_proof(&client, peer, request_id, config, &signature).await {
+                Ok(partial) => match partial.verify(alpha) {
+                    Ok((oracle, proof)) => {
+                        if !insert_proof(&mut proofs, oracle_set, eligible, &oracle, proof) {
+                            warn!(peer = %peer, oracle = %oracle, "Peer oracle may not prove this request");
+                        }
+                    }
+                    Err(e) => warn!(peer = %peer, error = %e, "Rejected peer proof"),
+                },
+                Err(e) => warn!(peer = %peer, error = %e, "Failed to fetch peer proof"),
+            }
+        }
+    }
+
+    anyhow::ensure!(
+        proofs.len() >= oracle_set.threshold,
+        "{} of {} required oracle proofs collected",
+        proofs.len(),
+        oracle_set.threshold
+    );
+    Ok(proofs
+        .into_iter()
+        .take(oracle_set.threshold)
+        .map(|(index, proof)| (index as u8, proof))
+        .collect())
+}
+
+/// Request a partial proof from a peer backend, authenticated by this
+/// oracle's `signature` over [`proof_request_message`].
+async fn fetch_partial_proof(
+    client: &reqwest::Client,
+    peer: &str,
+    request_id: u64,
+    config: &AppConfig,
+    signature: &Signature,
+) -> Result<PartialProof> {
+    let url = format!("{}/partial-proof/{request_id}", peer.trim_end_matches('/'));
+    client
+        .get(&url)
+        .query(&[
+            ("oracle", config.authority_keypair.pubkey().to_string()),
+            ("signature", signature.to_string()),
+        ])
+        .send()
+        .await
+        .context("peer request failed")?
+        .error_for_status()
+        .context("peer returned an error status")?
+        .json()
+        .await
+        .context("invalid peer response")
+}
+
+/// Store `proof` under `oracle`'s index in the registered set. Returns
+/// `false` if `oracle` is not registered or not `eligible`.
+fn insert_proof(
+    proofs: &mut BTreeMap<usize, [u8; ecvrf::PROOF_LEN]>,
+    oracle_set: &OracleSet,
+    eligible: &[usize],
+    oracle: &Pubkey,
+    proof: [u8; ecvrf::PROOF_LEN],
+) -> bool {
+    match oracle_set.oracles.iter().position(|key| key == oracle) {
+        Some(index) if eligible.contains(&index) => {
+            proofs.entry(index).or_insert(proof);
+            true
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_proof_roundtrip() {
+        let keypair = Keypair::new();
+        let alpha = ecvrf::vrf_input(&[1u8; 32], 100, 0);
+        let partial = PartialProof::prove(&keypair, &alpha);
+
+        let (oracle, _) = partial.verify(&alpha).unwrap();
+        assert_eq!(oracle, keypair.pubkey());
+        let other_alpha = ecvrf::vrf_input(&[1u8; 32], 100, 1);
+        assert!(partial.verify(&other_alpha).is_err());
+    }
+
+    #[test]
+    fn committee_rotates_with_the_request_id() {
+        let oracle_set = OracleSet {
+            oracles: (0..4).map(|_| Pubkey::new_unique()).collect(),
+            threshold: 2,
+        };
+        assert_eq!(oracle_set.committee(0), vec![0, 1]);
+        assert_eq!(oracle_set.committee(2), vec![2, 3]);
+        assert_eq!(oracle_set.committee(3), vec![0, 3]);
+        assert_eq!(oracle_set.committee((1 << 32) | 5), vec![1, 2]);
+    }
+
+    #[test]
+    fn proofs_are_kept_for_eligible_oracles_only() {
+        let oracle_set = OracleSet {
+            oracles: (0..3).map(|_| Pubkey::new_unique()).collect(),
+            threshold: 2,
+        };
+        let mut proofs = BTreeMap::new();
+
+        assert!(insert_proof(&mut proofs, &oracle_set, &[1, 2], &oracle_set.oracles[2], [2u8; ecvrf::PROOF_LEN]));
+        assert!(insert_proof(&mut proofs, &oracle_set, &[1, 2], &oracle_set.oracles[1], [1u8; ecvrf::PROOF_LEN]));
+        assert!(!insert_proof(&mut proofs, &oracle_set, &[1, 2], &oracle_set.oracles[0], [0u8; ecvrf::PROOF_LEN]));
+        assert!(!insert_proof(&mut proofs, &oracle_set, &[1, 2], &Pubkey::new_unique(), [3u8; ecvrf::PROOF_LEN]));
+        assert_eq!(
+            proofs.into_iter().collect::<Vec<_>>(),
+            vec![(1, [1u8; ecvrf::PROOF_LEN]), (2, [2u8; ecvrf::PROOF_LEN])]
+        );
+    }
+
+    #[test]
+    fn proof_requests_must_come_from_a_registered_oracle() {
+        let oracle = Keypair::new();
+        let outsider = Keypair::new();
+        let program_id = Pubkey::new_unique();
+        let oracle_set = OracleSet {
+            oracles: vec![oracle.pubkey()],
+            threshold: 1,
+        };
+        let sign = |keypair: &Keypair, request_id| {
+            keypair
+                .sign_message(&proof_request_message(&program_id, request_id))
+                .to_string()
+        };
+
+        let authorized = authorize_proof_request(
+            &oracle_set,
+            &program_id,
+            7,
+            &oracle.pubkey().to_string(),
+            &sign(&oracle, 7),
+        );
+        assert_eq!(authorized.unwrap(), oracle.pubkey());
+        // Signed for another request
+        assert!(authorize_proof_request(&oracle_set, &program_id, 8, &oracle.pubkey().to_string(), &sign(&oracle, 7)).is_err());
+        // Validly signed, but not registered
+        assert!(authorize_proof_request(&oracle_set, &program_id, 7, &outsider.pubkey().to_string(), &sign(&outsider, 7)).is_err());
+    }
+}
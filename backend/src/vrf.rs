@@ -3,9 +3,14 @@
 //! Uses HMAC-SHA256 keyed by the oracle's secret to produce a 32-byte
 //! pseudo-random output that is deterministic (same inputs = same output)
 //! but unpredictable without the secret key.
+//!
+//! The signature-hash scheme instead derives the output from the oracle's
+//! Ed25519 signature over a request-bound message, so no secret beyond the
+//! authority key is involved and anyone can recompute it.
 
 use hmac::{Hmac, Mac};
-use sha2::Sha256;
+use sha2::{Digest, Sha256};
+use solana_sdk::pubkey::Pubkey;
 
 type HmacSha256 = Hmac<Sha256>;
 
@@ -39,6 +44,31 @@ pub fn compute_randomness(
     output
 }
 
+/// Build the request-bound message signed under the signature-hash scheme
+/// (must match the on-chain `signature_randomness_message`).
+///
+/// ```text
+/// program_id (32) || request_id (8 LE) || seed (32) || request_slot (8 LE)
+/// ```
+pub fn signature_randomness_message(
+    program_id: &Pubkey,
+    request_id: u64,
+    seed: &[u8; 32],
+    request_slot: u64,
+) -> [u8; 80] {
+    let mut message = [0u8; 80];
+    message[..32].copy_from_slice(program_id.as_ref());
+    message[32..40].copy_from_slice(&request_id.to_le_bytes());
+    message[40..72].copy_from_slice(seed);
+    message[72..].copy_from_slice(&request_slot.to_le_bytes());
+    message
+}
+
+/// Derive the signature-hash randomness: `SHA256(signature)`.
+pub fn randomness_from_signature(signature: &[u8]) -> [u8; 32] {
+    Sha256::digest(signature).into()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -72,4 +102,17 @@ mod tests {
         let r2 = compute_randomness(secret, &seed, 100, 1);
         assert_ne!(r1, r2);
     }
+
+    #[test]
+    fn signature_message_binds_request() {
+        let program_id = Pubkey::new_unique();
+        let seed = [1u8; 32];
+
+        let m1 = signature_randomness_message(&program_id, 0, &seed, 100);
+        let m2 = signature_randomness_message(&program_id, 1, &seed, 100);
+        let m3 = signature_randomness_message(&Pubkey::new_unique(), 0, &seed, 100);
+        assert_ne!(m1, m2);
+        assert_ne!(m1, m3);
+        assert_eq!(&m1[..32], program_id.as_ref());
+    }
 }
@@ -0,0 +1,358 @@
+use anchor_lang::prelude::*;
+use vrf_sol::state::CONSUMER_AUTHORITY_SEED;
+
+declare_id!("EjqMDSYJT1LRFMBZEyVGqiBVvERYt5o2sdtpWNWooNYV");
+
+/// Minimal VRF consumer used by the coordinator test suite.
+///
+/// Unlike `roll-dice`, it exposes the full `request_random_words` surface
+/// (any `num_words`, store-only mode, arbitrary callback accounts and a
+/// sidecar) and its direct-funded variant, so tests can exercise coordinator paths that must be reached
+/// through a real consumer CPI. A second callback handler exercises callback
+/// prefixes.
+#[program]
+pub mod mock_consumer {
+    use super::*;
+
+    /// Forward a request to the coordinator, signing as this program's
+    /// consumer authority. Remaining accounts become callback accounts.
+    /// `callback` options are forwarded as is.
+    pub fn request<'info>(
+        ctx: Context<'_, '_, '_, 'info, Request<'info>>,
+        num_words: u32,
+        seed: [u8; 32],
+        callback_compute_limit: u32,
+        store_only: bool,
+        min_confirmation_slots: u64,
+        callback: vrf_sol::state::CallbackOptions,
+    ) -> Result<()> {
+        let cpi_accounts = vrf_sol::cpi::accounts::RequestRandomWords {
+            requester: ctx.accounts.requester.to_account_info(),
+            config: ctx.accounts.vrf_config.to_account_info(),
+            subscription: ctx.accounts.subscription.to_account_info(),
+            consumer_registration: ctx.accounts.consumer_registration.to_account_info(),
+            consumer_program: ctx.accounts.this_program.to_account_info(),
+            consumer_authority: ctx.accounts.consumer_authority.to_account_info(),
+            request: ctx.accounts.vrf_request.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            callback_accounts: ctx
+                .accounts
+                .callback_accounts
+                .as_ref()
+                .map(|a| a.to_account_info()),
+            callback_lookup_table: ctx
+                .accounts
+                .callback_lookup_table
+                .as_ref()
+                .map(|a| a.to_account_info()),
+            approved_mint: ctx
+                .accounts
+                .approved_mint
+                .as_ref()
+                .map(|a| a.to_account_info()),
+        };
+        let authority_seeds: &[&[&[u8]]] =
+            &[&[CONSUMER_AUTHORITY_SEED, &[ctx.bumps.consumer_authority]]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.vrf_program.to_account_info(),
+            cpi_accounts,
+            authority_seeds,
+        )
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+        let receipt = vrf_sol::cpi::request_random_words(
+            cpi_ctx,
+            num_words,
+            seed,
+            callback_compute_limit,
+            store_only,
+            min_confirmation_slots,
+            callback,
+        )?
+        .get();
+
+        msg!(
+            "Requested {} words (request_id={}, request={})",
+            num_words,
+            receipt.request_id,
+            receipt.request
+        );
+        Ok(())
+    }
+
+    /// Forward a direct-funded request to the coordinator; the requester pays
+    /// the fee. Remaining accounts become callback accounts.
+    pub fn request_direct<'info>(
+        ctx: Context<'_, '_, '_, 'info, RequestDirect<'info>>,
+        num_words: u32,
+        seed: [u8; 32],
+        callback_compute_limit: u32,
+        store_only: bool,
+        min_confirmation_slots: u64,
+        callback: vrf_sol::state::CallbackOptions,
+    ) -> Result<()> {
+        let cpi_accounts = vrf_sol::cpi::accounts::RequestRandomWordsDirect {
+            requester: ctx.accounts.requester.to_account_info(),
+            config: ctx.accounts.vrf_config.to_account_info(),
+            requester_nonce: ctx.accounts.requester_nonce.to_account_info(),
+            consumer_program: ctx.accounts.this_program.to_account_info(),
+            consumer_authority: ctx.accounts.consumer_authority.to_account_info(),
+            request: ctx.accounts.vrf_request.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            callback_accounts: ctx
+                .accounts
+                .callback_accounts
+                .as_ref()
+                .map(|a| a.to_account_info()),
+            callback_lookup_table: ctx
+                .accounts
+                .callback_lookup_table
+                .as_ref()
+                .map(|a| a.to_account_info()),
+        };
+        let authority_seeds: &[&[&[u8]]] =
+            &[&[CONSUMER_AUTHORITY_SEED, &[ctx.bumps.consumer_authority]]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.vrf_program.to_account_info(),
+            cpi_accounts,
+            authority_seeds,
+        )
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+        let receipt = vrf_sol::cpi::request_random_words_direct(
+            cpi_ctx,
+            num_words,
+            seed,
+            callback_compute_limit,
+            store_only,
+            min_confirmation_slots,
+            callback,
+        )?
+        .get();
+
+        msg!(
+            "Requested {} words directly (request_id={}, request={})",
+            num_words,
+            receipt.request_id,
+            receipt.request
+        );
+        Ok(())
+    }
+
+    /// Set this program's default callback prefix for the subscription.
+    pub fn set_callback(
+        ctx: Context<SetCallback>,
+        subscription_id: u64,
+        callback_prefix: Vec<u8>,
+    ) -> Result<()> {
+        let cpi_accounts = vrf_sol::cpi::accounts::SetConsumerCallback {
+            consumer_program: ctx.accounts.this_program.to_account_info(),
+            consumer_authority: ctx.accounts.consumer_authority.to_account_info(),
+            consumer_registration: ctx.accounts.consumer_registration.to_account_info(),
+        };
+        let authority_seeds: &[&[&[u8]]] =
+            &[&[CONSUMER_AUTHORITY_SEED, &[ctx.bumps.consumer_authority]]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.vrf_program.to_account_info(),
+            cpi_accounts,
+            authority_seeds,
+        );
+        vrf_sol::cpi::set_consumer_callback(cpi_ctx, subscription_id, callback_prefix)
+    }
+
+    /// Pull stored randomness from the coordinator.
+    pub fn consume(ctx: Context<Consume>, request_id: u64) -> Result<()> {
+        let cpi_accounts = vrf_sol::cpi::accounts::ConsumeRandomness {
+            consumer_authority: ctx.accounts.consumer_authority.to_account_info(),
+            request: ctx.accounts.vrf_request.to_account_info(),
+            requester: ctx.accounts.requester.to_account_info(),
+        };
+        let authority_seeds: &[&[&[u8]]] =
+            &[&[CONSUMER_AUTHORITY_SEED, &[ctx.bumps.consumer_authority]]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.vrf_program.to_account_info(),
+            cpi_accounts,
+            authority_seeds,
+        );
+        let words = vrf_sol::cpi::consume_randomness(cpi_ctx, request_id)?.get();
+
+        msg!("Consumed {} words (request_id={})", words.len(), request_id);
+        Ok(())
+    }
+
+    /// Pull the 32-byte output of stored randomness from the coordinator.
+    pub fn consume_seed(ctx: Context<Consume>, request_id: u64) -> Result<()> {
+        let cpi_accounts = vrf_sol::cpi::accounts::ConsumeRandomness {
+            consumer_authority: ctx.accounts.consumer_authority.to_account_info(),
+            request: ctx.accounts.vrf_request.to_account_info(),
+            requester: ctx.accounts.requester.to_account_info(),
+        };
+        let authority_seeds: &[&[&[u8]]] =
+            &[&[CONSUMER_AUTHORITY_SEED, &[ctx.bumps.consumer_authority]]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.vrf_program.to_account_info(),
+            cpi_accounts,
+            authority_seeds,
+        );
+        let seed = vrf_sol::cpi::consume_randomness_seed(cpi_ctx, request_id)?.get();
+
+        msg!("Consumed seed {:?} (request_id={})", &seed[..4], request_id);
+        Ok(())
+    }
+
+    /// Callback from the VRF coordinator. Accepts any callback accounts.
+    /// Keeps the two-argument signature; Anchor ignores the trailing
+    /// `user_data` of a `CALLBACK_V2` payload.
+    pub fn fulfill_random_words(
+        _ctx: Context<FulfillRandomWords>,
+        request_id: u64,
+        random_words: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        msg!(
+            "Received {} words (request_id={})",
+            random_words.len(),
+            request_id
+        );
+        Ok(())
+    }
+
+    /// Alternative callback, reached with the prefix
+    /// `sha256("global:fulfill_secondary")[..8]`.
+    pub fn fulfill_secondary(
+        _ctx: Context<FulfillRandomWords>,
+        request_id: u64,
+        random_words: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        msg!(
+            "Secondary handler received {} words (request_id={})",
+            random_words.len(),
+            request_id
+        );
+        Ok(())
+    }
+}
+
+/// Accounts for [`mock_consumer::request`].
+#[derive(Accounts)]
+pub struct Request<'info> {
+    #[account(mut)]
+    pub requester: Signer<'info>,
+
+    /// CHECK: Validated by the VRF program during CPI.
+    pub vrf_config: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the VRF program during CPI.
+    #[account(mut)]
+    pub subscription: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the VRF program during CPI.
+    #[account(mut)]
+    pub consumer_registration: UncheckedAccount<'info>,
+
+    /// CHECK: Must be this program's ID.
+    #[account(address = crate::ID)]
+    pub this_program: UncheckedAccount<'info>,
+
+    /// CHECK: PDA with no data. Seeds: `["consumer-authority"]`.
+    #[account(seeds = [CONSUMER_AUTHORITY_SEED], bump)]
+    pub consumer_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Created and validated by the VRF program during CPI.
+    #[account(mut)]
+    pub vrf_request: UncheckedAccount<'info>,
+
+    /// CHECK: Created and validated by the VRF program during CPI.
+    #[account(mut)]
+    pub callback_accounts: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Validated by the VRF program during CPI.
+    pub callback_lookup_table: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Validated by the VRF program during CPI.
+    pub approved_mint: Option<UncheckedAccount<'info>>,
+
+    pub vrf_program: Program<'info, vrf_sol::program::VrfSol>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for [`mock_consumer::request_direct`].
+#[derive(Accounts)]
+pub struct RequestDirect<'info> {
+    #[account(mut)]
+    pub requester: Signer<'info>,
+
+    /// CHECK: Validated by the VRF program during CPI.
+    pub vrf_config: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the VRF program during CPI.
+    #[account(mut)]
+    pub requester_nonce: UncheckedAccount<'info>,
+
+    /// CHECK: Must be this program's ID.
+    #[account(address = crate::ID)]
+    pub this_program: UncheckedAccount<'info>,
+
+    /// CHECK: PDA with no data. Seeds: `["consumer-authority"]`.
+    #[account(seeds = [CONSUMER_AUTHORITY_SEED], bump)]
+    pub consumer_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Created and validated by the VRF program during CPI.
+    #[account(mut)]
+    pub vrf_request: UncheckedAccount<'info>,
+
+    /// CHECK: Created and validated by the VRF program during CPI.
+    #[account(mut)]
+    pub callback_accounts: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Validated by the VRF program during CPI.
+    pub callback_lookup_table: Option<UncheckedAccount<'info>>,
+
+    pub vrf_program: Program<'info, vrf_sol::program::VrfSol>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for [`mock_consumer::set_callback`].
+#[derive(Accounts)]
+pub struct SetCallback<'info> {
+    /// CHECK: Must be this program's ID.
+    #[account(address = crate::ID)]
+    pub this_program: UncheckedAccount<'info>,
+
+    /// CHECK: PDA with no data. Seeds: `["consumer-authority"]`.
+    #[account(seeds = [CONSUMER_AUTHORITY_SEED], bump)]
+    pub consumer_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the VRF program during CPI.
+    #[account(mut)]
+    pub consumer_registration: UncheckedAccount<'info>,
+
+    pub vrf_program: Program<'info, vrf_sol::program::VrfSol>,
+}
+
+/// Accounts for [`mock_consumer::consume`] and [`mock_consumer::consume_seed`].
+#[derive(Accounts)]
+pub struct Consume<'info> {
+    /// CHECK: PDA with no data. Seeds: `["consumer-authority"]`.
+    #[account(seeds = [CONSUMER_AUTHORITY_SEED], bump)]
+    pub consumer_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Validated and closed by the VRF program during CPI.
+    #[account(mut)]
+    pub vrf_request: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the VRF program during CPI.
+    #[account(mut)]
+    pub requester: UncheckedAccount<'info>,
+
+    pub vrf_program: Program<'info, vrf_sol::program::VrfSol>,
+}
+
+/// Accounts for [`mock_consumer::fulfill_random_words`].
+#[derive(Accounts)]
+pub struct FulfillRandomWords<'info> {
+    /// The coordinator-config PDA that signed this CPI.
+    #[account(
+        seeds = [b"coordinator-config"],
+        bump,
+        seeds::program = vrf_sol::ID,
+    )]
+    pub coordinator_config: Signer<'info>,
+}
@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Allocate, Assign, CreateAccount, Transfer};
 
 declare_id!("8dYRQi4Ai7Am1oWArpHHQYoC11FGyfNVJ7FU9Rwtrvbv");
 
@@ -46,6 +47,9 @@ pub enum RollDiceError {
     /// Attempted to settle a roll that already has a non-zero result.
     #[msg("Dice roll has already been settled")]
     AlreadySettled,
+    /// The dice roll account is not the PDA for the assigned request ID.
+    #[msg("Dice roll account does not match the VRF request ID")]
+    InvalidDiceRoll,
 }
 
 /// Emitted when a player requests a new dice roll.
@@ -90,10 +94,11 @@ pub mod roll_dice {
     }
 
     /// Request a dice roll by CPI-ing into the VRF coordinator.
+    ///
+    /// The coordinator returns the assigned request ID, which seeds the
+    /// `DiceRoll` PDA. The account is created after the CPI, so the caller
+    /// passes the address it expects and the program checks it.
     pub fn request_roll(ctx: Context<RequestRoll>, seed: [u8; 32]) -> Result<()> {
-        let vrf_config = &ctx.accounts.vrf_config;
-        let request_id = vrf_config.request_counter;
-
         // CPI into vrf_sol::request_random_words
         let cpi_accounts = vrf_sol::cpi::accounts::RequestRandomWords {
             requester: ctx.accounts.player.to_account_info(),
@@ -101,22 +106,74 @@ pub mod roll_dice {
             subscription: ctx.accounts.subscription.to_account_info(),
             consumer_registration: ctx.accounts.consumer_registration.to_account_info(),
             consumer_program: ctx.accounts.this_program.to_account_info(),
+            consumer_authority: ctx.accounts.consumer_authority.to_account_info(),
             request: ctx.accounts.vrf_request.to_account_info(),
             system_program: ctx.accounts.system_program.to_account_info(),
+            callback_accounts: None,
+            callback_lookup_table: None,
+            approved_mint: None,
         };
-        let cpi_ctx = CpiContext::new(ctx.accounts.vrf_program.to_account_info(), cpi_accounts);
-        vrf_sol::cpi::request_random_words(
+        // Sign as our consumer authority so the coordinator knows the request
+        // comes from this program.
+        let authority_seeds: &[&[&[u8]]] = &[&[
+            vrf_sol::state::CONSUMER_AUTHORITY_SEED,
+            &[ctx.bumps.consumer_authority],
+        ]];
+        // Register the accounts our callback needs; the coordinator passes
+        // exactly these, in this order, when it fulfills.
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.vrf_program.to_account_info(),
+            cpi_accounts,
+            authority_seeds,
+        )
+        .with_remaining_accounts(vec![
+            ctx.accounts.game_config.to_account_info(),
+            ctx.accounts.dice_roll.to_account_info(),
+        ]);
+        let request_id = vrf_sol::cpi::request_random_words(
             cpi_ctx,
             1,    // num_words
             seed,
             200_000, // callback_compute_limit
+            false,   // store_only
+            0,       // min_confirmation_slots (coordinator minimum)
+            vrf_sol::state::CallbackOptions::default(), // two-argument `fulfill_random_words`
+        )?
+        .get()
+        .request_id;
+
+        let player = ctx.accounts.player.key();
+        let (expected_dice_roll, bump) = Pubkey::find_program_address(
+            &[b"dice-result", player.as_ref(), &request_id.to_le_bytes()],
+            ctx.program_id,
+        );
+        require!(
+            ctx.accounts.dice_roll.key() == expected_dice_roll,
+            RollDiceError::InvalidDiceRoll
+        );
+
+        let dice_roll_seeds: &[&[&[u8]]] = &[&[
+            b"dice-result",
+            player.as_ref(),
+            &request_id.to_le_bytes(),
+            &[bump],
+        ]];
+        create_dice_roll_account(
+            &ctx.accounts.player,
+            &ctx.accounts.dice_roll,
+            &ctx.accounts.system_program,
+            ctx.program_id,
+            dice_roll_seeds,
         )?;
 
-        let dice = &mut ctx.accounts.dice_roll;
-        dice.player = ctx.accounts.player.key();
-        dice.vrf_request_id = request_id;
-        dice.result = 0;
-        dice.bump = ctx.bumps.dice_roll;
+        let dice = DiceRoll {
+            player,
+            vrf_request_id: request_id,
+            result: 0,
+            bump,
+        };
+        let mut data = ctx.accounts.dice_roll.try_borrow_mut_data()?;
+        dice.try_serialize(&mut &mut data[..])?;
 
         emit!(DiceRollRequested {
             player: ctx.accounts.player.key(),
@@ -168,6 +225,73 @@ pub mod roll_dice {
     }
 }
 
+/// Create the `DiceRoll` PDA at `dice_roll`, paid by `player`.
+///
+/// The address is predictable before the request, so anyone can send it
+/// lamports first, which would make `create_account` fail. A pre-funded
+/// address is topped up to rent exemption, allocated and assigned instead.
+fn create_dice_roll_account<'info>(
+    player: &Signer<'info>,
+    dice_roll: &UncheckedAccount<'info>,
+    system: &Program<'info, System>,
+    program_id: &Pubkey,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    let space = 8 + DiceRoll::INIT_SPACE;
+    let rent = Rent::get()?.minimum_balance(space);
+
+    let current = dice_roll.lamports();
+    if current == 0 {
+        return system_program::create_account(
+            CpiContext::new_with_signer(
+                system.to_account_info(),
+                CreateAccount {
+                    from: player.to_account_info(),
+                    to: dice_roll.to_account_info(),
+                },
+                signer,
+            ),
+            rent,
+            space as u64,
+            program_id,
+        );
+    }
+
+    let top_up = rent.saturating_sub(current);
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system.to_account_info(),
+                Transfer {
+                    from: player.to_account_info(),
+                    to: dice_roll.to_account_info(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system.to_account_info(),
+            Allocate {
+                account_to_allocate: dice_roll.to_account_info(),
+            },
+            signer,
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system.to_account_info(),
+            Assign {
+                account_to_assign: dice_roll.to_account_info(),
+            },
+            signer,
+        ),
+        program_id,
+    )
+}
+
 /// Accounts for [`roll_dice::initialize`].
 #[derive(Accounts)]
 pub struct InitializeGame<'info> {
@@ -202,19 +326,20 @@ pub struct RequestRoll<'info> {
     )]
     pub game_config: Account<'info, GameConfig>,
 
-    /// VRF coordinator config account (read for `request_counter`, mutated by CPI).
+    /// VRF coordinator config account.
     /// CHECK: Validated by the VRF program during CPI.
-    #[account(mut)]
-    pub vrf_config: Account<'info, vrf_sol::state::CoordinatorConfig>,
+    pub vrf_config: UncheckedAccount<'info>,
 
     /// Subscription account (balance deducted by CPI).
     /// CHECK: Validated by the VRF program during CPI.
     #[account(mut)]
-    pub subscription: Account<'info, vrf_sol::state::Subscription>,
+    pub subscription: UncheckedAccount<'info>,
 
-    /// Consumer registration proving this program is authorized.
+    /// Consumer registration proving this program is authorized (usage
+    /// counters updated by CPI).
     /// CHECK: Validated by the VRF program during CPI.
-    pub consumer_registration: Account<'info, vrf_sol::state::ConsumerRegistration>,
+    #[account(mut)]
+    pub consumer_registration: UncheckedAccount<'info>,
 
     /// VRF request account (created by the VRF program CPI).
     /// CHECK: Created and validated by the VRF program during CPI.
@@ -226,15 +351,19 @@ pub struct RequestRoll<'info> {
     #[account(address = crate::ID)]
     pub this_program: UncheckedAccount<'info>,
 
-    /// Dice roll PDA. Seeds: `["dice-result", player, counter.to_le_bytes()]`.
+    /// This program's consumer authority PDA, signing the coordinator CPI.
+    /// CHECK: PDA with no data. Seeds: `["consumer-authority"]`.
     #[account(
-        init,
-        payer = player,
-        space = 8 + DiceRoll::INIT_SPACE,
-        seeds = [b"dice-result", player.key().as_ref(), &vrf_config.request_counter.to_le_bytes()],
+        seeds = [vrf_sol::state::CONSUMER_AUTHORITY_SEED],
         bump,
     )]
-    pub dice_roll: Account<'info, DiceRoll>,
+    pub consumer_authority: UncheckedAccount<'info>,
+
+    /// Dice roll PDA. Seeds: `["dice-result", player, request_id.to_le_bytes()]`.
+    /// CHECK: Created by `request_roll` once the coordinator has returned
+    /// the request ID; the address is checked against it there.
+    #[account(mut)]
+    pub dice_roll: UncheckedAccount<'info>,
 
     pub vrf_program: Program<'info, vrf_sol::program::VrfSol>,
     pub system_program: Program<'info, System>,
@@ -0,0 +1,155 @@
+use anchor_lang::prelude::*;
+use sha2::{Digest, Sha512};
+use solana_curve25519::edwards::{
+    multiply_edwards, subtract_edwards, validate_edwards, PodEdwardsPoint,
+};
+use solana_curve25519::scalar::PodScalar;
+
+use crate::errors::VrfError;
+
+/// Serialized ECVRF proof length: Gamma (32) + c (16) + s (32).
+pub const PROOF_LEN: usize = 80;
+
+/// Suite identifier for ECVRF-EDWARDS25519-SHA512-TAI.
+const SUITE_STRING: u8 = 0x03;
+
+/// Compressed Ed25519 base point.
+const BASEPOINT: PodEdwardsPoint = PodEdwardsPoint([
+    0x58, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+    0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+    0x66, 0x66,
+]);
+
+/// Compressed identity point (`y = 1`).
+const IDENTITY: PodEdwardsPoint = PodEdwardsPoint([
+    1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0,
+]);
+
+/// Edwards25519 cofactor as a scalar.
+const COFACTOR: PodScalar = PodScalar([
+    8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0,
+]);
+
+/// Build the VRF input (`alpha`) for a request.
+///
+/// ```text
+/// alpha = seed (32) || request_slot (8 LE) || request_id (8 LE)
+/// ```
+///
+/// Both values are fixed at request time, so the oracle cannot choose the
+/// input it proves over.
+pub fn vrf_input(seed: &[u8; 32], request_slot: u64, request_id: u64) -> [u8; 48] {
+    let mut alpha = [0u8; 48];
+    alpha[..32].copy_from_slice(seed);
+    alpha[32..40].copy_from_slice(&request_slot.to_le_bytes());
+    alpha[40..].copy_from_slice(&request_id.to_le_bytes());
+    alpha
+}
+
+/// Verify an ECVRF-EDWARDS25519-SHA512-TAI proof (RFC 9381, section 5.3)
+/// and return the first 32 bytes of the VRF output `beta`.
+///
+/// ## Proof layout
+///
+/// ```text
+/// [0..32]   Gamma — compressed Edwards point
+/// [32..48]  c     — 16-byte challenge (LE integer)
+/// [48..80]  s     — canonical scalar (LE)
+/// ```
+///
+/// Group operations use the `curve25519` syscalls, so verification costs a
+/// handful of scalar multiplications rather than a full software curve
+/// implementation.
+pub fn verify(public_key: &Pubkey, alpha: &[u8], proof: &[u8; PROOF_LEN]) -> Result<[u8; 32]> {
+    let y = PodEdwardsPoint(public_key.to_bytes());
+    require!(validate_edwards(&y), VrfError::InvalidVrfProof);
+
+    let mut gamma_bytes = [0u8; 32];
+    gamma_bytes.copy_from_slice(&proof[..32]);
+    let gamma = PodEdwardsPoint(gamma_bytes);
+    require!(validate_edwards(&gamma), VrfError::InvalidVrfProof);
+
+    let mut c = [0u8; 16];
+    c.copy_from_slice(&proof[32..48]);
+    let mut c_scalar = [0u8; 32];
+    c_scalar[..16].copy_from_slice(&c);
+    let c_scalar = PodScalar(c_scalar);
+
+    // Non-canonical `s` (>= group order) is rejected by the multiplication syscall.
+    let mut s = [0u8; 32];
+    s.copy_from_slice(&proof[48..]);
+    let s = PodScalar(s);
+
+    let h = encode_to_curve(&y, alpha)?;
+
+    // U = s*B - c*Y
+    let u = subtract_edwards(
+        &multiply_edwards(&s, &BASEPOINT).ok_or(VrfError::InvalidVrfProof)?,
+        &multiply_edwards(&c_scalar, &y).ok_or(VrfError::InvalidVrfProof)?,
+    )
+    .ok_or(VrfError::InvalidVrfProof)?;
+
+    // V = s*H - c*Gamma
+    let v = subtract_edwards(
+        &multiply_edwards(&s, &h).ok_or(VrfError::InvalidVrfProof)?,
+        &multiply_edwards(&c_scalar, &gamma).ok_or(VrfError::InvalidVrfProof)?,
+    )
+    .ok_or(VrfError::InvalidVrfProof)?;
+
+    require!(
+        challenge(&[&y, &h, &gamma, &u, &v]) == c,
+        VrfError::InvalidVrfProof
+    );
+
+    // beta = SHA512(suite || 0x03 || cofactor * Gamma || 0x00)
+    let cleared = multiply_edwards(&COFACTOR, &gamma).ok_or(VrfError::InvalidVrfProof)?;
+    let hash = Sha512::new()
+        .chain_update([SUITE_STRING, 0x03])
+        .chain_update(cleared.0)
+        .chain_update([0x00])
+        .finalize();
+    let mut output = [0u8; 32];
+    output.copy_from_slice(&hash[..32]);
+    Ok(output)
+}
+
+/// Try-and-increment hash to curve (RFC 9381, section 5.4.1.1), salted with
+/// the public key.
+fn encode_to_curve(public_key: &PodEdwardsPoint, alpha: &[u8]) -> Result<PodEdwardsPoint> {
+    for ctr in 0..=u8::MAX {
+        let hash = Sha512::new()
+            .chain_update([SUITE_STRING, 0x01])
+            .chain_update(public_key.0)
+            .chain_update(alpha)
+            .chain_update([ctr, 0x00])
+            .finalize();
+        let mut candidate = [0u8; 32];
+        candidate.copy_from_slice(&hash[..32]);
+        let candidate = PodEdwardsPoint(candidate);
+        if !validate_edwards(&candidate) {
+            continue;
+        }
+        let point = multiply_edwards(&COFACTOR, &candidate).ok_or(VrfError::InvalidVrfProof)?;
+        if point != IDENTITY {
+            return Ok(point);
+        }
+    }
+    err!(VrfError::InvalidVrfProof)
+}
+
+/// Challenge generation (RFC 9381, section 5.4.3): the first 16 bytes of
+/// `SHA512(suite || 0x02 || P1 || ... || P5 || 0x00)`.
+fn challenge(points: &[&PodEdwardsPoint; 5]) -> [u8; 16] {
+    let mut hasher = Sha512::new();
+    hasher.update([SUITE_STRING, 0x02]);
+    for point in points {
+        hasher.update(point.0);
+    }
+    hasher.update([0x00]);
+    let hash = hasher.finalize();
+    let mut c = [0u8; 16];
+    c.copy_from_slice(&hash[..16]);
+    c
+}
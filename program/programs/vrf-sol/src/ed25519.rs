@@ -1,10 +1,28 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
 use anchor_lang::solana_program::sysvar::instructions as sysvar_instructions;
+use sha2::{Digest, Sha256};
 use solana_sdk_ids::ed25519_program;
 
 use crate::errors::VrfError;
 
-/// Introspect the Instructions sysvar to find and verify an Ed25519 signature
+/// Maximum number of transaction instructions scanned for the Ed25519
+/// precompile instruction.
+const MAX_SCANNED_INSTRUCTIONS: usize = 16;
+
+/// Size of one `Ed25519SignatureOffsets` entry (7 x u16).
+const SIGNATURE_OFFSETS_LEN: usize = 14;
+
+/// The signature, public key, and message carried by an Ed25519 precompile
+/// instruction. The runtime has already verified the signature by the time
+/// the coordinator reads it.
+pub struct Ed25519Signature {
+    pub public_key: [u8; 32],
+    pub signature: [u8; 64],
+    pub message: Vec<u8>,
+}
+
+/// Introspect the Instructions sysvar to find and parse an Ed25519 signature
 /// verification instruction in the transaction.
 ///
 /// ## Ed25519 instruction data layout
@@ -22,47 +40,72 @@ use crate::errors::VrfError;
 ///
 /// All `*_instruction_index` fields must be `0xFFFF` (self-referencing), meaning
 /// the signature, public key, and message are all embedded in the same instruction.
-pub fn verify_ed25519_instruction(
+pub fn load_ed25519_signature(instructions_sysvar: &UncheckedAccount) -> Result<Ed25519Signature> {
+    let ix = find_ed25519_instruction(instructions_sysvar)?;
+
+    let data = &ix.data;
+    require!(data.len() >= 16, VrfError::InvalidEd25519Instruction);
+
+    let num_signatures = data[0];
+    require!(num_signatures == 1, VrfError::InvalidSignatureCount);
+
+    parse_signature_entry(data, 0)
+}
+
+/// Load the signatures of a batch fulfillment: the first Ed25519 precompile
+/// instruction must carry exactly `count` signatures, and entry `i` proves
+/// the batch's `i`-th fulfillment.
+pub fn load_ed25519_batch_signatures(
     instructions_sysvar: &UncheckedAccount,
-    expected_pubkey: &Pubkey,
-    request_id: u64,
-    randomness: &[u8; 32],
-) -> Result<()> {
-    // Scan up to 8 instructions to find the Ed25519 signature-verify instruction.
-    // This allows ComputeBudget instructions to precede the Ed25519 instruction.
-    let mut ix = None;
-    for idx in 0..8u16 {
+    count: usize,
+) -> Result<Vec<Ed25519Signature>> {
+    let ix = find_ed25519_instruction(instructions_sysvar)?;
+
+    let data = &ix.data;
+    require!(data.len() >= 2, VrfError::InvalidEd25519Instruction);
+    require!(data[0] as usize == count, VrfError::InvalidSignatureCount);
+
+    (0..count).map(|i| parse_signature_entry(data, i)).collect()
+}
+
+/// Find the first Ed25519 precompile instruction. Up to
+/// `MAX_SCANNED_INSTRUCTIONS` instructions are scanned, so ComputeBudget
+/// instructions may precede it.
+fn find_ed25519_instruction(instructions_sysvar: &UncheckedAccount) -> Result<Instruction> {
+    for idx in 0..MAX_SCANNED_INSTRUCTIONS {
         match sysvar_instructions::load_instruction_at_checked(
-            idx as usize,
+            idx,
             &instructions_sysvar.to_account_info(),
         ) {
-            Ok(loaded_ix) if loaded_ix.program_id == ed25519_program::ID => {
-                ix = Some(loaded_ix);
-                break;
-            }
+            Ok(loaded_ix) if loaded_ix.program_id == ed25519_program::ID => return Ok(loaded_ix),
             Ok(_) => continue,
             Err(_) => break,
         }
     }
-    let ix = ix.ok_or(VrfError::InvalidEd25519Instruction)?;
-
-    let data = &ix.data;
-    require!(data.len() >= 16, VrfError::InvalidEd25519Instruction);
+    err!(VrfError::InvalidEd25519Instruction)
+}
 
-    let num_signatures = data[0];
-    require!(num_signatures == 1, VrfError::InvalidSignatureCount);
+/// Parse the `index`-th `Ed25519SignatureOffsets` entry of a precompile
+/// instruction and copy out the data it references.
+fn parse_signature_entry(data: &[u8], index: usize) -> Result<Ed25519Signature> {
+    let start = 2 + index * SIGNATURE_OFFSETS_LEN;
+    require!(
+        data.len() >= start + SIGNATURE_OFFSETS_LEN,
+        VrfError::InvalidEd25519Instruction
+    );
+    let offsets = &data[start..start + SIGNATURE_OFFSETS_LEN];
+    let read_u16 = |i: usize| u16::from_le_bytes([offsets[i], offsets[i + 1]]);
 
     // Parse Ed25519SignatureOffsets
-    let sig_offset = u16::from_le_bytes([data[2], data[3]]);
-    let sig_ix_index = u16::from_le_bytes([data[4], data[5]]);
-    let pubkey_offset = u16::from_le_bytes([data[6], data[7]]);
-    let pubkey_ix_index = u16::from_le_bytes([data[8], data[9]]);
-    let msg_offset = u16::from_le_bytes([data[10], data[11]]);
-    let msg_size = u16::from_le_bytes([data[12], data[13]]);
-    let msg_ix_index = u16::from_le_bytes([data[14], data[15]]);
+    let sig_offset = read_u16(0);
+    let sig_ix_index = read_u16(2);
+    let pubkey_offset = read_u16(4);
+    let pubkey_ix_index = read_u16(6);
+    let msg_offset = read_u16(8);
+    let msg_size = read_u16(10);
+    let msg_ix_index = read_u16(12);
 
     // All indices must be self-referencing (0xFFFF = data within the same instruction)
-    let _ = sig_offset;
     require!(
         sig_ix_index == 0xFFFF,
         VrfError::InvalidEd25519InstructionIndex
@@ -76,30 +119,117 @@ pub fn verify_ed25519_instruction(
         VrfError::InvalidEd25519InstructionIndex
     );
 
-    // Verify the embedded public key matches the configured authority
     let pubkey_start = pubkey_offset as usize;
     let pubkey_end = pubkey_start + 32;
     require!(data.len() >= pubkey_end, VrfError::InvalidEd25519Instruction);
-    let pubkey_bytes = &data[pubkey_start..pubkey_end];
-    require!(
-        pubkey_bytes == expected_pubkey.to_bytes(),
-        VrfError::InvalidEd25519Pubkey
-    );
+    let mut public_key = [0u8; 32];
+    public_key.copy_from_slice(&data[pubkey_start..pubkey_end]);
+
+    let sig_start = sig_offset as usize;
+    let sig_end = sig_start + 64;
+    require!(data.len() >= sig_end, VrfError::InvalidEd25519Instruction);
+    let mut signature = [0u8; 64];
+    signature.copy_from_slice(&data[sig_start..sig_end]);
 
-    // Verify the signed message matches `request_id (8 LE) || randomness (32)`
     let msg_start = msg_offset as usize;
     let msg_end = msg_start + msg_size as usize;
     require!(data.len() >= msg_end, VrfError::InvalidEd25519Instruction);
-    let message = &data[msg_start..msg_end];
+    let message = data[msg_start..msg_end].to_vec();
+
+    Ok(Ed25519Signature {
+        public_key,
+        signature,
+        message,
+    })
+}
 
+/// Verify that the transaction's Ed25519 instruction proves `config.authority`
+/// signed `request_id (8 LE) || randomness (32)`.
+pub fn verify_ed25519_instruction(
+    instructions_sysvar: &UncheckedAccount,
+    expected_pubkey: &Pubkey,
+    request_id: u64,
+    randomness: &[u8; 32],
+) -> Result<()> {
+    let signed = load_ed25519_signature(instructions_sysvar)?;
+    check_ed25519_message(&signed, expected_pubkey, request_id, randomness)
+}
+
+/// Check that `signed` is `expected_pubkey`'s signature over
+/// `request_id (8 LE) || randomness (32)`.
+pub fn check_ed25519_message(
+    signed: &Ed25519Signature,
+    expected_pubkey: &Pubkey,
+    request_id: u64,
+    randomness: &[u8; 32],
+) -> Result<()> {
+    // Verify the embedded public key matches the configured authority
+    require!(
+        signed.public_key == expected_pubkey.to_bytes(),
+        VrfError::InvalidEd25519Pubkey
+    );
+
+    // Verify the signed message matches `request_id (8 LE) || randomness (32)`
     let mut expected_message = Vec::with_capacity(40);
     expected_message.extend_from_slice(&request_id.to_le_bytes());
     expected_message.extend_from_slice(randomness);
 
     require!(
-        message == expected_message.as_slice(),
+        signed.message == expected_message,
         VrfError::InvalidEd25519Message
     );
 
     Ok(())
 }
+
+/// Build the request-bound message signed under the signature-hash scheme.
+///
+/// ```text
+/// program_id (32) || request_id (8 LE) || seed (32) || request_slot (8 LE)
+/// ```
+pub fn signature_randomness_message(
+    program_id: &Pubkey,
+    request_id: u64,
+    seed: &[u8; 32],
+    request_slot: u64,
+) -> [u8; 80] {
+    let mut message = [0u8; 80];
+    message[..32].copy_from_slice(program_id.as_ref());
+    message[32..40].copy_from_slice(&request_id.to_le_bytes());
+    message[40..72].copy_from_slice(seed);
+    message[72..].copy_from_slice(&request_slot.to_le_bytes());
+    message
+}
+
+/// Verify that the transaction's Ed25519 instruction proves `config.authority`
+/// signed `expected_message`, and return `SHA256(signature)` as the randomness.
+///
+/// Anyone holding the fulfillment transaction can recompute the output from
+/// the signature it carries.
+pub fn verify_signature_randomness(
+    instructions_sysvar: &UncheckedAccount,
+    expected_pubkey: &Pubkey,
+    expected_message: &[u8],
+) -> Result<[u8; 32]> {
+    let signed = load_ed25519_signature(instructions_sysvar)?;
+    signature_randomness(&signed, expected_pubkey, expected_message)
+}
+
+/// Check that `signed` is `expected_pubkey`'s signature over
+/// `expected_message` and return `SHA256(signature)`.
+pub fn signature_randomness(
+    signed: &Ed25519Signature,
+    expected_pubkey: &Pubkey,
+    expected_message: &[u8],
+) -> Result<[u8; 32]> {
+    require!(
+        signed.public_key == expected_pubkey.to_bytes(),
+        VrfError::InvalidEd25519Pubkey
+    );
+    require!(
+        signed.message == expected_message,
+        VrfError::InvalidEd25519Message
+    );
+
+    Ok(Sha256::digest(signed.signature).into())
+}
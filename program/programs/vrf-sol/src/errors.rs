@@ -14,13 +14,13 @@ pub enum VrfError {
     /// The instruction at index 0 does not target the native Ed25519 program.
     #[msg("Invalid Ed25519 program")]
     InvalidEd25519Program,
-    /// Expected exactly one signature in the Ed25519 instruction.
+    /// The Ed25519 instruction carries an unexpected number of signatures.
     #[msg("Invalid signature count")]
     InvalidSignatureCount,
     /// The public key in the Ed25519 instruction does not match `config.authority`.
     #[msg("Invalid Ed25519 pubkey")]
     InvalidEd25519Pubkey,
-    /// The signed message does not match `request_id || randomness`.
+    /// The signed message does not match the message expected for the request.
     #[msg("Invalid Ed25519 message")]
     InvalidEd25519Message,
     /// Ed25519 instruction offset indices must be self-referencing (0xFFFF).
@@ -35,13 +35,15 @@ pub enum VrfError {
     /// The request counter would overflow u64.
     #[msg("Request counter overflow")]
     CounterOverflow,
-    /// The requested num_words exceeds the coordinator's max_num_words.
+    /// The requested num_words exceeds the coordinator's max_num_words, or
+    /// `MAX_STORE_ONLY_WORDS` where the words are returned as return data.
     #[msg("Number of words requested exceeds maximum")]
     NumWordsTooLarge,
     /// The subscription does not have enough balance to cover the fee.
     #[msg("Insufficient subscription balance")]
     InsufficientSubscriptionBalance,
-    /// The calling program is not a registered consumer for this subscription.
+    /// The calling program is not a registered consumer for this subscription,
+    /// or did not sign with its consumer authority PDA.
     #[msg("Invalid consumer program")]
     InvalidConsumerProgram,
     /// Cannot cancel a subscription that still has registered consumers.
@@ -50,4 +52,155 @@ pub enum VrfError {
     /// The callback CPI into the consumer program failed.
     #[msg("Consumer callback failed")]
     CallbackFailed,
+    /// The proof scheme value is not one of the `PROOF_SCHEME_*` constants.
+    #[msg("Unsupported proof scheme")]
+    InvalidProofScheme,
+    /// The fulfillment instruction does not match `config.proof_scheme`.
+    #[msg("Fulfillment does not match the configured proof scheme")]
+    ProofSchemeMismatch,
+    /// The ECVRF proof is malformed or does not verify against `config.authority`.
+    #[msg("Invalid VRF proof")]
+    InvalidVrfProof,
+    /// The account to migrate is not owned by this program, is of another
+    /// type, or is already larger than the current layout.
+    #[msg("Invalid account migration")]
+    InvalidMigration,
+    /// The submitted randomness is not `SHA256(signature)` of the verified signature.
+    #[msg("Randomness does not match the signature-derived output")]
+    RandomnessMismatch,
+    /// The oracle set is empty, too large, contains duplicates or the zero
+    /// address, or the threshold is out of range.
+    #[msg("Invalid oracle set or threshold")]
+    InvalidOracleSet,
+    /// An oracle appears more than once, or out of index order, in a
+    /// multi-oracle fulfillment.
+    #[msg("Duplicate or unordered oracle proof")]
+    DuplicateOracleSignature,
+    /// A multi-oracle fulfillment carries fewer proofs than
+    /// `config.oracle_threshold` requires.
+    #[msg("Not enough oracle proofs")]
+    InsufficientOracleSignatures,
+    /// A multi-oracle fulfillment carries more proofs than
+    /// `config.oracle_threshold`.
+    #[msg("Too many oracle proofs")]
+    TooManyOracleProofs,
+    /// A multi-oracle proof names an unregistered oracle or, before the
+    /// request's fallback slot, an oracle outside its committee.
+    #[msg("Oracle not selected for this request")]
+    OracleNotSelected,
+    /// The request's timeout has not yet elapsed.
+    #[msg("Request has not expired yet")]
+    RequestNotExpired,
+    /// `request_timeout_slots` must be non-zero.
+    #[msg("Request timeout must be greater than zero")]
+    InvalidRequestTimeout,
+    /// The request is not awaiting a callback retry.
+    #[msg("Request is not awaiting callback retry")]
+    CallbackNotFailed,
+    /// The request has reached `MAX_CALLBACK_ATTEMPTS` recorded failures.
+    #[msg("Callback retries exhausted")]
+    CallbackRetriesExhausted,
+    /// A reported callback failure reason is longer than
+    /// `MAX_CALLBACK_FAILURE_LEN`.
+    #[msg("Callback failure reason too long")]
+    CallbackFailureTooLong,
+    /// The request holds no stored randomness to consume.
+    #[msg("Request has no stored randomness to consume")]
+    RandomnessNotStored,
+    /// The signer is not the consumer program's `consumer-authority` PDA.
+    #[msg("Invalid consumer authority")]
+    InvalidConsumerAuthority,
+    /// More callback accounts than the request can store: over
+    /// `MAX_CALLBACK_ACCOUNTS` without a sidecar, or over
+    /// `MAX_SIDECAR_CALLBACK_ACCOUNTS` with one.
+    #[msg("Too many callback accounts")]
+    TooManyCallbackAccounts,
+    /// The callback accounts sidecar or lookup table is not the expected account.
+    #[msg("Invalid callback accounts sidecar or lookup table")]
+    InvalidCallbackAccounts,
+    /// The request PDA still exists, so its sidecar cannot be closed yet.
+    #[msg("Request is still open")]
+    RequestStillOpen,
+    /// The remaining accounts passed for the callback differ from the stored
+    /// callback accounts in key, order, count or writability.
+    #[msg("Callback accounts do not match the request")]
+    CallbackAccountsMismatch,
+    /// The subscription still has pending requests whose fees it holds.
+    #[msg("Subscription has pending requests")]
+    SubscriptionHasPendingRequests,
+    /// `protocol_fee_bps` is above `MAX_PROTOCOL_FEE_BPS`.
+    #[msg("Protocol fee must be at most 10000 basis points")]
+    InvalidProtocolFee,
+    /// The withdrawal exceeds the accrued fees.
+    #[msg("Insufficient fees to withdraw")]
+    InsufficientFees,
+    /// The signer is not the subscription's pending owner, or no transfer is pending.
+    #[msg("Signer is not the pending subscription owner")]
+    NotPendingSubscriptionOwner,
+    /// The mint is a Token-2022 mint with an extension outside the supported
+    /// set (e.g. a transfer hook, transfer fee or permanent delegate).
+    #[msg("Mint has unsupported token extensions")]
+    UnsupportedMint,
+    /// The mint is not approved for billing, is disabled, or its
+    /// `ApprovedMint` account was not supplied.
+    #[msg("Mint is not approved for billing")]
+    MintNotApproved,
+    /// A SOL instruction was used on a token-billed subscription, or the reverse.
+    #[msg("Instruction does not match the subscription's billing currency")]
+    BillingCurrencyMismatch,
+    /// A token account or token program is missing or is not the expected one.
+    #[msg("Invalid token account")]
+    InvalidTokenAccount,
+    /// The request's `callback_compute_limit` exceeds
+    /// `config.max_callback_compute_limit`, or that maximum exceeds a
+    /// transaction's compute budget.
+    #[msg("Callback compute limit too large")]
+    CallbackComputeLimitTooLarge,
+    /// Fulfillment was attempted before `request_slot + min_confirmation_slots`.
+    #[msg("Request has not reached its confirmation slot")]
+    RequestNotConfirmed,
+    /// `min_confirmation_slots` must be below `request_timeout_slots`, so a
+    /// request can be fulfilled before it can be cancelled.
+    #[msg("Confirmation slots must be below the request timeout")]
+    InvalidConfirmationSlots,
+    /// The coordinator is paused.
+    #[msg("Coordinator is paused")]
+    CoordinatorPaused,
+    /// The staged config update's delay has not yet elapsed.
+    #[msg("Config update is still timelocked")]
+    ConfigUpdateNotReady,
+    /// The signer is not the pending admin, or no admin transfer is pending.
+    #[msg("Signer is not the pending admin")]
+    NotPendingAdmin,
+    /// `config_update_delay_slots` is outside
+    /// `MIN_CONFIG_UPDATE_DELAY_SLOTS..=MAX_CONFIG_UPDATE_DELAY_SLOTS`.
+    #[msg("Config update delay out of range")]
+    InvalidConfigUpdateDelay,
+    /// A batch must fulfill between 1 and `MAX_BATCH_FULFILLMENTS` requests.
+    #[msg("Invalid batch size")]
+    InvalidBatchSize,
+    /// The request would take the consumer's fees this epoch above its
+    /// `max_fee_per_epoch`.
+    #[msg("Consumer fee limit exceeded")]
+    ConsumerFeeLimitExceeded,
+    /// The consumer has made `max_requests_per_window` requests in the
+    /// current window.
+    #[msg("Consumer rate limit exceeded")]
+    ConsumerRateLimitExceeded,
+    /// A request rate limit needs a non-zero `request_window_slots`.
+    #[msg("Invalid consumer limits")]
+    InvalidConsumerLimits,
+    /// The subscription holding the request's fee was not passed.
+    #[msg("Fee account required")]
+    MissingFeeAccount,
+    /// A wallet request was fulfilled without its `RandomnessResult`.
+    #[msg("Randomness result account required")]
+    MissingRandomnessResult,
+    /// `user_data` is longer than `MAX_USER_DATA_LEN`.
+    #[msg("User data too long")]
+    UserDataTooLong,
+
+    /// A callback prefix is longer than `MAX_CALLBACK_PREFIX_LEN`.
+    #[msg("Callback prefix too long")]
+    CallbackPrefixTooLong,
 }
@@ -1,10 +1,14 @@
 use anchor_lang::prelude::*;
 
+use crate::state::ConfigChanges;
+
 /// Emitted when a new subscription is created.
 #[event]
 pub struct SubscriptionCreated {
     pub subscription_id: u64,
     pub owner: Pubkey,
+    /// Billing mint, or the default pubkey for SOL.
+    pub billing_mint: Pubkey,
 }
 
 /// Emitted when a subscription is funded with SOL.
@@ -15,6 +19,32 @@ pub struct SubscriptionFunded {
     pub new_balance: u64,
 }
 
+/// Emitted when the owner withdraws part of a subscription's balance.
+#[event]
+pub struct SubscriptionWithdrawn {
+    pub subscription_id: u64,
+    pub old_balance: u64,
+    pub new_balance: u64,
+    pub recipient: Pubkey,
+}
+
+/// Emitted when the owner proposes a new subscription owner. A default
+/// `pending_owner` means the proposal was withdrawn.
+#[event]
+pub struct SubscriptionOwnerTransferRequested {
+    pub subscription_id: u64,
+    pub owner: Pubkey,
+    pub pending_owner: Pubkey,
+}
+
+/// Emitted when the pending owner accepts a subscription.
+#[event]
+pub struct SubscriptionOwnerTransferred {
+    pub subscription_id: u64,
+    pub old_owner: Pubkey,
+    pub new_owner: Pubkey,
+}
+
 /// Emitted when a subscription is cancelled and balance refunded.
 #[event]
 pub struct SubscriptionCancelled {
@@ -37,6 +67,79 @@ pub struct ConsumerRemoved {
     pub consumer_program: Pubkey,
 }
 
+/// Emitted when a subscription owner changes a consumer's limits.
+#[event]
+pub struct ConsumerLimitsUpdated {
+    pub subscription_id: u64,
+    pub consumer_program: Pubkey,
+    pub max_fee_per_epoch: u64,
+    pub max_requests_per_window: u32,
+    pub request_window_slots: u64,
+    pub max_num_words: u32,
+}
+
+/// Emitted when a consumer program changes its default callback prefix.
+#[event]
+pub struct ConsumerCallbackUpdated {
+    pub subscription_id: u64,
+    pub consumer_program: Pubkey,
+    pub callback_prefix: Vec<u8>,
+}
+
+/// Emitted when an executed config update replaces the multi-oracle set.
+#[event]
+pub struct OracleSetUpdated {
+    pub oracles: Vec<Pubkey>,
+    pub threshold: u8,
+}
+
+/// Emitted when the admin stages a config update.
+#[event]
+pub struct ConfigUpdateProposed {
+    pub changes: ConfigChanges,
+    pub proposed_slot: u64,
+    pub executable_slot: u64,
+}
+
+/// Emitted when a staged config update is applied.
+#[event]
+pub struct ConfigUpdateExecuted {
+    pub changes: ConfigChanges,
+}
+
+/// Emitted when the admin discards a staged config update.
+#[event]
+pub struct ConfigUpdateCancelled {
+    pub changes: ConfigChanges,
+}
+
+/// Emitted when an executed config update names a new admin, who must
+/// accept with `accept_admin_transfer`.
+#[event]
+pub struct AdminTransferRequested {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+/// Emitted when the pending admin accepts the role.
+#[event]
+pub struct AdminTransferred {
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+/// Emitted when the pauser or admin pauses the coordinator.
+#[event]
+pub struct CoordinatorPaused {
+    pub paused_by: Pubkey,
+}
+
+/// Emitted when the admin unpauses the coordinator.
+#[event]
+pub struct CoordinatorUnpaused {
+    pub unpaused_by: Pubkey,
+}
+
 /// Emitted when a new randomness request is created.
 ///
 /// The off-chain oracle backend subscribes to these events via WebSocket log
@@ -51,6 +154,34 @@ pub struct RandomWordsRequested {
     pub seed: [u8; 32],
     pub request_slot: u64,
     pub callback_compute_limit: u32,
+    /// Slots after `request_slot` the oracle must wait before fulfilling.
+    pub min_confirmation_slots: u64,
+}
+
+/// Emitted when an expired request is cancelled and its fee re-credited.
+///
+/// The oracle backend stops working on the request when it sees this event.
+#[event]
+pub struct RandomWordsCancelled {
+    pub request_id: u64,
+    pub subscription_id: u64,
+    pub cancelled_by: Pubkey,
+    pub refunded_fee: u64,
+}
+
+/// Emitted when verified randomness is stored but the consumer callback could
+/// not be delivered. The request stays open for `retry_callback`.
+///
+/// `reason` is the failure the oracle observed when simulating the callback.
+/// The coordinator cannot observe a reverted CPI, so it is reported by the
+/// oracle, not checked.
+#[event]
+pub struct RandomWordsCallbackFailed {
+    pub request_id: u64,
+    pub randomness: [u8; 32],
+    pub consumer_program: Pubkey,
+    pub attempts: u8,
+    pub reason: String,
 }
 
 /// Emitted when the oracle fulfills a request and delivers the callback.
@@ -60,3 +191,78 @@ pub struct RandomWordsFulfilled {
     pub randomness: [u8; 32],
     pub consumer_program: Pubkey,
 }
+
+/// Emitted when the admin grows an account to the current layout.
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub old_len: u32,
+    pub new_len: u32,
+}
+
+/// Emitted when a consumer pulls stored randomness and closes the request.
+#[event]
+pub struct RandomnessConsumed {
+    pub request_id: u64,
+    pub consumer_program: Pubkey,
+}
+
+/// Emitted when a fulfilled request's fee moves from its subscription, or a
+/// direct request's PDA, to the fee vault.
+#[event]
+pub struct FeesCollected {
+    pub request_id: u64,
+    pub subscription_id: u64,
+    pub oracle_fee: u64,
+    pub protocol_fee: u64,
+    /// Mint the fees are denominated in, or the default pubkey for lamports.
+    pub fee_mint: Pubkey,
+}
+
+/// Emitted when the oracle authority withdraws its earnings.
+#[event]
+pub struct OracleFeesWithdrawn {
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+/// Emitted when the admin withdraws protocol earnings.
+#[event]
+pub struct ProtocolFeesWithdrawn {
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+/// Emitted when the admin approves a mint for subscription billing.
+#[event]
+pub struct MintApproved {
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    pub fee_per_word: u64,
+}
+
+/// Emitted when the admin changes an approved mint's prices or status.
+#[event]
+pub struct ApprovedMintUpdated {
+    pub mint: Pubkey,
+    pub fee_per_word: u64,
+    pub enabled: bool,
+    pub base_fee: u64,
+    pub compute_unit_price: u64,
+}
+
+/// Emitted when the oracle authority withdraws token earnings.
+#[event]
+pub struct OracleTokenFeesWithdrawn {
+    pub mint: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+/// Emitted when the admin withdraws protocol token earnings.
+#[event]
+pub struct ProtocolTokenFeesWithdrawn {
+    pub mint: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
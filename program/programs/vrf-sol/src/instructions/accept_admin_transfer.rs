@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::VrfError;
+use crate::events::AdminTransferred;
+use crate::state::CoordinatorConfig;
+
+/// Accounts required to accept the admin role.
+#[derive(Accounts)]
+pub struct AcceptAdminTransfer<'info> {
+    /// The pending admin named by an executed config update; must sign.
+    pub new_admin: Signer<'info>,
+
+    /// Coordinator configuration PDA to update.
+    #[account(
+        mut,
+        seeds = [b"coordinator-config"],
+        bump = config.bump,
+        constraint = config.pending_admin != Pubkey::default()
+            && config.pending_admin == new_admin.key() @ VrfError::NotPendingAdmin,
+    )]
+    pub config: Account<'info, CoordinatorConfig>,
+}
+
+/// Make the pending admin the admin and clear the pending slot.
+pub fn handler(ctx: Context<AcceptAdminTransfer>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let old_admin = config.admin;
+    config.admin = ctx.accounts.new_admin.key();
+    config.pending_admin = Pubkey::default();
+
+    emit!(AdminTransferred {
+        old_admin,
+        new_admin: config.admin,
+    });
+
+    Ok(())
+}
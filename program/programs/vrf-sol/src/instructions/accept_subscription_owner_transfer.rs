@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::VrfError;
+use crate::events::SubscriptionOwnerTransferred;
+use crate::state::Subscription;
+
+/// Accounts required to accept ownership of a subscription.
+#[derive(Accounts)]
+#[instruction(subscription_id: u64)]
+pub struct AcceptSubscriptionOwnerTransfer<'info> {
+    /// The pending owner named by the current owner; must sign.
+    pub new_owner: Signer<'info>,
+
+    /// The subscription PDA being transferred.
+    #[account(
+        mut,
+        seeds = [b"subscription", subscription_id.to_le_bytes().as_ref()],
+        bump = subscription.bump,
+        constraint = subscription.pending_owner != Pubkey::default()
+            && subscription.pending_owner == new_owner.key() @ VrfError::NotPendingSubscriptionOwner,
+    )]
+    pub subscription: Account<'info, Subscription>,
+}
+
+/// Make the pending owner the subscription owner and clear the pending slot.
+///
+/// Consumer registrations are keyed by subscription ID, not owner, so they
+/// carry over unchanged.
+pub fn handler(ctx: Context<AcceptSubscriptionOwnerTransfer>, _subscription_id: u64) -> Result<()> {
+    let subscription = &mut ctx.accounts.subscription;
+    let old_owner = subscription.owner;
+    subscription.owner = ctx.accounts.new_owner.key();
+    subscription.pending_owner = Pubkey::default();
+
+    emit!(SubscriptionOwnerTransferred {
+        subscription_id: subscription.id,
+        old_owner,
+        new_owner: subscription.owner,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::errors::VrfError;
+use crate::events::MintApproved;
+use crate::state::{ApprovedMint, CoordinatorConfig, FeeVault};
+use crate::token::check_mint_extensions;
+
+/// Accounts required to approve an SPL mint for subscription billing.
+#[derive(Accounts)]
+pub struct ApproveMint<'info> {
+    /// Current admin; must sign and pays for account creation.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Coordinator configuration PDA (read for `admin`).
+    #[account(
+        seeds = [b"coordinator-config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ VrfError::Unauthorized,
+    )]
+    pub config: Account<'info, CoordinatorConfig>,
+
+    /// The mint to approve.
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// New approved mint PDA. Seeds: `["approved-mint", mint]`.
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + ApprovedMint::INIT_SPACE,
+        seeds = [b"approved-mint", mint.key().as_ref()],
+        bump,
+    )]
+    pub approved_mint: Account<'info, ApprovedMint>,
+
+    /// Fee vault PDA; owns the token account that collects this mint's fees.
+    #[account(
+        seeds = [b"fee-vault"],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+
+    /// The fee vault's associated token account for `mint`.
+    #[account(
+        init_if_needed,
+        payer = admin,
+        associated_token::mint = mint,
+        associated_token::authority = fee_vault,
+        associated_token::token_program = token_program,
+    )]
+    pub fee_vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Approve `mint` for billing at `fee_per_word` base units per word.
+pub fn handler(ctx: Context<ApproveMint>, fee_per_word: u64) -> Result<()> {
+    check_mint_extensions(&ctx.accounts.mint.to_account_info())?;
+
+    let approved_mint = &mut ctx.accounts.approved_mint;
+    approved_mint.mint = ctx.accounts.mint.key();
+    approved_mint.token_program = ctx.accounts.token_program.key();
+    approved_mint.fee_per_word = fee_per_word;
+    approved_mint.enabled = true;
+    approved_mint.oracle_fees = 0;
+    approved_mint.protocol_fees = 0;
+    approved_mint.bump = ctx.bumps.approved_mint;
+    approved_mint.base_fee = 0;
+    approved_mint.compute_unit_price = 0;
+
+    emit!(MintApproved {
+        mint: approved_mint.mint,
+        token_program: approved_mint.token_program,
+        fee_per_word,
+    });
+
+    Ok(())
+}
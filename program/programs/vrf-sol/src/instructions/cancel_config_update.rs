@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::VrfError;
+use crate::events::ConfigUpdateCancelled;
+use crate::state::{CoordinatorConfig, PendingConfigUpdate};
+
+/// Accounts required to discard a staged config update.
+#[derive(Accounts)]
+pub struct CancelConfigUpdate<'info> {
+    /// Current admin; must sign and receives the pending update's rent.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Coordinator configuration PDA (used to verify the admin).
+    #[account(
+        seeds = [b"coordinator-config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ VrfError::Unauthorized,
+    )]
+    pub config: Account<'info, CoordinatorConfig>,
+
+    /// The staged update to discard.
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"pending-config-update"],
+        bump = pending_config_update.bump,
+    )]
+    pub pending_config_update: Account<'info, PendingConfigUpdate>,
+}
+
+/// Discard the staged config update without applying it.
+pub fn handler(ctx: Context<CancelConfigUpdate>) -> Result<()> {
+    emit!(ConfigUpdateCancelled {
+        changes: ctx.accounts.pending_config_update.changes.clone(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::VrfError;
+use crate::events::RandomWordsCancelled;
+use crate::state::{CoordinatorConfig, RandomnessRequest, Subscription};
+
+/// Accounts required to cancel an expired randomness request.
+#[derive(Accounts)]
+#[instruction(request_id: u64)]
+pub struct CancelRequest<'info> {
+    /// The original requester or the subscription owner (only the requester
+    /// for direct-funded requests).
+    pub caller: Signer<'info>,
+
+    /// Coordinator configuration PDA (read for `request_timeout_slots`).
+    #[account(
+        seeds = [b"coordinator-config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, CoordinatorConfig>,
+
+    /// The pending request to cancel. Closed; rent, and a direct-funded
+    /// request's fee, refunded to requester.
+    #[account(
+        mut,
+        close = requester,
+        seeds = [b"vrf-request", request_id.to_le_bytes().as_ref()],
+        bump = request.bump,
+        constraint = request.status == RandomnessRequest::STATUS_PENDING @ VrfError::RequestNotPending,
+    )]
+    pub request: Account<'info, RandomnessRequest>,
+
+    /// The subscription that paid for the request. The fee is re-credited.
+    /// Omitted for direct-funded requests.
+    #[account(
+        mut,
+        seeds = [b"subscription", request.subscription_id.to_le_bytes().as_ref()],
+        bump = subscription.bump,
+    )]
+    pub subscription: Option<Account<'info, Subscription>>,
+
+    /// The original requester who receives the rent refund.
+    /// CHECK: Validated by matching request.requester.
+    #[account(
+        mut,
+        constraint = requester.key() == request.requester @ VrfError::Unauthorized,
+    )]
+    pub requester: UncheckedAccount<'info>,
+}
+
+/// Cancel a request that the oracle has not fulfilled within
+/// `config.request_timeout_slots` of `request_slot`.
+///
+/// A subscription request's fee is re-credited to the subscription balance;
+/// a direct-funded request's fee is held in the request PDA and refunded to
+/// the requester when it closes.
+pub fn handler(ctx: Context<CancelRequest>, request_id: u64) -> Result<()> {
+    let caller = ctx.accounts.caller.key();
+    let request = &ctx.accounts.request;
+    let is_owner = ctx
+        .accounts
+        .subscription
+        .as_ref()
+        .is_some_and(|subscription| subscription.owner == caller);
+    require!(
+        caller == request.requester || is_owner,
+        VrfError::Unauthorized
+    );
+
+    let deadline = request
+        .request_slot
+        .saturating_add(ctx.accounts.config.request_timeout_slots);
+    require!(
+        Clock::get()?.slot >= deadline,
+        VrfError::RequestNotExpired
+    );
+
+    let refunded_fee = request.fee_paid;
+    let subscription_id = request.subscription_id;
+    if subscription_id != Subscription::DIRECT_SUBSCRIPTION_ID {
+        // The fee is still held in the subscription PDA as a pending fee, so
+        // moving it back to the balance is enough.
+        let subscription = ctx
+            .accounts
+            .subscription
+            .as_mut()
+            .ok_or(VrfError::MissingFeeAccount)?;
+        subscription.pending_fees = subscription
+            .pending_fees
+            .checked_sub(refunded_fee)
+            .ok_or(VrfError::CounterOverflow)?;
+        subscription.balance = subscription
+            .balance
+            .checked_add(refunded_fee)
+            .ok_or(VrfError::CounterOverflow)?;
+    }
+
+    emit!(RandomWordsCancelled {
+        request_id,
+        subscription_id,
+        cancelled_by: caller,
+        refunded_fee,
+    });
+
+    Ok(())
+}
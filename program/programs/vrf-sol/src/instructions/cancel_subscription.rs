@@ -12,27 +12,28 @@ pub struct CancelSubscription<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
 
-    /// The subscription PDA to cancel. Must have no registered consumers.
+    /// The SOL-billed subscription PDA to cancel. Must have no registered
+    /// consumers and no pending requests.
     #[account(
         mut,
         seeds = [b"subscription", subscription_id.to_le_bytes().as_ref()],
         bump = subscription.bump,
         constraint = subscription.owner == owner.key() @ VrfError::Unauthorized,
+        constraint = !subscription.is_token_billed() @ VrfError::BillingCurrencyMismatch,
         constraint = subscription.consumer_count == 0 @ VrfError::SubscriptionHasConsumers,
+        constraint = subscription.pending_fees == 0 @ VrfError::SubscriptionHasPendingRequests,
         close = owner,
     )]
     pub subscription: Account<'info, Subscription>,
 }
 
-/// Cancel a subscription, refunding the remaining balance and rent to the owner.
+/// Cancel a subscription, refunding the unspent balance and rent to the owner.
 pub fn handler(ctx: Context<CancelSubscription>, _subscription_id: u64) -> Result<()> {
     let subscription = &ctx.accounts.subscription;
     let refunded = subscription.balance;
 
-    // If the subscription has a balance beyond rent, transfer it back to the owner
-    // before Anchor closes the account. We need to do a manual lamport transfer
-    // since the subscription balance is tracked separately from the account lamports.
-    // Note: Anchor's `close` will handle returning all lamports (including balance).
+    // Fees of fulfilled requests have already moved to the fee vault and there
+    // are no pending fees, so Anchor's `close` returns exactly rent + balance.
 
     emit!(SubscriptionCancelled {
         subscription_id: subscription.id,
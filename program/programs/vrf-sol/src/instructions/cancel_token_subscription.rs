@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, CloseAccount, Mint, TokenAccount, TokenInterface};
+
+use crate::errors::VrfError;
+use crate::events::SubscriptionCancelled;
+use crate::state::Subscription;
+use crate::token::transfer_from_pda;
+
+/// Accounts required to cancel a token-billed subscription and reclaim its
+/// tokens and rent.
+#[derive(Accounts)]
+#[instruction(subscription_id: u64)]
+pub struct CancelTokenSubscription<'info> {
+    /// The subscription owner; receives the rent of both closed accounts.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The token-billed subscription PDA to cancel. Must have no registered
+    /// consumers and no pending requests.
+    #[account(
+        mut,
+        seeds = [b"subscription", subscription_id.to_le_bytes().as_ref()],
+        bump = subscription.bump,
+        constraint = subscription.owner == owner.key() @ VrfError::Unauthorized,
+        constraint = subscription.is_token_billed() @ VrfError::BillingCurrencyMismatch,
+        constraint = subscription.consumer_count == 0 @ VrfError::SubscriptionHasConsumers,
+        constraint = subscription.pending_fees == 0 @ VrfError::SubscriptionHasPendingRequests,
+        close = owner,
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    /// The subscription's billing mint.
+    #[account(address = subscription.billing_mint @ VrfError::BillingCurrencyMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// The subscription's associated token account; emptied and closed.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = subscription,
+        associated_token::token_program = token_program,
+    )]
+    pub subscription_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Receives the refunded tokens.
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub recipient: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Cancel a token-billed subscription: refund every token in its vault to
+/// `recipient`, close the vault and the subscription, returning rent to the owner.
+pub fn handler(ctx: Context<CancelTokenSubscription>, _subscription_id: u64) -> Result<()> {
+    let subscription = &ctx.accounts.subscription;
+    let refunded = ctx.accounts.subscription_vault.amount;
+    let id = subscription.id.to_le_bytes();
+    let seeds: &[&[&[u8]]] = &[&[b"subscription", id.as_ref(), &[subscription.bump]]];
+
+    if refunded > 0 {
+        transfer_from_pda(
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.subscription_vault.to_account_info(),
+            ctx.accounts.recipient.to_account_info(),
+            &ctx.accounts.mint,
+            subscription.to_account_info(),
+            seeds,
+            refunded,
+        )?;
+    }
+
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.subscription_vault.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: subscription.to_account_info(),
+        },
+        seeds,
+    ))?;
+
+    emit!(SubscriptionCancelled {
+        subscription_id: subscription.id,
+        owner: subscription.owner,
+        refunded_amount: refunded,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::VrfError;
+use crate::state::CallbackAccounts;
+
+/// Accounts required to close a request's callback accounts sidecar.
+#[derive(Accounts)]
+#[instruction(request_id: u64)]
+pub struct CloseCallbackAccounts<'info> {
+    /// The sidecar to close. Rent refunded to the requester.
+    #[account(
+        mut,
+        close = requester,
+        seeds = [b"callback-accounts", request_id.to_le_bytes().as_ref()],
+        bump = callback_accounts.bump,
+    )]
+    pub callback_accounts: Account<'info, CallbackAccounts>,
+
+    /// The request PDA, which must already be closed.
+    /// CHECK: Only its address and emptiness are checked.
+    #[account(
+        seeds = [b"vrf-request", request_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub request: UncheckedAccount<'info>,
+
+    /// The requester who paid the sidecar rent.
+    /// CHECK: Validated by matching callback_accounts.requester.
+    #[account(
+        mut,
+        constraint = requester.key() == callback_accounts.requester @ VrfError::Unauthorized,
+    )]
+    pub requester: UncheckedAccount<'info>,
+}
+
+/// Close the sidecar of a request that has been fulfilled, consumed or
+/// cancelled. Permissionless: the rent always goes back to the requester.
+pub fn handler(ctx: Context<CloseCallbackAccounts>, _request_id: u64) -> Result<()> {
+    require!(
+        ctx.accounts.request.data_is_empty(),
+        VrfError::RequestStillOpen
+    );
+    Ok(())
+}
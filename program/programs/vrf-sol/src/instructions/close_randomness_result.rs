@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::VrfError;
+use crate::state::RandomnessResult;
+
+/// Accounts required to close a wallet request's result.
+#[derive(Accounts)]
+#[instruction(request_id: u64)]
+pub struct CloseRandomnessResult<'info> {
+    /// The requester who paid the result rent; only they may close it.
+    #[account(
+        mut,
+        constraint = requester.key() == randomness_result.requester @ VrfError::Unauthorized,
+    )]
+    pub requester: Signer<'info>,
+
+    /// The result to close. Rent refunded to the requester.
+    #[account(
+        mut,
+        close = requester,
+        seeds = [b"randomness-result", request_id.to_le_bytes().as_ref()],
+        bump = randomness_result.bump,
+    )]
+    pub randomness_result: Account<'info, RandomnessResult>,
+
+    /// The request PDA, which must already be closed.
+    /// CHECK: Only its address and emptiness are checked.
+    #[account(
+        seeds = [b"vrf-request", request_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub request: UncheckedAccount<'info>,
+}
+
+/// Close the result of a wallet request that has been fulfilled or
+/// cancelled. Unlike callback sidecars the result is the requester's record
+/// of the output, so only the requester can close it.
+pub fn handler(ctx: Context<CloseRandomnessResult>, _request_id: u64) -> Result<()> {
+    require!(
+        ctx.accounts.request.data_is_empty(),
+        VrfError::RequestStillOpen
+    );
+    Ok(())
+}
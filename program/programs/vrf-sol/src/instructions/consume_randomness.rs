@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::VrfError;
+use crate::events::RandomnessConsumed;
+use crate::instructions::fulfill_random_words::expand_randomness;
+use crate::state::{RandomnessRequest, CONSUMER_AUTHORITY_SEED};
+
+/// Accounts required to consume stored randomness.
+///
+/// Called via CPI from the consumer program, which signs with its
+/// `["consumer-authority"]` PDA.
+#[derive(Accounts)]
+#[instruction(request_id: u64)]
+pub struct ConsumeRandomness<'info> {
+    /// The consumer program's authority PDA.
+    #[account(
+        constraint = consumer_authority.key()
+            == Pubkey::find_program_address(&[CONSUMER_AUTHORITY_SEED], &request.consumer_program).0
+            @ VrfError::InvalidConsumerAuthority,
+    )]
+    pub consumer_authority: Signer<'info>,
+
+    /// The request holding stored randomness. Closed; rent refunded to requester.
+    #[account(
+        mut,
+        close = requester,
+        seeds = [b"vrf-request", request_id.to_le_bytes().as_ref()],
+        bump = request.bump,
+        constraint = request.status == RandomnessRequest::STATUS_STORED
+            || request.status == RandomnessRequest::STATUS_CALLBACK_FAILED
+            @ VrfError::RandomnessNotStored,
+    )]
+    pub request: Account<'info, RandomnessRequest>,
+
+    /// The original requester who receives the rent refund.
+    /// CHECK: Validated by matching request.requester.
+    #[account(
+        mut,
+        constraint = requester.key() == request.requester @ VrfError::Unauthorized,
+    )]
+    pub requester: UncheckedAccount<'info>,
+}
+
+/// Return the expanded random words of a stored request and close it.
+///
+/// Accepts store-only requests (`STATUS_STORED`) and requests whose callback
+/// failed (`STATUS_CALLBACK_FAILED`), so a consumer can stop waiting for
+/// `retry_callback` and pull the result instead.
+///
+/// A failed callback may have more than `MAX_STORE_ONLY_WORDS` words, which
+/// do not fit in return data. Those requests are rejected; the consumer pulls
+/// them with `consume_randomness_seed` instead.
+pub fn handler(ctx: Context<ConsumeRandomness>, request_id: u64) -> Result<Vec<[u8; 32]>> {
+    let request = &ctx.accounts.request;
+    require!(
+        request.num_words <= RandomnessRequest::MAX_STORE_ONLY_WORDS,
+        VrfError::NumWordsTooLarge
+    );
+    let words = expand_randomness(&request.randomness, request.num_words);
+
+    emit!(RandomnessConsumed {
+        request_id,
+        consumer_program: request.consumer_program,
+    });
+
+    Ok(words)
+}
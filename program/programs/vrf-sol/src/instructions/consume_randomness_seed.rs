@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+
+use crate::events::RandomnessConsumed;
+use crate::instructions::consume_randomness::ConsumeRandomness;
+
+/// Return the 32-byte output of a stored request and close it.
+///
+/// Takes the same accounts and statuses as `consume_randomness` but works for
+/// any `num_words`: the consumer expands the output itself, as
+/// `SHA256(randomness || i_le_bytes)` for word `i`.
+pub fn handler(ctx: Context<ConsumeRandomness>, request_id: u64) -> Result<[u8; 32]> {
+    let request = &ctx.accounts.request;
+
+    emit!(RandomnessConsumed {
+        request_id,
+        consumer_program: request.consumer_program,
+    });
+
+    Ok(request.randomness)
+}
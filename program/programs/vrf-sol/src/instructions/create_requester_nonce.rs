@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::VrfError;
+use crate::state::{CoordinatorConfig, RequesterNonce, RequesterNonceCounter, Subscription};
+
+/// Accounts required to create a requester's nonce for direct requests.
+#[derive(Accounts)]
+pub struct CreateRequesterNonce<'info> {
+    /// The wallet that will make direct requests; pays for account creation.
+    #[account(mut)]
+    pub requester: Signer<'info>,
+
+    /// Coordinator configuration PDA.
+    #[account(
+        seeds = [b"coordinator-config"],
+        bump = config.bump,
+        constraint = !config.paused @ VrfError::CoordinatorPaused,
+    )]
+    pub config: Account<'info, CoordinatorConfig>,
+
+    /// Nonce counter PDA, created by the first nonce (mutated to count it).
+    #[account(
+        init_if_needed,
+        payer = requester,
+        space = 8 + RequesterNonceCounter::INIT_SPACE,
+        seeds = [b"requester-nonce-counter"],
+        bump,
+    )]
+    pub nonce_counter: Account<'info, RequesterNonceCounter>,
+
+    /// New requester nonce PDA.
+    #[account(
+        init,
+        payer = requester,
+        space = 8 + RequesterNonce::INIT_SPACE,
+        seeds = [b"requester-nonce", requester.key().as_ref()],
+        bump,
+    )]
+    pub requester_nonce: Account<'info, RequesterNonce>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the nonce that numbers `requester`'s direct-funded requests.
+///
+/// Needed once, before the requester's first `request_random_words_direct`
+/// or `request_wallet_randomness`. Those only write the nonce, so direct
+/// requests by different requesters run in parallel.
+pub fn handler(ctx: Context<CreateRequesterNonce>) -> Result<()> {
+    let nonce_counter = &mut ctx.accounts.nonce_counter;
+    let id = Subscription::FIRST_REQUESTER_NONCE_ID + nonce_counter.count;
+    // The last ID is reserved as the subscription ID of direct requests.
+    require!(
+        id < Subscription::DIRECT_SUBSCRIPTION_ID,
+        VrfError::CounterOverflow
+    );
+    nonce_counter.count += 1;
+    nonce_counter.bump = ctx.bumps.nonce_counter;
+
+    let requester_nonce = &mut ctx.accounts.requester_nonce;
+    requester_nonce.requester = ctx.accounts.requester.key();
+    requester_nonce.id = id;
+    requester_nonce.req_count = 0;
+    requester_nonce.bump = ctx.bumps.requester_nonce;
+    Ok(())
+}
@@ -16,6 +16,7 @@ pub struct CreateSubscription<'info> {
         mut,
         seeds = [b"coordinator-config"],
         bump = config.bump,
+        constraint = !config.paused @ VrfError::CoordinatorPaused,
     )]
     pub config: Account<'info, CoordinatorConfig>,
 
@@ -34,16 +35,41 @@ pub struct CreateSubscription<'info> {
 
 /// Create a new subscription.
 pub fn handler(ctx: Context<CreateSubscription>) -> Result<()> {
-    let config = &mut ctx.accounts.config;
+    init_subscription(
+        &mut ctx.accounts.config,
+        &mut ctx.accounts.subscription,
+        ctx.accounts.owner.key(),
+        ctx.bumps.subscription,
+        Pubkey::default(),
+    )
+}
+
+/// Initialize a subscription created at `config.subscription_counter`,
+/// advance the counter and emit `SubscriptionCreated`. A default
+/// `billing_mint` means the subscription pays in SOL.
+pub(crate) fn init_subscription(
+    config: &mut CoordinatorConfig,
+    subscription: &mut Subscription,
+    owner: Pubkey,
+    bump: u8,
+    billing_mint: Pubkey,
+) -> Result<()> {
     let sub_id = config.subscription_counter;
+    // IDs from `FIRST_REQUESTER_NONCE_ID` up belong to requester nonces.
+    require!(
+        sub_id < Subscription::FIRST_REQUESTER_NONCE_ID,
+        VrfError::CounterOverflow
+    );
 
-    let subscription = &mut ctx.accounts.subscription;
     subscription.id = sub_id;
-    subscription.owner = ctx.accounts.owner.key();
+    subscription.owner = owner;
     subscription.balance = 0;
     subscription.req_count = 0;
     subscription.consumer_count = 0;
-    subscription.bump = ctx.bumps.subscription;
+    subscription.bump = bump;
+    subscription.pending_fees = 0;
+    subscription.pending_owner = Pubkey::default();
+    subscription.billing_mint = billing_mint;
 
     config.subscription_counter = config
         .subscription_counter
@@ -52,7 +78,8 @@ pub fn handler(ctx: Context<CreateSubscription>) -> Result<()> {
 
     emit!(SubscriptionCreated {
         subscription_id: sub_id,
-        owner: subscription.owner,
+        owner,
+        billing_mint,
     });
 
     Ok(())
@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::errors::VrfError;
+use crate::instructions::create_subscription::init_subscription;
+use crate::state::{ApprovedMint, CoordinatorConfig, Subscription};
+
+/// Accounts required to create a subscription billed in an approved SPL mint.
+#[derive(Accounts)]
+pub struct CreateTokenSubscription<'info> {
+    /// The owner of the new subscription; pays for account creation.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Coordinator configuration PDA (mutated to increment subscription_counter).
+    #[account(
+        mut,
+        seeds = [b"coordinator-config"],
+        bump = config.bump,
+        constraint = !config.paused @ VrfError::CoordinatorPaused,
+    )]
+    pub config: Account<'info, CoordinatorConfig>,
+
+    /// The approved mint the subscription will pay in. Must be enabled.
+    #[account(
+        seeds = [b"approved-mint", mint.key().as_ref()],
+        bump = approved_mint.bump,
+        constraint = approved_mint.enabled @ VrfError::MintNotApproved,
+    )]
+    pub approved_mint: Account<'info, ApprovedMint>,
+
+    /// The billing mint.
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// New subscription PDA.
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Subscription::INIT_SPACE,
+        seeds = [b"subscription", config.subscription_counter.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    /// The subscription's associated token account for `mint`, holding its
+    /// balance and pending fees.
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = subscription,
+        associated_token::token_program = token_program,
+    )]
+    pub subscription_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = approved_mint.token_program @ VrfError::InvalidTokenAccount)]
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Create a new subscription billed in `mint`.
+pub fn handler(ctx: Context<CreateTokenSubscription>) -> Result<()> {
+    init_subscription(
+        &mut ctx.accounts.config,
+        &mut ctx.accounts.subscription,
+        ctx.accounts.owner.key(),
+        ctx.bumps.subscription,
+        ctx.accounts.mint.key(),
+    )
+}
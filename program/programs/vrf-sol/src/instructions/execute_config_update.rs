@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::VrfError;
+use crate::events::{AdminTransferRequested, ConfigUpdateExecuted, OracleSetUpdated};
+use crate::instructions::update_config::apply_config_changes;
+use crate::state::{CoordinatorConfig, PendingConfigUpdate};
+
+/// Accounts required to apply a staged config update.
+#[derive(Accounts)]
+pub struct ExecuteConfigUpdate<'info> {
+    /// Current admin; must sign and receives the pending update's rent.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Coordinator configuration PDA to update.
+    #[account(
+        mut,
+        seeds = [b"coordinator-config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ VrfError::Unauthorized,
+    )]
+    pub config: Account<'info, CoordinatorConfig>,
+
+    /// The staged update; closed once applied.
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"pending-config-update"],
+        bump = pending_config_update.bump,
+    )]
+    pub pending_config_update: Account<'info, PendingConfigUpdate>,
+}
+
+/// Apply the staged config update once its `executable_slot` is reached.
+pub fn handler(ctx: Context<ExecuteConfigUpdate>) -> Result<()> {
+    let pending = &ctx.accounts.pending_config_update;
+    require!(
+        Clock::get()?.slot >= pending.executable_slot,
+        VrfError::ConfigUpdateNotReady
+    );
+
+    let config = &mut ctx.accounts.config;
+    apply_config_changes(config, &pending.changes)?;
+
+    if pending.changes.new_admin.is_some() {
+        emit!(AdminTransferRequested {
+            admin: config.admin,
+            pending_admin: config.pending_admin,
+        });
+    }
+    if let Some(oracle_set) = &pending.changes.new_oracle_set {
+        emit!(OracleSetUpdated {
+            oracles: oracle_set.oracles.clone(),
+            threshold: oracle_set.threshold,
+        });
+    }
+    emit!(ConfigUpdateExecuted {
+        changes: pending.changes.clone(),
+    });
+
+    Ok(())
+}
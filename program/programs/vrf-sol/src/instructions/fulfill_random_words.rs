@@ -2,24 +2,40 @@ use anchor_lang::prelude::*;
 use anchor_lang::solana_program::instruction::Instruction;
 use anchor_lang::solana_program::program::invoke_signed;
 use anchor_lang::solana_program::sysvar::instructions as sysvar_instructions;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use sha2::{Digest, Sha256};
 
-use crate::ed25519::verify_ed25519_instruction;
+use crate::ed25519::{
+    signature_randomness_message, verify_ed25519_instruction, verify_signature_randomness,
+};
 use crate::errors::VrfError;
-use crate::events::RandomWordsFulfilled;
-use crate::state::{CoordinatorConfig, RandomnessRequest};
+use crate::events::{FeesCollected, RandomWordsCallbackFailed, RandomWordsFulfilled};
+use crate::state::{
+    ApprovedMint, CallbackAccountMeta, CallbackAccounts, CoordinatorConfig, FeeVault,
+    RandomnessRequest, RandomnessResult, Subscription, MAX_CALLBACK_FAILURE_LEN,
+};
+use crate::token::{require_associated_token_account, transfer_from_pda};
 
 /// Accounts required to fulfill a pending randomness request.
 ///
-/// The transaction **must** include a native Ed25519 signature-verify
-/// instruction at index 0. After verification, the coordinator:
-/// 1. Expands randomness into num_words values
-/// 2. CPIs into the consumer program's `fulfill_random_words` instruction
-/// 3. Closes the request PDA, returning rent to the requester
+/// Shared by `fulfill_random_words`, `fulfill_random_words_ecvrf` and
+/// `fulfill_random_words_multi`. For the
+/// Ed25519 scheme the transaction **must** include a native Ed25519
+/// signature-verify instruction. After verification, the coordinator:
+/// 1. Moves the request's fee from the subscription, or for direct requests
+///    the request PDA, to the fee vault
+/// 2. Expands randomness into num_words values
+/// 3. CPIs into the consumer program's `fulfill_random_words` instruction
+/// 4. Closes the request PDA, returning rent to the requester
+///
+/// With a `callback_failure` it stores the randomness instead and leaves the
+/// PDA open for `retry_callback`. Wallet requests make no callback: the
+/// randomness goes to their `RandomnessResult`.
 #[derive(Accounts)]
 #[instruction(request_id: u64)]
 pub struct FulfillRandomWords<'info> {
-    /// Oracle authority that signs fulfillment proofs. Must match `config.authority`.
+    /// Oracle that submits the fulfillment. Must be `config.authority`, or any
+    /// registered oracle under the multi-oracle scheme.
     #[account(mut)]
     pub authority: Signer<'info>,
 
@@ -27,15 +43,15 @@ pub struct FulfillRandomWords<'info> {
     #[account(
         seeds = [b"coordinator-config"],
         bump = config.bump,
-        constraint = config.authority == authority.key() @ VrfError::Unauthorized,
+        constraint = config.is_fulfiller(&authority.key()) @ VrfError::Unauthorized,
+        constraint = !config.paused @ VrfError::CoordinatorPaused,
     )]
     pub config: Account<'info, CoordinatorConfig>,
 
     /// The request PDA to fulfill. Must be in `Pending` status.
-    /// Closed after fulfillment; rent refunded to requester.
+    /// Closed after callback delivery; rent refunded to requester.
     #[account(
         mut,
-        close = requester,
         seeds = [b"vrf-request", request_id.to_le_bytes().as_ref()],
         bump = request.bump,
         constraint = request.status == RandomnessRequest::STATUS_PENDING @ VrfError::RequestNotPending,
@@ -50,23 +66,81 @@ pub struct FulfillRandomWords<'info> {
     )]
     pub requester: UncheckedAccount<'info>,
 
-    /// The consumer program to CPI into for the callback.
+    /// The consumer program to CPI into for the callback; the System Program
+    /// (`Pubkey::default()`) for wallet requests.
     /// CHECK: Validated by matching request.consumer_program.
     #[account(
         constraint = consumer_program.key() == request.consumer_program @ VrfError::InvalidConsumerProgram,
     )]
     pub consumer_program: UncheckedAccount<'info>,
 
-    /// Native Instructions sysvar used to introspect the Ed25519 instruction.
+    /// Native Instructions sysvar used to introspect the Ed25519 instruction
+    /// (unused by the ECVRF scheme).
     /// CHECK: Validated by the address constraint.
     #[account(address = sysvar_instructions::ID)]
     pub instructions_sysvar: UncheckedAccount<'info>,
 
-    // remaining_accounts: consumer-specific accounts for the callback CPI
+    /// Callback accounts sidecar; required when `request.callback_sidecar` is set.
+    #[account(
+        seeds = [b"callback-accounts", request_id.to_le_bytes().as_ref()],
+        bump = callback_accounts.bump,
+    )]
+    pub callback_accounts: Option<Account<'info, CallbackAccounts>>,
+
+    /// The subscription that paid for the request; releases its pending fee.
+    /// Omitted for direct-funded requests.
+    #[account(
+        mut,
+        seeds = [b"subscription", request.subscription_id.to_le_bytes().as_ref()],
+        bump = subscription.bump,
+    )]
+    pub subscription: Option<Account<'info, Subscription>>,
+
+    /// Fee vault credited with the request's fee.
+    #[account(
+        mut,
+        seeds = [b"fee-vault"],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+
+    /// The approved mint of a token-billed request (`request.fee_mint`),
+    /// credited with the fee split. The token accounts below are required
+    /// with it and omitted for SOL requests.
+    #[account(
+        mut,
+        seeds = [b"approved-mint", request.fee_mint.as_ref()],
+        bump = approved_mint.bump,
+    )]
+    pub approved_mint: Option<Account<'info, ApprovedMint>>,
+
+    /// The request's fee mint.
+    pub fee_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// The subscription's associated token account, paying the fee.
+    #[account(mut)]
+    pub subscription_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The fee vault's associated token account, receiving the fee.
+    #[account(mut)]
+    pub fee_vault_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// Result account of a wallet request, receiving the randomness.
+    /// Omitted for consumer program requests.
+    #[account(
+        mut,
+        seeds = [b"randomness-result", request_id.to_le_bytes().as_ref()],
+        bump = randomness_result.bump,
+    )]
+    pub randomness_result: Option<Account<'info, RandomnessResult>>,
+
+    // remaining_accounts: the stored callback accounts, in order
 }
 
 /// Expand base randomness into multiple words: `word[i] = SHA256(randomness || i_le_bytes)`.
-fn expand_randomness(base_randomness: &[u8; 32], num_words: u32) -> Vec<[u8; 32]> {
+pub(crate) fn expand_randomness(base_randomness: &[u8; 32], num_words: u32) -> Vec<[u8; 32]> {
     let mut words = Vec::with_capacity(num_words as usize);
     for i in 0..num_words {
         let mut hasher = Sha256::new();
@@ -80,9 +154,12 @@ fn expand_randomness(base_randomness: &[u8; 32], num_words: u32) -> Vec<[u8; 32]
     words
 }
 
-/// Build the `fulfill_random_words` discriminator for the consumer callback.
+/// Build the `fulfill_random_words` discriminator for the consumer callback,
+/// used when the request has no callback prefix.
 ///
-/// Consumer programs must implement: `fulfill_random_words(request_id: u64, random_words: Vec<[u8; 32]>)`
+/// Such consumer programs must implement `fulfill_random_words(request_id: u64, random_words: Vec<[u8; 32]>)`,
+/// or, for requests made with `user_data` (`CALLBACK_V2`),
+/// `fulfill_random_words(request_id: u64, random_words: Vec<[u8; 32]>, user_data: Vec<u8>)`.
 fn consumer_callback_discriminator() -> [u8; 8] {
     let mut hasher = Sha256::new();
     hasher.update(b"global:fulfill_random_words");
@@ -93,87 +170,371 @@ fn consumer_callback_discriminator() -> [u8; 8] {
 }
 
 /// Fulfill a pending randomness request with callback delivery.
+///
+/// Under `PROOF_SCHEME_ED25519` the oracle signs `request_id || randomness`.
+/// Under `PROOF_SCHEME_SIGNATURE_HASH` it signs the request-bound message from
+/// [`signature_randomness_message`] and `randomness` must equal
+/// `SHA256(signature)`.
 pub fn handler<'info>(
     ctx: Context<'_, '_, '_, 'info, FulfillRandomWords<'info>>,
     request_id: u64,
     randomness: [u8; 32],
+    callback_failure: Option<String>,
 ) -> Result<()> {
     // 1. Verify Ed25519 signature proof
-    verify_ed25519_instruction(
-        &ctx.accounts.instructions_sysvar,
-        &ctx.accounts.config.authority,
-        request_id,
-        &randomness,
-    )?;
+    match ctx.accounts.config.proof_scheme {
+        CoordinatorConfig::PROOF_SCHEME_ED25519 => {
+            verify_ed25519_instruction(
+                &ctx.accounts.instructions_sysvar,
+                &ctx.accounts.config.authority,
+                request_id,
+                &randomness,
+            )?;
+        }
+        CoordinatorConfig::PROOF_SCHEME_SIGNATURE_HASH => {
+            let request = &ctx.accounts.request;
+            let message = signature_randomness_message(
+                ctx.program_id,
+                request_id,
+                &request.seed,
+                request.request_slot,
+            );
+            let derived = verify_signature_randomness(
+                &ctx.accounts.instructions_sysvar,
+                &ctx.accounts.config.authority,
+                &message,
+            )?;
+            require!(derived == randomness, VrfError::RandomnessMismatch);
+        }
+        _ => return err!(VrfError::ProofSchemeMismatch),
+    }
 
-    let request = &ctx.accounts.request;
-    let num_words = request.num_words;
+    deliver(ctx, request_id, randomness, callback_failure)
+}
 
-    // 2. Expand base randomness into num_words values
-    let random_words = expand_randomness(&randomness, num_words);
+/// Record verified randomness, CPI the consumer callback, close the request
+/// and emit the fulfillment event. Shared by every proof scheme once its proof
+/// has been checked. Fails with `RequestNotConfirmed` before the request's
+/// `fulfillable_slot`.
+///
+/// Store-only requests are left open with `STATUS_STORED` for
+/// [`consume_randomness`](crate::instructions::consume_randomness). Wallet
+/// requests copy the randomness to their `RandomnessResult` and close. Both
+/// ignore `callback_failure`.
+///
+/// With a `callback_failure` the randomness is stored with
+/// `STATUS_CALLBACK_FAILED`, the request PDA stays open for
+/// [`retry_callback`](crate::instructions::retry_callback) and the reason is
+/// emitted in `RandomWordsCallbackFailed`. A reverted CPI aborts the whole
+/// transaction, so the coordinator cannot attempt the callback and catch the
+/// failure: the oracle reports the failure its simulation hit, and is trusted
+/// to. A false report cannot change the output, which is already verified,
+/// and anyone can deliver it with `retry_callback`.
+pub(crate) fn deliver<'info>(
+    ctx: Context<'_, '_, '_, 'info, FulfillRandomWords<'info>>,
+    request_id: u64,
+    randomness: [u8; 32],
+    callback_failure: Option<String>,
+) -> Result<()> {
+    // 2. The request must have had time to confirm
+    let slot = Clock::get()?.slot;
+    require!(
+        slot >= ctx.accounts.request.fulfillable_slot(),
+        VrfError::RequestNotConfirmed
+    );
 
-    // 3. Update request state
+    // 3. Pay the fee out of the subscription
+    collect_fee(ctx.accounts)?;
+
+    // 4. Update request state
     let request = &mut ctx.accounts.request;
     request.randomness = randomness;
+    request.fulfilled_slot = slot;
+
+    if request.store_only {
+        request.status = RandomnessRequest::STATUS_STORED;
+        emit!(RandomWordsFulfilled {
+            request_id,
+            randomness,
+            consumer_program: ctx.accounts.consumer_program.key(),
+        });
+        return Ok(());
+    }
+
+    if request.is_wallet_request() {
+        let result = ctx
+            .accounts
+            .randomness_result
+            .as_mut()
+            .ok_or(VrfError::MissingRandomnessResult)?;
+        result.randomness = randomness;
+        result.fulfilled_slot = slot;
+        request.status = RandomnessRequest::STATUS_FULFILLED;
+        ctx.accounts
+            .request
+            .close(ctx.accounts.requester.to_account_info())?;
+        emit!(RandomWordsFulfilled {
+            request_id,
+            randomness,
+            consumer_program: Pubkey::default(),
+        });
+        return Ok(());
+    }
+
+    if let Some(reason) = callback_failure {
+        require!(
+            reason.len() <= MAX_CALLBACK_FAILURE_LEN,
+            VrfError::CallbackFailureTooLong
+        );
+        request.status = RandomnessRequest::STATUS_CALLBACK_FAILED;
+        request.callback_attempts = 1;
+        emit!(RandomWordsCallbackFailed {
+            request_id,
+            randomness,
+            consumer_program: ctx.accounts.consumer_program.key(),
+            attempts: request.callback_attempts,
+            reason,
+        });
+        return Ok(());
+    }
     request.status = RandomnessRequest::STATUS_FULFILLED;
-    request.fulfilled_slot = Clock::get()?.slot;
+    let expected = stored_callback_accounts(request, ctx.accounts.callback_accounts.as_ref())?;
+
+    // 5. CPI into consumer program's fulfill_random_words instruction
+    invoke_callback(
+        &ctx.accounts.config,
+        &ctx.accounts.consumer_program,
+        &expected,
+        ctx.remaining_accounts,
+        request,
+        &randomness,
+    )?;
+
+    // 6. Close the request PDA, refunding rent to the requester.
+    ctx.accounts
+        .request
+        .close(ctx.accounts.requester.to_account_info())?;
+
+    // 7. Emit event
+    emit!(RandomWordsFulfilled {
+        request_id,
+        randomness,
+        consumer_program: ctx.accounts.consumer_program.key(),
+    });
+
+    Ok(())
+}
+
+/// Move `request.fee_paid` from the subscription's pending fees to the fee
+/// vault, split between protocol and oracle by `config.protocol_fee_bps`.
+///
+/// Lamport fees move between the two PDAs directly and are counted on the
+/// `FeeVault`. Token fees move from the subscription's token account to the
+/// fee vault's and are counted on the `ApprovedMint`. Direct-funded fees move
+/// from the request PDA, which holds them on top of its rent.
+fn collect_fee(accounts: &mut FulfillRandomWords) -> Result<()> {
+    let fee = accounts.request.fee_paid;
+    let fee_mint = accounts.request.fee_mint;
+
+    let protocol_fee = (fee as u128 * accounts.config.protocol_fee_bps as u128
+        / CoordinatorConfig::MAX_PROTOCOL_FEE_BPS as u128) as u64;
+    let oracle_fee = fee - protocol_fee;
+
+    if accounts.request.subscription_id == Subscription::DIRECT_SUBSCRIPTION_ID {
+        let fee_vault = &mut accounts.fee_vault;
+        credit_fee_vault(fee_vault, protocol_fee, oracle_fee)?;
+        accounts.request.sub_lamports(fee)?;
+        fee_vault.add_lamports(fee)?;
+    } else {
+        let subscription = accounts
+            .subscription
+            .as_mut()
+            .ok_or(VrfError::MissingFeeAccount)?;
+        subscription.pending_fees = subscription
+            .pending_fees
+            .checked_sub(fee)
+            .ok_or(VrfError::CounterOverflow)?;
+
+        if fee_mint == Pubkey::default() {
+            let fee_vault = &mut accounts.fee_vault;
+            credit_fee_vault(fee_vault, protocol_fee, oracle_fee)?;
+
+            // Both accounts are owned by this program, so lamports move directly.
+            subscription.sub_lamports(fee)?;
+            fee_vault.add_lamports(fee)?;
+        } else {
+            let (Some(approved_mint), Some(mint), Some(from), Some(to), Some(token_program)) = (
+                accounts.approved_mint.as_mut(),
+                accounts.fee_mint.as_ref(),
+                accounts.subscription_vault.as_ref(),
+                accounts.fee_vault_token_account.as_ref(),
+                accounts.token_program.as_ref(),
+            ) else {
+                return err!(VrfError::InvalidTokenAccount);
+            };
+            require_keys_eq!(mint.key(), fee_mint, VrfError::InvalidTokenAccount);
+            require_keys_eq!(
+                token_program.key(),
+                approved_mint.token_program,
+                VrfError::InvalidTokenAccount
+            );
+            require_associated_token_account(
+                &from.key(),
+                &subscription.key(),
+                &fee_mint,
+                &token_program.key(),
+            )?;
+            require_associated_token_account(
+                &to.key(),
+                &accounts.fee_vault.key(),
+                &fee_mint,
+                &token_program.key(),
+            )?;
+
+            approved_mint.protocol_fees = approved_mint
+                .protocol_fees
+                .checked_add(protocol_fee)
+                .ok_or(VrfError::CounterOverflow)?;
+            approved_mint.oracle_fees = approved_mint
+                .oracle_fees
+                .checked_add(oracle_fee)
+                .ok_or(VrfError::CounterOverflow)?;
+
+            let id = subscription.id.to_le_bytes();
+            let seeds: &[&[&[u8]]] = &[&[b"subscription", id.as_ref(), &[subscription.bump]]];
+            transfer_from_pda(
+                token_program.to_account_info(),
+                from.to_account_info(),
+                to.to_account_info(),
+                mint,
+                subscription.to_account_info(),
+                seeds,
+                fee,
+            )?;
+        }
+    }
+
+    emit!(FeesCollected {
+        request_id: accounts.request.request_id,
+        subscription_id: accounts.request.subscription_id,
+        oracle_fee,
+        protocol_fee,
+        fee_mint,
+    });
+
+    Ok(())
+}
+
+/// Count a collected lamport fee on the fee vault.
+fn credit_fee_vault(fee_vault: &mut FeeVault, protocol_fee: u64, oracle_fee: u64) -> Result<()> {
+    fee_vault.protocol_fees = fee_vault
+        .protocol_fees
+        .checked_add(protocol_fee)
+        .ok_or(VrfError::CounterOverflow)?;
+    fee_vault.oracle_fees = fee_vault
+        .oracle_fees
+        .checked_add(oracle_fee)
+        .ok_or(VrfError::CounterOverflow)?;
+    Ok(())
+}
+
+/// The callback accounts stored for `request`: inline, or from its sidecar.
+pub(crate) fn stored_callback_accounts(
+    request: &RandomnessRequest,
+    sidecar: Option<&Account<CallbackAccounts>>,
+) -> Result<Vec<CallbackAccountMeta>> {
+    if !request.callback_sidecar {
+        return Ok(request.inline_callback_accounts());
+    }
+    let sidecar = sidecar.ok_or(VrfError::CallbackAccountsMismatch)?;
+    Ok(sidecar.accounts.clone())
+}
+
+/// Expand `randomness` into `num_words` values and CPI the consumer's
+/// `fulfill_random_words` callback, signed by the coordinator-config PDA.
+/// `remaining_accounts` must match the stored callback accounts in `expected`.
+/// The request's `user_data` is appended to the arguments for `CALLBACK_V2`
+/// requests, and a non-empty callback prefix replaces the Anchor
+/// discriminator.
+pub(crate) fn invoke_callback<'info>(
+    config: &Account<'info, CoordinatorConfig>,
+    consumer_program: &AccountInfo<'info>,
+    expected: &[CallbackAccountMeta],
+    remaining_accounts: &[AccountInfo<'info>],
+    request: &RandomnessRequest,
+    randomness: &[u8; 32],
+) -> Result<()> {
+    let request_id = request.request_id;
+    let num_words = request.num_words;
+    let prefix = request.callback_prefix();
+    // The oracle must pass exactly the stored callback accounts, in order. An
+    // account stored as writable must be writable; one stored as read-only may
+    // still be writable in the transaction (e.g. it is also the requester), but
+    // is forwarded read-only.
+    require!(
+        remaining_accounts.len() == expected.len(),
+        VrfError::CallbackAccountsMismatch
+    );
+    for (account, meta) in remaining_accounts.iter().zip(expected) {
+        require!(
+            account.key() == meta.pubkey && (account.is_writable || !meta.is_writable),
+            VrfError::CallbackAccountsMismatch
+        );
+    }
+
+    let random_words = expand_randomness(randomness, num_words);
 
-    // 4. CPI into consumer program's fulfill_random_words instruction
     // The coordinator-config PDA signs the CPI so the consumer can verify the caller.
-    let config_bump = ctx.accounts.config.bump;
-    let signer_seeds: &[&[u8]] = &[b"coordinator-config", &[config_bump]];
+    let signer_seeds: &[&[u8]] = &[b"coordinator-config", &[config.bump]];
 
-    // Build callback instruction data: discriminator + request_id + random_words (borsh-encoded Vec)
+    // Build callback instruction data: prefix + request_id + random_words (borsh-encoded Vec)
     let mut callback_data = Vec::new();
-    callback_data.extend_from_slice(&consumer_callback_discriminator());
+    if prefix.is_empty() {
+        callback_data.extend_from_slice(&consumer_callback_discriminator());
+    } else {
+        callback_data.extend_from_slice(prefix);
+    }
     callback_data.extend_from_slice(&request_id.to_le_bytes());
     // Borsh Vec encoding: length as u32 LE, then each [u8; 32] element
     callback_data.extend_from_slice(&num_words.to_le_bytes());
     for word in &random_words {
         callback_data.extend_from_slice(word);
     }
+    // CALLBACK_V2: user_data as a borsh Vec<u8>
+    if let Some(user_data) = request.callback_user_data() {
+        callback_data.extend_from_slice(&(user_data.len() as u32).to_le_bytes());
+        callback_data.extend_from_slice(user_data);
+    }
 
     // Build account metas for the consumer callback.
     // The first account is always the coordinator-config PDA as signer.
-    // Remaining accounts are passed through from the transaction's remaining_accounts.
-    let mut callback_accounts = Vec::with_capacity(1 + ctx.remaining_accounts.len());
+    // Callback account metas come from the stored list, never the transaction's flags.
+    let mut callback_accounts = Vec::with_capacity(1 + expected.len());
     callback_accounts.push(AccountMeta::new_readonly(
-        ctx.accounts.config.key(),
+        config.key(),
         true, // signer (PDA signs via invoke_signed)
     ));
-    for account in ctx.remaining_accounts {
-        if account.is_writable {
-            callback_accounts.push(AccountMeta::new(*account.key, account.is_signer));
+    for meta in expected {
+        if meta.is_writable {
+            callback_accounts.push(AccountMeta::new(meta.pubkey, false));
         } else {
-            callback_accounts.push(AccountMeta::new_readonly(*account.key, account.is_signer));
+            callback_accounts.push(AccountMeta::new_readonly(meta.pubkey, false));
         }
     }
 
     let callback_ix = Instruction {
-        program_id: ctx.accounts.consumer_program.key(),
+        program_id: consumer_program.key(),
         accounts: callback_accounts,
         data: callback_data,
     };
 
     // Collect all account infos needed for the CPI
-    let mut cpi_account_infos = Vec::with_capacity(2 + ctx.remaining_accounts.len());
-    cpi_account_infos.push(ctx.accounts.config.to_account_info());
-    for account in ctx.remaining_accounts {
+    let mut cpi_account_infos = Vec::with_capacity(1 + remaining_accounts.len());
+    cpi_account_infos.push(config.to_account_info());
+    for account in remaining_accounts {
         cpi_account_infos.push(account.to_account_info());
     }
 
     invoke_signed(&callback_ix, &cpi_account_infos, &[signer_seeds])
-        .map_err(|_| error!(VrfError::CallbackFailed))?;
-
-    // 5. Request PDA is closed automatically by Anchor's `close = requester` constraint.
-    //    Rent is refunded to the requester.
-
-    // 6. Emit event
-    emit!(RandomWordsFulfilled {
-        request_id,
-        randomness,
-        consumer_program: ctx.accounts.consumer_program.key(),
-    });
-
-    Ok(())
+        .map_err(|_| error!(VrfError::CallbackFailed))
 }
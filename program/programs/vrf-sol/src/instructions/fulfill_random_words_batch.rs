@@ -0,0 +1,126 @@
+use std::collections::BTreeSet;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions as sysvar_instructions;
+
+use crate::ed25519::{
+    check_ed25519_message, load_ed25519_batch_signatures, signature_randomness,
+    signature_randomness_message,
+};
+use crate::errors::VrfError;
+use crate::instructions::fulfill_random_words::{
+    deliver, stored_callback_accounts, FulfillRandomWords, FulfillRandomWordsBumps,
+};
+use crate::state::{BatchFulfillment, CoordinatorConfig, MAX_BATCH_FULFILLMENTS};
+
+/// Accounts required to fulfill several requests in one instruction.
+///
+/// Each request brings its own accounts through `remaining_accounts`: the
+/// full `FulfillRandomWords` account list, in order, followed by the
+/// request's stored callback accounts (none for store-only and wallet
+/// requests).
+#[derive(Accounts)]
+pub struct FulfillRandomWordsBatch<'info> {
+    /// Oracle that submits the batch. Must be `config.authority`.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Coordinator configuration PDA.
+    #[account(
+        seeds = [b"coordinator-config"],
+        bump = config.bump,
+        constraint = config.is_fulfiller(&authority.key()) @ VrfError::Unauthorized,
+        constraint = !config.paused @ VrfError::CoordinatorPaused,
+    )]
+    pub config: Account<'info, CoordinatorConfig>,
+
+    /// Native Instructions sysvar used to introspect the Ed25519 instruction.
+    /// CHECK: Validated by the address constraint.
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+    // remaining_accounts: per fulfillment, the `FulfillRandomWords` accounts
+    // and then its callback accounts
+}
+
+/// Fulfill `fulfillments` in order, each exactly as `fulfill_random_words`
+/// would without a `callback_failure`.
+///
+/// One Ed25519 precompile instruction carries a signature per fulfillment,
+/// in the same order, over the message the proof scheme defines (see
+/// [`fulfill_random_words`](crate::instructions::fulfill_random_words::handler)).
+/// Each request's accounts are validated with the `FulfillRandomWords`
+/// constraints and written back before the next request is loaded, so
+/// requests sharing a subscription see each other's fee collection. A
+/// reverting callback fails the whole batch.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, FulfillRandomWordsBatch<'info>>,
+    fulfillments: Vec<BatchFulfillment>,
+) -> Result<()> {
+    require!(
+        !fulfillments.is_empty() && fulfillments.len() <= MAX_BATCH_FULFILLMENTS,
+        VrfError::InvalidBatchSize
+    );
+    let config = &ctx.accounts.config;
+    let proof_scheme = config.proof_scheme;
+    require!(
+        proof_scheme == CoordinatorConfig::PROOF_SCHEME_ED25519
+            || proof_scheme == CoordinatorConfig::PROOF_SCHEME_SIGNATURE_HASH,
+        VrfError::ProofSchemeMismatch
+    );
+    let signatures =
+        load_ed25519_batch_signatures(&ctx.accounts.instructions_sysvar, fulfillments.len())?;
+
+    let mut remaining: &'info [AccountInfo<'info>] = ctx.remaining_accounts;
+    for (fulfillment, signed) in fulfillments.iter().zip(&signatures) {
+        let BatchFulfillment {
+            request_id,
+            randomness,
+        } = *fulfillment;
+
+        let mut bumps = FulfillRandomWordsBumps::default();
+        let mut accounts = FulfillRandomWords::try_accounts(
+            ctx.program_id,
+            &mut remaining,
+            &request_id.to_le_bytes(),
+            &mut bumps,
+            &mut BTreeSet::new(),
+        )?;
+
+        let request = &accounts.request;
+        if proof_scheme == CoordinatorConfig::PROOF_SCHEME_ED25519 {
+            check_ed25519_message(signed, &config.authority, request_id, &randomness)?;
+        } else {
+            let message = signature_randomness_message(
+                ctx.program_id,
+                request_id,
+                &request.seed,
+                request.request_slot,
+            );
+            let derived = signature_randomness(signed, &config.authority, &message)?;
+            require!(derived == randomness, VrfError::RandomnessMismatch);
+        }
+
+        let callback_count = if request.store_only || request.is_wallet_request() {
+            0
+        } else {
+            stored_callback_accounts(request, accounts.callback_accounts.as_ref())?.len()
+        };
+        require!(
+            remaining.len() >= callback_count,
+            VrfError::CallbackAccountsMismatch
+        );
+        let (callback_accounts, rest) = remaining.split_at(callback_count);
+        remaining = rest;
+
+        deliver(
+            Context::new(ctx.program_id, &mut accounts, callback_accounts, bumps),
+            request_id,
+            randomness,
+            None,
+        )?;
+        accounts.exit(ctx.program_id)?;
+    }
+    require!(remaining.is_empty(), VrfError::CallbackAccountsMismatch);
+
+    Ok(())
+}
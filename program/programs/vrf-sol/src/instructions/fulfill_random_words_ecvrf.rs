@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::ecvrf;
+use crate::errors::VrfError;
+use crate::instructions::fulfill_random_words::{deliver, FulfillRandomWords};
+use crate::state::CoordinatorConfig;
+
+/// Fulfill a pending randomness request with an ECVRF proof.
+///
+/// The proof is verified against `config.authority` over the request's
+/// `seed`, `request_slot` and `request_id`. The randomness is derived from the
+/// proof itself, so the oracle cannot choose it and anyone can recompute it
+/// from the fulfillment transaction.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, FulfillRandomWords<'info>>,
+    request_id: u64,
+    proof: [u8; ecvrf::PROOF_LEN],
+    callback_failure: Option<String>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.config.proof_scheme == CoordinatorConfig::PROOF_SCHEME_ECVRF,
+        VrfError::ProofSchemeMismatch
+    );
+
+    let request = &ctx.accounts.request;
+    let alpha = ecvrf::vrf_input(&request.seed, request.request_slot, request_id);
+    let randomness = ecvrf::verify(&ctx.accounts.config.authority, &alpha, &proof)?;
+
+    deliver(ctx, request_id, randomness, callback_failure)
+}
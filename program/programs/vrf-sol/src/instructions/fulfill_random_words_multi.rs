@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+use sha2::{Digest, Sha256};
+
+use crate::ecvrf;
+use crate::errors::VrfError;
+use crate::instructions::fulfill_random_words::{deliver, FulfillRandomWords};
+use crate::state::{CoordinatorConfig, OracleProof};
+
+/// Fulfill a pending randomness request with ECVRF proofs from
+/// `config.oracle_threshold` registered oracles.
+///
+/// `proofs` name their oracles by index, in increasing order, and each must
+/// be an ECVRF proof by that oracle over the request's VRF input, so each
+/// oracle has exactly one valid output per request. The final randomness is
+/// `SHA256(beta_0 || ... || beta_{M-1})` in index order.
+///
+/// Until `CoordinatorConfig::MULTI_ORACLE_FALLBACK_SLOTS` after the request
+/// becomes fulfillable, only the request's committee may prove it, so the
+/// output is fixed by the request alone. After that any `oracle_threshold`
+/// registered oracles may, so one offline committee member delays delivery
+/// by at most the fallback window.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, FulfillRandomWords<'info>>,
+    request_id: u64,
+    proofs: Vec<OracleProof>,
+    callback_failure: Option<String>,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    require!(
+        config.proof_scheme == CoordinatorConfig::PROOF_SCHEME_MULTI_ORACLE,
+        VrfError::ProofSchemeMismatch
+    );
+
+    let threshold = config.oracle_threshold as usize;
+    require!(
+        proofs.len() >= threshold,
+        VrfError::InsufficientOracleSignatures
+    );
+    require!(proofs.len() == threshold, VrfError::TooManyOracleProofs);
+
+    let request = &ctx.accounts.request;
+    let fallback_slot = request
+        .fulfillable_slot()
+        .saturating_add(CoordinatorConfig::MULTI_ORACLE_FALLBACK_SLOTS);
+    let committee_only = Clock::get()?.slot < fallback_slot;
+    let oracles = config.oracle_set();
+    for (i, entry) in proofs.iter().enumerate() {
+        // Strictly increasing indices: distinct oracles in a canonical order
+        require!(
+            i == 0 || proofs[i - 1].oracle_index < entry.oracle_index,
+            VrfError::DuplicateOracleSignature
+        );
+        let index = entry.oracle_index as usize;
+        require!(
+            index < oracles.len()
+                && (!committee_only || config.is_on_committee(request_id, index)),
+            VrfError::OracleNotSelected
+        );
+    }
+
+    let alpha = ecvrf::vrf_input(&request.seed, request.request_slot, request_id);
+    let mut hasher = Sha256::new();
+    for entry in &proofs {
+        let oracle = &oracles[entry.oracle_index as usize];
+        hasher.update(ecvrf::verify(oracle, &alpha, &entry.proof)?);
+    }
+    let randomness: [u8; 32] = hasher.finalize().into();
+
+    deliver(ctx, request_id, randomness, callback_failure)
+}
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
 
+use crate::errors::VrfError;
 use crate::events::SubscriptionFunded;
 use crate::state::Subscription;
 
@@ -12,11 +13,12 @@ pub struct FundSubscription<'info> {
     #[account(mut)]
     pub funder: Signer<'info>,
 
-    /// The subscription PDA to fund.
+    /// The SOL-billed subscription PDA to fund.
     #[account(
         mut,
         seeds = [b"subscription", subscription_id.to_le_bytes().as_ref()],
         bump = subscription.bump,
+        constraint = !subscription.is_token_billed() @ VrfError::BillingCurrencyMismatch,
     )]
     pub subscription: Account<'info, Subscription>,
 
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::errors::VrfError;
+use crate::events::SubscriptionFunded;
+use crate::state::Subscription;
+
+/// Accounts required to fund a token-billed subscription.
+#[derive(Accounts)]
+#[instruction(subscription_id: u64)]
+pub struct FundSubscriptionToken<'info> {
+    /// The account funding the subscription; owns `funder_token_account`.
+    pub funder: Signer<'info>,
+
+    /// The token-billed subscription PDA to fund.
+    #[account(
+        mut,
+        seeds = [b"subscription", subscription_id.to_le_bytes().as_ref()],
+        bump = subscription.bump,
+        constraint = subscription.is_token_billed() @ VrfError::BillingCurrencyMismatch,
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    /// The subscription's billing mint.
+    #[account(address = subscription.billing_mint @ VrfError::BillingCurrencyMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Token account the tokens are paid from.
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = funder,
+        token::token_program = token_program,
+    )]
+    pub funder_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The subscription's associated token account for `mint`.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = subscription,
+        associated_token::token_program = token_program,
+    )]
+    pub subscription_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Transfer `amount` tokens from the funder into the subscription vault,
+/// incrementing the balance.
+pub fn handler(
+    ctx: Context<FundSubscriptionToken>,
+    _subscription_id: u64,
+    amount: u64,
+) -> Result<()> {
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.funder_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.subscription_vault.to_account_info(),
+                authority: ctx.accounts.funder.to_account_info(),
+            },
+        ),
+        amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    let subscription = &mut ctx.accounts.subscription;
+    let old_balance = subscription.balance;
+    subscription.balance = subscription
+        .balance
+        .checked_add(amount)
+        .ok_or(VrfError::CounterOverflow)?;
+
+    emit!(SubscriptionFunded {
+        subscription_id: subscription.id,
+        old_balance,
+        new_balance: subscription.balance,
+    });
+
+    Ok(())
+}
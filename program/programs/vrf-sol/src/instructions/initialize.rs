@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
 
 use crate::errors::VrfError;
-use crate::state::CoordinatorConfig;
+use crate::state::{CoordinatorConfig, FeeVault, MAX_ORACLES};
 
 /// Accounts required to initialize the coordinator configuration singleton.
 #[derive(Accounts)]
@@ -24,15 +24,35 @@ pub struct Initialize<'info> {
     )]
     pub config: Account<'info, CoordinatorConfig>,
 
+    /// Singleton fee vault PDA collecting fulfilled requests' fees.
+    /// Seeds: `["fee-vault"]`.
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + FeeVault::INIT_SPACE,
+        seeds = [b"fee-vault"],
+        bump,
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+
     pub system_program: Program<'info, System>,
 }
 
-/// Initialize the coordinator configuration.
-pub fn handler(ctx: Context<Initialize>, fee_per_word: u64, max_num_words: u32) -> Result<()> {
+/// Initialize the coordinator configuration and the fee vault.
+pub fn handler(
+    ctx: Context<Initialize>,
+    fee_per_word: u64,
+    max_num_words: u32,
+    config_update_delay_slots: u64,
+) -> Result<()> {
     require!(
         ctx.accounts.authority.key() != Pubkey::default(),
         VrfError::ZeroAddressNotAllowed
     );
+    require!(
+        CoordinatorConfig::is_valid_config_update_delay(config_update_delay_slots),
+        VrfError::InvalidConfigUpdateDelay
+    );
 
     let config = &mut ctx.accounts.config;
     config.admin = ctx.accounts.admin.key();
@@ -42,5 +62,24 @@ pub fn handler(ctx: Context<Initialize>, fee_per_word: u64, max_num_words: u32)
     config.request_counter = 0;
     config.subscription_counter = 0;
     config.bump = ctx.bumps.config;
+    config.proof_scheme = CoordinatorConfig::PROOF_SCHEME_ED25519;
+    config.oracle_count = 0;
+    config.oracle_threshold = 0;
+    config.oracles = [Pubkey::default(); MAX_ORACLES];
+    config.request_timeout_slots = CoordinatorConfig::DEFAULT_REQUEST_TIMEOUT_SLOTS;
+    config.protocol_fee_bps = 0;
+    config.base_fee = 0;
+    config.compute_unit_price = 0;
+    config.max_callback_compute_limit = CoordinatorConfig::DEFAULT_MAX_CALLBACK_COMPUTE_LIMIT;
+    config.min_confirmation_slots = 0;
+    config.pauser = ctx.accounts.admin.key();
+    config.paused = false;
+    config.config_update_delay_slots = config_update_delay_slots;
+    config.pending_admin = Pubkey::default();
+
+    let fee_vault = &mut ctx.accounts.fee_vault;
+    fee_vault.oracle_fees = 0;
+    fee_vault.protocol_fees = 0;
+    fee_vault.bump = ctx.bumps.fee_vault;
     Ok(())
 }
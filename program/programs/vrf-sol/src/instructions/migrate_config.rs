@@ -0,0 +1,132 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+
+use crate::errors::VrfError;
+use crate::events::AccountMigrated;
+use crate::state::{CoordinatorConfig, FeeVault};
+
+/// Accounts required to migrate the coordinator configuration.
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    /// Current admin; must sign and pays for the extra rent.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Coordinator configuration PDA, possibly in an older, shorter layout
+    /// that `Account<CoordinatorConfig>` cannot deserialize.
+    /// CHECK: Owner, discriminator and admin are checked in the handler.
+    #[account(
+        mut,
+        seeds = [b"coordinator-config"],
+        bump,
+    )]
+    pub config: UncheckedAccount<'info>,
+
+    /// Fee vault PDA, created if the coordinator predates it.
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + FeeVault::INIT_SPACE,
+        seeds = [b"fee-vault"],
+        bump,
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grow the configuration to the current layout and give the fields it
+/// gained their defaults. A no-op on an up-to-date config.
+pub fn handler(ctx: Context<MigrateConfig>) -> Result<()> {
+    let config_info = ctx.accounts.config.to_account_info();
+    let admin = ctx.accounts.admin.key();
+
+    // The admin is the first field of every layout
+    {
+        let data = config_info.try_borrow_data()?;
+        require!(data.len() >= 8 + 32, VrfError::InvalidMigration);
+        require!(data[8..40] == admin.to_bytes(), VrfError::Unauthorized);
+    }
+
+    let new_len = 8 + CoordinatorConfig::INIT_SPACE;
+    let old_len = grow_account(
+        &config_info,
+        &ctx.accounts.admin,
+        &ctx.accounts.system_program,
+        CoordinatorConfig::DISCRIMINATOR,
+        new_len,
+    )?;
+
+    if old_len < new_len {
+        let mut data = config_info.try_borrow_mut_data()?;
+        let mut config = CoordinatorConfig::try_deserialize(&mut &data[..])?;
+        if config.request_timeout_slots == 0 {
+            config.request_timeout_slots = CoordinatorConfig::DEFAULT_REQUEST_TIMEOUT_SLOTS;
+        }
+        if config.max_callback_compute_limit == 0 {
+            config.max_callback_compute_limit =
+                CoordinatorConfig::DEFAULT_MAX_CALLBACK_COMPUTE_LIMIT;
+        }
+        if config.pauser == Pubkey::default() {
+            config.pauser = admin;
+        }
+        if config.config_update_delay_slots == 0 {
+            config.config_update_delay_slots =
+                CoordinatorConfig::DEFAULT_CONFIG_UPDATE_DELAY_SLOTS;
+        }
+        config.try_serialize(&mut &mut data[..])?;
+    }
+
+    let fee_vault = &mut ctx.accounts.fee_vault;
+    fee_vault.bump = ctx.bumps.fee_vault;
+
+    emit!(AccountMigrated {
+        account: config_info.key(),
+        old_len: old_len as u32,
+        new_len: new_len as u32,
+    });
+
+    Ok(())
+}
+
+/// Grow a program account holding `discriminator` to `new_len` bytes,
+/// topping its rent up from `payer`. The added bytes are zeroed.
+///
+/// Returns the previous length. Fails if the account is not owned by this
+/// program, is of another type, or is already longer than `new_len`.
+pub(crate) fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system: &Program<'info, System>,
+    discriminator: &[u8],
+    new_len: usize,
+) -> Result<usize> {
+    require_keys_eq!(*account.owner, crate::ID, VrfError::InvalidMigration);
+    let old_len = account.data_len();
+    require!(
+        old_len <= new_len && account.try_borrow_data()?.starts_with(discriminator),
+        VrfError::InvalidMigration
+    );
+    if old_len == new_len {
+        return Ok(old_len);
+    }
+
+    let top_up = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(account.lamports());
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system.to_account_info(),
+                Transfer {
+                    from: payer.to_account_info(),
+                    to: account.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    account.resize(new_len)?;
+
+    Ok(old_len)
+}
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::VrfError;
+use crate::events::AccountMigrated;
+use crate::instructions::migrate_config::grow_account;
+use crate::state::{ConsumerRegistration, CoordinatorConfig};
+
+/// Accounts required to migrate a consumer registration.
+#[derive(Accounts)]
+#[instruction(subscription_id: u64)]
+pub struct MigrateRegistration<'info> {
+    /// Current admin; must sign and pays for the extra rent.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Coordinator configuration PDA, already migrated.
+    #[account(
+        seeds = [b"coordinator-config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ VrfError::Unauthorized,
+    )]
+    pub config: Account<'info, CoordinatorConfig>,
+
+    /// The registered consumer program.
+    /// CHECK: Used only for PDA derivation.
+    pub consumer_program: UncheckedAccount<'info>,
+
+    /// Consumer registration PDA, possibly in an older, shorter layout.
+    /// CHECK: Owner and discriminator are checked in the handler.
+    #[account(
+        mut,
+        seeds = [b"consumer", subscription_id.to_le_bytes().as_ref(), consumer_program.key().as_ref()],
+        bump,
+    )]
+    pub consumer_registration: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grow a consumer registration to the current layout. The fields it gains
+/// start at zero: no limits, empty usage counters and the default callback
+/// prefix. A no-op on an up-to-date registration.
+pub fn handler(ctx: Context<MigrateRegistration>, _subscription_id: u64) -> Result<()> {
+    let registration = ctx.accounts.consumer_registration.to_account_info();
+    let new_len = 8 + ConsumerRegistration::INIT_SPACE;
+    let old_len = grow_account(
+        &registration,
+        &ctx.accounts.admin,
+        &ctx.accounts.system_program,
+        ConsumerRegistration::DISCRIMINATOR,
+        new_len,
+    )?;
+
+    emit!(AccountMigrated {
+        account: registration.key(),
+        old_len: old_len as u32,
+        new_len: new_len as u32,
+    });
+
+    Ok(())
+}
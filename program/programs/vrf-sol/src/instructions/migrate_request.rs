@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::VrfError;
+use crate::events::AccountMigrated;
+use crate::instructions::migrate_config::grow_account;
+use crate::state::{CoordinatorConfig, RandomnessRequest, MAX_CALLBACK_PREFIX_LEN};
+
+/// Offset of the `user_data` length prefix in a request account, which
+/// `INIT_SPACE` ends with along with `callback_prefix_len` and
+/// `callback_prefix`.
+const USER_DATA_LEN_OFFSET: usize =
+    8 + RandomnessRequest::INIT_SPACE - (4 + 1 + MAX_CALLBACK_PREFIX_LEN);
+
+/// Accounts required to migrate a randomness request.
+#[derive(Accounts)]
+#[instruction(request_id: u64)]
+pub struct MigrateRequest<'info> {
+    /// Current admin; must sign and pays for the extra rent.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Coordinator configuration PDA, already migrated.
+    #[account(
+        seeds = [b"coordinator-config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ VrfError::Unauthorized,
+    )]
+    pub config: Account<'info, CoordinatorConfig>,
+
+    /// Request PDA, possibly in an older, shorter layout.
+    /// CHECK: Owner and discriminator are checked in the handler.
+    #[account(
+        mut,
+        seeds = [b"vrf-request", request_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub request: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grow a request left open across an upgrade to the current layout, so it
+/// can again be fulfilled, retried, consumed or cancelled.
+///
+/// Every layout is a prefix of the current one, so the fields a request
+/// gains start at zero: no fee paid, no callback attempts or sidecar, not
+/// store-only, SOL billing, no extra confirmation slots, the default callback
+/// prefix and no user data. Its callback ABI is set to `CALLBACK_V1`, the
+/// only one that existed before. The rent top-up is refunded to the
+/// requester with the rest of the rent when the request closes. A no-op on
+/// an up-to-date request.
+pub fn handler(ctx: Context<MigrateRequest>, _request_id: u64) -> Result<()> {
+    let request = ctx.accounts.request.to_account_info();
+
+    // Requests written with a `user_data` field keep it; the PDA is sized to it
+    let user_data_len = {
+        let data = request.try_borrow_data()?;
+        match data.get(USER_DATA_LEN_OFFSET..USER_DATA_LEN_OFFSET + 4) {
+            Some(len) => u32::from_le_bytes(len.try_into().unwrap()) as usize,
+            None => 0,
+        }
+    };
+    let new_len = RandomnessRequest::space(user_data_len);
+    let old_len = grow_account(
+        &request,
+        &ctx.accounts.admin,
+        &ctx.accounts.system_program,
+        RandomnessRequest::DISCRIMINATOR,
+        new_len,
+    )?;
+
+    if old_len < new_len {
+        let mut data = request.try_borrow_mut_data()?;
+        let mut randomness_request = RandomnessRequest::try_deserialize(&mut &data[..])?;
+        if randomness_request.callback_version == 0 {
+            randomness_request.callback_version = RandomnessRequest::CALLBACK_V1;
+        }
+        randomness_request.try_serialize(&mut &mut data[..])?;
+    }
+
+    emit!(AccountMigrated {
+        account: request.key(),
+        old_len: old_len as u32,
+        new_len: new_len as u32,
+    });
+
+    Ok(())
+}
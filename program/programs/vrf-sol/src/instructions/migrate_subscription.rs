@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::VrfError;
+use crate::events::AccountMigrated;
+use crate::instructions::migrate_config::grow_account;
+use crate::state::{CoordinatorConfig, Subscription};
+
+/// Accounts required to migrate a subscription.
+#[derive(Accounts)]
+#[instruction(subscription_id: u64)]
+pub struct MigrateSubscription<'info> {
+    /// Current admin; must sign and pays for the extra rent.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Coordinator configuration PDA, already migrated.
+    #[account(
+        seeds = [b"coordinator-config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ VrfError::Unauthorized,
+    )]
+    pub config: Account<'info, CoordinatorConfig>,
+
+    /// Subscription PDA, possibly in an older, shorter layout.
+    /// CHECK: Owner and discriminator are checked in the handler.
+    #[account(
+        mut,
+        seeds = [b"subscription", subscription_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub subscription: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grow a subscription to the current layout. The fields it gains start at
+/// zero: no pending fees or owner transfer, billed in SOL.
+///
+/// Request IDs used to come from the global `config.request_counter`, and
+/// request PDAs left open from then are seeded by those IDs. `req_count` is
+/// raised so the subscription's next ID is past all of them; only
+/// subscription 0, whose IDs start at 0, is affected. Until it is migrated a
+/// subscription fails to deserialize, so it cannot request into an old PDA.
+/// A no-op on an up-to-date subscription.
+pub fn handler(ctx: Context<MigrateSubscription>, _subscription_id: u64) -> Result<()> {
+    let subscription = ctx.accounts.subscription.to_account_info();
+    let new_len = 8 + Subscription::INIT_SPACE;
+    let old_len = grow_account(
+        &subscription,
+        &ctx.accounts.admin,
+        &ctx.accounts.system_program,
+        Subscription::DISCRIMINATOR,
+        new_len,
+    )?;
+
+    {
+        let mut data = subscription.try_borrow_mut_data()?;
+        let mut sub = Subscription::try_deserialize(&mut &data[..])?;
+        let first_free_index = ctx
+            .accounts
+            .config
+            .request_counter
+            .saturating_sub(sub.id << Subscription::REQUEST_INDEX_BITS);
+        if sub.req_count < first_free_index {
+            sub.req_count = first_free_index;
+            sub.try_serialize(&mut &mut data[..])?;
+        }
+    }
+
+    emit!(AccountMigrated {
+        account: subscription.key(),
+        old_len: old_len as u32,
+        new_len: new_len as u32,
+    });
+
+    Ok(())
+}
@@ -4,18 +4,87 @@ pub mod initialize;
 pub mod create_subscription;
 pub mod fund_subscription;
 pub mod cancel_subscription;
+pub mod create_token_subscription;
+pub mod fund_subscription_token;
+pub mod withdraw_subscription_tokens;
+pub mod cancel_token_subscription;
+pub mod request_subscription_owner_transfer;
+pub mod accept_subscription_owner_transfer;
+pub mod withdraw_subscription_funds;
 pub mod add_consumer;
 pub mod remove_consumer;
+pub mod set_consumer_callback;
+pub mod set_consumer_limits;
 pub mod request_random_words;
+pub mod request_random_words_direct;
+pub mod request_wallet_randomness;
 pub mod fulfill_random_words;
+pub mod fulfill_random_words_ecvrf;
+pub mod fulfill_random_words_multi;
+pub mod fulfill_random_words_batch;
+pub mod cancel_request;
+pub mod retry_callback;
+pub mod consume_randomness;
+pub mod consume_randomness_seed;
+pub mod close_callback_accounts;
+pub mod close_randomness_result;
 pub mod update_config;
+pub mod execute_config_update;
+pub mod cancel_config_update;
+pub mod accept_admin_transfer;
+pub mod migrate_config;
+pub mod migrate_subscription;
+pub mod migrate_registration;
+pub mod migrate_request;
+pub mod create_requester_nonce;
+pub mod pause;
+pub mod unpause;
+pub mod withdraw_oracle_fees;
+pub mod withdraw_protocol_fees;
+pub mod approve_mint;
+pub mod update_approved_mint;
+pub mod withdraw_oracle_token_fees;
+pub mod withdraw_protocol_token_fees;
 
 pub use initialize::*;
 pub use create_subscription::*;
 pub use fund_subscription::*;
 pub use cancel_subscription::*;
+pub use create_token_subscription::*;
+pub use fund_subscription_token::*;
+pub use withdraw_subscription_tokens::*;
+pub use cancel_token_subscription::*;
+pub use request_subscription_owner_transfer::*;
+pub use accept_subscription_owner_transfer::*;
+pub use withdraw_subscription_funds::*;
 pub use add_consumer::*;
 pub use remove_consumer::*;
+pub use set_consumer_callback::*;
+pub use set_consumer_limits::*;
 pub use request_random_words::*;
+pub use request_random_words_direct::*;
+pub use request_wallet_randomness::*;
 pub use fulfill_random_words::*;
+pub use fulfill_random_words_batch::*;
+pub use cancel_request::*;
+pub use retry_callback::*;
+pub use consume_randomness::*;
+pub use close_callback_accounts::*;
+pub use close_randomness_result::*;
 pub use update_config::*;
+pub use execute_config_update::*;
+pub use cancel_config_update::*;
+pub use accept_admin_transfer::*;
+pub use migrate_config::*;
+pub use migrate_subscription::*;
+pub use migrate_registration::*;
+pub use migrate_request::*;
+pub use create_requester_nonce::*;
+pub use pause::*;
+pub use unpause::*;
+pub use withdraw_oracle_fees::*;
+pub use withdraw_protocol_fees::*;
+pub use approve_mint::*;
+pub use update_approved_mint::*;
+pub use withdraw_oracle_token_fees::*;
+pub use withdraw_protocol_token_fees::*;
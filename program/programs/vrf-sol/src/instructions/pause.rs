@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::VrfError;
+use crate::events::CoordinatorPaused;
+use crate::state::CoordinatorConfig;
+
+/// Accounts required to pause the coordinator.
+#[derive(Accounts)]
+pub struct Pause<'info> {
+    /// The pauser or the admin; must sign.
+    pub signer: Signer<'info>,
+
+    /// Coordinator configuration PDA to update.
+    #[account(
+        mut,
+        seeds = [b"coordinator-config"],
+        bump = config.bump,
+        constraint = config.pauser == signer.key() || config.admin == signer.key() @ VrfError::Unauthorized,
+    )]
+    pub config: Account<'info, CoordinatorConfig>,
+}
+
+/// Halt new subscriptions, requests, fulfillments and callback retries.
+///
+/// Pending requests stay pending, so they can still be cancelled once their
+/// timeout elapses, or fulfilled after the admin unpauses.
+pub fn handler(ctx: Context<Pause>) -> Result<()> {
+    ctx.accounts.config.paused = true;
+
+    emit!(CoordinatorPaused {
+        paused_by: ctx.accounts.signer.key(),
+    });
+
+    Ok(())
+}
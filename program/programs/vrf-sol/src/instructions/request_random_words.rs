@@ -1,23 +1,39 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Allocate, Assign, CreateAccount, Transfer};
+use solana_sdk_ids::address_lookup_table;
 
 use crate::errors::VrfError;
 use crate::events::RandomWordsRequested;
-use crate::state::{CoordinatorConfig, ConsumerRegistration, RandomnessRequest, Subscription, MAX_CALLBACK_ACCOUNTS};
+use crate::state::{
+    ApprovedMint, CallbackAccountMeta, CallbackAccounts, CallbackOptions, CoordinatorConfig, ConsumerRegistration,
+    RandomnessRequest, RequestReceipt, Subscription, CONSUMER_AUTHORITY_SEED, MAX_CALLBACK_ACCOUNTS,
+    MAX_CALLBACK_PREFIX_LEN, MAX_SIDECAR_CALLBACK_ACCOUNTS, MAX_USER_DATA_LEN,
+};
 
 /// Accounts required to request random words.
 ///
-/// Called via CPI from a registered consumer program.
+/// Called via CPI from a registered consumer program, which signs with its
+/// `["consumer-authority"]` PDA.
 #[derive(Accounts)]
+#[instruction(
+    num_words: u32,
+    seed: [u8; 32],
+    callback_compute_limit: u32,
+    store_only: bool,
+    min_confirmation_slots: u64,
+    callback: CallbackOptions,
+)]
 pub struct RequestRandomWords<'info> {
     /// The account paying for the request PDA rent (typically the end-user).
     #[account(mut)]
     pub requester: Signer<'info>,
 
-    /// Coordinator configuration PDA (mutated to increment `request_counter`).
+    /// Coordinator configuration PDA. Read-only, so requests on different
+    /// subscriptions can execute in parallel.
     #[account(
-        mut,
         seeds = [b"coordinator-config"],
         bump = config.bump,
+        constraint = !config.paused @ VrfError::CoordinatorPaused,
     )]
     pub config: Account<'info, CoordinatorConfig>,
 
@@ -30,55 +46,149 @@ pub struct RequestRandomWords<'info> {
     pub subscription: Account<'info, Subscription>,
 
     /// Consumer registration proving the calling program is authorized.
+    /// Its limits are enforced and its usage counters updated.
     #[account(
+        mut,
         seeds = [b"consumer", subscription.id.to_le_bytes().as_ref(), consumer_program.key().as_ref()],
         bump = consumer_registration.bump,
     )]
     pub consumer_registration: Account<'info, ConsumerRegistration>,
 
     /// The consumer program making this CPI call.
-    /// CHECK: Validated via consumer_registration PDA derivation.
+    /// CHECK: Validated via consumer_registration PDA derivation; proven to be
+    /// the caller by `consumer_authority`.
     pub consumer_program: UncheckedAccount<'info>,
 
-    /// New request PDA. Seeds: `["request", counter.to_le_bytes()]`.
+    /// The consumer program's `["consumer-authority"]` PDA. Only
+    /// `consumer_program` can sign for it, so a signature proves the request
+    /// comes from that program rather than a wallet naming it.
+    /// CHECK: Must sign and match the PDA derived from `consumer_program`.
+    #[account(
+        constraint = consumer_authority.is_signer
+            && consumer_authority.key()
+                == Pubkey::find_program_address(&[CONSUMER_AUTHORITY_SEED], &consumer_program.key()).0
+            @ VrfError::InvalidConsumerProgram,
+    )]
+    pub consumer_authority: UncheckedAccount<'info>,
+
+    /// New request PDA, sized to `callback.user_data`.
+    /// Seeds: `["vrf-request", subscription.next_request_id().to_le_bytes()]`.
     #[account(
         init,
         payer = requester,
-        space = 8 + RandomnessRequest::INIT_SPACE,
-        seeds = [b"vrf-request", config.request_counter.to_le_bytes().as_ref()],
+        space = RandomnessRequest::space(callback.user_data.as_ref().map_or(0, Vec::len)),
+        seeds = [b"vrf-request", subscription.next_request_id().to_le_bytes().as_ref()],
         bump,
     )]
     pub request: Account<'info, RandomnessRequest>,
 
     pub system_program: Program<'info, System>,
+
+    /// Optional callback accounts sidecar, created by the handler and sized
+    /// to the remaining accounts. Required for more than
+    /// `MAX_CALLBACK_ACCOUNTS` callback accounts.
+    /// CHECK: Seeds `["callback-accounts", request_id]`; created in the handler.
+    #[account(
+        mut,
+        seeds = [b"callback-accounts", subscription.next_request_id().to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub callback_accounts: Option<UncheckedAccount<'info>>,
+
+    /// Optional address lookup table covering the callback accounts, recorded
+    /// in the sidecar so the oracle can fit them into a v0 transaction.
+    /// CHECK: Owner must be the address lookup table program.
+    #[account(owner = address_lookup_table::ID @ VrfError::InvalidCallbackAccounts)]
+    pub callback_lookup_table: Option<UncheckedAccount<'info>>,
+
+    /// The subscription's approved billing mint; required for token-billed
+    /// subscriptions, which pay its prices.
+    #[account(
+        seeds = [b"approved-mint", subscription.billing_mint.as_ref()],
+        bump = approved_mint.bump,
+    )]
+    pub approved_mint: Option<Account<'info, ApprovedMint>>,
 }
 
 /// Request random words from the VRF oracle.
 ///
-/// Remaining accounts (if any) are stored in the request PDA as callback
-/// accounts. The backend oracle reads them and passes them as
-/// remaining_accounts when fulfilling, so the consumer's callback receives
-/// the accounts it needs.
+/// The fee covers a base fee, `num_words` and the `callback_compute_limit`
+/// the oracle must buy for the callback (see `FeeSchedule`). Limits above
+/// `config.max_callback_compute_limit` are rejected.
+///
+/// The request cannot be fulfilled until `min_confirmation_slots` (at least
+/// `config.min_confirmation_slots`) have passed since `request_slot`.
+///
+/// Remaining accounts (if any) are stored as callback accounts: inline in the
+/// request PDA (up to `MAX_CALLBACK_ACCOUNTS`), or in the `callback_accounts`
+/// sidecar when one is supplied (up to `MAX_SIDECAR_CALLBACK_ACCOUNTS`).
+/// Longer lists are rejected rather than truncated. The backend oracle reads
+/// them and passes them as remaining_accounts when fulfilling, so the
+/// consumer's callback receives the accounts it needs.
+///
+/// With `store_only` no callback is made: fulfillment stores the output and
+/// the consumer pulls it with `consume_randomness`. Callback accounts are
+/// ignored (no sidecar is created) and `num_words` is capped at
+/// `MAX_STORE_ONLY_WORDS`.
+///
+/// With `callback.user_data` (at most `MAX_USER_DATA_LEN` bytes) the request is
+/// stored with `CALLBACK_V2` and the callback receives the data as a third
+/// argument. Without it the callback keeps the two-argument `CALLBACK_V1`
+/// signature.
+///
+/// The callback instruction data starts with `callback.prefix` (at most
+/// `MAX_CALLBACK_PREFIX_LEN` bytes) or, without one, the registration's
+/// prefix set by `set_consumer_callback`. An empty prefix means Anchor's
+/// `fulfill_random_words` discriminator.
+///
+/// The consumer's registration limits (see `set_consumer_limits`) are
+/// checked against the fee, and its usage counters updated.
 pub fn handler<'info>(
     ctx: Context<'_, '_, '_, 'info, RequestRandomWords<'info>>,
     num_words: u32,
     seed: [u8; 32],
     callback_compute_limit: u32,
-) -> Result<()> {
+    store_only: bool,
+    min_confirmation_slots: u64,
+    callback: CallbackOptions,
+) -> Result<RequestReceipt> {
     let config = &ctx.accounts.config;
+    let mut params = RequestParams {
+        num_words,
+        seed,
+        callback_compute_limit,
+        store_only,
+        min_confirmation_slots,
+        user_data: callback.user_data,
+        callback_prefix: callback.prefix,
+    }
+    .validate(config)?;
+    params
+        .callback_prefix
+        .get_or_insert_with(|| ctx.accounts.consumer_registration.callback_prefix().to_vec());
 
-    // Validate num_words
-    require!(
-        num_words > 0 && num_words <= config.max_num_words,
-        VrfError::NumWordsTooLarge
-    );
-
-    // Calculate fee and check subscription balance
-    let total_fee = config
-        .fee_per_word
-        .checked_mul(num_words as u64)
+    // Calculate fee and check subscription balance. Token-billed
+    // subscriptions pay the approved mint's prices, in its base units.
+    let fee_schedule = if ctx.accounts.subscription.is_token_billed() {
+        let approved_mint = ctx
+            .accounts
+            .approved_mint
+            .as_ref()
+            .ok_or(VrfError::MintNotApproved)?;
+        require!(approved_mint.enabled, VrfError::MintNotApproved);
+        approved_mint.fee_schedule()
+    } else {
+        config.fee_schedule()
+    };
+    let total_fee = fee_schedule
+        .request_fee(params.num_words, params.callback_compute_limit)
         .ok_or(VrfError::CounterOverflow)?;
 
+    let clock = Clock::get()?;
+    ctx.accounts
+        .consumer_registration
+        .record_request(params.num_words, total_fee, &clock)?;
+
     let subscription = &mut ctx.accounts.subscription;
     require!(
         subscription.balance >= total_fee,
@@ -91,56 +201,298 @@ pub fn handler<'info>(
         .checked_sub(total_fee)
         .ok_or(VrfError::InsufficientSubscriptionBalance)?;
 
-    subscription.req_count = subscription.req_count.checked_add(1).unwrap();
+    // The fee stays in the subscription PDA until the request is fulfilled or
+    // cancelled.
+    subscription.pending_fees = subscription
+        .pending_fees
+        .checked_add(total_fee)
+        .ok_or(VrfError::CounterOverflow)?;
 
-    // Initialize the request PDA
-    let config = &mut ctx.accounts.config;
-    let request_id = config.request_counter;
+    // The request index must fit in the low bits of the request ID.
+    require!(
+        subscription.req_count < Subscription::MAX_REQUEST_ID_PART,
+        VrfError::CounterOverflow
+    );
+    let request_id = subscription.next_request_id();
+    subscription.req_count += 1;
+
+    init_request(
+        &mut ctx.accounts.request,
+        ctx.bumps.request,
+        &ctx.accounts.requester,
+        &ctx.accounts.system_program,
+        ctx.accounts.callback_accounts.as_ref(),
+        ctx.bumps.callback_accounts,
+        ctx.accounts.callback_lookup_table.as_ref(),
+        ctx.remaining_accounts,
+        ctx.program_id,
+        request_id,
+        ctx.accounts.subscription.id,
+        ctx.accounts.consumer_program.key(),
+        &params,
+        total_fee,
+        ctx.accounts.subscription.billing_mint,
+        clock.slot,
+    )
+}
+
+/// Caller-supplied parameters of a new request.
+pub(crate) struct RequestParams {
+    pub num_words: u32,
+    pub seed: [u8; 32],
+    pub callback_compute_limit: u32,
+    pub store_only: bool,
+    pub min_confirmation_slots: u64,
+    pub user_data: Option<Vec<u8>>,
+    pub callback_prefix: Option<Vec<u8>>,
+}
+
+impl RequestParams {
+    /// Check the parameters against the coordinator's limits. Store-only
+    /// requests reserve no callback compute, and `min_confirmation_slots` is
+    /// raised to the coordinator's minimum.
+    pub(crate) fn validate(mut self, config: &CoordinatorConfig) -> Result<Self> {
+        require!(
+            self.num_words > 0 && self.num_words <= config.max_num_words,
+            VrfError::NumWordsTooLarge
+        );
+        require!(
+            !self.store_only || self.num_words <= RandomnessRequest::MAX_STORE_ONLY_WORDS,
+            VrfError::NumWordsTooLarge
+        );
+
+        // Store-only requests make no callback, so there is no callback compute
+        // to reserve or charge for.
+        if self.store_only {
+            self.callback_compute_limit = 0;
+        }
+        require!(
+            self.callback_compute_limit <= config.max_callback_compute_limit,
+            VrfError::CallbackComputeLimitTooLarge
+        );
+
+        self.min_confirmation_slots = self
+            .min_confirmation_slots
+            .max(config.min_confirmation_slots);
+        require!(
+            self.min_confirmation_slots < config.request_timeout_slots,
+            VrfError::InvalidConfirmationSlots
+        );
+        require!(
+            self.user_data
+                .as_ref()
+                .is_none_or(|data| data.len() <= MAX_USER_DATA_LEN),
+            VrfError::UserDataTooLong
+        );
+        require!(
+            self.callback_prefix
+                .as_ref()
+                .is_none_or(|prefix| prefix.len() <= MAX_CALLBACK_PREFIX_LEN),
+            VrfError::CallbackPrefixTooLong
+        );
+        Ok(self)
+    }
+}
+
+/// Write a new request PDA, store its callback accounts and emit
+/// `RandomWordsRequested`. The fee has already been charged.
+///
+/// Callback accounts come from `remaining_accounts`, inline or in the
+/// `callback_accounts` sidecar, as described on [`handler`].
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn init_request<'info>(
+    request: &mut Account<'info, RandomnessRequest>,
+    bump: u8,
+    requester: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    callback_accounts: Option<&UncheckedAccount<'info>>,
+    callback_accounts_bump: Option<u8>,
+    callback_lookup_table: Option<&UncheckedAccount<'info>>,
+    remaining_accounts: &[AccountInfo<'info>],
+    program_id: &Pubkey,
+    request_id: u64,
+    subscription_id: u64,
+    consumer_program: Pubkey,
+    params: &RequestParams,
+    fee: u64,
+    fee_mint: Pubkey,
+    slot: u64,
+) -> Result<RequestReceipt> {
+    let store_only = params.store_only;
 
-    let request = &mut ctx.accounts.request;
     request.request_id = request_id;
-    request.subscription_id = ctx.accounts.subscription.id;
-    request.consumer_program = ctx.accounts.consumer_program.key();
-    request.requester = ctx.accounts.requester.key();
-    request.num_words = num_words;
-    request.seed = seed;
-    request.request_slot = Clock::get()?.slot;
-    request.callback_compute_limit = callback_compute_limit;
+    request.subscription_id = subscription_id;
+    request.consumer_program = consumer_program;
+    request.requester = requester.key();
+    request.num_words = params.num_words;
+    request.seed = params.seed;
+    request.request_slot = slot;
+    request.callback_compute_limit = params.callback_compute_limit;
     request.status = RandomnessRequest::STATUS_PENDING;
     request.randomness = [0u8; 32];
     request.fulfilled_slot = 0;
-    request.bump = ctx.bumps.request;
-
-    // Store callback accounts from remaining_accounts (up to MAX_CALLBACK_ACCOUNTS).
-    let remaining = ctx.remaining_accounts;
-    let count = remaining.len().min(MAX_CALLBACK_ACCOUNTS);
-    request.callback_account_count = count as u8;
-    let mut keys = [Pubkey::default(); MAX_CALLBACK_ACCOUNTS];
-    let mut bitmap: u8 = 0;
-    for i in 0..count {
-        keys[i] = remaining[i].key();
-        if remaining[i].is_writable {
-            bitmap |= 1 << i;
+    request.bump = bump;
+
+    request.fee_paid = fee;
+    request.fee_mint = fee_mint;
+    request.min_confirmation_slots = params.min_confirmation_slots;
+    request.callback_version = if params.user_data.is_some() {
+        RandomnessRequest::CALLBACK_V2
+    } else {
+        RandomnessRequest::CALLBACK_V1
+    };
+    request.user_data = params.user_data.clone().unwrap_or_default();
+    let callback_prefix = params.callback_prefix.as_deref().unwrap_or_default();
+    request.callback_prefix = [0u8; MAX_CALLBACK_PREFIX_LEN];
+    request.callback_prefix[..callback_prefix.len()].copy_from_slice(callback_prefix);
+    request.callback_prefix_len = callback_prefix.len() as u8;
+    request.callback_attempts = 0;
+    request.store_only = store_only;
+    request.callback_account_keys = [Pubkey::default(); MAX_CALLBACK_ACCOUNTS];
+    request.callback_account_count = 0;
+    request.callback_writable_bitmap = 0;
+    request.callback_sidecar = false;
+
+    // Store callback accounts from remaining_accounts.
+    let remaining = if store_only { &[][..] } else { remaining_accounts };
+    match callback_accounts {
+        Some(sidecar) if !store_only => {
+            require!(
+                remaining.len() <= MAX_SIDECAR_CALLBACK_ACCOUNTS,
+                VrfError::TooManyCallbackAccounts
+            );
+            let lookup_table =
+                callback_lookup_table.map_or(Pubkey::default(), |table| table.key());
+            let bump = callback_accounts_bump.ok_or(VrfError::InvalidCallbackAccounts)?;
+            create_callback_accounts(
+                requester,
+                sidecar,
+                system_program,
+                program_id,
+                CallbackAccounts {
+                    request_id,
+                    requester: requester.key(),
+                    lookup_table,
+                    bump,
+                    accounts: remaining
+                        .iter()
+                        .map(|account| CallbackAccountMeta {
+                            pubkey: account.key(),
+                            is_writable: account.is_writable,
+                        })
+                        .collect(),
+                },
+            )?;
+            request.callback_sidecar = true;
+        }
+        _ => {
+            require!(
+                callback_lookup_table.is_none() || store_only,
+                VrfError::InvalidCallbackAccounts
+            );
+            require!(
+                remaining.len() <= MAX_CALLBACK_ACCOUNTS,
+                VrfError::TooManyCallbackAccounts
+            );
+            let mut bitmap: u8 = 0;
+            for (i, account) in remaining.iter().enumerate() {
+                request.callback_account_keys[i] = account.key();
+                if account.is_writable {
+                    bitmap |= 1 << i;
+                }
+            }
+            request.callback_account_count = remaining.len() as u8;
+            request.callback_writable_bitmap = bitmap;
         }
     }
-    request.callback_account_keys = keys;
-    request.callback_writable_bitmap = bitmap;
-
-    config.request_counter = config
-        .request_counter
-        .checked_add(1)
-        .ok_or(VrfError::CounterOverflow)?;
 
     emit!(RandomWordsRequested {
         request_id,
-        subscription_id: request.subscription_id,
-        consumer_program: request.consumer_program,
+        subscription_id,
+        consumer_program,
         requester: request.requester,
-        num_words,
-        seed,
-        request_slot: request.request_slot,
-        callback_compute_limit,
+        num_words: params.num_words,
+        seed: params.seed,
+        request_slot: slot,
+        callback_compute_limit: params.callback_compute_limit,
+        min_confirmation_slots: params.min_confirmation_slots,
     });
 
-    Ok(())
+    Ok(RequestReceipt {
+        request_id,
+        request: request.key(),
+    })
+}
+
+/// Create the `CallbackAccounts` sidecar PDA sized to `data` and write it.
+///
+/// Like Anchor's `init`, a PDA already holding lamports (e.g. pre-funded by a
+/// third party) is topped up, allocated and assigned instead of failing
+/// `create_account`.
+fn create_callback_accounts<'info>(
+    payer: &Signer<'info>,
+    sidecar: &UncheckedAccount<'info>,
+    system: &Program<'info, System>,
+    program_id: &Pubkey,
+    data: CallbackAccounts,
+) -> Result<()> {
+    let space = CallbackAccounts::space(data.accounts.len());
+    let rent = Rent::get()?.minimum_balance(space);
+    let request_id = data.request_id.to_le_bytes();
+    let seeds: &[&[u8]] = &[b"callback-accounts", request_id.as_ref(), &[data.bump]];
+    let signer = &[seeds];
+
+    let current = sidecar.lamports();
+    if current == 0 {
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                system.to_account_info(),
+                CreateAccount {
+                    from: payer.to_account_info(),
+                    to: sidecar.to_account_info(),
+                },
+                signer,
+            ),
+            rent,
+            space as u64,
+            program_id,
+        )?;
+    } else {
+        let top_up = rent.saturating_sub(current);
+        if top_up > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    system.to_account_info(),
+                    Transfer {
+                        from: payer.to_account_info(),
+                        to: sidecar.to_account_info(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+        system_program::allocate(
+            CpiContext::new_with_signer(
+                system.to_account_info(),
+                Allocate {
+                    account_to_allocate: sidecar.to_account_info(),
+                },
+                signer,
+            ),
+            space as u64,
+        )?;
+        system_program::assign(
+            CpiContext::new_with_signer(
+                system.to_account_info(),
+                Assign {
+                    account_to_assign: sidecar.to_account_info(),
+                },
+                signer,
+            ),
+            program_id,
+        )?;
+    }
+
+    let mut buf = sidecar.try_borrow_mut_data()?;
+    data.try_serialize(&mut &mut buf[..])
 }
@@ -0,0 +1,183 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use solana_sdk_ids::address_lookup_table;
+
+use crate::errors::VrfError;
+use crate::instructions::request_random_words::{init_request, RequestParams};
+use crate::state::{
+    CallbackOptions, CoordinatorConfig, RandomnessRequest, RequestReceipt, RequesterNonce,
+    Subscription, CONSUMER_AUTHORITY_SEED,
+};
+
+/// Accounts required to request random words paid for by the requester.
+///
+/// Called via CPI from a consumer program, which signs with its
+/// `["consumer-authority"]` PDA. No subscription or consumer registration is
+/// involved.
+#[derive(Accounts)]
+#[instruction(
+    num_words: u32,
+    seed: [u8; 32],
+    callback_compute_limit: u32,
+    store_only: bool,
+    min_confirmation_slots: u64,
+    callback: CallbackOptions,
+)]
+pub struct RequestRandomWordsDirect<'info> {
+    /// Pays the fee and the request PDA rent.
+    #[account(mut)]
+    pub requester: Signer<'info>,
+
+    /// Coordinator configuration PDA (read for prices and limits).
+    #[account(
+        seeds = [b"coordinator-config"],
+        bump = config.bump,
+        constraint = !config.paused @ VrfError::CoordinatorPaused,
+    )]
+    pub config: Account<'info, CoordinatorConfig>,
+
+    /// The requester's nonce, numbering their direct requests.
+    #[account(
+        mut,
+        seeds = [b"requester-nonce", requester.key().as_ref()],
+        bump = requester_nonce.bump,
+    )]
+    pub requester_nonce: Account<'info, RequesterNonce>,
+
+    /// The consumer program making this CPI call.
+    /// CHECK: Proven to be the caller by `consumer_authority`.
+    pub consumer_program: UncheckedAccount<'info>,
+
+    /// The consumer program's `["consumer-authority"]` PDA.
+    /// CHECK: Must sign and match the PDA derived from `consumer_program`.
+    #[account(
+        constraint = consumer_authority.is_signer
+            && consumer_authority.key()
+                == Pubkey::find_program_address(&[CONSUMER_AUTHORITY_SEED], &consumer_program.key()).0
+            @ VrfError::InvalidConsumerProgram,
+    )]
+    pub consumer_authority: UncheckedAccount<'info>,
+
+    /// New request PDA, sized to `callback.user_data`; also holds the fee.
+    /// Seeds: `["vrf-request", requester_nonce.next_request_id().to_le_bytes()]`.
+    #[account(
+        init,
+        payer = requester,
+        space = RandomnessRequest::space(callback.user_data.as_ref().map_or(0, Vec::len)),
+        seeds = [b"vrf-request", requester_nonce.next_request_id().to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub request: Account<'info, RandomnessRequest>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Optional callback accounts sidecar, as on `request_random_words`.
+    /// CHECK: Seeds `["callback-accounts", request_id]`; created in the handler.
+    #[account(
+        mut,
+        seeds = [b"callback-accounts", requester_nonce.next_request_id().to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub callback_accounts: Option<UncheckedAccount<'info>>,
+
+    /// Optional address lookup table covering the callback accounts.
+    /// CHECK: Owner must be the address lookup table program.
+    #[account(owner = address_lookup_table::ID @ VrfError::InvalidCallbackAccounts)]
+    pub callback_lookup_table: Option<UncheckedAccount<'info>>,
+}
+
+/// Request random words, paying the fee from `requester` instead of a
+/// subscription.
+///
+/// The fee is the coordinator's SOL price for the request (see
+/// `FeeSchedule`) and moves into the request PDA, held there until
+/// fulfillment pays it to the fee vault split between oracle and protocol,
+/// or cancellation refunds it to the requester with the rent. Paying the
+/// vault at request time would write-lock it in every direct request. The
+/// request's ID comes from the requester's `RequesterNonce` and its
+/// `subscription_id` is `Subscription::DIRECT_SUBSCRIPTION_ID`. Parameters,
+/// user data, callback accounts and the rest of the request's lifecycle are the same
+/// as for `request_random_words`, except that with no registration a missing
+/// `callback.prefix` means Anchor's `fulfill_random_words` discriminator.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, RequestRandomWordsDirect<'info>>,
+    num_words: u32,
+    seed: [u8; 32],
+    callback_compute_limit: u32,
+    store_only: bool,
+    min_confirmation_slots: u64,
+    callback: CallbackOptions,
+) -> Result<RequestReceipt> {
+    let config = &ctx.accounts.config;
+    let params = RequestParams {
+        num_words,
+        seed,
+        callback_compute_limit,
+        store_only,
+        min_confirmation_slots,
+        user_data: callback.user_data,
+        callback_prefix: callback.prefix,
+    }
+    .validate(config)?;
+    let total_fee = config
+        .fee_schedule()
+        .request_fee(params.num_words, params.callback_compute_limit)
+        .ok_or(VrfError::CounterOverflow)?;
+
+    let request_id = charge_direct_request(
+        &ctx.accounts.requester,
+        &mut ctx.accounts.requester_nonce,
+        &ctx.accounts.request,
+        &ctx.accounts.system_program,
+        total_fee,
+    )?;
+
+    init_request(
+        &mut ctx.accounts.request,
+        ctx.bumps.request,
+        &ctx.accounts.requester,
+        &ctx.accounts.system_program,
+        ctx.accounts.callback_accounts.as_ref(),
+        ctx.bumps.callback_accounts,
+        ctx.accounts.callback_lookup_table.as_ref(),
+        ctx.remaining_accounts,
+        ctx.program_id,
+        request_id,
+        Subscription::DIRECT_SUBSCRIPTION_ID,
+        ctx.accounts.consumer_program.key(),
+        &params,
+        total_fee,
+        Pubkey::default(),
+        Clock::get()?.slot,
+    )
+}
+
+/// Move a direct-funded request's fee from `requester` into its request
+/// PDA, advance the requester's nonce, and return the request's ID.
+pub(crate) fn charge_direct_request<'info>(
+    requester: &Signer<'info>,
+    requester_nonce: &mut Account<'info, RequesterNonce>,
+    request: &Account<'info, RandomnessRequest>,
+    system_program: &Program<'info, System>,
+    fee: u64,
+) -> Result<u64> {
+    system_program::transfer(
+        CpiContext::new(
+            system_program.to_account_info(),
+            Transfer {
+                from: requester.to_account_info(),
+                to: request.to_account_info(),
+            },
+        ),
+        fee,
+    )?;
+
+    // The request index must fit in the low bits of the request ID.
+    require!(
+        requester_nonce.req_count < Subscription::MAX_REQUEST_ID_PART,
+        VrfError::CounterOverflow
+    );
+    let request_id = requester_nonce.next_request_id();
+    requester_nonce.req_count += 1;
+    Ok(request_id)
+}
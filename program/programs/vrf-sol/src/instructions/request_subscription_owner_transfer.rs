@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::VrfError;
+use crate::events::SubscriptionOwnerTransferRequested;
+use crate::state::Subscription;
+
+/// Accounts required to propose a new subscription owner.
+#[derive(Accounts)]
+#[instruction(subscription_id: u64)]
+pub struct RequestSubscriptionOwnerTransfer<'info> {
+    /// The current subscription owner; must sign.
+    pub owner: Signer<'info>,
+
+    /// The subscription PDA to transfer.
+    #[account(
+        mut,
+        seeds = [b"subscription", subscription_id.to_le_bytes().as_ref()],
+        bump = subscription.bump,
+        constraint = subscription.owner == owner.key() @ VrfError::Unauthorized,
+    )]
+    pub subscription: Account<'info, Subscription>,
+}
+
+/// Record `new_owner` as the subscription's pending owner. Ownership only
+/// changes once they accept. Passing the default pubkey withdraws the offer.
+pub fn handler(
+    ctx: Context<RequestSubscriptionOwnerTransfer>,
+    _subscription_id: u64,
+    new_owner: Pubkey,
+) -> Result<()> {
+    let subscription = &mut ctx.accounts.subscription;
+    subscription.pending_owner = new_owner;
+
+    emit!(SubscriptionOwnerTransferRequested {
+        subscription_id: subscription.id,
+        owner: subscription.owner,
+        pending_owner: new_owner,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::VrfError;
+use crate::instructions::request_random_words::{init_request, RequestParams};
+use crate::instructions::request_random_words_direct::charge_direct_request;
+use crate::state::{
+    CoordinatorConfig, RandomnessRequest, RandomnessResult, RequestReceipt, RequesterNonce,
+    Subscription,
+};
+
+/// Accounts required to request randomness straight from a wallet.
+///
+/// No consumer program, subscription or consumer registration is involved:
+/// the requester signs and pays, and reads the output from the
+/// `RandomnessResult` afterwards.
+#[derive(Accounts)]
+pub struct RequestWalletRandomness<'info> {
+    /// Pays the fee and the rent of both new PDAs.
+    #[account(mut)]
+    pub requester: Signer<'info>,
+
+    /// Coordinator configuration PDA (read for prices and limits).
+    #[account(
+        seeds = [b"coordinator-config"],
+        bump = config.bump,
+        constraint = !config.paused @ VrfError::CoordinatorPaused,
+    )]
+    pub config: Account<'info, CoordinatorConfig>,
+
+    /// The requester's nonce, numbering their direct requests.
+    #[account(
+        mut,
+        seeds = [b"requester-nonce", requester.key().as_ref()],
+        bump = requester_nonce.bump,
+    )]
+    pub requester_nonce: Account<'info, RequesterNonce>,
+
+    /// New request PDA; also holds the fee.
+    /// Seeds: `["vrf-request", requester_nonce.next_request_id().to_le_bytes()]`.
+    #[account(
+        init,
+        payer = requester,
+        space = RandomnessRequest::space(0),
+        seeds = [b"vrf-request", requester_nonce.next_request_id().to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub request: Account<'info, RandomnessRequest>,
+
+    /// New result PDA, written on fulfillment.
+    /// Seeds: `["randomness-result", requester_nonce.next_request_id().to_le_bytes()]`.
+    #[account(
+        init,
+        payer = requester,
+        space = 8 + RandomnessResult::INIT_SPACE,
+        seeds = [b"randomness-result", requester_nonce.next_request_id().to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub randomness_result: Account<'info, RandomnessResult>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Request random words for a wallet, with no consumer program.
+///
+/// The request is direct-funded: the SOL fee for `num_words` with no
+/// callback compute moves into the request PDA, as for
+/// `request_random_words_direct`, and its `subscription_id` is
+/// `Subscription::DIRECT_SUBSCRIPTION_ID`. The request's `consumer_program`
+/// is `Pubkey::default()`, so fulfillment makes no callback and writes the
+/// randomness to the `RandomnessResult` created here. Cancellation after
+/// `request_timeout_slots` refunds the fee as for any direct request.
+pub fn handler(
+    ctx: Context<RequestWalletRandomness>,
+    num_words: u32,
+    seed: [u8; 32],
+    min_confirmation_slots: u64,
+) -> Result<RequestReceipt> {
+    let config = &ctx.accounts.config;
+    let params = RequestParams {
+        num_words,
+        seed,
+        callback_compute_limit: 0,
+        store_only: false,
+        min_confirmation_slots,
+        user_data: None,
+        callback_prefix: None,
+    }
+    .validate(config)?;
+    let total_fee = config
+        .fee_schedule()
+        .request_fee(params.num_words, 0)
+        .ok_or(VrfError::CounterOverflow)?;
+
+    let request_id = charge_direct_request(
+        &ctx.accounts.requester,
+        &mut ctx.accounts.requester_nonce,
+        &ctx.accounts.request,
+        &ctx.accounts.system_program,
+        total_fee,
+    )?;
+    let slot = Clock::get()?.slot;
+
+    let result = &mut ctx.accounts.randomness_result;
+    result.request_id = request_id;
+    result.requester = ctx.accounts.requester.key();
+    result.num_words = params.num_words;
+    result.seed = params.seed;
+    result.request_slot = slot;
+    result.randomness = [0u8; 32];
+    result.fulfilled_slot = 0;
+    result.bump = ctx.bumps.randomness_result;
+
+    init_request(
+        &mut ctx.accounts.request,
+        ctx.bumps.request,
+        &ctx.accounts.requester,
+        &ctx.accounts.system_program,
+        None,
+        None,
+        None,
+        &[],
+        ctx.program_id,
+        request_id,
+        Subscription::DIRECT_SUBSCRIPTION_ID,
+        Pubkey::default(),
+        &params,
+        total_fee,
+        Pubkey::default(),
+        slot,
+    )
+}
@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::VrfError;
+use crate::events::{RandomWordsCallbackFailed, RandomWordsFulfilled};
+use crate::instructions::fulfill_random_words::{invoke_callback, stored_callback_accounts};
+use crate::state::{
+    CallbackAccounts, CoordinatorConfig, RandomnessRequest, MAX_CALLBACK_FAILURE_LEN,
+};
+
+/// Accounts required to retry delivery of a stored randomness result.
+#[derive(Accounts)]
+#[instruction(request_id: u64)]
+pub struct RetryCallback<'info> {
+    /// Anyone may retry delivery; recording a failure requires an oracle key.
+    pub caller: Signer<'info>,
+
+    /// Coordinator configuration PDA (CPI signer).
+    #[account(
+        seeds = [b"coordinator-config"],
+        bump = config.bump,
+        constraint = !config.paused @ VrfError::CoordinatorPaused,
+    )]
+    pub config: Account<'info, CoordinatorConfig>,
+
+    /// The request whose callback previously failed.
+    /// Closed after delivery; rent refunded to requester.
+    #[account(
+        mut,
+        seeds = [b"vrf-request", request_id.to_le_bytes().as_ref()],
+        bump = request.bump,
+        constraint = request.status == RandomnessRequest::STATUS_CALLBACK_FAILED @ VrfError::CallbackNotFailed,
+    )]
+    pub request: Account<'info, RandomnessRequest>,
+
+    /// The original requester who receives rent refund when request is closed.
+    /// CHECK: Validated by matching request.requester.
+    #[account(
+        mut,
+        constraint = requester.key() == request.requester @ VrfError::Unauthorized,
+    )]
+    pub requester: UncheckedAccount<'info>,
+
+    /// The consumer program to CPI into for the callback.
+    /// CHECK: Validated by matching request.consumer_program.
+    #[account(
+        constraint = consumer_program.key() == request.consumer_program @ VrfError::InvalidConsumerProgram,
+    )]
+    pub consumer_program: UncheckedAccount<'info>,
+
+    /// Callback accounts sidecar; required when `request.callback_sidecar` is set.
+    #[account(
+        seeds = [b"callback-accounts", request_id.to_le_bytes().as_ref()],
+        bump = callback_accounts.bump,
+    )]
+    pub callback_accounts: Option<Account<'info, CallbackAccounts>>,
+
+    // remaining_accounts: the stored callback accounts, in order
+}
+
+/// Re-deliver the stored randomness to the consumer.
+///
+/// A reverted callback aborts the transaction, so failed retries leave no
+/// trace on-chain. With a `record_failure` reason an oracle instead counts a
+/// failed attempt (observed in simulation), emitting the reason; after
+/// `MAX_CALLBACK_ATTEMPTS` further retries are rejected and the consumer must
+/// pull the result with `consume_randomness`, or `consume_randomness_seed`
+/// for more words than fit in return data.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, RetryCallback<'info>>,
+    request_id: u64,
+    record_failure: Option<String>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.request.callback_attempts < RandomnessRequest::MAX_CALLBACK_ATTEMPTS,
+        VrfError::CallbackRetriesExhausted
+    );
+
+    if let Some(reason) = record_failure {
+        require!(
+            ctx.accounts.config.is_fulfiller(&ctx.accounts.caller.key()),
+            VrfError::Unauthorized
+        );
+        require!(
+            reason.len() <= MAX_CALLBACK_FAILURE_LEN,
+            VrfError::CallbackFailureTooLong
+        );
+        let request = &mut ctx.accounts.request;
+        request.callback_attempts += 1;
+        emit!(RandomWordsCallbackFailed {
+            request_id,
+            randomness: request.randomness,
+            consumer_program: request.consumer_program,
+            attempts: request.callback_attempts,
+            reason,
+        });
+        return Ok(());
+    }
+
+    let request = &mut ctx.accounts.request;
+    let randomness = request.randomness;
+    request.status = RandomnessRequest::STATUS_FULFILLED;
+    let expected = stored_callback_accounts(request, ctx.accounts.callback_accounts.as_ref())?;
+
+    invoke_callback(
+        &ctx.accounts.config,
+        &ctx.accounts.consumer_program,
+        &expected,
+        ctx.remaining_accounts,
+        request,
+        &randomness,
+    )?;
+
+    ctx.accounts
+        .request
+        .close(ctx.accounts.requester.to_account_info())?;
+
+    emit!(RandomWordsFulfilled {
+        request_id,
+        randomness,
+        consumer_program: ctx.accounts.consumer_program.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::VrfError;
+use crate::events::ConsumerCallbackUpdated;
+use crate::state::{ConsumerRegistration, CONSUMER_AUTHORITY_SEED, MAX_CALLBACK_PREFIX_LEN};
+
+/// Accounts required to change a consumer's default callback prefix.
+///
+/// Called via CPI from the consumer program, which signs with its
+/// `["consumer-authority"]` PDA: the callback format is the program's own
+/// ABI, so only the program may choose it.
+#[derive(Accounts)]
+#[instruction(subscription_id: u64)]
+pub struct SetConsumerCallback<'info> {
+    /// The consumer program whose callback prefix changes.
+    /// CHECK: Used for PDA derivation; proven by `consumer_authority`.
+    pub consumer_program: UncheckedAccount<'info>,
+
+    /// The consumer program's `["consumer-authority"]` PDA.
+    /// CHECK: Must sign and match the PDA derived from `consumer_program`.
+    #[account(
+        constraint = consumer_authority.is_signer
+            && consumer_authority.key()
+                == Pubkey::find_program_address(&[CONSUMER_AUTHORITY_SEED], &consumer_program.key()).0
+            @ VrfError::InvalidConsumerProgram,
+    )]
+    pub consumer_authority: UncheckedAccount<'info>,
+
+    /// Consumer registration PDA holding the prefix.
+    #[account(
+        mut,
+        seeds = [b"consumer", subscription_id.to_le_bytes().as_ref(), consumer_program.key().as_ref()],
+        bump = consumer_registration.bump,
+    )]
+    pub consumer_registration: Account<'info, ConsumerRegistration>,
+}
+
+/// Set the instruction prefix of callbacks for this consumer's requests
+/// under the subscription. An empty prefix restores Anchor's
+/// `fulfill_random_words` discriminator.
+///
+/// The prefix is copied into each request when it is made, so pending
+/// requests keep the prefix they were made with. Requests that pass their
+/// own `callback_prefix` ignore it.
+pub fn handler(
+    ctx: Context<SetConsumerCallback>,
+    subscription_id: u64,
+    callback_prefix: Vec<u8>,
+) -> Result<()> {
+    require!(
+        callback_prefix.len() <= MAX_CALLBACK_PREFIX_LEN,
+        VrfError::CallbackPrefixTooLong
+    );
+
+    let registration = &mut ctx.accounts.consumer_registration;
+    registration.callback_prefix = [0u8; MAX_CALLBACK_PREFIX_LEN];
+    registration.callback_prefix[..callback_prefix.len()].copy_from_slice(&callback_prefix);
+    registration.callback_prefix_len = callback_prefix.len() as u8;
+
+    emit!(ConsumerCallbackUpdated {
+        subscription_id,
+        consumer_program: ctx.accounts.consumer_program.key(),
+        callback_prefix,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::VrfError;
+use crate::events::ConsumerLimitsUpdated;
+use crate::state::{ConsumerRegistration, Subscription};
+
+/// Accounts required to change a consumer's limits.
+#[derive(Accounts)]
+#[instruction(subscription_id: u64)]
+pub struct SetConsumerLimits<'info> {
+    /// The subscription owner.
+    pub owner: Signer<'info>,
+
+    /// The subscription the consumer is registered under.
+    #[account(
+        seeds = [b"subscription", subscription_id.to_le_bytes().as_ref()],
+        bump = subscription.bump,
+        constraint = subscription.owner == owner.key() @ VrfError::Unauthorized,
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    /// The consumer program whose limits change.
+    /// CHECK: Used only for PDA derivation.
+    pub consumer_program: UncheckedAccount<'info>,
+
+    /// Consumer registration PDA holding the limits.
+    #[account(
+        mut,
+        seeds = [b"consumer", subscription_id.to_le_bytes().as_ref(), consumer_program.key().as_ref()],
+        bump = consumer_registration.bump,
+    )]
+    pub consumer_registration: Account<'info, ConsumerRegistration>,
+}
+
+/// Set a consumer's spending, rate and `num_words` limits. 0 disables a
+/// limit.
+///
+/// Usage counters are kept, so lowering a limit takes effect against what
+/// the consumer has already spent in the current epoch and window.
+pub fn handler(
+    ctx: Context<SetConsumerLimits>,
+    subscription_id: u64,
+    max_fee_per_epoch: u64,
+    max_requests_per_window: u32,
+    request_window_slots: u64,
+    max_num_words: u32,
+) -> Result<()> {
+    require!(
+        max_requests_per_window == 0 || request_window_slots > 0,
+        VrfError::InvalidConsumerLimits
+    );
+
+    let registration = &mut ctx.accounts.consumer_registration;
+    registration.max_fee_per_epoch = max_fee_per_epoch;
+    registration.max_requests_per_window = max_requests_per_window;
+    registration.request_window_slots = request_window_slots;
+    registration.max_num_words = max_num_words;
+
+    emit!(ConsumerLimitsUpdated {
+        subscription_id,
+        consumer_program: ctx.accounts.consumer_program.key(),
+        max_fee_per_epoch,
+        max_requests_per_window,
+        request_window_slots,
+        max_num_words,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::VrfError;
+use crate::events::CoordinatorUnpaused;
+use crate::state::CoordinatorConfig;
+
+/// Accounts required to unpause the coordinator.
+#[derive(Accounts)]
+pub struct Unpause<'info> {
+    /// Current admin; must sign.
+    pub admin: Signer<'info>,
+
+    /// Coordinator configuration PDA to update.
+    #[account(
+        mut,
+        seeds = [b"coordinator-config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ VrfError::Unauthorized,
+    )]
+    pub config: Account<'info, CoordinatorConfig>,
+}
+
+/// Resume normal operation. Admin-only, so a leaked pauser key can halt the
+/// coordinator but never resume it.
+pub fn handler(ctx: Context<Unpause>) -> Result<()> {
+    ctx.accounts.config.paused = false;
+
+    emit!(CoordinatorUnpaused {
+        unpaused_by: ctx.accounts.admin.key(),
+    });
+
+    Ok(())
+}
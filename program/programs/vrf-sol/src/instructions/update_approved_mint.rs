@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::VrfError;
+use crate::events::ApprovedMintUpdated;
+use crate::state::{ApprovedMint, CoordinatorConfig};
+
+/// Accounts required to update an approved billing mint.
+#[derive(Accounts)]
+pub struct UpdateApprovedMint<'info> {
+    /// Current admin; must sign.
+    pub admin: Signer<'info>,
+
+    /// Coordinator configuration PDA (read for `admin`).
+    #[account(
+        seeds = [b"coordinator-config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ VrfError::Unauthorized,
+    )]
+    pub config: Account<'info, CoordinatorConfig>,
+
+    /// The approved mint PDA to update.
+    #[account(
+        mut,
+        seeds = [b"approved-mint", approved_mint.mint.as_ref()],
+        bump = approved_mint.bump,
+    )]
+    pub approved_mint: Account<'info, ApprovedMint>,
+}
+
+/// Update the mint's prices and/or enabled flag.
+pub fn handler(
+    ctx: Context<UpdateApprovedMint>,
+    new_fee_per_word: Option<u64>,
+    enabled: Option<bool>,
+    new_base_fee: Option<u64>,
+    new_compute_unit_price: Option<u64>,
+) -> Result<()> {
+    let approved_mint = &mut ctx.accounts.approved_mint;
+
+    if let Some(fee) = new_fee_per_word {
+        approved_mint.fee_per_word = fee;
+    }
+    if let Some(enabled) = enabled {
+        approved_mint.enabled = enabled;
+    }
+    if let Some(fee) = new_base_fee {
+        approved_mint.base_fee = fee;
+    }
+    if let Some(price) = new_compute_unit_price {
+        approved_mint.compute_unit_price = price;
+    }
+
+    emit!(ApprovedMintUpdated {
+        mint: approved_mint.mint,
+        fee_per_word: approved_mint.fee_per_word,
+        enabled: approved_mint.enabled,
+        base_fee: approved_mint.base_fee,
+        compute_unit_price: approved_mint.compute_unit_price,
+    });
+
+    Ok(())
+}
@@ -1,54 +1,186 @@
 use anchor_lang::prelude::*;
 
 use crate::errors::VrfError;
-use crate::state::CoordinatorConfig;
+use crate::events::ConfigUpdateProposed;
+use crate::state::{ConfigChanges, CoordinatorConfig, OracleSet, PendingConfigUpdate, MAX_ORACLES};
 
-/// Accounts required to update the coordinator configuration.
+/// Accounts required to stage a coordinator configuration update.
 #[derive(Accounts)]
 pub struct UpdateConfig<'info> {
-    /// Current admin; must sign.
+    /// Current admin; must sign and pays the pending update's rent.
+    #[account(mut)]
     pub admin: Signer<'info>,
 
-    /// Coordinator configuration PDA to update.
+    /// Coordinator configuration PDA the update will apply to.
     #[account(
-        mut,
         seeds = [b"coordinator-config"],
         bump = config.bump,
         constraint = config.admin == admin.key() @ VrfError::Unauthorized,
     )]
     pub config: Account<'info, CoordinatorConfig>,
+
+    /// Pending update PDA. Seeds: `["pending-config-update"]`. Fails if an
+    /// update is already pending.
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + PendingConfigUpdate::INIT_SPACE,
+        seeds = [b"pending-config-update"],
+        bump,
+    )]
+    pub pending_config_update: Account<'info, PendingConfigUpdate>,
+
+    pub system_program: Program<'info, System>,
 }
 
-/// Update one or more coordinator configuration fields.
-pub fn handler(
-    ctx: Context<UpdateConfig>,
-    new_authority: Option<Pubkey>,
-    new_fee_per_word: Option<u64>,
-    new_max_num_words: Option<u32>,
-    new_admin: Option<Pubkey>,
-) -> Result<()> {
-    let config = &mut ctx.accounts.config;
+/// Stage one or more coordinator configuration changes.
+///
+/// The changes are validated now against the current configuration and again
+/// when `execute_config_update` applies them, after
+/// `config.config_update_delay_slots`.
+pub fn handler(ctx: Context<UpdateConfig>, changes: ConfigChanges) -> Result<()> {
+    // Fail fast on changes that could never be executed.
+    let mut preview = CoordinatorConfig::clone(&ctx.accounts.config);
+    apply_config_changes(&mut preview, &changes)?;
+
+    let proposed_slot = Clock::get()?.slot;
+    let executable_slot = proposed_slot
+        .saturating_add(ctx.accounts.config.config_update_delay_slots);
+
+    let pending = &mut ctx.accounts.pending_config_update;
+    pending.changes = changes.clone();
+    pending.proposed_slot = proposed_slot;
+    pending.executable_slot = executable_slot;
+    pending.bump = ctx.bumps.pending_config_update;
+
+    emit!(ConfigUpdateProposed {
+        changes,
+        proposed_slot,
+        executable_slot,
+    });
+
+    Ok(())
+}
 
-    if let Some(authority) = new_authority {
+/// Validate `changes` and apply them to `config`. A new admin only becomes
+/// `pending_admin`; the handover completes with `accept_admin_transfer`.
+pub(crate) fn apply_config_changes(
+    config: &mut CoordinatorConfig,
+    changes: &ConfigChanges,
+) -> Result<()> {
+    if let Some(authority) = changes.new_authority {
         require!(
             authority != Pubkey::default(),
             VrfError::ZeroAddressNotAllowed
         );
         config.authority = authority;
     }
-    if let Some(fee) = new_fee_per_word {
+    if let Some(fee) = changes.new_fee_per_word {
         config.fee_per_word = fee;
     }
-    if let Some(max_words) = new_max_num_words {
+    if let Some(max_words) = changes.new_max_num_words {
         config.max_num_words = max_words;
     }
-    if let Some(admin) = new_admin {
+    if let Some(admin) = changes.new_admin {
         require!(
             admin != Pubkey::default(),
             VrfError::ZeroAddressNotAllowed
         );
-        config.admin = admin;
+        config.pending_admin = admin;
+    }
+    // Applied before the proof scheme, so an update can register an oracle
+    // set and switch to the multi-oracle scheme together.
+    if let Some(oracle_set) = &changes.new_oracle_set {
+        apply_oracle_set(config, oracle_set)?;
+    }
+    if let Some(scheme) = changes.new_proof_scheme {
+        require!(
+            CoordinatorConfig::is_valid_proof_scheme(scheme),
+            VrfError::InvalidProofScheme
+        );
+        if scheme == CoordinatorConfig::PROOF_SCHEME_MULTI_ORACLE {
+            // The oracle set must be registered first, or in the same update.
+            require!(config.oracle_threshold > 0, VrfError::InvalidOracleSet);
+        }
+        config.proof_scheme = scheme;
+    }
+    if let Some(timeout) = changes.new_request_timeout_slots {
+        require!(timeout > 0, VrfError::InvalidRequestTimeout);
+        config.request_timeout_slots = timeout;
+    }
+    if let Some(bps) = changes.new_protocol_fee_bps {
+        require!(
+            bps <= CoordinatorConfig::MAX_PROTOCOL_FEE_BPS,
+            VrfError::InvalidProtocolFee
+        );
+        config.protocol_fee_bps = bps;
+    }
+    if let Some(fee) = changes.new_base_fee {
+        config.base_fee = fee;
+    }
+    if let Some(price) = changes.new_compute_unit_price {
+        config.compute_unit_price = price;
+    }
+    if let Some(limit) = changes.new_max_callback_compute_limit {
+        require!(
+            limit <= CoordinatorConfig::MAX_TRANSACTION_COMPUTE_UNITS,
+            VrfError::CallbackComputeLimitTooLarge
+        );
+        config.max_callback_compute_limit = limit;
+    }
+    if let Some(slots) = changes.new_min_confirmation_slots {
+        config.min_confirmation_slots = slots;
+    }
+    if let Some(pauser) = changes.new_pauser {
+        require!(
+            pauser != Pubkey::default(),
+            VrfError::ZeroAddressNotAllowed
+        );
+        config.pauser = pauser;
+    }
+    if let Some(delay) = changes.new_config_update_delay_slots {
+        require!(
+            CoordinatorConfig::is_valid_config_update_delay(delay),
+            VrfError::InvalidConfigUpdateDelay
+        );
+        config.config_update_delay_slots = delay;
+    }
+    // Checked after both may have changed, so they can be raised together.
+    require!(
+        config.min_confirmation_slots < config.request_timeout_slots,
+        VrfError::InvalidConfirmationSlots
+    );
+
+    Ok(())
+}
+
+/// Validate an oracle set and make it the multi-oracle scheme's set.
+fn apply_oracle_set(config: &mut CoordinatorConfig, oracle_set: &OracleSet) -> Result<()> {
+    let oracles = &oracle_set.oracles;
+    let threshold = oracle_set.threshold;
+    require!(
+        !oracles.is_empty() && oracles.len() <= MAX_ORACLES,
+        VrfError::InvalidOracleSet
+    );
+    require!(
+        threshold > 0 && threshold as usize <= oracles.len(),
+        VrfError::InvalidOracleSet
+    );
+    for (i, oracle) in oracles.iter().enumerate() {
+        require!(
+            *oracle != Pubkey::default(),
+            VrfError::ZeroAddressNotAllowed
+        );
+        require!(
+            !oracles[..i].contains(oracle),
+            VrfError::InvalidOracleSet
+        );
     }
 
+    let mut keys = [Pubkey::default(); MAX_ORACLES];
+    keys[..oracles.len()].copy_from_slice(oracles);
+    config.oracles = keys;
+    config.oracle_count = oracles.len() as u8;
+    config.oracle_threshold = threshold;
     Ok(())
 }
@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::VrfError;
+use crate::events::OracleFeesWithdrawn;
+use crate::state::{CoordinatorConfig, FeeVault};
+
+/// Accounts required to withdraw the oracle's share of collected fees.
+#[derive(Accounts)]
+pub struct WithdrawOracleFees<'info> {
+    /// The oracle authority; must sign.
+    pub authority: Signer<'info>,
+
+    /// Coordinator configuration PDA (read for `authority`).
+    #[account(
+        seeds = [b"coordinator-config"],
+        bump = config.bump,
+        constraint = config.authority == authority.key() @ VrfError::Unauthorized,
+    )]
+    pub config: Account<'info, CoordinatorConfig>,
+
+    /// Fee vault holding the oracle's earnings.
+    #[account(
+        mut,
+        seeds = [b"fee-vault"],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+
+    /// Receives the withdrawn lamports.
+    /// CHECK: Any account chosen by the authority.
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+}
+
+/// Transfer `amount` lamports of oracle earnings from the fee vault to `recipient`.
+pub fn handler(ctx: Context<WithdrawOracleFees>, amount: u64) -> Result<()> {
+    let fee_vault = &mut ctx.accounts.fee_vault;
+    fee_vault.oracle_fees = fee_vault
+        .oracle_fees
+        .checked_sub(amount)
+        .ok_or(VrfError::InsufficientFees)?;
+
+    fee_vault.sub_lamports(amount)?;
+    ctx.accounts.recipient.add_lamports(amount)?;
+
+    emit!(OracleFeesWithdrawn {
+        recipient: ctx.accounts.recipient.key(),
+        amount,
+    });
+
+    Ok(())
+}
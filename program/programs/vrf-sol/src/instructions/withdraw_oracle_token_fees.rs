@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::errors::VrfError;
+use crate::events::OracleTokenFeesWithdrawn;
+use crate::state::{ApprovedMint, CoordinatorConfig, FeeVault};
+use crate::token::transfer_from_pda;
+
+/// Accounts required to withdraw the oracle's share of collected token fees.
+#[derive(Accounts)]
+pub struct WithdrawOracleTokenFees<'info> {
+    /// The oracle authority; must sign.
+    pub authority: Signer<'info>,
+
+    /// Coordinator configuration PDA (read for `authority`).
+    #[account(
+        seeds = [b"coordinator-config"],
+        bump = config.bump,
+        constraint = config.authority == authority.key() @ VrfError::Unauthorized,
+    )]
+    pub config: Account<'info, CoordinatorConfig>,
+
+    /// The approved mint tracking this mint's earnings.
+    #[account(
+        mut,
+        seeds = [b"approved-mint", mint.key().as_ref()],
+        bump = approved_mint.bump,
+    )]
+    pub approved_mint: Account<'info, ApprovedMint>,
+
+    /// The mint to withdraw.
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Fee vault PDA; authority of `fee_vault_token_account`.
+    #[account(
+        seeds = [b"fee-vault"],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+
+    /// The fee vault's associated token account for `mint`.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = fee_vault,
+        associated_token::token_program = token_program,
+    )]
+    pub fee_vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Receives the withdrawn tokens.
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub recipient: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = approved_mint.token_program @ VrfError::InvalidTokenAccount)]
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Transfer `amount` of the oracle's share of `mint` fees to `recipient`.
+pub fn handler(ctx: Context<WithdrawOracleTokenFees>, amount: u64) -> Result<()> {
+    let approved_mint = &mut ctx.accounts.approved_mint;
+    approved_mint.oracle_fees = approved_mint
+        .oracle_fees
+        .checked_sub(amount)
+        .ok_or(VrfError::InsufficientFees)?;
+
+    let seeds: &[&[&[u8]]] = &[&[b"fee-vault", &[ctx.accounts.fee_vault.bump]]];
+    transfer_from_pda(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.fee_vault_token_account.to_account_info(),
+        ctx.accounts.recipient.to_account_info(),
+        &ctx.accounts.mint,
+        ctx.accounts.fee_vault.to_account_info(),
+        seeds,
+        amount,
+    )?;
+
+    emit!(OracleTokenFeesWithdrawn {
+        mint: approved_mint.mint,
+        recipient: ctx.accounts.recipient.key(),
+        amount,
+    });
+
+    Ok(())
+}
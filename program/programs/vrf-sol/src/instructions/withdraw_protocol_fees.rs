@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::VrfError;
+use crate::events::ProtocolFeesWithdrawn;
+use crate::state::{CoordinatorConfig, FeeVault};
+
+/// Accounts required to withdraw the protocol's share of collected fees.
+#[derive(Accounts)]
+pub struct WithdrawProtocolFees<'info> {
+    /// Current admin; must sign.
+    pub admin: Signer<'info>,
+
+    /// Coordinator configuration PDA (read for `admin`).
+    #[account(
+        seeds = [b"coordinator-config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ VrfError::Unauthorized,
+    )]
+    pub config: Account<'info, CoordinatorConfig>,
+
+    /// Fee vault holding the protocol's earnings.
+    #[account(
+        mut,
+        seeds = [b"fee-vault"],
+        bump = fee_vault.bump,
+    )]
+    pub fee_vault: Account<'info, FeeVault>,
+
+    /// Receives the withdrawn lamports.
+    /// CHECK: Any account chosen by the admin.
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+}
+
+/// Transfer `amount` lamports of protocol earnings from the fee vault to `recipient`.
+pub fn handler(ctx: Context<WithdrawProtocolFees>, amount: u64) -> Result<()> {
+    let fee_vault = &mut ctx.accounts.fee_vault;
+    fee_vault.protocol_fees = fee_vault
+        .protocol_fees
+        .checked_sub(amount)
+        .ok_or(VrfError::InsufficientFees)?;
+
+    fee_vault.sub_lamports(amount)?;
+    ctx.accounts.recipient.add_lamports(amount)?;
+
+    emit!(ProtocolFeesWithdrawn {
+        recipient: ctx.accounts.recipient.key(),
+        amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::VrfError;
+use crate::events::SubscriptionWithdrawn;
+use crate::state::Subscription;
+
+/// Accounts required to withdraw part of a live subscription's balance.
+#[derive(Accounts)]
+#[instruction(subscription_id: u64)]
+pub struct WithdrawSubscriptionFunds<'info> {
+    /// The subscription owner; must sign.
+    pub owner: Signer<'info>,
+
+    /// The SOL-billed subscription PDA to withdraw from.
+    #[account(
+        mut,
+        seeds = [b"subscription", subscription_id.to_le_bytes().as_ref()],
+        bump = subscription.bump,
+        constraint = subscription.owner == owner.key() @ VrfError::Unauthorized,
+        constraint = !subscription.is_token_billed() @ VrfError::BillingCurrencyMismatch,
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    /// Receives the withdrawn lamports.
+    /// CHECK: Any account chosen by the owner.
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+}
+
+/// Transfer `amount` lamports of unspent balance from the subscription to
+/// `recipient`. Rent and the fees held for pending requests stay in the PDA.
+pub fn handler(
+    ctx: Context<WithdrawSubscriptionFunds>,
+    _subscription_id: u64,
+    amount: u64,
+) -> Result<()> {
+    let subscription = &mut ctx.accounts.subscription;
+    let old_balance = subscription.balance;
+    subscription.balance = old_balance
+        .checked_sub(amount)
+        .ok_or(VrfError::InsufficientSubscriptionBalance)?;
+
+    // `balance` excludes rent and `pending_fees`, so this only guards against
+    // the lamport invariant having drifted.
+    let reserved = Rent::get()?
+        .minimum_balance(subscription.to_account_info().data_len())
+        .checked_add(subscription.pending_fees)
+        .ok_or(VrfError::CounterOverflow)?;
+    let remaining = subscription
+        .get_lamports()
+        .checked_sub(amount)
+        .ok_or(VrfError::InsufficientSubscriptionBalance)?;
+    require!(remaining >= reserved, VrfError::InsufficientSubscriptionBalance);
+
+    subscription.sub_lamports(amount)?;
+    ctx.accounts.recipient.add_lamports(amount)?;
+
+    emit!(SubscriptionWithdrawn {
+        subscription_id: subscription.id,
+        old_balance,
+        new_balance: subscription.balance,
+        recipient: ctx.accounts.recipient.key(),
+    });
+
+    Ok(())
+}
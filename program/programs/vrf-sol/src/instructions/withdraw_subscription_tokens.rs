@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::errors::VrfError;
+use crate::events::SubscriptionWithdrawn;
+use crate::state::Subscription;
+use crate::token::transfer_from_pda;
+
+/// Accounts required to withdraw part of a token-billed subscription's balance.
+#[derive(Accounts)]
+#[instruction(subscription_id: u64)]
+pub struct WithdrawSubscriptionTokens<'info> {
+    /// The subscription owner; must sign.
+    pub owner: Signer<'info>,
+
+    /// The token-billed subscription PDA to withdraw from.
+    #[account(
+        mut,
+        seeds = [b"subscription", subscription_id.to_le_bytes().as_ref()],
+        bump = subscription.bump,
+        constraint = subscription.owner == owner.key() @ VrfError::Unauthorized,
+        constraint = subscription.is_token_billed() @ VrfError::BillingCurrencyMismatch,
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    /// The subscription's billing mint.
+    #[account(address = subscription.billing_mint @ VrfError::BillingCurrencyMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// The subscription's associated token account for `mint`.
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = subscription,
+        associated_token::token_program = token_program,
+    )]
+    pub subscription_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Receives the withdrawn tokens.
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub recipient: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Transfer `amount` tokens of unspent balance from the subscription vault to
+/// `recipient`. Fees held for pending requests stay in the vault.
+pub fn handler(
+    ctx: Context<WithdrawSubscriptionTokens>,
+    _subscription_id: u64,
+    amount: u64,
+) -> Result<()> {
+    let subscription = &mut ctx.accounts.subscription;
+    let old_balance = subscription.balance;
+    subscription.balance = old_balance
+        .checked_sub(amount)
+        .ok_or(VrfError::InsufficientSubscriptionBalance)?;
+
+    let remaining = ctx
+        .accounts
+        .subscription_vault
+        .amount
+        .checked_sub(amount)
+        .ok_or(VrfError::InsufficientSubscriptionBalance)?;
+    require!(
+        remaining >= subscription.pending_fees,
+        VrfError::InsufficientSubscriptionBalance
+    );
+
+    let id = subscription.id.to_le_bytes();
+    let seeds: &[&[&[u8]]] = &[&[b"subscription", id.as_ref(), &[subscription.bump]]];
+    transfer_from_pda(
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.subscription_vault.to_account_info(),
+        ctx.accounts.recipient.to_account_info(),
+        &ctx.accounts.mint,
+        subscription.to_account_info(),
+        seeds,
+        amount,
+    )?;
+
+    emit!(SubscriptionWithdrawn {
+        subscription_id: subscription.id,
+        old_balance,
+        new_balance: subscription.balance,
+        recipient: ctx.accounts.recipient.key(),
+    });
+
+    Ok(())
+}
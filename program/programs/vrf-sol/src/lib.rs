@@ -1,10 +1,12 @@
 use anchor_lang::prelude::*;
 
+pub mod ecvrf;
 pub mod ed25519;
 pub mod errors;
 pub mod events;
 pub mod instructions;
 pub mod state;
+pub mod token;
 
 use instructions::*;
 
@@ -17,26 +19,52 @@ declare_id!("GyTSsNWHUfvmjuz8WfiSM7SrT7a4e9aqe2PR2MkPXKQF");
 ///
 /// ## Architecture
 ///
-/// - **Subscriptions** — hold a SOL balance that pays for VRF requests.
+/// - **Subscriptions** — hold a SOL (or approved SPL token) balance that pays for VRF requests.
 /// - **Consumers** — programs registered to a subscription that can request random words.
 /// - **Coordinator** — verifies VRF proofs and CPIs into consumer programs with results.
 ///
 /// ## Request lifecycle
 ///
-/// 1. **Request** — consumer CPIs `request_random_words`; fee deducted from subscription.
-/// 2. **Fulfill** — oracle submits `fulfill_random_words` with Ed25519 proof;
-///    coordinator expands randomness, CPIs callback into consumer, closes request PDA.
+/// 1. **Request** — consumer CPIs `request_random_words`; the fee (base fee, per-word fee
+///    and callback compute) is deducted from subscription balance and held as a pending fee.
+/// 2. **Fulfill** — oracle submits `fulfill_random_words` with an Ed25519 proof, or
+///    `fulfill_random_words_ecvrf` with an ECVRF proof (per `config.proof_scheme`);
+///    coordinator moves the fee to the fee vault, expands randomness, CPIs callback
+///    into consumer, closes request PDA.
+/// 3. **Retry** — if the callback reverts, the oracle re-submits with a `callback_failure`;
+///    the output is stored (`STATUS_CALLBACK_FAILED`) and delivered later via `retry_callback`.
+/// 4. **Cancel** — if unfulfilled after `config.request_timeout_slots`, the requester or
+///    subscription owner may `cancel_request`; the fee is re-credited and rent refunded.
+/// 5. **Consume** — `store_only` requests skip the callback; the output is stored
+///    (`STATUS_STORED`) and the consumer pulls it with `consume_randomness`.
+///
+/// Collected fees are split by `config.protocol_fee_bps` and withdrawn with
+/// `withdraw_oracle_fees` (authority) and `withdraw_protocol_fees` (admin).
+///
+/// Subscriptions created with `create_token_subscription` pay in an
+/// admin-approved SPL mint instead of SOL, at the mint's own prices.
+/// Their tokens sit in the subscription's associated token account and their
+/// fees are withdrawn with the `*_token_fees` instructions.
 #[program]
 pub mod vrf_sol {
     use super::*;
 
-    /// Create the singleton coordinator configuration PDA.
+    /// Create the singleton coordinator configuration and fee vault PDAs.
+    /// `config_update_delay_slots` must be at least
+    /// `MIN_CONFIG_UPDATE_DELAY_SLOTS`; `DEFAULT_CONFIG_UPDATE_DELAY_SLOTS`
+    /// (~1 hour) suits most deployments.
     pub fn initialize(
         ctx: Context<Initialize>,
         fee_per_word: u64,
         max_num_words: u32,
+        config_update_delay_slots: u64,
     ) -> Result<()> {
-        instructions::initialize::handler(ctx, fee_per_word, max_num_words)
+        instructions::initialize::handler(
+            ctx,
+            fee_per_word,
+            max_num_words,
+            config_update_delay_slots,
+        )
     }
 
     /// Create a new subscription.
@@ -53,7 +81,70 @@ pub mod vrf_sol {
         instructions::fund_subscription::handler(ctx, subscription_id, amount)
     }
 
-    /// Cancel a subscription and refund the remaining balance.
+    /// Withdraw part of a subscription's unspent balance (owner-only). Rent
+    /// and fees held for pending requests cannot be withdrawn.
+    pub fn withdraw_subscription_funds(
+        ctx: Context<WithdrawSubscriptionFunds>,
+        subscription_id: u64,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw_subscription_funds::handler(ctx, subscription_id, amount)
+    }
+
+    /// Propose a new owner for a subscription (owner-only). Passing the
+    /// default pubkey withdraws a pending proposal.
+    pub fn request_subscription_owner_transfer(
+        ctx: Context<RequestSubscriptionOwnerTransfer>,
+        subscription_id: u64,
+        new_owner: Pubkey,
+    ) -> Result<()> {
+        instructions::request_subscription_owner_transfer::handler(ctx, subscription_id, new_owner)
+    }
+
+    /// Accept a pending subscription ownership transfer (pending owner only).
+    pub fn accept_subscription_owner_transfer(
+        ctx: Context<AcceptSubscriptionOwnerTransfer>,
+        subscription_id: u64,
+    ) -> Result<()> {
+        instructions::accept_subscription_owner_transfer::handler(ctx, subscription_id)
+    }
+
+    /// Create a subscription billed in an approved SPL mint, with an
+    /// associated token account holding its balance.
+    pub fn create_token_subscription(ctx: Context<CreateTokenSubscription>) -> Result<()> {
+        instructions::create_token_subscription::handler(ctx)
+    }
+
+    /// Fund a token-billed subscription with its billing mint.
+    pub fn fund_subscription_token(
+        ctx: Context<FundSubscriptionToken>,
+        subscription_id: u64,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::fund_subscription_token::handler(ctx, subscription_id, amount)
+    }
+
+    /// Withdraw part of a token-billed subscription's unspent balance
+    /// (owner-only).
+    pub fn withdraw_subscription_tokens(
+        ctx: Context<WithdrawSubscriptionTokens>,
+        subscription_id: u64,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw_subscription_tokens::handler(ctx, subscription_id, amount)
+    }
+
+    /// Cancel a token-billed subscription, refund its tokens and close its
+    /// token account. Fails while it has consumers or pending requests.
+    pub fn cancel_token_subscription(
+        ctx: Context<CancelTokenSubscription>,
+        subscription_id: u64,
+    ) -> Result<()> {
+        instructions::cancel_token_subscription::handler(ctx, subscription_id)
+    }
+
+    /// Cancel a subscription and refund its unspent balance. Fails while it
+    /// has pending requests.
     pub fn cancel_subscription(
         ctx: Context<CancelSubscription>,
         subscription_id: u64,
@@ -71,36 +162,343 @@ pub mod vrf_sol {
         instructions::remove_consumer::handler(ctx, subscription_id)
     }
 
+    /// Set a consumer's per-epoch fee, request rate and `num_words` limits
+    /// (subscription owner only). 0 disables a limit.
+    pub fn set_consumer_limits(
+        ctx: Context<SetConsumerLimits>,
+        subscription_id: u64,
+        max_fee_per_epoch: u64,
+        max_requests_per_window: u32,
+        request_window_slots: u64,
+        max_num_words: u32,
+    ) -> Result<()> {
+        instructions::set_consumer_limits::handler(
+            ctx,
+            subscription_id,
+            max_fee_per_epoch,
+            max_requests_per_window,
+            request_window_slots,
+            max_num_words,
+        )
+    }
+
+    /// Set the default instruction prefix of a consumer's callbacks (called
+    /// via CPI from the consumer program). Empty restores Anchor's
+    /// `fulfill_random_words` discriminator.
+    pub fn set_consumer_callback(
+        ctx: Context<SetConsumerCallback>,
+        subscription_id: u64,
+        callback_prefix: Vec<u8>,
+    ) -> Result<()> {
+        instructions::set_consumer_callback::handler(ctx, subscription_id, callback_prefix)
+    }
+
     /// Request random words (called via CPI from a consumer program).
     ///
-    /// Remaining accounts are stored in the request PDA as callback accounts
-    /// so the oracle backend can include them when fulfilling.
+    /// The consumer signs with its `["consumer-authority"]` PDA, so only the
+    /// registered program itself can spend the subscription's balance.
+    ///
+    /// Remaining accounts are stored as callback accounts (in the request PDA,
+    /// or in the optional `callback_accounts` sidecar for longer lists) so the
+    /// oracle backend can include them when fulfilling. With
+    /// `store_only`, no callback is made and the consumer pulls the result
+    /// with `consume_randomness`. Fulfillment waits `min_confirmation_slots`
+    /// (at least the coordinator's minimum) after the request slot.
+    /// `callback.user_data`, if given, is stored with the request and passed
+    /// back to the callback as a third argument. `callback.prefix`, if given,
+    /// replaces the registration's callback instruction prefix for this
+    /// request.
+    ///
+    /// Returns the new request's ID and PDA address as return data.
     pub fn request_random_words<'info>(
         ctx: Context<'_, '_, '_, 'info, RequestRandomWords<'info>>,
         num_words: u32,
         seed: [u8; 32],
         callback_compute_limit: u32,
-    ) -> Result<()> {
-        instructions::request_random_words::handler(ctx, num_words, seed, callback_compute_limit)
+        store_only: bool,
+        min_confirmation_slots: u64,
+        callback: state::CallbackOptions,
+    ) -> Result<state::RequestReceipt> {
+        instructions::request_random_words::handler(
+            ctx,
+            num_words,
+            seed,
+            callback_compute_limit,
+            store_only,
+            min_confirmation_slots,
+            callback,
+        )
+    }
+
+    /// Request random words paid for by the requester (called via CPI from a
+    /// consumer program), with no subscription or consumer registration.
+    ///
+    /// The fee moves from the requester into the request PDA and is held there
+    /// until fulfillment, or refunded on cancellation. The ID comes from the
+    /// requester's `RequesterNonce`. Otherwise behaves like
+    /// `request_random_words`, and also returns the request's ID and PDA.
+    pub fn request_random_words_direct<'info>(
+        ctx: Context<'_, '_, '_, 'info, RequestRandomWordsDirect<'info>>,
+        num_words: u32,
+        seed: [u8; 32],
+        callback_compute_limit: u32,
+        store_only: bool,
+        min_confirmation_slots: u64,
+        callback: state::CallbackOptions,
+    ) -> Result<state::RequestReceipt> {
+        instructions::request_random_words_direct::handler(
+            ctx,
+            num_words,
+            seed,
+            callback_compute_limit,
+            store_only,
+            min_confirmation_slots,
+            callback,
+        )
+    }
+
+    /// Request random words straight from a wallet, with no consumer program.
+    ///
+    /// Direct-funded like `request_random_words_direct`. Fulfillment makes no
+    /// callback and writes the output to a `RandomnessResult` PDA owned by the
+    /// requester, which anyone can read. Returns the request's ID and PDA.
+    pub fn request_wallet_randomness(
+        ctx: Context<RequestWalletRandomness>,
+        num_words: u32,
+        seed: [u8; 32],
+        min_confirmation_slots: u64,
+    ) -> Result<state::RequestReceipt> {
+        instructions::request_wallet_randomness::handler(
+            ctx,
+            num_words,
+            seed,
+            min_confirmation_slots,
+        )
     }
 
     /// Fulfill a pending request with VRF output, deliver callback, and close request.
+    /// With a `callback_failure` (the reason the oracle's simulation of the
+    /// callback failed), store the output and leave the request open for
+    /// `retry_callback` instead.
     pub fn fulfill_random_words<'info>(
         ctx: Context<'_, '_, '_, 'info, FulfillRandomWords<'info>>,
         request_id: u64,
         randomness: [u8; 32],
+        callback_failure: Option<String>,
+    ) -> Result<()> {
+        instructions::fulfill_random_words::handler(ctx, request_id, randomness, callback_failure)
+    }
+
+    /// Fulfill a pending request with an ECVRF proof. The randomness is derived
+    /// from the proof on-chain rather than supplied by the oracle.
+    pub fn fulfill_random_words_ecvrf<'info>(
+        ctx: Context<'_, '_, '_, 'info, FulfillRandomWords<'info>>,
+        request_id: u64,
+        proof: [u8; 80],
+        callback_failure: Option<String>,
+    ) -> Result<()> {
+        instructions::fulfill_random_words_ecvrf::handler(ctx, request_id, proof, callback_failure)
+    }
+
+    /// Fulfill a pending request with one ECVRF proof from each of
+    /// `config.oracle_threshold` registered oracles: the request's committee,
+    /// or after the fallback window any of them, in index order.
+    pub fn fulfill_random_words_multi<'info>(
+        ctx: Context<'_, '_, '_, 'info, FulfillRandomWords<'info>>,
+        request_id: u64,
+        proofs: Vec<state::OracleProof>,
+        callback_failure: Option<String>,
     ) -> Result<()> {
-        instructions::fulfill_random_words::handler(ctx, request_id, randomness)
+        instructions::fulfill_random_words_multi::handler(ctx, request_id, proofs, callback_failure)
     }
 
-    /// Update the coordinator configuration (admin-only).
-    pub fn update_config(
-        ctx: Context<UpdateConfig>,
-        new_authority: Option<Pubkey>,
+    /// Fulfill several pending requests in one instruction, proven by one
+    /// multi-signature Ed25519 precompile instruction (ed25519 and
+    /// signature-hash schemes). Callbacks are delivered in order.
+    pub fn fulfill_random_words_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FulfillRandomWordsBatch<'info>>,
+        fulfillments: Vec<state::BatchFulfillment>,
+    ) -> Result<()> {
+        instructions::fulfill_random_words_batch::handler(ctx, fulfillments)
+    }
+
+    /// Re-attempt the consumer callback for a request whose randomness is
+    /// stored with `STATUS_CALLBACK_FAILED`, or (oracle only, with a
+    /// `record_failure` reason) record another failed attempt.
+    pub fn retry_callback<'info>(
+        ctx: Context<'_, '_, '_, 'info, RetryCallback<'info>>,
+        request_id: u64,
+        record_failure: Option<String>,
+    ) -> Result<()> {
+        instructions::retry_callback::handler(ctx, request_id, record_failure)
+    }
+
+    /// Return the expanded words of a stored request and close it (called via
+    /// CPI by the consumer, signing with its `consumer-authority` PDA).
+    /// Fails for a failed callback with more words than fit in return data;
+    /// use `consume_randomness_seed` for those.
+    pub fn consume_randomness(
+        ctx: Context<ConsumeRandomness>,
+        request_id: u64,
+    ) -> Result<Vec<[u8; 32]>> {
+        instructions::consume_randomness::handler(ctx, request_id)
+    }
+
+    /// Return the 32-byte output of a stored request and close it, for the
+    /// consumer to expand into any number of words. Same accounts and
+    /// signer as `consume_randomness`.
+    pub fn consume_randomness_seed(
+        ctx: Context<ConsumeRandomness>,
+        request_id: u64,
+    ) -> Result<[u8; 32]> {
+        instructions::consume_randomness_seed::handler(ctx, request_id)
+    }
+
+    /// Close a request's callback accounts sidecar once the request PDA is
+    /// closed, refunding rent to the requester (permissionless).
+    pub fn close_callback_accounts(
+        ctx: Context<CloseCallbackAccounts>,
+        request_id: u64,
+    ) -> Result<()> {
+        instructions::close_callback_accounts::handler(ctx, request_id)
+    }
+
+    /// Close a wallet request's `RandomnessResult` once the request PDA is
+    /// closed, refunding rent to the requester (requester only).
+    pub fn close_randomness_result(
+        ctx: Context<CloseRandomnessResult>,
+        request_id: u64,
+    ) -> Result<()> {
+        instructions::close_randomness_result::handler(ctx, request_id)
+    }
+
+    /// Cancel an expired pending request, re-crediting its fee to the subscription
+    /// and refunding rent to the requester.
+    pub fn cancel_request(ctx: Context<CancelRequest>, request_id: u64) -> Result<()> {
+        instructions::cancel_request::handler(ctx, request_id)
+    }
+
+    /// Stage a coordinator configuration update (admin-only). It takes effect
+    /// through `execute_config_update` after `config_update_delay_slots`.
+    ///
+    /// Fields left `None` in `changes` are unchanged; this includes the
+    /// multi-oracle set, so oracle changes are timelocked too.
+    pub fn update_config(ctx: Context<UpdateConfig>, changes: state::ConfigChanges) -> Result<()> {
+        instructions::update_config::handler(ctx, changes)
+    }
+
+    /// Apply the staged config update once its delay has passed (admin-only).
+    pub fn execute_config_update(ctx: Context<ExecuteConfigUpdate>) -> Result<()> {
+        instructions::execute_config_update::handler(ctx)
+    }
+
+    /// Discard the staged config update (admin-only).
+    pub fn cancel_config_update(ctx: Context<CancelConfigUpdate>) -> Result<()> {
+        instructions::cancel_config_update::handler(ctx)
+    }
+
+    /// Accept the admin role named by an executed config update (pending admin only).
+    pub fn accept_admin_transfer(ctx: Context<AcceptAdminTransfer>) -> Result<()> {
+        instructions::accept_admin_transfer::handler(ctx)
+    }
+
+    /// Grow the configuration written by an earlier program version to the
+    /// current layout, defaulting the fields it gains, and create the fee
+    /// vault if missing (admin-only). Run this first after an upgrade.
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        instructions::migrate_config::handler(ctx)
+    }
+
+    /// Grow a subscription written by an earlier program version to the
+    /// current layout and move its request IDs past those issued from the
+    /// old global counter (admin-only).
+    pub fn migrate_subscription(
+        ctx: Context<MigrateSubscription>,
+        subscription_id: u64,
+    ) -> Result<()> {
+        instructions::migrate_subscription::handler(ctx, subscription_id)
+    }
+
+    /// Grow a consumer registration written by an earlier program version
+    /// to the current layout (admin-only).
+    pub fn migrate_registration(
+        ctx: Context<MigrateRegistration>,
+        subscription_id: u64,
+    ) -> Result<()> {
+        instructions::migrate_registration::handler(ctx, subscription_id)
+    }
+
+    /// Grow a request left open across an upgrade to the current layout so
+    /// it can be fulfilled or cancelled again (admin-only).
+    pub fn migrate_request(ctx: Context<MigrateRequest>, request_id: u64) -> Result<()> {
+        instructions::migrate_request::handler(ctx, request_id)
+    }
+
+    /// Create the nonce numbering a requester's direct-funded requests.
+    /// Required once before the requester's first direct or wallet request.
+    pub fn create_requester_nonce(ctx: Context<CreateRequesterNonce>) -> Result<()> {
+        instructions::create_requester_nonce::handler(ctx)
+    }
+
+    /// Pause new subscriptions, requests, fulfillments and callback retries
+    /// (pauser or admin). Withdrawals and cancellations keep working.
+    pub fn pause(ctx: Context<Pause>) -> Result<()> {
+        instructions::pause::handler(ctx)
+    }
+
+    /// Resume normal operation after a pause (admin-only).
+    pub fn unpause(ctx: Context<Unpause>) -> Result<()> {
+        instructions::unpause::handler(ctx)
+    }
+
+    /// Withdraw the oracle's share of collected fees (authority-only).
+    pub fn withdraw_oracle_fees(ctx: Context<WithdrawOracleFees>, amount: u64) -> Result<()> {
+        instructions::withdraw_oracle_fees::handler(ctx, amount)
+    }
+
+    /// Withdraw the protocol's share of collected fees (admin-only).
+    pub fn withdraw_protocol_fees(ctx: Context<WithdrawProtocolFees>, amount: u64) -> Result<()> {
+        instructions::withdraw_protocol_fees::handler(ctx, amount)
+    }
+
+    /// Approve an SPL Token or Token-2022 mint for subscription billing at
+    /// `fee_per_word` base units per word (admin-only). Token-2022 mints with
+    /// extensions outside a small allowlist (e.g. a transfer hook, transfer
+    /// fee or permanent delegate) are rejected.
+    pub fn approve_mint(ctx: Context<ApproveMint>, fee_per_word: u64) -> Result<()> {
+        instructions::approve_mint::handler(ctx, fee_per_word)
+    }
+
+    /// Update an approved mint's prices or enable/disable it (admin-only).
+    pub fn update_approved_mint(
+        ctx: Context<UpdateApprovedMint>,
         new_fee_per_word: Option<u64>,
-        new_max_num_words: Option<u32>,
-        new_admin: Option<Pubkey>,
+        enabled: Option<bool>,
+        new_base_fee: Option<u64>,
+        new_compute_unit_price: Option<u64>,
+    ) -> Result<()> {
+        instructions::update_approved_mint::handler(
+            ctx,
+            new_fee_per_word,
+            enabled,
+            new_base_fee,
+            new_compute_unit_price,
+        )
+    }
+
+    /// Withdraw the oracle's share of a mint's collected fees (authority-only).
+    pub fn withdraw_oracle_token_fees(
+        ctx: Context<WithdrawOracleTokenFees>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw_oracle_token_fees::handler(ctx, amount)
+    }
+
+    /// Withdraw the protocol's share of a mint's collected fees (admin-only).
+    pub fn withdraw_protocol_token_fees(
+        ctx: Context<WithdrawProtocolTokenFees>,
+        amount: u64,
     ) -> Result<()> {
-        instructions::update_config::handler(ctx, new_authority, new_fee_per_word, new_max_num_words, new_admin)
+        instructions::withdraw_protocol_token_fees::handler(ctx, amount)
     }
 }
@@ -1,11 +1,19 @@
 use anchor_lang::prelude::*;
 
+use crate::errors::VrfError;
+
+/// Maximum number of oracles in the multi-oracle set.
+pub const MAX_ORACLES: usize = 8;
+
 /// Global coordinator configuration, stored as a singleton PDA.
 ///
 /// Seeds: `["coordinator-config"]`
 ///
-/// Only the `admin` may update this account via [`update_config`]. The
+/// Only the `admin` may change this account, by staging an [`update_config`]
+/// and applying it with [`execute_config_update`] once
+/// `config_update_delay_slots` have passed. The
 /// `authority` is the off-chain oracle key that signs fulfillment proofs.
+/// The `pauser` may halt requests and fulfillments via [`pause`].
 #[account]
 #[derive(InitSpace)]
 pub struct CoordinatorConfig {
@@ -17,12 +25,177 @@ pub struct CoordinatorConfig {
     pub fee_per_word: u64,
     /// Maximum number of random words a consumer may request at once.
     pub max_num_words: u32,
-    /// Monotonically increasing counter used to derive unique request PDA seeds.
+    /// Global request counter from before request IDs were derived per
+    /// subscription. No longer advanced, so requests never write this account;
+    /// `migrate_subscription` starts subscription 0's `req_count` here so its
+    /// IDs cannot collide with request PDAs from before the upgrade.
     pub request_counter: u64,
     /// Monotonically increasing counter used to derive unique subscription PDA seeds.
     pub subscription_counter: u64,
     /// PDA bump seed cached for efficient re-derivation.
     pub bump: u8,
+    /// How fulfillment proofs are verified. See `PROOF_SCHEME_*` constants.
+    pub proof_scheme: u8,
+    /// Number of registered oracles in `oracles` (0 to MAX_ORACLES).
+    pub oracle_count: u8,
+    /// Number of distinct registered oracles that must each prove a
+    /// multi-oracle fulfillment.
+    pub oracle_threshold: u8,
+    /// Ed25519 public keys of the registered oracles (multi-oracle scheme).
+    pub oracles: [Pubkey; MAX_ORACLES],
+    /// Slots after `request_slot` before a pending request may be cancelled.
+    pub request_timeout_slots: u64,
+    /// Share of each fulfilled request's fee credited to the protocol, in
+    /// basis points. The rest is credited to the oracle.
+    pub protocol_fee_bps: u16,
+    /// Flat fee (in lamports) charged per request.
+    pub base_fee: u64,
+    /// Price (in micro-lamports) of each unit of a request's
+    /// `callback_compute_limit`, in the same unit as the ComputeBudget price
+    /// the oracle pays for its fulfillment transaction.
+    pub compute_unit_price: u64,
+    /// Largest `callback_compute_limit` a request may ask for.
+    pub max_callback_compute_limit: u32,
+    /// Minimum slots every request waits after `request_slot` before it can
+    /// be fulfilled. Requests may ask for more.
+    pub min_confirmation_slots: u64,
+    /// Key that may pause the coordinator. Only the admin may unpause.
+    pub pauser: Pubkey,
+    /// While set, new subscriptions, requests, fulfillments and callback
+    /// retries are rejected. Withdrawals and cancellations still work.
+    pub paused: bool,
+    /// Slots a staged config update must wait before it can be executed.
+    pub config_update_delay_slots: u64,
+    /// Admin named by an executed config update, awaiting
+    /// `accept_admin_transfer`, or `Pubkey::default()` if none.
+    pub pending_admin: Pubkey,
+}
+
+impl CoordinatorConfig {
+    /// The oracle signs `request_id || randomness` with Ed25519. The signature
+    /// authenticates the output but does not constrain how it was chosen.
+    pub const PROOF_SCHEME_ED25519: u8 = 0;
+    /// The oracle submits an ECVRF-EDWARDS25519-SHA512-TAI proof (RFC 9381)
+    /// over the request's `seed`, `request_slot` and `request_id`. The output
+    /// is derived from the proof and is unique per request.
+    pub const PROOF_SCHEME_ECVRF: u8 = 1;
+    /// The oracle signs a request-bound message with Ed25519 and the output is
+    /// `SHA256(signature)`, so anyone can recompute it from the transaction.
+    pub const PROOF_SCHEME_SIGNATURE_HASH: u8 = 2;
+    /// `oracle_threshold` registered oracles each submit an ECVRF proof over
+    /// the request; the output is a hash of all their VRF outputs. See
+    /// [`CoordinatorConfig::is_on_committee`] for which oracles may prove.
+    pub const PROOF_SCHEME_MULTI_ORACLE: u8 = 3;
+
+    /// Slots after a request becomes fulfillable during which only its
+    /// multi-oracle committee may prove it (~1 minute).
+    pub const MULTI_ORACLE_FALLBACK_SLOTS: u64 = 150;
+
+    /// Default `request_timeout_slots` set at initialization (~10 minutes).
+    pub const DEFAULT_REQUEST_TIMEOUT_SLOTS: u64 = 1_500;
+
+    /// Upper bound for `protocol_fee_bps` (100%).
+    pub const MAX_PROTOCOL_FEE_BPS: u16 = 10_000;
+
+    /// Default `max_callback_compute_limit` set at initialization. Leaves room
+    /// for proof verification within a transaction's 1.4M compute units.
+    pub const DEFAULT_MAX_CALLBACK_COMPUTE_LIMIT: u32 = 1_000_000;
+
+    /// Upper bound for `max_callback_compute_limit`: the most compute units a
+    /// transaction can request.
+    pub const MAX_TRANSACTION_COMPUTE_UNITS: u32 = 1_400_000;
+
+    /// Upper bound for `config_update_delay_slots` (~30 days), so a mistaken
+    /// delay cannot lock the admin out of the configuration.
+    pub const MAX_CONFIG_UPDATE_DELAY_SLOTS: u64 = 6_480_000;
+
+    /// Lower bound for `config_update_delay_slots`, so a staged change is
+    /// always visible on-chain before it applies and can never be staged and
+    /// executed in one transaction.
+    pub const MIN_CONFIG_UPDATE_DELAY_SLOTS: u64 = 10;
+
+    /// `config_update_delay_slots` given to a coordinator that predates the
+    /// timelock (~1 hour).
+    pub const DEFAULT_CONFIG_UPDATE_DELAY_SLOTS: u64 = 9_000;
+
+    /// Whether `delay` is an allowed `config_update_delay_slots`.
+    pub fn is_valid_config_update_delay(delay: u64) -> bool {
+        (Self::MIN_CONFIG_UPDATE_DELAY_SLOTS..=Self::MAX_CONFIG_UPDATE_DELAY_SLOTS)
+            .contains(&delay)
+    }
+
+    /// Whether `scheme` is a supported `PROOF_SCHEME_*` value.
+    pub fn is_valid_proof_scheme(scheme: u8) -> bool {
+        matches!(
+            scheme,
+            Self::PROOF_SCHEME_ED25519
+                | Self::PROOF_SCHEME_ECVRF
+                | Self::PROOF_SCHEME_SIGNATURE_HASH
+                | Self::PROOF_SCHEME_MULTI_ORACLE
+        )
+    }
+
+    /// The registered oracle keys.
+    pub fn oracle_set(&self) -> &[Pubkey] {
+        &self.oracles[..self.oracle_count as usize]
+    }
+
+    /// Whether the oracle at `index` of the registered set is on the
+    /// multi-oracle committee of `request_id`: the `oracle_threshold`
+    /// oracles from `request_id % oracle_count` on, wrapping around. Until
+    /// the fallback slot only the committee may prove the request, so its
+    /// output does not depend on which oracles answer first.
+    pub fn is_on_committee(&self, request_id: u64, index: usize) -> bool {
+        let count = self.oracle_count as u64;
+        let start = request_id % count;
+        (index as u64 + count - start) % count < self.oracle_threshold as u64
+    }
+
+    /// Prices of SOL-billed requests.
+    pub fn fee_schedule(&self) -> FeeSchedule {
+        FeeSchedule {
+            base_fee: self.base_fee,
+            fee_per_word: self.fee_per_word,
+            compute_unit_price: self.compute_unit_price,
+        }
+    }
+
+    /// Whether `key` may submit fulfillment transactions. Under the
+    /// multi-oracle scheme any registered oracle may submit, so one offline
+    /// node does not stall delivery.
+    pub fn is_fulfiller(&self, key: &Pubkey) -> bool {
+        self.authority == *key
+            || (self.proof_scheme == Self::PROOF_SCHEME_MULTI_ORACLE
+                && self.oracle_set().contains(key))
+    }
+}
+
+/// Prices of a request in one billing currency.
+///
+/// `fee = base_fee + fee_per_word × num_words + ⌈compute_unit_price × callback_compute_limit / 10^6⌉`
+pub struct FeeSchedule {
+    /// Flat fee per request.
+    pub base_fee: u64,
+    /// Fee per random word.
+    pub fee_per_word: u64,
+    /// Price per callback compute unit, in millionths of the currency's base unit.
+    pub compute_unit_price: u64,
+}
+
+impl FeeSchedule {
+    /// `compute_unit_price` units per base unit of the billing currency.
+    pub const COMPUTE_UNIT_PRICE_SCALE: u128 = 1_000_000;
+
+    /// The fee for a request of `num_words` with `callback_compute_limit`, or
+    /// `None` on overflow.
+    pub fn request_fee(&self, num_words: u32, callback_compute_limit: u32) -> Option<u64> {
+        let compute_fee = (self.compute_unit_price as u128 * callback_compute_limit as u128)
+            .div_ceil(Self::COMPUTE_UNIT_PRICE_SCALE);
+        self.fee_per_word
+            .checked_mul(num_words as u64)?
+            .checked_add(self.base_fee)?
+            .checked_add(u64::try_from(compute_fee).ok()?)
+    }
 }
 
 /// A subscription account that holds a SOL balance for paying VRF fees.
@@ -30,7 +203,10 @@ pub struct CoordinatorConfig {
 /// Seeds: `["subscription", subscription_id.to_le_bytes()]`
 ///
 /// The subscription owner manages consumers and funds. Fees are deducted
-/// from the subscription balance at request time.
+/// from the subscription balance at request time and held in the PDA as
+/// `pending_fees` until the request is fulfilled, when they move to the
+/// `FeeVault`, or cancelled, when they are re-credited to `balance`. The PDA
+/// therefore always holds rent + `balance` + `pending_fees`.
 #[account]
 #[derive(InitSpace)]
 pub struct Subscription {
@@ -40,12 +216,51 @@ pub struct Subscription {
     pub owner: Pubkey,
     /// Current balance in lamports available for VRF fees.
     pub balance: u64,
-    /// Total number of VRF requests made through this subscription.
+    /// Total number of VRF requests made through this subscription; the
+    /// low bits of the next request's ID.
     pub req_count: u64,
     /// Number of consumer programs currently registered.
     pub consumer_count: u32,
     /// PDA bump seed cached for efficient re-derivation.
     pub bump: u8,
+    /// Fees of pending requests, still held in this PDA.
+    pub pending_fees: u64,
+    /// Proposed new owner awaiting acceptance, or the default pubkey if none.
+    pub pending_owner: Pubkey,
+    /// SPL mint the subscription pays in, or the default pubkey for native
+    /// SOL. For token subscriptions `balance` and `pending_fees` are in the
+    /// mint's base units and the tokens sit in the subscription's associated
+    /// token account.
+    pub billing_mint: Pubkey,
+}
+
+impl Subscription {
+    /// Request IDs are `id << REQUEST_INDEX_BITS | req_count`, so they are
+    /// globally unique without a shared counter and requests on different
+    /// subscriptions do not contend for a writable account.
+    pub const REQUEST_INDEX_BITS: u32 = 32;
+
+    /// Exclusive upper bound for both the subscription ID and `req_count`.
+    pub const MAX_REQUEST_ID_PART: u64 = 1 << Self::REQUEST_INDEX_BITS;
+
+    /// Subscription ID recorded on direct-funded requests, which have no
+    /// subscription. Never assigned to a real subscription.
+    pub const DIRECT_SUBSCRIPTION_ID: u64 = Self::MAX_REQUEST_ID_PART - 1;
+
+    /// First `RequesterNonce::id`. Subscription IDs stay below it and nonce
+    /// IDs below `DIRECT_SUBSCRIPTION_ID`, so their request IDs never overlap
+    /// and neither counter needs to read the other.
+    pub const FIRST_REQUESTER_NONCE_ID: u64 = Self::MAX_REQUEST_ID_PART / 2;
+
+    /// ID, and request PDA seed, of this subscription's next request.
+    pub fn next_request_id(&self) -> u64 {
+        (self.id << Self::REQUEST_INDEX_BITS) | self.req_count
+    }
+
+    /// Whether this subscription pays in an SPL mint rather than SOL.
+    pub fn is_token_billed(&self) -> bool {
+        self.billing_mint != Pubkey::default()
+    }
 }
 
 /// Registration of a consumer program for a specific subscription.
@@ -53,6 +268,10 @@ pub struct Subscription {
 /// Seeds: `["consumer", subscription_id.to_le_bytes(), consumer_program_id]`
 ///
 /// Only registered consumers may request random words charged to the subscription.
+///
+/// The subscription owner can cap what a consumer spends with
+/// `set_consumer_limits`; a limit of 0 means unlimited. Usage counters are
+/// updated by every `request_random_words`.
 #[account]
 #[derive(InitSpace)]
 pub struct ConsumerRegistration {
@@ -64,24 +283,247 @@ pub struct ConsumerRegistration {
     pub nonce: u64,
     /// PDA bump seed cached for efficient re-derivation.
     pub bump: u8,
+    /// Maximum fees charged per epoch, in the subscription's billing units
+    /// (lamports, or base units of its mint). 0 = unlimited.
+    pub max_fee_per_epoch: u64,
+    /// Maximum requests per `request_window_slots`. 0 = unlimited.
+    pub max_requests_per_window: u32,
+    /// Length of the rate-limit window in slots.
+    pub request_window_slots: u64,
+    /// Maximum `num_words` per request. 0 = only the coordinator's limit.
+    pub max_num_words: u32,
+    /// Epoch `epoch_fees` is counted for.
+    pub fee_epoch: u64,
+    /// Fees charged during `fee_epoch`.
+    pub epoch_fees: u64,
+    /// First slot of the current rate-limit window.
+    pub window_start_slot: u64,
+    /// Requests made during the current rate-limit window.
+    pub window_requests: u32,
+    /// Requests made by this consumer since registration.
+    pub total_requests: u64,
+    /// Fees charged to the subscription for this consumer's requests since
+    /// registration. Refunds from cancelled requests are not subtracted.
+    pub total_fees_paid: u64,
+    /// Slot of this consumer's most recent request (0 if none).
+    pub last_request_slot: u64,
+    /// Length of `callback_prefix` in use. 0 = Anchor's
+    /// `fulfill_random_words` discriminator.
+    pub callback_prefix_len: u8,
+    /// Default callback instruction prefix for requests that do not set
+    /// their own. Set by the consumer program with `set_consumer_callback`.
+    pub callback_prefix: [u8; MAX_CALLBACK_PREFIX_LEN],
 }
 
-/// Maximum number of callback accounts that can be stored in a request.
+impl ConsumerRegistration {
+    /// The default callback prefix, empty for Anchor's discriminator.
+    pub fn callback_prefix(&self) -> &[u8] {
+        &self.callback_prefix[..self.callback_prefix_len as usize]
+    }
+
+    /// Check the owner-set limits for a request of `num_words` costing `fee`
+    /// and record it in the usage counters.
+    pub fn record_request(&mut self, num_words: u32, fee: u64, clock: &Clock) -> Result<()> {
+        require!(
+            self.max_num_words == 0 || num_words <= self.max_num_words,
+            VrfError::NumWordsTooLarge
+        );
+
+        if self.fee_epoch != clock.epoch {
+            self.fee_epoch = clock.epoch;
+            self.epoch_fees = 0;
+        }
+        let epoch_fees = self
+            .epoch_fees
+            .checked_add(fee)
+            .ok_or(VrfError::CounterOverflow)?;
+        require!(
+            self.max_fee_per_epoch == 0 || epoch_fees <= self.max_fee_per_epoch,
+            VrfError::ConsumerFeeLimitExceeded
+        );
+
+        if clock.slot >= self.window_start_slot.saturating_add(self.request_window_slots) {
+            self.window_start_slot = clock.slot;
+            self.window_requests = 0;
+        }
+        require!(
+            self.max_requests_per_window == 0
+                || self.window_requests < self.max_requests_per_window,
+            VrfError::ConsumerRateLimitExceeded
+        );
+
+        self.epoch_fees = epoch_fees;
+        self.window_requests = self
+            .window_requests
+            .checked_add(1)
+            .ok_or(VrfError::CounterOverflow)?;
+        self.total_requests = self
+            .total_requests
+            .checked_add(1)
+            .ok_or(VrfError::CounterOverflow)?;
+        self.total_fees_paid = self
+            .total_fees_paid
+            .checked_add(fee)
+            .ok_or(VrfError::CounterOverflow)?;
+        self.last_request_slot = clock.slot;
+        Ok(())
+    }
+}
+
+/// Fees earned from fulfilled requests, stored as a singleton PDA.
+///
+/// Seeds: `["fee-vault"]`
+///
+/// Holds the lamports of every collected fee on top of its rent. The oracle
+/// share is withdrawn by `config.authority` with `withdraw_oracle_fees`, the
+/// protocol share by `config.admin` with `withdraw_protocol_fees`. Requests
+/// never write it; only fulfillment and withdrawals do.
+#[account]
+#[derive(InitSpace)]
+pub struct FeeVault {
+    /// Withdrawable oracle earnings in lamports.
+    pub oracle_fees: u64,
+    /// Withdrawable protocol earnings in lamports.
+    pub protocol_fees: u64,
+    /// PDA bump seed cached for efficient re-derivation.
+    pub bump: u8,
+}
+
+/// Counter of a requester's direct-funded requests.
+///
+/// Seeds: `["requester-nonce", requester]`
+///
+/// Created once per requester with `create_requester_nonce`. Its `id` is
+/// taken from the `RequesterNonceCounter`, above every subscription ID, so
+/// direct request IDs are `id << REQUEST_INDEX_BITS | req_count` like a
+/// subscription's, never overlap them, and requests by different requesters
+/// share no writable account.
+#[account]
+#[derive(InitSpace)]
+pub struct RequesterNonce {
+    /// The wallet whose direct requests this counts.
+    pub requester: Pubkey,
+    /// High bits of the requester's request IDs; never a subscription ID.
+    pub id: u64,
+    /// Number of direct requests made; the low bits of the next request's ID.
+    pub req_count: u64,
+    /// PDA bump seed cached for efficient re-derivation.
+    pub bump: u8,
+}
+
+impl RequesterNonce {
+    /// ID, and request PDA seed, of the requester's next direct request.
+    pub fn next_request_id(&self) -> u64 {
+        (self.id << Subscription::REQUEST_INDEX_BITS) | self.req_count
+    }
+}
+
+/// Singleton that numbers requester nonces.
+///
+/// Seeds: `["requester-nonce-counter"]`
+///
+/// Created by the first `create_requester_nonce` and written only by it, so
+/// nonce IDs are assigned without touching the `CoordinatorConfig`.
+#[account]
+#[derive(InitSpace)]
+pub struct RequesterNonceCounter {
+    /// Number of requester nonces created; the next nonce's ID is
+    /// `Subscription::FIRST_REQUESTER_NONCE_ID` plus this.
+    pub count: u64,
+    /// PDA bump seed cached for efficient re-derivation.
+    pub bump: u8,
+}
+
+/// An SPL mint the admin has approved for subscription billing.
+///
+/// Seeds: `["approved-mint", mint]`
+///
+/// Token fees are collected into the `FeeVault` PDA's associated token
+/// account for the mint, created alongside this account. Their oracle and
+/// protocol shares are tracked here rather than on the `FeeVault`, which only
+/// counts lamports.
+#[account]
+#[derive(InitSpace)]
+pub struct ApprovedMint {
+    /// The approved mint.
+    pub mint: Pubkey,
+    /// Token program owning the mint (SPL Token or Token-2022).
+    pub token_program: Pubkey,
+    /// Fee charged per random word, in the mint's base units.
+    pub fee_per_word: u64,
+    /// New token subscriptions and requests are only accepted while enabled.
+    /// Disabling keeps existing subscriptions withdrawable.
+    pub enabled: bool,
+    /// Withdrawable oracle earnings in the mint's base units.
+    pub oracle_fees: u64,
+    /// Withdrawable protocol earnings in the mint's base units.
+    pub protocol_fees: u64,
+    /// PDA bump seed cached for efficient re-derivation.
+    pub bump: u8,
+    /// Flat fee charged per request, in the mint's base units.
+    pub base_fee: u64,
+    /// Price of each callback compute unit, in millionths of a base unit.
+    pub compute_unit_price: u64,
+}
+
+impl ApprovedMint {
+    /// Prices of requests billed in this mint.
+    pub fn fee_schedule(&self) -> FeeSchedule {
+        FeeSchedule {
+            base_fee: self.base_fee,
+            fee_per_word: self.fee_per_word,
+            compute_unit_price: self.compute_unit_price,
+        }
+    }
+}
+
+/// Maximum number of callback accounts that can be stored inline in a request.
+/// Longer lists go in a `CallbackAccounts` sidecar.
 pub const MAX_CALLBACK_ACCOUNTS: usize = 4;
 
+/// Maximum number of callback accounts a `CallbackAccounts` sidecar can hold,
+/// bounded by the account locks available to the fulfillment transaction.
+pub const MAX_SIDECAR_CALLBACK_ACCOUNTS: usize = 32;
+
+/// Maximum length of the callback failure reason an oracle reports with
+/// `fulfill_random_words*` or `retry_callback`.
+pub const MAX_CALLBACK_FAILURE_LEN: usize = 128;
+
+/// Maximum length of the `user_data` a request carries to its callback.
+pub const MAX_USER_DATA_LEN: usize = 128;
+
+/// Maximum length of a callback instruction prefix. 8 fits an Anchor
+/// discriminator; native programs typically use a 1 or 4 byte tag.
+pub const MAX_CALLBACK_PREFIX_LEN: usize = 8;
+
+/// Maximum number of requests fulfilled by one `fulfill_random_words_batch`.
+/// The transaction size limit usually binds first.
+pub const MAX_BATCH_FULFILLMENTS: usize = 8;
+
+/// Seed of the PDA, derived under the consumer program, that must sign
+/// `consume_randomness`.
+pub const CONSUMER_AUTHORITY_SEED: &[u8] = b"consumer-authority";
+
 /// Individual randomness request account, one per request.
 ///
 /// Seeds: `["vrf-request", request_id.to_le_bytes()]`
 ///
-/// Lifecycle: Pending (0) -> Fulfilled+Callback (coordinator closes the account).
+/// Lifecycle: Pending (0) -> Fulfilled+Callback (coordinator closes the account),
+/// or Pending -> Cancelled after `config.request_timeout_slots` via `cancel_request`.
+/// If the callback cannot be delivered: Pending -> CallbackFailed (2), with the
+/// randomness stored, -> Fulfilled+Callback via `retry_callback`.
+/// Store-only requests: Pending -> Stored (3) -> closed by `consume_randomness`.
 ///
 /// Callback accounts: When the consumer requests randomness, it may pass
-/// remaining_accounts that its callback instruction needs. These are stored
-/// in the request PDA so the backend oracle can read them and include them
-/// as remaining_accounts in the fulfillment transaction.
+/// remaining_accounts that its callback instruction needs. Up to
+/// `MAX_CALLBACK_ACCOUNTS` are stored in the request PDA; if the consumer
+/// supplies a `CallbackAccounts` sidecar, the full list is stored there
+/// instead. The backend oracle reads them and includes them as
+/// remaining_accounts in the fulfillment transaction.
 #[account]
 pub struct RandomnessRequest {
-    /// Unique identifier derived from `CoordinatorConfig::request_counter` at creation time.
+    /// Unique identifier derived from the subscription ID and its
+    /// `req_count` at creation time. See [`Subscription::next_request_id`].
     pub request_id: u64,
     /// The subscription that is paying for this request.
     pub subscription_id: u64,
@@ -111,6 +553,36 @@ pub struct RandomnessRequest {
     pub callback_account_keys: [Pubkey; MAX_CALLBACK_ACCOUNTS],
     /// Bitmap: bit i = 1 means callback_account_keys[i] is writable.
     pub callback_writable_bitmap: u8,
+    /// Fee (in lamports, or `fee_mint` base units) deducted from the
+    /// subscription for this request, paid to the `FeeVault` on fulfillment
+    /// or re-credited if the request is cancelled. A direct-funded request
+    /// holds its fee in its own PDA, on top of the rent.
+    pub fee_paid: u64,
+    /// Failed callback deliveries recorded so far (see `STATUS_CALLBACK_FAILED`).
+    pub callback_attempts: u8,
+    /// Store-only delivery: fulfillment skips the consumer callback and the
+    /// consumer pulls the words with `consume_randomness`.
+    pub store_only: bool,
+    /// Callback accounts are stored in the request's `CallbackAccounts`
+    /// sidecar rather than `callback_account_keys`.
+    pub callback_sidecar: bool,
+    /// Mint `fee_paid` is denominated in, or the default pubkey for lamports.
+    pub fee_mint: Pubkey,
+    /// Slots after `request_slot` before the request can be fulfilled, so a
+    /// request on a dropped fork is never answered. The larger of the
+    /// requested value and `config.min_confirmation_slots`.
+    pub min_confirmation_slots: u64,
+    /// Callback ABI the consumer is called with. See `CALLBACK_V*` constants.
+    pub callback_version: u8,
+    /// Opaque consumer data (up to `MAX_USER_DATA_LEN` bytes), passed back to
+    /// `CALLBACK_V2` callbacks. The PDA is sized to it.
+    pub user_data: Vec<u8>,
+    /// Length of `callback_prefix` in use. 0 = Anchor's
+    /// `fulfill_random_words` discriminator.
+    pub callback_prefix_len: u8,
+    /// Instruction data the callback payload is prefixed with, taken from
+    /// the request or else the consumer's registration.
+    pub callback_prefix: [u8; MAX_CALLBACK_PREFIX_LEN],
 }
 
 impl RandomnessRequest {
@@ -118,9 +590,230 @@ impl RandomnessRequest {
     pub const STATUS_PENDING: u8 = 0;
     /// Oracle has fulfilled and callback has been delivered.
     pub const STATUS_FULFILLED: u8 = 1;
+    /// Randomness is verified and stored, but the consumer callback failed.
+    /// The PDA stays open so delivery can be retried or the result read directly.
+    pub const STATUS_CALLBACK_FAILED: u8 = 2;
+    /// Store-only request fulfilled; awaiting `consume_randomness`.
+    pub const STATUS_STORED: u8 = 3;
+
+    /// Callback `fulfill_random_words(request_id, random_words)`.
+    pub const CALLBACK_V1: u8 = 1;
+    /// Callback `fulfill_random_words(request_id, random_words, user_data)`.
+    pub const CALLBACK_V2: u8 = 2;
+
+    /// Maximum recorded callback failures; once reached, `retry_callback` is rejected.
+    pub const MAX_CALLBACK_ATTEMPTS: u8 = 3;
 
-    /// Manually computed space (Anchor discriminator NOT included).
-    /// 8+8+32+32+4+32+8+4+1+32+8+1 + 1+128+1 = 300
+    /// Largest `num_words` for a store-only request: `consume_randomness`
+    /// returns a borsh `Vec<[u8; 32]>` (4 + 32n bytes) within the 1024-byte
+    /// return data limit. Larger failed callbacks are consumed without
+    /// return data.
+    pub const MAX_STORE_ONLY_WORDS: u32 = 31;
+
+    /// Manually computed space with empty `user_data` (Anchor discriminator
+    /// NOT included).
+    /// 8+8+32+32+4+32+8+4+1+32+8+1 + 1+128+1 + 8+1+1+1 + 32 + 8 + 1+4 + 1+8 = 365
     pub const INIT_SPACE: usize = 8 + 8 + 32 + 32 + 4 + 32 + 8 + 4 + 1 + 32 + 8 + 1
-        + 1 + (32 * MAX_CALLBACK_ACCOUNTS) + 1;
+        + 1 + (32 * MAX_CALLBACK_ACCOUNTS) + 1 + 8 + 1 + 1 + 1 + 32 + 8 + 1 + 4
+        + 1 + MAX_CALLBACK_PREFIX_LEN;
+
+    /// Account space for a request carrying `user_data_len` bytes of user
+    /// data (Anchor discriminator included).
+    pub fn space(user_data_len: usize) -> usize {
+        8 + Self::INIT_SPACE + user_data_len
+    }
+
+    /// First slot at which the request may be fulfilled.
+    pub fn fulfillable_slot(&self) -> u64 {
+        self.request_slot.saturating_add(self.min_confirmation_slots)
+    }
+
+    /// The user data passed to the callback: `Some` for `CALLBACK_V2`
+    /// requests, even if empty, and `None` for `CALLBACK_V1`.
+    pub fn callback_user_data(&self) -> Option<&[u8]> {
+        (self.callback_version == Self::CALLBACK_V2).then_some(self.user_data.as_slice())
+    }
+
+    /// The callback instruction prefix, empty for Anchor's
+    /// `fulfill_random_words` discriminator.
+    pub fn callback_prefix(&self) -> &[u8] {
+        &self.callback_prefix[..self.callback_prefix_len as usize]
+    }
+
+    /// Wallet requests have no consumer program: fulfillment writes the
+    /// randomness to the request's `RandomnessResult` instead of calling back.
+    pub fn is_wallet_request(&self) -> bool {
+        self.consumer_program == Pubkey::default()
+    }
+
+    /// Callback accounts stored inline in `callback_account_keys`, with
+    /// writability from `callback_writable_bitmap`.
+    pub fn inline_callback_accounts(&self) -> Vec<CallbackAccountMeta> {
+        self.callback_account_keys[..self.callback_account_count as usize]
+            .iter()
+            .enumerate()
+            .map(|(i, pubkey)| CallbackAccountMeta {
+                pubkey: *pubkey,
+                is_writable: (self.callback_writable_bitmap >> i) & 1 == 1,
+            })
+            .collect()
+    }
+}
+
+/// Verified output of a wallet request, kept after the request PDA closes.
+///
+/// Seeds: `["randomness-result", request_id.to_le_bytes()]`
+///
+/// Created by `request_wallet_randomness`, paid for by the requester, and
+/// filled in by fulfillment. Anyone can read it; the words are expanded from
+/// `randomness` as `SHA256(randomness || i_le_bytes)`, the same values a
+/// consumer callback receives. Closed by the requester with
+/// `close_randomness_result` once the request PDA is gone.
+#[account]
+#[derive(InitSpace)]
+pub struct RandomnessResult {
+    /// The request this result belongs to.
+    pub request_id: u64,
+    /// The wallet that made the request and paid the rent (refunded on close).
+    pub requester: Pubkey,
+    /// Number of random words requested.
+    pub num_words: u32,
+    /// Caller-provided entropy mixed into the VRF input.
+    pub seed: [u8; 32],
+    /// Solana slot at which the request was created.
+    pub request_slot: u64,
+    /// The 32-byte base VRF output; zero until fulfilled.
+    pub randomness: [u8; 32],
+    /// Solana slot at which the oracle fulfilled the request; 0 until then.
+    pub fulfilled_slot: u64,
+    /// PDA bump seed cached for efficient re-derivation.
+    pub bump: u8,
+}
+
+/// Return data of `request_random_words`. A consumer reads it from the
+/// CPI with `vrf_sol::cpi::request_random_words(..)?.get()`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RequestReceipt {
+    /// ID assigned to the new request.
+    pub request_id: u64,
+    /// Address of the request PDA.
+    pub request: Pubkey,
+}
+
+/// Callback options of `request_random_words` and
+/// `request_random_words_direct`. `CallbackOptions::default()` asks for the
+/// two-argument `fulfill_random_words` callback.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CallbackOptions {
+    /// Up to `MAX_USER_DATA_LEN` bytes passed back to a `CALLBACK_V2`
+    /// callback. `None` keeps the two-argument `CALLBACK_V1` callback.
+    pub user_data: Option<Vec<u8>>,
+    /// Callback instruction prefix for this request (at most
+    /// `MAX_CALLBACK_PREFIX_LEN` bytes). `None` uses the registration's.
+    pub prefix: Option<Vec<u8>>,
+}
+
+/// One request fulfilled by `fulfill_random_words_batch`, with the same
+/// arguments as `fulfill_random_words`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BatchFulfillment {
+    pub request_id: u64,
+    pub randomness: [u8; 32],
+}
+
+/// One entry of a `CallbackAccounts` sidecar.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CallbackAccountMeta {
+    pub pubkey: Pubkey,
+    pub is_writable: bool,
+}
+
+/// Variable-size callback account list for a single request.
+///
+/// Seeds: `["callback-accounts", request_id.to_le_bytes()]`
+///
+/// Created by `request_random_words` when the consumer supplies it, sized to
+/// the actual list. Closed with `close_callback_accounts` once the request
+/// PDA is gone, refunding rent to the requester.
+#[account]
+pub struct CallbackAccounts {
+    /// The request these accounts belong to.
+    pub request_id: u64,
+    /// The account that paid the rent (refunded on close).
+    pub requester: Pubkey,
+    /// Address lookup table covering the accounts, or `Pubkey::default()`.
+    /// The oracle includes it when building the v0 fulfillment transaction.
+    pub lookup_table: Pubkey,
+    /// PDA bump seed cached for efficient re-derivation.
+    pub bump: u8,
+    /// Callback accounts in the order the consumer's callback expects them.
+    pub accounts: Vec<CallbackAccountMeta>,
+}
+
+impl CallbackAccounts {
+    /// Account space for `count` entries (Anchor discriminator included).
+    /// 8 + 8+32+32+1 + 4 + 33 * count
+    pub fn space(count: usize) -> usize {
+        8 + 8 + 32 + 32 + 1 + 4 + 33 * count
+    }
+}
+
+/// One oracle's proof in `fulfill_random_words_multi`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OracleProof {
+    /// Index of the proving oracle in `config.oracles`.
+    pub oracle_index: u8,
+    /// ECVRF proof over the request's VRF input.
+    pub proof: [u8; 80],
+}
+
+/// Configuration changes staged by `update_config`. `None` leaves a field
+/// unchanged.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct ConfigChanges {
+    pub new_authority: Option<Pubkey>,
+    pub new_fee_per_word: Option<u64>,
+    pub new_max_num_words: Option<u32>,
+    /// Becomes `config.pending_admin`; the new admin must accept.
+    pub new_admin: Option<Pubkey>,
+    pub new_proof_scheme: Option<u8>,
+    pub new_request_timeout_slots: Option<u64>,
+    pub new_protocol_fee_bps: Option<u16>,
+    pub new_base_fee: Option<u64>,
+    pub new_compute_unit_price: Option<u64>,
+    pub new_max_callback_compute_limit: Option<u32>,
+    pub new_min_confirmation_slots: Option<u64>,
+    pub new_pauser: Option<Pubkey>,
+    pub new_config_update_delay_slots: Option<u64>,
+    /// Replaces the multi-oracle set and threshold.
+    pub new_oracle_set: Option<OracleSet>,
+}
+
+/// The registered oracles and threshold of the multi-oracle scheme, as
+/// staged in [`ConfigChanges`].
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct OracleSet {
+    #[max_len(MAX_ORACLES)]
+    pub oracles: Vec<Pubkey>,
+    pub threshold: u8,
+}
+
+/// A config update waiting out the timelock, stored as a singleton PDA.
+///
+/// Seeds: `["pending-config-update"]`
+///
+/// Created by `update_config` and closed, with rent returned to the admin, by
+/// `execute_config_update` or `cancel_config_update`. Only one update can be
+/// pending at a time.
+#[account]
+#[derive(InitSpace)]
+pub struct PendingConfigUpdate {
+    /// The staged changes.
+    pub changes: ConfigChanges,
+    /// Slot at which the update was staged.
+    pub proposed_slot: u64,
+    /// First slot at which `execute_config_update` may apply the changes.
+    pub executable_slot: u64,
+    /// PDA bump seed cached for efficient re-derivation.
+    pub bump: u8,
 }
@@ -0,0 +1,97 @@
+//! SPL token helpers for subscriptions billed in an approved mint.
+//!
+//! Both SPL Token and Token-2022 mints are supported. A Token-2022 mint may
+//! only carry extensions that leave raw transfers between token accounts
+//! untouched (metadata, groups, display-only amounts, a close authority).
+//! Anything else is rejected when the mint is approved: for example a
+//! transfer hook runs an arbitrary program on every transfer, a transfer fee
+//! makes the amount received differ from the amount charged, and a permanent
+//! delegate, non-transferable, pausable or default-frozen mint lets fees or
+//! balances be taken or locked out of the program's token accounts. Unknown
+//! future extensions are rejected too.
+
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+};
+use anchor_spl::token_2022::spl_token_2022::state::Mint as MintState;
+use anchor_spl::token_interface::{self, Mint, TransferChecked};
+
+use crate::errors::VrfError;
+
+/// The only Token-2022 extensions a billing mint may have.
+///
+/// `DefaultAccountState` is not listed even when it defaults to
+/// `Initialized`, because the freeze authority can switch new accounts to
+/// `Frozen` at any time.
+const SUPPORTED_EXTENSIONS: [ExtensionType; 9] = [
+    ExtensionType::MintCloseAuthority,
+    ExtensionType::InterestBearingConfig,
+    ExtensionType::ScaledUiAmount,
+    ExtensionType::MetadataPointer,
+    ExtensionType::TokenMetadata,
+    ExtensionType::GroupPointer,
+    ExtensionType::TokenGroup,
+    ExtensionType::GroupMemberPointer,
+    ExtensionType::TokenGroupMember,
+];
+
+/// Fail with `UnsupportedMint` if a Token-2022 `mint` carries an extension
+/// outside `SUPPORTED_EXTENSIONS`. SPL Token mints have no extensions.
+pub fn check_mint_extensions(mint: &AccountInfo) -> Result<()> {
+    if mint.owner != &anchor_spl::token_2022::ID {
+        return Ok(());
+    }
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<MintState>::unpack(&data)?;
+    let extensions = state.get_extension_types()?;
+    require!(
+        extensions.iter().all(|e| SUPPORTED_EXTENSIONS.contains(e)),
+        VrfError::UnsupportedMint
+    );
+    Ok(())
+}
+
+/// Fail with `InvalidTokenAccount` unless `account` is the associated token
+/// account of `authority` for `mint` under `token_program`.
+pub fn require_associated_token_account(
+    account: &Pubkey,
+    authority: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<()> {
+    require_keys_eq!(
+        *account,
+        get_associated_token_address_with_program_id(authority, mint, token_program),
+        VrfError::InvalidTokenAccount
+    );
+    Ok(())
+}
+
+/// `transfer_checked` `amount` of `mint` from a token account owned by one of
+/// this program's PDAs, signing with `signer_seeds`.
+pub fn transfer_from_pda<'info>(
+    token_program: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    authority: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program,
+            TransferChecked {
+                from,
+                mint: mint.to_account_info(),
+                to,
+                authority,
+            },
+            signer_seeds,
+        ),
+        amount,
+        mint.decimals,
+    )
+}
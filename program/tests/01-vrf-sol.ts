@@ -2,6 +2,7 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { VrfSol } from "../target/types/vrf_sol";
 import { RollDice } from "../target/types/roll_dice";
+import { MockConsumer } from "../target/types/mock_consumer";
 import { expect } from "chai";
 import {
   Keypair,
@@ -12,7 +13,10 @@ import {
   Transaction,
 } from "@solana/web3.js";
 import fs from "fs";
+import nacl from "tweetnacl";
 import * as testKeys from "./keys/load";
+import { applyConfigUpdate, CONFIG_UPDATE_DELAY_SLOTS, configChanges } from "./utils/config";
+import { nextDirectRequestId } from "./utils/nonce";
 
 describe("vrf-sol coordinator", () => {
   const provider = anchor.AnchorProvider.env();
@@ -20,6 +24,7 @@ describe("vrf-sol coordinator", () => {
 
   const program = anchor.workspace.vrfSol as Program<VrfSol>;
   const diceProgram = anchor.workspace.rollDice as Program<RollDice>;
+  const mockConsumer = anchor.workspace.mockConsumer as Program<MockConsumer>;
   const admin = provider.wallet as anchor.Wallet;
 
   // Oracle authority keypair
@@ -36,6 +41,11 @@ describe("vrf-sol coordinator", () => {
     program.programId
   );
 
+  const [feeVaultPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("fee-vault")],
+    program.programId
+  );
+
   // Track whether config was already initialized
   let configAlreadyExisted = false;
 
@@ -65,6 +75,14 @@ describe("vrf-sol coordinator", () => {
     return pda;
   }
 
+  function getConsumerAuthorityPda(consumerProgram: PublicKey): PublicKey {
+    const [pda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("consumer-authority")],
+      consumerProgram
+    );
+    return pda;
+  }
+
   function getRequestPda(requestId: number | anchor.BN): PublicKey {
     const id = new anchor.BN(requestId);
     const [pda] = PublicKey.findProgramAddressSync(
@@ -74,9 +92,10 @@ describe("vrf-sol coordinator", () => {
     return pda;
   }
 
-  async function getNextRequestId(): Promise<number> {
-    const config = await program.account.coordinatorConfig.fetch(configPda);
-    return config.requestCounter.toNumber();
+  // Request IDs are `subscription_id << 32 | req_count`.
+  async function getNextRequestId(subId: number = subscriptionId): Promise<number> {
+    const sub = await program.account.subscription.fetch(getSubscriptionPda(subId));
+    return subId * 2 ** 32 + sub.reqCount.toNumber();
   }
 
   async function getNextSubscriptionId(): Promise<number> {
@@ -84,6 +103,20 @@ describe("vrf-sol coordinator", () => {
     return config.subscriptionCounter.toNumber();
   }
 
+  async function waitForSlot(slot: number): Promise<void> {
+    while ((await provider.connection.getSlot()) < slot) {
+      await new Promise((r) => setTimeout(r, 400));
+    }
+  }
+
+  // Wait until a store-only request has its output stored, by us or the backend.
+  async function waitForStoredRequest(requestId: number): Promise<void> {
+    const requestPda = getRequestPda(requestId);
+    while ((await program.account.randomnessRequest.fetch(requestPda)).status !== 3) {
+      await new Promise((r) => setTimeout(r, 400));
+    }
+  }
+
   async function fundAccount(
     destination: PublicKey,
     lamports: number
@@ -112,13 +145,7 @@ describe("vrf-sol coordinator", () => {
     if (existingConfig) {
       configAlreadyExisted = true;
       // Update config to use our local authority for this run
-      await program.methods
-        .updateConfig(authority.publicKey, feePerWord, maxNumWords, null)
-        .accounts({
-          admin: admin.publicKey,
-          config: configPda,
-        })
-        .rpc();
+      await applyConfigUpdate(program, { newAuthority: authority.publicKey, newFeePerWord: feePerWord, newMaxNumWords: maxNumWords });
     }
   });
 
@@ -139,11 +166,12 @@ describe("vrf-sol coordinator", () => {
     }
 
     await program.methods
-      .initialize(feePerWord, maxNumWords)
+      .initialize(feePerWord, maxNumWords, new anchor.BN(CONFIG_UPDATE_DELAY_SLOTS))
       .accounts({
         admin: admin.publicKey,
         authority: authority.publicKey,
         config: configPda,
+        feeVault: feeVaultPda,
         systemProgram: SystemProgram.programId,
       })
       .rpc();
@@ -155,16 +183,18 @@ describe("vrf-sol coordinator", () => {
     expect(config.maxNumWords).to.equal(maxNumWords);
     expect(config.requestCounter.toNumber()).to.equal(0);
     expect(config.subscriptionCounter.toNumber()).to.equal(0);
+    expect(config.configUpdateDelaySlots.toNumber()).to.equal(CONFIG_UPDATE_DELAY_SLOTS);
   });
 
   it("Fails to initialize config twice", async () => {
     try {
       await program.methods
-        .initialize(feePerWord, maxNumWords)
+        .initialize(feePerWord, maxNumWords, new anchor.BN(CONFIG_UPDATE_DELAY_SLOTS))
         .accounts({
           admin: admin.publicKey,
           authority: authority.publicKey,
           config: configPda,
+          feeVault: feeVaultPda,
           systemProgram: SystemProgram.programId,
         })
         .rpc();
@@ -240,6 +270,24 @@ describe("vrf-sol coordinator", () => {
     expect(sub.consumerCount).to.equal(1);
   });
 
+  it("Adds the mock consumer to the subscription", async () => {
+    const consumerPda = getConsumerPda(subscriptionId, mockConsumer.programId);
+
+    await program.methods
+      .addConsumer(new anchor.BN(subscriptionId))
+      .accounts({
+        owner: admin.publicKey,
+        subscription: subscriptionPda,
+        consumerProgram: mockConsumer.programId,
+        consumerRegistration: consumerPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const sub = await program.account.subscription.fetch(subscriptionPda);
+    expect(sub.consumerCount).to.equal(2);
+  });
+
   it("Fails to cancel subscription with consumers", async () => {
     try {
       await program.methods
@@ -278,6 +326,70 @@ describe("vrf-sol coordinator", () => {
     }
   });
 
+  it("Withdraws part of a subscription balance while consumers are registered", async () => {
+    const amount = new anchor.BN(LAMPORTS_PER_SOL / 10);
+    const nonAdmin = testKeys.nonAdmin;
+
+    try {
+      await program.methods
+        .withdrawSubscriptionFunds(new anchor.BN(subscriptionId), amount)
+        .accounts({
+          owner: nonAdmin.publicKey,
+          subscription: subscriptionPda,
+          recipient: nonAdmin.publicKey,
+        })
+        .signers([nonAdmin])
+        .rpc();
+      expect.fail("Should have failed - not owner");
+    } catch (e: any) {
+      expect(e.toString()).to.contain("Unauthorized");
+    }
+
+    try {
+      await program.methods
+        .withdrawSubscriptionFunds(
+          new anchor.BN(subscriptionId),
+          new anchor.BN(LAMPORTS_PER_SOL + 1)
+        )
+        .accounts({
+          owner: admin.publicKey,
+          subscription: subscriptionPda,
+          recipient: admin.publicKey,
+        })
+        .rpc();
+      expect.fail("Should have failed - more than the balance");
+    } catch (e: any) {
+      expect(e.toString()).to.contain("InsufficientSubscriptionBalance");
+    }
+
+    const recipient = Keypair.generate().publicKey;
+    await program.methods
+      .withdrawSubscriptionFunds(new anchor.BN(subscriptionId), amount)
+      .accounts({
+        owner: admin.publicKey,
+        subscription: subscriptionPda,
+        recipient,
+      })
+      .rpc();
+
+    let sub = await program.account.subscription.fetch(subscriptionPda);
+    expect(sub.balance.toNumber()).to.equal(LAMPORTS_PER_SOL - amount.toNumber());
+    expect(sub.consumerCount).to.equal(2);
+    expect(await provider.connection.getBalance(recipient)).to.equal(amount.toNumber());
+
+    // Top the subscription back up for the request tests below
+    await program.methods
+      .fundSubscription(new anchor.BN(subscriptionId), amount)
+      .accounts({
+        funder: admin.publicKey,
+        subscription: subscriptionPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    sub = await program.account.subscription.fetch(subscriptionPda);
+    expect(sub.balance.toNumber()).to.equal(LAMPORTS_PER_SOL);
+  });
+
   // === REQUEST RANDOM WORDS ===
 
   it("Requests random words via CPI from dice program", async () => {
@@ -313,7 +425,7 @@ describe("vrf-sol coordinator", () => {
 
     const consumerPda = getConsumerPda(subscriptionId, diceProgram.programId);
 
-    await diceProgram.methods
+    const sig = await diceProgram.methods
       .requestRoll([...seed] as any)
       .accounts({
         player: admin.publicKey,
@@ -329,6 +441,15 @@ describe("vrf-sol coordinator", () => {
       })
       .rpc();
 
+    // The coordinator config is only read, so requests on different
+    // subscriptions do not serialize on it
+    const tx = await provider.connection.getTransaction(sig, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const configIndex = tx!.transaction.message.staticAccountKeys.findIndex((key) => key.equals(configPda));
+    expect(tx!.transaction.message.isAccountWritable(configIndex)).to.be.false;
+
     // Verify request was created (may be closed by backend if fulfilled quickly)
     const requestAccount = await provider.connection.getAccountInfo(requestPda);
     if (requestAccount) {
@@ -355,6 +476,38 @@ describe("vrf-sol coordinator", () => {
     expect(diceRoll.vrfRequestId.toNumber()).to.equal(requestId);
   });
 
+  it("Rejects a request that is not signed by the consumer program", async () => {
+    const requestId = await getNextRequestId();
+    const consumerPda = getConsumerPda(subscriptionId, diceProgram.programId);
+
+    // A wallet names the registered dice program, passing either its own
+    // signature or the dice program's authority PDA without a signature
+    for (const consumerAuthority of [admin.publicKey, getConsumerAuthorityPda(diceProgram.programId)]) {
+      try {
+        await program.methods
+          .requestRandomWords(1, [...Buffer.alloc(32, 0x03)] as any, 200_000, false, new anchor.BN(0), { userData: null, prefix: null })
+          .accounts({
+            requester: admin.publicKey,
+            config: configPda,
+            subscription: subscriptionPda,
+            consumerRegistration: consumerPda,
+            consumerProgram: diceProgram.programId,
+            consumerAuthority,
+            request: getRequestPda(requestId),
+            systemProgram: SystemProgram.programId,
+            callbackAccounts: null,
+            callbackLookupTable: null,
+            approvedMint: null,
+          })
+          .rpc();
+        expect.fail("Should have failed - request not signed by the consumer program");
+      } catch (e: any) {
+        const errStr = e?.logs?.join(" ") ?? e.toString();
+        expect(errStr).to.contain("InvalidConsumerProgram");
+      }
+    }
+  });
+
   it("Fails with insufficient subscription balance", async () => {
     // Create a new subscription with no funds
     const newSubId = await getNextSubscriptionId();
@@ -370,37 +523,38 @@ describe("vrf-sol coordinator", () => {
       })
       .rpc();
 
-    // Register the dice program as consumer
-    const consumerPda = getConsumerPda(newSubId, diceProgram.programId);
+    // Register the mock consumer, which can request against any subscription
+    const consumerPda = getConsumerPda(newSubId, mockConsumer.programId);
     await program.methods
       .addConsumer(new anchor.BN(newSubId))
       .accounts({
         owner: admin.publicKey,
         subscription: newSubPda,
-        consumerProgram: diceProgram.programId,
+        consumerProgram: mockConsumer.programId,
         consumerRegistration: consumerPda,
         systemProgram: SystemProgram.programId,
       })
       .rpc();
 
     // Try to request (should fail — no balance)
-    const requestId = await getNextRequestId();
+    const requestId = await getNextRequestId(newSubId);
     const seed = Buffer.alloc(32, 0x02);
     const requestPda = getRequestPda(requestId);
 
-    // Initialize a separate game config for this test if needed
-    // Actually, the dice program uses its own game-config which points to the original subscription.
-    // We need to call request_random_words directly to test with the unfunded subscription.
     try {
-      await program.methods
-        .requestRandomWords(1, [...seed] as any, 200_000)
+      await mockConsumer.methods
+        .request(1, [...seed] as any, 200_000, false, new anchor.BN(0), { userData: null, prefix: null })
         .accounts({
           requester: admin.publicKey,
-          config: configPda,
+          vrfConfig: configPda,
           subscription: newSubPda,
           consumerRegistration: consumerPda,
-          consumerProgram: diceProgram.programId,
-          request: requestPda,
+          thisProgram: mockConsumer.programId,
+          vrfRequest: requestPda,
+          callbackAccounts: null,
+          callbackLookupTable: null,
+          approvedMint: null,
+          vrfProgram: program.programId,
           systemProgram: SystemProgram.programId,
         })
         .rpc();
@@ -413,6 +567,70 @@ describe("vrf-sol coordinator", () => {
 
   // === FULFILL RANDOM WORDS ===
 
+  it("Rejects fulfillment with callback accounts that differ from the stored ones", async () => {
+    const requestId = (await getNextRequestId()) - 1;
+    const reqId = new anchor.BN(requestId);
+    const requestPda = getRequestPda(requestId);
+
+    // Backend may have raced us and fulfilled the request already
+    const pending = await program.account.randomnessRequest.fetchNullable(requestPda);
+    if (!pending || pending.status !== 0) {
+      return;
+    }
+
+    const [gameConfigPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("game-config")],
+      diceProgram.programId
+    );
+    const diceRollPda = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("dice-result"),
+        admin.publicKey.toBuffer(),
+        reqId.toArrayLike(Buffer, "le", 8),
+      ],
+      diceProgram.programId
+    )[0];
+    const randomness = Buffer.alloc(32, 0x42);
+    const ed25519Ix = Ed25519Program.createInstructionWithPrivateKey({
+      privateKey: authority.secretKey,
+      message: Buffer.concat([reqId.toArrayLike(Buffer, "le", 8), randomness]),
+    });
+
+    // Stored order is [game_config, dice_roll]; swap them
+    try {
+      await program.methods
+        .fulfillRandomWords(reqId, [...randomness] as any, null)
+        .accounts({
+          authority: authority.publicKey,
+          config: configPda,
+          request: requestPda,
+          requester: admin.publicKey,
+          consumerProgram: diceProgram.programId,
+          instructionsSysvar: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          callbackAccounts: null,
+          subscription: subscriptionPda,
+          feeVault: feeVaultPda,
+          approvedMint: null,
+          feeMint: null,
+          subscriptionVault: null,
+          feeVaultTokenAccount: null,
+          tokenProgram: null,
+          randomnessResult: null,
+        })
+        .remainingAccounts([
+          { pubkey: diceRollPda, isWritable: true, isSigner: false },
+          { pubkey: gameConfigPda, isWritable: true, isSigner: false },
+        ])
+        .preInstructions([ed25519Ix])
+        .signers([authority])
+        .rpc();
+      expect.fail("Should have failed - callback accounts swapped");
+    } catch (e: any) {
+      const errStr = e?.logs?.join(" ") ?? e.toString();
+      expect(errStr).to.contain("CallbackAccountsMismatch");
+    }
+  });
+
   it("Fulfills random words with Ed25519 proof and delivers callback", async () => {
     // Get the last created request (from the dice roll test)
     const requestId = (await getNextRequestId()) - 1;
@@ -461,7 +679,7 @@ describe("vrf-sol coordinator", () => {
 
     try {
       await program.methods
-        .fulfillRandomWords(reqId, [...randomness] as any)
+        .fulfillRandomWords(reqId, [...randomness] as any, null)
         .accounts({
           authority: authority.publicKey,
           config: configPda,
@@ -469,6 +687,15 @@ describe("vrf-sol coordinator", () => {
           requester: admin.publicKey,
           consumerProgram: diceProgram.programId,
           instructionsSysvar: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          callbackAccounts: null,
+          subscription: subscriptionPda,
+          feeVault: feeVaultPda,
+          approvedMint: null,
+          feeMint: null,
+          subscriptionVault: null,
+          feeVaultTokenAccount: null,
+          tokenProgram: null,
+          randomnessResult: null,
         })
         .remainingAccounts([
           { pubkey: gameConfigPda, isWritable: false, isSigner: false },
@@ -502,6 +729,145 @@ describe("vrf-sol coordinator", () => {
     expect(requestAfter).to.be.null;
   });
 
+  it("Rejects fulfillment before the request's confirmation slot", async () => {
+    const consumerPda = getConsumerPda(subscriptionId, mockConsumer.programId);
+    const request = (requestId: number, storeOnly: boolean, minConfirmationSlots: number) =>
+      mockConsumer.methods
+        .request(1, [...Buffer.alloc(32, 0x55)] as any, 0, storeOnly, new anchor.BN(minConfirmationSlots), { userData: null, prefix: null })
+        .accounts({
+          requester: admin.publicKey,
+          vrfConfig: configPda,
+          subscription: subscriptionPda,
+          consumerRegistration: consumerPda,
+          thisProgram: mockConsumer.programId,
+          vrfRequest: getRequestPda(requestId),
+          callbackAccounts: null,
+          callbackLookupTable: null,
+          approvedMint: null,
+          vrfProgram: program.programId,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    const setMinConfirmationSlots = async (slots: number) =>
+      applyConfigUpdate(program, { newMinConfirmationSlots: new anchor.BN(slots) });
+
+    // A request cannot wait longer than it takes to become cancellable
+    const config = await program.account.coordinatorConfig.fetch(configPda);
+    try {
+      await request(await getNextRequestId(), false, config.requestTimeoutSlots.toNumber());
+      expect.fail("Should have failed - confirmation slots not below the timeout");
+    } catch (e: any) {
+      const errStr = e?.logs?.join(" ") ?? e.toString();
+      expect(errStr).to.contain("InvalidConfirmationSlots");
+    }
+
+    const requestId = await getNextRequestId();
+    const reqId = new anchor.BN(requestId);
+    const requestPda = getRequestPda(requestId);
+    await request(requestId, false, 1_000);
+    expect((await program.account.randomnessRequest.fetch(requestPda)).minConfirmationSlots.toNumber()).to.equal(1_000);
+
+    const randomness = Buffer.alloc(32, 0x56);
+    try {
+      await program.methods
+        .fulfillRandomWords(reqId, [...randomness] as any, null)
+        .accounts({
+          authority: authority.publicKey,
+          config: configPda,
+          request: requestPda,
+          requester: admin.publicKey,
+          consumerProgram: mockConsumer.programId,
+          instructionsSysvar: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          callbackAccounts: null,
+          subscription: subscriptionPda,
+          feeVault: feeVaultPda,
+          approvedMint: null,
+          feeMint: null,
+          subscriptionVault: null,
+          feeVaultTokenAccount: null,
+          tokenProgram: null,
+          randomnessResult: null,
+        })
+        .preInstructions([
+          Ed25519Program.createInstructionWithPrivateKey({
+            privateKey: authority.secretKey,
+            message: Buffer.concat([reqId.toArrayLike(Buffer, "le", 8), randomness]),
+          }),
+        ])
+        .signers([authority])
+        .rpc();
+      expect.fail("Should have failed - request not yet confirmed");
+    } catch (e: any) {
+      const errStr = e?.logs?.join(" ") ?? e.toString();
+      expect(errStr).to.contain("RequestNotConfirmed");
+    }
+
+    // The coordinator minimum applies to requests asking for fewer slots
+    await setMinConfirmationSlots(10);
+    const storedId = await getNextRequestId();
+    await request(storedId, true, 0);
+    const stored = await program.account.randomnessRequest.fetch(getRequestPda(storedId));
+    expect(stored.minConfirmationSlots.toNumber()).to.equal(10);
+    await setMinConfirmationSlots(0);
+  });
+
+  it("Credits collected fees to the fee vault for the authority to withdraw", async () => {
+    // The dice request above has been fulfilled, by this suite or the backend
+    const vault = await program.account.feeVault.fetch(feeVaultPda);
+    expect(vault.oracleFees.toNumber()).to.be.greaterThan(0);
+
+    const wrongAuthority = testKeys.wrongAuthority;
+    try {
+      await program.methods
+        .withdrawOracleFees(vault.oracleFees)
+        .accounts({
+          authority: wrongAuthority.publicKey,
+          config: configPda,
+          feeVault: feeVaultPda,
+          recipient: wrongAuthority.publicKey,
+        })
+        .signers([wrongAuthority])
+        .rpc();
+      expect.fail("Should have failed - not the oracle authority");
+    } catch (e: any) {
+      expect(e.toString()).to.contain("Unauthorized");
+    }
+
+    try {
+      await program.methods
+        .withdrawOracleFees(vault.oracleFees.addn(1))
+        .accounts({
+          authority: authority.publicKey,
+          config: configPda,
+          feeVault: feeVaultPda,
+          recipient: admin.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+      expect.fail("Should have failed - more than the accrued oracle fees");
+    } catch (e: any) {
+      expect(e.toString()).to.contain("InsufficientFees");
+    }
+
+    const amount = new anchor.BN(10_000);
+    const before = await provider.connection.getBalance(admin.publicKey);
+    await program.methods
+      .withdrawOracleFees(amount)
+      .accounts({
+        authority: authority.publicKey,
+        config: configPda,
+        feeVault: feeVaultPda,
+        recipient: admin.publicKey,
+      })
+      .signers([authority])
+      .rpc();
+
+    const after = await program.account.feeVault.fetch(feeVaultPda);
+    // The backend may collect more fees concurrently, so compare loosely
+    expect(after.oracleFees.toNumber()).to.be.gte(vault.oracleFees.toNumber() - 10_000);
+    expect(await provider.connection.getBalance(admin.publicKey)).to.equal(before + 10_000);
+  });
+
   it("Fails to fulfill with wrong authority", async () => {
     // Create a new request for this test
     const requestId = await getNextRequestId();
@@ -560,7 +926,7 @@ describe("vrf-sol coordinator", () => {
 
     try {
       await program.methods
-        .fulfillRandomWords(reqId, [...randomness] as any)
+        .fulfillRandomWords(reqId, [...randomness] as any, null)
         .accounts({
           authority: wrongAuthority.publicKey,
           config: configPda,
@@ -568,6 +934,15 @@ describe("vrf-sol coordinator", () => {
           requester: admin.publicKey,
           consumerProgram: diceProgram.programId,
           instructionsSysvar: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          callbackAccounts: null,
+          subscription: subscriptionPda,
+          feeVault: feeVaultPda,
+          approvedMint: null,
+          feeMint: null,
+          subscriptionVault: null,
+          feeVaultTokenAccount: null,
+          tokenProgram: null,
+          randomnessResult: null,
         })
         .remainingAccounts([
           { pubkey: gameConfigPda, isWritable: false, isSigner: false },
@@ -592,43 +967,1475 @@ describe("vrf-sol coordinator", () => {
     }
   });
 
-  // === REMOVE CONSUMER ===
-
-  it("Removes a consumer from the subscription", async () => {
-    // Add a second consumer to test removal
-    const fakeConsumer = Keypair.generate();
-    const consumerPda = getConsumerPda(subscriptionId, fakeConsumer.publicKey);
+  it("Fails to cancel a request before it expires", async () => {
+    const requestId = await getNextRequestId();
+    const seed = Buffer.alloc(32, 0x05);
+    const requestPda = getRequestPda(requestId);
+    const consumerPda = getConsumerPda(subscriptionId, diceProgram.programId);
+    const [gameConfigPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("game-config")],
+      diceProgram.programId
+    );
+    const diceRollPda = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("dice-result"),
+        admin.publicKey.toBuffer(),
+        new anchor.BN(requestId).toArrayLike(Buffer, "le", 8),
+      ],
+      diceProgram.programId
+    )[0];
 
-    await program.methods
-      .addConsumer(new anchor.BN(subscriptionId))
+    await diceProgram.methods
+      .requestRoll([...seed] as any)
       .accounts({
-        owner: admin.publicKey,
+        player: admin.publicKey,
+        gameConfig: gameConfigPda,
+        vrfConfig: configPda,
         subscription: subscriptionPda,
-        consumerProgram: fakeConsumer.publicKey,
         consumerRegistration: consumerPda,
+        vrfRequest: requestPda,
+        thisProgram: diceProgram.programId,
+        diceRoll: diceRollPda,
+        vrfProgram: program.programId,
         systemProgram: SystemProgram.programId,
       })
       .rpc();
 
-    const subBefore = await program.account.subscription.fetch(subscriptionPda);
-    const countBefore = subBefore.consumerCount;
+    const request = await program.account.randomnessRequest.fetchNullable(requestPda);
+    if (request) {
+      expect(request.feePaid.toNumber()).to.equal(10_000);
+    }
 
-    await program.methods
-      .removeConsumer(new anchor.BN(subscriptionId))
-      .accounts({
-        owner: admin.publicKey,
+    try {
+      await program.methods
+        .cancelRequest(new anchor.BN(requestId))
+        .accounts({
+          caller: admin.publicKey,
+          config: configPda,
+          request: requestPda,
+          subscription: subscriptionPda,
+          requester: admin.publicKey,
+        })
+        .rpc();
+      expect.fail("Should have failed - request not expired");
+    } catch (e: any) {
+      // The backend may already have fulfilled and closed the request.
+      const errStr = [e?.message, e?.logs?.join(" "), JSON.stringify(e)].filter(Boolean).join(" ");
+      const isExpectedError =
+        errStr.includes("RequestNotExpired") || errStr.includes("AccountNotInitialized");
+      expect(isExpectedError, `Expected RequestNotExpired, got: ${errStr.substring(0, 200)}`).to.be.true;
+    }
+  });
+
+  it("Stores randomness on a reported callback failure and delivers it via retry_callback", async () => {
+    // Reuse the pending request from the cancellation test
+    const requestId = (await getNextRequestId()) - 1;
+    const reqId = new anchor.BN(requestId);
+    const requestPda = getRequestPda(requestId);
+    const [gameConfigPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("game-config")],
+      diceProgram.programId
+    );
+    const diceRollPda = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("dice-result"),
+        admin.publicKey.toBuffer(),
+        reqId.toArrayLike(Buffer, "le", 8),
+      ],
+      diceProgram.programId
+    )[0];
+    const callbackAccounts = [
+      { pubkey: gameConfigPda, isWritable: false, isSigner: false },
+      { pubkey: diceRollPda, isWritable: true, isSigner: false },
+    ];
+
+    // Backend may have raced us and fulfilled the request already
+    const pending = await program.account.randomnessRequest.fetchNullable(requestPda);
+    if (!pending || pending.status !== 0) {
+      return;
+    }
+
+    const randomness = Buffer.alloc(32, 0x24);
+    const ed25519Ix = Ed25519Program.createInstructionWithPrivateKey({
+      privateKey: authority.secretKey,
+      message: Buffer.concat([reqId.toArrayLike(Buffer, "le", 8), randomness]),
+    });
+
+    try {
+      await program.methods
+        .fulfillRandomWords(reqId, [...randomness] as any, "simulated callback failure")
+        .accounts({
+          authority: authority.publicKey,
+          config: configPda,
+          request: requestPda,
+          requester: admin.publicKey,
+          consumerProgram: diceProgram.programId,
+          instructionsSysvar: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          callbackAccounts: null,
+          subscription: subscriptionPda,
+          feeVault: feeVaultPda,
+          approvedMint: null,
+          feeMint: null,
+          subscriptionVault: null,
+          feeVaultTokenAccount: null,
+          tokenProgram: null,
+          randomnessResult: null,
+        })
+        .preInstructions([ed25519Ix])
+        .signers([authority])
+        .rpc();
+    } catch (e: any) {
+      const errStr = [e?.message, e?.logs?.join(" "), JSON.stringify(e)].filter(Boolean).join(" ");
+      if (errStr.includes("RequestNotPending") || errStr.includes("AccountNotInitialized")) {
+        return;
+      }
+      throw e;
+    }
+
+    const stored = await program.account.randomnessRequest.fetch(requestPda);
+    expect(stored.status).to.equal(2); // CallbackFailed
+    expect(stored.callbackAttempts).to.equal(1);
+    expect(Buffer.from(stored.randomness).equals(randomness)).to.be.true;
+
+    await program.methods
+      .retryCallback(reqId, null)
+      .accounts({
+        caller: admin.publicKey,
+        config: configPda,
+        request: requestPda,
+        requester: admin.publicKey,
+        consumerProgram: diceProgram.programId,
+        callbackAccounts: null,
+      })
+      .remainingAccounts(callbackAccounts)
+      .rpc();
+
+    const diceRoll = await diceProgram.account.diceRoll.fetch(diceRollPda);
+    expect(diceRoll.result).to.be.gte(1).and.lte(6);
+    expect(await provider.connection.getAccountInfo(requestPda)).to.be.null;
+  });
+
+  it("Stores store-only randomness and only lets the consumer program consume it", async () => {
+    const requestId = await getNextRequestId();
+    const reqId = new anchor.BN(requestId);
+    const requestPda = getRequestPda(requestId);
+    const consumerPda = getConsumerPda(subscriptionId, mockConsumer.programId);
+
+    const requestSig = await mockConsumer.methods
+      .request(2, [...Buffer.alloc(32, 0x33)] as any, 0, true, new anchor.BN(0), { userData: null, prefix: null })
+      .accounts({
+        requester: admin.publicKey,
+        vrfConfig: configPda,
+        subscription: subscriptionPda,
+        consumerRegistration: consumerPda,
+        thisProgram: mockConsumer.programId,
+        vrfRequest: requestPda,
+        callbackAccounts: null,
+        callbackLookupTable: null,
+        approvedMint: null,
+        vrfProgram: program.programId,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    // The consumer logs the request ID and PDA the coordinator returned
+    const requestTx = await provider.connection.getTransaction(requestSig, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    expect(requestTx!.meta!.logMessages!.join("\n")).to.contain(
+      `request_id=${requestId}, request=${requestPda.toBase58()}`
+    );
+
+    const pending = await program.account.randomnessRequest.fetch(requestPda);
+    expect(pending.storeOnly).to.be.true;
+
+    // Backend may have raced us and stored the result already
+    if (pending.status === 0) {
+      const randomness = Buffer.alloc(32, 0x55);
+      const ed25519Ix = Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: authority.secretKey,
+        message: Buffer.concat([reqId.toArrayLike(Buffer, "le", 8), randomness]),
+      });
+      await program.methods
+        .fulfillRandomWords(reqId, [...randomness] as any, null)
+        .accounts({
+          authority: authority.publicKey,
+          config: configPda,
+          request: requestPda,
+          requester: admin.publicKey,
+          consumerProgram: mockConsumer.programId,
+          instructionsSysvar: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          callbackAccounts: null,
+          subscription: subscriptionPda,
+          feeVault: feeVaultPda,
+          approvedMint: null,
+          feeMint: null,
+          subscriptionVault: null,
+          feeVaultTokenAccount: null,
+          tokenProgram: null,
+          randomnessResult: null,
+        })
+        .preInstructions([ed25519Ix])
+        .signers([authority])
+        .rpc();
+    }
+
+    // No callback: the request stays open with the output stored
+    const stored = await program.account.randomnessRequest.fetch(requestPda);
+    expect(stored.status).to.equal(3); // Stored
+    expect(stored.fulfilledSlot.toNumber()).to.be.greaterThan(0);
+
+    try {
+      await program.methods
+        .consumeRandomness(reqId)
+        .accounts({
+          consumerAuthority: admin.publicKey,
+          request: requestPda,
+          requester: admin.publicKey,
+        })
+        .rpc();
+      expect.fail("Should have failed - signer is not the consumer authority PDA");
+    } catch (e: any) {
+      const errStr = e?.logs?.join(" ") ?? e.toString();
+      expect(errStr).to.contain("InvalidConsumerAuthority");
+    }
+
+    // The consumer program pulls the words and the request is closed
+    await mockConsumer.methods
+      .consume(reqId)
+      .accounts({
+        vrfRequest: requestPda,
+        requester: admin.publicKey,
+        vrfProgram: program.programId,
+      })
+      .rpc();
+    expect(await provider.connection.getAccountInfo(requestPda)).to.be.null;
+  });
+
+  it("Returns the seed of a failed callback with too many words to return", async () => {
+    await applyConfigUpdate(program, { newMaxNumWords: 32 });
+    const requestId = await getNextRequestId();
+    const reqId = new anchor.BN(requestId);
+    const requestPda = getRequestPda(requestId);
+    const consumerPda = getConsumerPda(subscriptionId, mockConsumer.programId);
+
+    try {
+      await mockConsumer.methods
+        .request(32, [...Buffer.alloc(32, 0x34)] as any, 200_000, false, new anchor.BN(0), { userData: null, prefix: null })
+        .accounts({
+          requester: admin.publicKey,
+          vrfConfig: configPda,
+          subscription: subscriptionPda,
+          consumerRegistration: consumerPda,
+          thisProgram: mockConsumer.programId,
+          vrfRequest: requestPda,
+          callbackAccounts: null,
+          callbackLookupTable: null,
+          approvedMint: null,
+          vrfProgram: program.programId,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      // Backend may have raced us and delivered the callback already
+      const pending = await program.account.randomnessRequest.fetchNullable(requestPda);
+      if (!pending || pending.status !== 0) {
+        return;
+      }
+
+      const randomness = Buffer.alloc(32, 0x56);
+      const ed25519Ix = Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: authority.secretKey,
+        message: Buffer.concat([reqId.toArrayLike(Buffer, "le", 8), randomness]),
+      });
+      await program.methods
+        .fulfillRandomWords(reqId, [...randomness] as any, "simulated callback failure")
+        .accounts({
+          authority: authority.publicKey,
+          config: configPda,
+          request: requestPda,
+          requester: admin.publicKey,
+          consumerProgram: mockConsumer.programId,
+          instructionsSysvar: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          callbackAccounts: null,
+          subscription: subscriptionPda,
+          feeVault: feeVaultPda,
+          approvedMint: null,
+          feeMint: null,
+          subscriptionVault: null,
+          feeVaultTokenAccount: null,
+          tokenProgram: null,
+          randomnessResult: null,
+        })
+        .preInstructions([ed25519Ix])
+        .signers([authority])
+        .rpc();
+      expect((await program.account.randomnessRequest.fetch(requestPda)).status).to.equal(2); // CallbackFailed
+
+      // 32 words do not fit in return data, so the words cannot be pulled...
+      try {
+        await mockConsumer.methods
+          .consume(reqId)
+          .accounts({
+            vrfRequest: requestPda,
+            requester: admin.publicKey,
+            vrfProgram: program.programId,
+          })
+          .rpc();
+        expect.fail("Should have failed - too many words to return");
+      } catch (e: any) {
+        const errStr = e?.logs?.join(" ") ?? e.toString();
+        expect(errStr).to.contain("NumWordsTooLarge");
+      }
+      expect(await provider.connection.getAccountInfo(requestPda)).to.not.be.null;
+
+      // ...but the seed can, and that closes the request
+      await mockConsumer.methods
+        .consumeSeed(reqId)
+        .accounts({
+          vrfRequest: requestPda,
+          requester: admin.publicKey,
+          vrfProgram: program.programId,
+        })
+        .rpc();
+      expect(await provider.connection.getAccountInfo(requestPda)).to.be.null;
+    } finally {
+      await applyConfigUpdate(program, { newMaxNumWords: maxNumWords });
+    }
+  });
+
+  it("Charges a direct-funded request to the requester and holds the fee in the request PDA", async () => {
+    // Direct requests record subscription ID 2^32 - 1 and take their IDs from the requester's nonce
+    const directSubscriptionId = new anchor.BN(2).pow(new anchor.BN(32)).subn(1);
+    const { requestId: reqId, noncePda } = await nextDirectRequestId(program, admin.publicKey);
+    const [requestPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vrf-request"), reqId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const before = await program.account.feeVault.fetch(feeVaultPda);
+
+    await mockConsumer.methods
+      .requestDirect(2, [...Buffer.alloc(32, 0x3d)] as any, 0, true, new anchor.BN(0), { userData: null, prefix: null })
+      .accounts({
+        requester: admin.publicKey,
+        vrfConfig: configPda,
+        requesterNonce: noncePda,
+        thisProgram: mockConsumer.programId,
+        vrfRequest: requestPda,
+        callbackAccounts: null,
+        callbackLookupTable: null,
+        vrfProgram: program.programId,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const config = await program.account.coordinatorConfig.fetch(configPda);
+    const fee = config.baseFee.add(config.feePerWord.muln(2));
+    const request = await program.account.randomnessRequest.fetch(requestPda);
+    expect(request.subscriptionId.eq(directSubscriptionId)).to.be.true;
+    expect(request.feePaid.eq(fee)).to.be.true;
+
+    const nonce = await program.account.requesterNonce.fetch(noncePda);
+    expect(nonce.id.shln(32).add(nonce.reqCount).eq(reqId.addn(1))).to.be.true;
+    // Nonce IDs sit above every subscription ID
+    expect(nonce.id.gte(new anchor.BN(2).pow(new anchor.BN(31)))).to.be.true;
+    // Backend may have raced us and fulfilled it already
+    if (request.status === 0) {
+      const rent = await provider.connection.getMinimumBalanceForRentExemption(
+        (await provider.connection.getAccountInfo(requestPda))!.data.length
+      );
+      expect(await provider.connection.getBalance(requestPda)).to.equal(rent + fee.toNumber());
+    }
+
+    if (request.status === 0) {
+      const randomness = Buffer.alloc(32, 0x3e);
+      const ed25519Ix = Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: authority.secretKey,
+        message: Buffer.concat([reqId.toArrayLike(Buffer, "le", 8), randomness]),
+      });
+      try {
+        await program.methods
+          .fulfillRandomWords(reqId, [...randomness] as any, null)
+          .accounts({
+            authority: authority.publicKey,
+            config: configPda,
+            request: requestPda,
+            requester: admin.publicKey,
+            consumerProgram: mockConsumer.programId,
+            instructionsSysvar: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+            callbackAccounts: null,
+            subscription: null,
+            feeVault: feeVaultPda,
+            approvedMint: null,
+            feeMint: null,
+            subscriptionVault: null,
+            feeVaultTokenAccount: null,
+            tokenProgram: null,
+            randomnessResult: null,
+          })
+          .preInstructions([ed25519Ix])
+          .signers([authority])
+          .rpc();
+      } catch (e: any) {
+        const errStr = e?.logs?.join(" ") ?? e.toString();
+        if (!errStr.includes("RequestNotPending")) {
+          throw e;
+        }
+      }
+    }
+
+    // Fulfillment moves the fee out of the request PDA into the oracle and protocol shares
+    const stored = await program.account.randomnessRequest.fetch(requestPda);
+    expect(stored.status).to.equal(3); // Stored
+    const storedInfo = await provider.connection.getAccountInfo(requestPda);
+    expect(storedInfo!.lamports).to.equal(
+      await provider.connection.getMinimumBalanceForRentExemption(storedInfo!.data.length)
+    );
+    const afterFulfill = await program.account.feeVault.fetch(feeVaultPda);
+    expect(
+      afterFulfill.oracleFees
+        .add(afterFulfill.protocolFees)
+        .sub(before.oracleFees.add(before.protocolFees))
+        .gte(fee)
+    ).to.be.true;
+
+    await mockConsumer.methods
+      .consume(reqId)
+      .accounts({
+        vrfRequest: requestPda,
+        requester: admin.publicKey,
+        vrfProgram: program.programId,
+      })
+      .rpc();
+    expect(await provider.connection.getAccountInfo(requestPda)).to.be.null;
+  });
+
+  it("Writes a wallet request's randomness to a result account the requester can close", async () => {
+    const { requestId: reqId, noncePda } = await nextDirectRequestId(program, admin.publicKey);
+    const requestPda = getRequestPda(reqId);
+    const [resultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("randomness-result"), reqId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    await program.methods
+      .requestWalletRandomness(3, [...Buffer.alloc(32, 0x4a)] as any, new anchor.BN(0))
+      .accounts({
+        requester: admin.publicKey,
+        config: configPda,
+        requesterNonce: noncePda,
+        request: requestPda,
+        randomnessResult: resultPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const created = await program.account.randomnessResult.fetch(resultPda);
+    expect(created.requestId.eq(reqId)).to.be.true;
+    expect(created.requester.toBase58()).to.equal(admin.publicKey.toBase58());
+    expect(created.numWords).to.equal(3);
+
+    // The result cannot be closed while the request is open
+    const requestInfo = await provider.connection.getAccountInfo(requestPda);
+    if (requestInfo !== null) {
+      try {
+        await program.methods
+          .closeRandomnessResult(reqId)
+          .accounts({ requester: admin.publicKey, randomnessResult: resultPda, request: requestPda })
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (e: any) {
+        const errStr = e?.logs?.join(" ") ?? e.toString();
+        expect(errStr).to.include("RequestStillOpen");
+      }
+
+      const randomness = Buffer.alloc(32, 0x4b);
+      const ed25519Ix = Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: authority.secretKey,
+        message: Buffer.concat([reqId.toArrayLike(Buffer, "le", 8), randomness]),
+      });
+      try {
+        await program.methods
+          .fulfillRandomWords(reqId, [...randomness] as any, null)
+          .accounts({
+            authority: authority.publicKey,
+            config: configPda,
+            request: requestPda,
+            requester: admin.publicKey,
+            consumerProgram: SystemProgram.programId,
+            instructionsSysvar: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+            callbackAccounts: null,
+            subscription: null,
+            feeVault: feeVaultPda,
+            approvedMint: null,
+            feeMint: null,
+            subscriptionVault: null,
+            feeVaultTokenAccount: null,
+            tokenProgram: null,
+            randomnessResult: resultPda,
+          })
+          .preInstructions([ed25519Ix])
+          .signers([authority])
+          .rpc();
+      } catch (e: any) {
+        const errStr = e?.logs?.join(" ") ?? e.toString();
+        // Backend may have raced us
+        if (!errStr.includes("RequestNotPending") && !errStr.includes("AccountNotInitialized")) {
+          throw e;
+        }
+      }
+    }
+
+    // Fulfillment closes the request and leaves the output in the result
+    expect(await provider.connection.getAccountInfo(requestPda)).to.be.null;
+    const result = await program.account.randomnessResult.fetch(resultPda);
+    expect(result.fulfilledSlot.toNumber()).to.be.greaterThan(0);
+    expect(Buffer.from(result.randomness).equals(Buffer.alloc(32))).to.be.false;
+
+    // Only the requester may close it
+    try {
+      await program.methods
+        .closeRandomnessResult(reqId)
+        .accounts({ requester: testKeys.nonAdmin.publicKey, randomnessResult: resultPda, request: requestPda })
+        .signers([testKeys.nonAdmin])
+        .rpc();
+      expect.fail("Should have thrown");
+    } catch (e: any) {
+      const errStr = e?.logs?.join(" ") ?? e.toString();
+      expect(errStr).to.include("Unauthorized");
+    }
+
+    await program.methods
+      .closeRandomnessResult(reqId)
+      .accounts({ requester: admin.publicKey, randomnessResult: resultPda, request: requestPda })
+      .rpc();
+    expect(await provider.connection.getAccountInfo(resultPda)).to.be.null;
+  });
+
+  it("Fulfills several requests in one batch proven by one Ed25519 instruction", async () => {
+    const consumerPda = getConsumerPda(subscriptionId, mockConsumer.programId);
+    const requestIds: number[] = [];
+    for (const fill of [0x61, 0x62]) {
+      const requestId = await getNextRequestId();
+      await mockConsumer.methods
+        .request(1, [...Buffer.alloc(32, fill)] as any, 0, true, new anchor.BN(0), { userData: null, prefix: null })
+        .accounts({
+          requester: admin.publicKey,
+          vrfConfig: configPda,
+          subscription: subscriptionPda,
+          consumerRegistration: consumerPda,
+          thisProgram: mockConsumer.programId,
+          vrfRequest: getRequestPda(requestId),
+          callbackAccounts: null,
+          callbackLookupTable: null,
+          approvedMint: null,
+          vrfProgram: program.programId,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      requestIds.push(requestId);
+    }
+
+    const fulfillments = requestIds.map((id, i) => ({
+      requestId: new anchor.BN(id),
+      randomness: Buffer.alloc(32, 0x70 + i),
+    }));
+    const message = (f: { requestId: anchor.BN; randomness: Buffer }) =>
+      Buffer.concat([f.requestId.toArrayLike(Buffer, "le", 8), f.randomness]);
+
+    // One precompile instruction, one signature per fulfillment in batch
+    // order, each entry referencing its own message.
+    const batchEd25519Ix = (messages: Buffer[]) => {
+      const headerLen = 2 + 14 * messages.length;
+      const offsets = Buffer.alloc(headerLen);
+      offsets.writeUInt8(messages.length, 0);
+      const payload: Buffer[] = [];
+      let cursor = headerLen;
+      messages.forEach((msg, i) => {
+        const signature = Buffer.from(nacl.sign.detached(msg, authority.secretKey));
+        const at = 2 + 14 * i;
+        offsets.writeUInt16LE(cursor + 32, at); // signature
+        offsets.writeUInt16LE(0xffff, at + 2);
+        offsets.writeUInt16LE(cursor, at + 4); // public key
+        offsets.writeUInt16LE(0xffff, at + 6);
+        offsets.writeUInt16LE(cursor + 96, at + 8); // message
+        offsets.writeUInt16LE(msg.length, at + 10);
+        offsets.writeUInt16LE(0xffff, at + 12);
+        payload.push(authority.publicKey.toBuffer(), signature, msg);
+        cursor += 96 + msg.length;
+      });
+      return new anchor.web3.TransactionInstruction({
+        programId: Ed25519Program.programId,
+        keys: [],
+        data: Buffer.concat([offsets, ...payload]),
+      });
+    };
+
+    // Each request brings the full fulfill_random_words account list; absent
+    // optional accounts are passed as the program ID.
+    const requestAccounts = (id: number) =>
+      [
+        [authority.publicKey, true, true],
+        [configPda, false, false],
+        [getRequestPda(id), true, false],
+        [admin.publicKey, true, false],
+        [mockConsumer.programId, false, false],
+        [anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY, false, false],
+        [program.programId, false, false],
+        [subscriptionPda, true, false],
+        [feeVaultPda, true, false],
+        ...Array(6).fill([program.programId, false, false]),
+      ].map(([pubkey, isWritable, isSigner]) => ({ pubkey, isWritable, isSigner })) as any;
+
+    const fulfillBatch = (ed25519Ix: anchor.web3.TransactionInstruction) =>
+      program.methods
+        .fulfillRandomWordsBatch(fulfillments as any)
+        .accounts({
+          authority: authority.publicKey,
+          config: configPda,
+          instructionsSysvar: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+        })
+        .remainingAccounts(requestIds.flatMap(requestAccounts))
+        .preInstructions([ed25519Ix])
+        .signers([authority])
+        .rpc();
+
+    // The precompile must carry exactly one signature per fulfillment
+    try {
+      await fulfillBatch(batchEd25519Ix([message(fulfillments[0])]));
+      expect.fail("Should have failed - one signature for two fulfillments");
+    } catch (e: any) {
+      const errStr = e?.logs?.join(" ") ?? e.toString();
+      expect(errStr).to.contain("InvalidSignatureCount");
+    }
+
+    // Backend may have raced us and stored either result already
+    const pending = await Promise.all(
+      requestIds.map((id) => program.account.randomnessRequest.fetch(getRequestPda(id)))
+    );
+    if (pending.every((request) => request.status === 0)) {
+      await fulfillBatch(batchEd25519Ix(fulfillments.map(message)));
+      for (const [i, id] of requestIds.entries()) {
+        const stored = await program.account.randomnessRequest.fetch(getRequestPda(id));
+        expect(stored.status).to.equal(3); // Stored
+        expect(Buffer.from(stored.randomness)).to.deep.equal(fulfillments[i].randomness);
+      }
+    }
+
+    for (const id of requestIds) {
+      await waitForStoredRequest(id);
+      await mockConsumer.methods
+        .consume(new anchor.BN(id))
+        .accounts({
+          vrfRequest: getRequestPda(id),
+          requester: admin.publicKey,
+          vrfProgram: program.programId,
+        })
+        .rpc();
+    }
+  });
+
+  it("Rejects more than 4 callback accounts without a sidecar", async () => {
+    const requestId = await getNextRequestId();
+    const consumerPda = getConsumerPda(subscriptionId, mockConsumer.programId);
+    const callbackAccounts = Array.from({ length: 5 }, () => ({
+      pubkey: Keypair.generate().publicKey,
+      isWritable: false,
+      isSigner: false,
+    }));
+
+    try {
+      await mockConsumer.methods
+        .request(1, [...Buffer.alloc(32, 0x44)] as any, 200_000, false, new anchor.BN(0), { userData: null, prefix: null })
+        .accounts({
+          requester: admin.publicKey,
+          vrfConfig: configPda,
+          subscription: subscriptionPda,
+          consumerRegistration: consumerPda,
+          thisProgram: mockConsumer.programId,
+          vrfRequest: getRequestPda(requestId),
+          callbackAccounts: null,
+          callbackLookupTable: null,
+          approvedMint: null,
+          vrfProgram: program.programId,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(callbackAccounts)
+        .rpc();
+      expect.fail("Should have failed - too many callback accounts");
+    } catch (e: any) {
+      const errStr = e?.logs?.join(" ") ?? e.toString();
+      expect(errStr).to.contain("TooManyCallbackAccounts");
+    }
+  });
+
+  it("Stores a long callback account list in a sidecar", async () => {
+    const requestId = await getNextRequestId();
+    const idBuffer = new anchor.BN(requestId).toArrayLike(Buffer, "le", 8);
+    const requestPda = getRequestPda(requestId);
+    const [sidecarPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("callback-accounts"), idBuffer],
+      program.programId
+    );
+    const consumerPda = getConsumerPda(subscriptionId, mockConsumer.programId);
+    const callbackAccounts = Array.from({ length: 10 }, (_, i) => ({
+      pubkey: Keypair.generate().publicKey,
+      isWritable: i % 2 === 0,
+      isSigner: false,
+    }));
+
+    await mockConsumer.methods
+      .request(1, [...Buffer.alloc(32, 0x45)] as any, 200_000, false, new anchor.BN(0), { userData: null, prefix: null })
+      .accounts({
+        requester: admin.publicKey,
+        vrfConfig: configPda,
+        subscription: subscriptionPda,
+        consumerRegistration: consumerPda,
+        thisProgram: mockConsumer.programId,
+        vrfRequest: requestPda,
+        callbackAccounts: sidecarPda,
+        callbackLookupTable: null,
+        approvedMint: null,
+        vrfProgram: program.programId,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(callbackAccounts)
+      .rpc();
+
+    const request = await program.account.randomnessRequest.fetch(requestPda);
+    expect(request.callbackSidecar).to.be.true;
+    expect(request.callbackAccountCount).to.equal(0);
+
+    const sidecar = await program.account.callbackAccounts.fetch(sidecarPda);
+    expect(sidecar.requestId.toNumber()).to.equal(requestId);
+    expect(sidecar.lookupTable.equals(PublicKey.default)).to.be.true;
+    expect(sidecar.accounts.length).to.equal(callbackAccounts.length);
+    sidecar.accounts.forEach((meta: any, i: number) => {
+      expect(meta.pubkey.equals(callbackAccounts[i].pubkey)).to.be.true;
+      expect(meta.isWritable).to.equal(callbackAccounts[i].isWritable);
+    });
+
+    // The sidecar cannot be closed while its request is still open
+    try {
+      await program.methods
+        .closeCallbackAccounts(new anchor.BN(requestId))
+        .accounts({
+          callbackAccounts: sidecarPda,
+          request: requestPda,
+          requester: admin.publicKey,
+        })
+        .rpc();
+      expect.fail("Should have failed - request still open");
+    } catch (e: any) {
+      const errStr = e?.logs?.join(" ") ?? e.toString();
+      expect(errStr).to.contain("RequestStillOpen");
+    }
+  });
+
+  it("Stores user data with the request and passes it to the callback", async () => {
+    const consumerPda = getConsumerPda(subscriptionId, mockConsumer.programId);
+    const requestWithData = (requestPda: PublicKey, userData: Buffer) =>
+      mockConsumer.methods
+        .request(1, [...Buffer.alloc(32, 0x46)] as any, 200_000, false, new anchor.BN(0), { userData, prefix: null })
+        .accounts({
+          requester: admin.publicKey,
+          vrfConfig: configPda,
+          subscription: subscriptionPda,
+          consumerRegistration: consumerPda,
+          thisProgram: mockConsumer.programId,
+          vrfRequest: requestPda,
+          callbackAccounts: null,
+          callbackLookupTable: null,
+          approvedMint: null,
+          vrfProgram: program.programId,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    const requestId = await getNextRequestId();
+    const requestPda = getRequestPda(requestId);
+    try {
+      await requestWithData(requestPda, Buffer.alloc(129, 0x01));
+      expect.fail("Should have failed - user data too long");
+    } catch (e: any) {
+      const errStr = e?.logs?.join(" ") ?? e.toString();
+      expect(errStr).to.contain("UserDataTooLong");
+    }
+
+    const userData = Buffer.from([0xca, 0xfe, 0x00, 0x01, 0x02]);
+    await requestWithData(requestPda, userData);
+
+    // Backend may have raced us and fulfilled the request already
+    const info = await provider.connection.getAccountInfo(requestPda);
+    if (info !== null) {
+      // The PDA is sized to the user data
+      expect(info.data.length).to.equal(373 + userData.length);
+      const request = await program.account.randomnessRequest.fetch(requestPda);
+      expect(request.callbackVersion).to.equal(2);
+      expect(Buffer.from(request.userData).equals(userData)).to.be.true;
+
+      // The two-argument mock callback still accepts the longer payload
+      const reqId = new anchor.BN(requestId);
+      const randomness = Buffer.alloc(32, 0x47);
+      const ed25519Ix = Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: authority.secretKey,
+        message: Buffer.concat([reqId.toArrayLike(Buffer, "le", 8), randomness]),
+      });
+      try {
+        await program.methods
+          .fulfillRandomWords(reqId, [...randomness] as any, null)
+          .accounts({
+            authority: authority.publicKey,
+            config: configPda,
+            request: requestPda,
+            requester: admin.publicKey,
+            consumerProgram: mockConsumer.programId,
+            instructionsSysvar: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+            callbackAccounts: null,
+            subscription: subscriptionPda,
+            feeVault: feeVaultPda,
+            approvedMint: null,
+            feeMint: null,
+            subscriptionVault: null,
+            feeVaultTokenAccount: null,
+            tokenProgram: null,
+            randomnessResult: null,
+          })
+          .preInstructions([ed25519Ix])
+          .signers([authority])
+          .rpc();
+      } catch (e: any) {
+        const errStr = e?.logs?.join(" ") ?? e.toString();
+        if (!errStr.includes("RequestNotPending") && !errStr.includes("AccountNotInitialized")) {
+          throw e;
+        }
+      }
+    }
+    expect(await provider.connection.getAccountInfo(requestPda)).to.be.null;
+  });
+
+  it("Routes callbacks to the consumer's chosen instruction prefix", async () => {
+    const consumerPda = getConsumerPda(subscriptionId, mockConsumer.programId);
+    const setCallback = (prefix: Buffer) =>
+      mockConsumer.methods
+        .setCallback(new anchor.BN(subscriptionId), prefix)
+        .accounts({
+          thisProgram: mockConsumer.programId,
+          consumerRegistration: consumerPda,
+          vrfProgram: program.programId,
+        })
+        .rpc();
+    const requestWithPrefix = (requestPda: PublicKey, prefix: Buffer | null) =>
+      mockConsumer.methods
+        .request(1, [...Buffer.alloc(32, 0x48)] as any, 200_000, false, new anchor.BN(0), { userData: null, prefix })
+        .accounts({
+          requester: admin.publicKey,
+          vrfConfig: configPda,
+          subscription: subscriptionPda,
+          consumerRegistration: consumerPda,
+          thisProgram: mockConsumer.programId,
+          vrfRequest: requestPda,
+          callbackAccounts: null,
+          callbackLookupTable: null,
+          approvedMint: null,
+          vrfProgram: program.programId,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    const fulfill = async (requestId: number, requestPda: PublicKey) => {
+      const reqId = new anchor.BN(requestId);
+      const randomness = Buffer.alloc(32, 0x49);
+      const ed25519Ix = Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: authority.secretKey,
+        message: Buffer.concat([reqId.toArrayLike(Buffer, "le", 8), randomness]),
+      });
+      try {
+        await program.methods
+          .fulfillRandomWords(reqId, [...randomness] as any, null)
+          .accounts({
+            authority: authority.publicKey,
+            config: configPda,
+            request: requestPda,
+            requester: admin.publicKey,
+            consumerProgram: mockConsumer.programId,
+            instructionsSysvar: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+            callbackAccounts: null,
+            subscription: subscriptionPda,
+            feeVault: feeVaultPda,
+            approvedMint: null,
+            feeMint: null,
+            subscriptionVault: null,
+            feeVaultTokenAccount: null,
+            tokenProgram: null,
+            randomnessResult: null,
+          })
+          .preInstructions([ed25519Ix])
+          .signers([authority])
+          .rpc();
+      } catch (e: any) {
+        const errStr = e?.logs?.join(" ") ?? e.toString();
+        if (!errStr.includes("RequestNotPending") && !errStr.includes("AccountNotInitialized")) {
+          throw e;
+        }
+      }
+      // A callback that failed would leave the request open
+      expect(await provider.connection.getAccountInfo(requestPda)).to.be.null;
+    };
+
+    // Anchor discriminator of mock-consumer's `fulfill_secondary`
+    const secondary = Buffer.from([141, 0, 174, 43, 242, 187, 165, 255]);
+
+    try {
+      await setCallback(Buffer.alloc(9, 0x01));
+      expect.fail("Should have failed - callback prefix too long");
+    } catch (e: any) {
+      const errStr = e?.logs?.join(" ") ?? e.toString();
+      expect(errStr).to.contain("CallbackPrefixTooLong");
+    }
+
+    await setCallback(secondary);
+    const registration = await program.account.consumerRegistration.fetch(consumerPda);
+    expect(registration.callbackPrefixLen).to.equal(8);
+    expect(Buffer.from(registration.callbackPrefix).equals(secondary)).to.be.true;
+
+    // Without its own prefix a request copies the registration's
+    let requestId = await getNextRequestId();
+    let requestPda = getRequestPda(requestId);
+    await requestWithPrefix(requestPda, null);
+    let request = await program.account.randomnessRequest.fetchNullable(requestPda);
+    if (request !== null) {
+      expect(request.callbackPrefixLen).to.equal(8);
+      expect(Buffer.from(request.callbackPrefix).equals(secondary)).to.be.true;
+    }
+    await fulfill(requestId, requestPda);
+
+    // An empty prefix on the request restores `fulfill_random_words`
+    requestId = await getNextRequestId();
+    requestPda = getRequestPda(requestId);
+    await requestWithPrefix(requestPda, Buffer.alloc(0));
+    request = await program.account.randomnessRequest.fetchNullable(requestPda);
+    if (request !== null) {
+      expect(request.callbackPrefixLen).to.equal(0);
+    }
+    await fulfill(requestId, requestPda);
+
+    await setCallback(Buffer.alloc(0));
+  });
+
+  // === REMOVE CONSUMER ===
+
+  it("Removes a consumer from the subscription", async () => {
+    // Add a second consumer to test removal
+    const fakeConsumer = Keypair.generate();
+    const consumerPda = getConsumerPda(subscriptionId, fakeConsumer.publicKey);
+
+    await program.methods
+      .addConsumer(new anchor.BN(subscriptionId))
+      .accounts({
+        owner: admin.publicKey,
+        subscription: subscriptionPda,
+        consumerProgram: fakeConsumer.publicKey,
+        consumerRegistration: consumerPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const subBefore = await program.account.subscription.fetch(subscriptionPda);
+    const countBefore = subBefore.consumerCount;
+
+    await program.methods
+      .removeConsumer(new anchor.BN(subscriptionId))
+      .accounts({
+        owner: admin.publicKey,
         subscription: subscriptionPda,
         consumerProgram: fakeConsumer.publicKey,
         consumerRegistration: consumerPda,
       })
       .rpc();
 
-    const subAfter = await program.account.subscription.fetch(subscriptionPda);
-    expect(subAfter.consumerCount).to.equal(countBefore - 1);
+    const subAfter = await program.account.subscription.fetch(subscriptionPda);
+    expect(subAfter.consumerCount).to.equal(countBefore - 1);
+
+    // Registration account should be closed
+    const regAccount = await provider.connection.getAccountInfo(consumerPda);
+    expect(regAccount).to.be.null;
+  });
+
+  it("Transfers subscription ownership in two steps", async () => {
+    const newOwner = testKeys.nonAdmin;
+    const newSubId = await getNextSubscriptionId();
+    const newSubPda = getSubscriptionPda(newSubId);
+    const id = new anchor.BN(newSubId);
+    const fakeConsumer = Keypair.generate();
+    const consumerPda = getConsumerPda(newSubId, fakeConsumer.publicKey);
+
+    await program.methods
+      .createSubscription()
+      .accounts({
+        owner: admin.publicKey,
+        config: configPda,
+        subscription: newSubPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods
+      .addConsumer(id)
+      .accounts({
+        owner: admin.publicKey,
+        subscription: newSubPda,
+        consumerProgram: fakeConsumer.publicKey,
+        consumerRegistration: consumerPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await program.methods
+      .requestSubscriptionOwnerTransfer(id, newOwner.publicKey)
+      .accounts({ owner: admin.publicKey, subscription: newSubPda })
+      .rpc();
+
+    let sub = await program.account.subscription.fetch(newSubPda);
+    expect(sub.owner.toBase58()).to.equal(admin.publicKey.toBase58());
+    expect(sub.pendingOwner.toBase58()).to.equal(newOwner.publicKey.toBase58());
+
+    const stranger = testKeys.wrongPlayer;
+    try {
+      await program.methods
+        .acceptSubscriptionOwnerTransfer(id)
+        .accounts({ newOwner: stranger.publicKey, subscription: newSubPda })
+        .signers([stranger])
+        .rpc();
+      expect.fail("Should have failed - not the pending owner");
+    } catch (e: any) {
+      expect(e.toString()).to.contain("NotPendingSubscriptionOwner");
+    }
+
+    await program.methods
+      .acceptSubscriptionOwnerTransfer(id)
+      .accounts({ newOwner: newOwner.publicKey, subscription: newSubPda })
+      .signers([newOwner])
+      .rpc();
+
+    sub = await program.account.subscription.fetch(newSubPda);
+    expect(sub.owner.toBase58()).to.equal(newOwner.publicKey.toBase58());
+    expect(sub.pendingOwner.toBase58()).to.equal(PublicKey.default.toBase58());
+    expect(sub.consumerCount).to.equal(1);
+
+    // The previous owner has lost control
+    try {
+      await program.methods
+        .cancelSubscription(id)
+        .accounts({ owner: admin.publicKey, subscription: newSubPda })
+        .rpc();
+      expect.fail("Should have failed - no longer the owner");
+    } catch (e: any) {
+      expect(e.toString()).to.contain("Unauthorized");
+    }
+
+    // The new owner manages the existing registration and closes the subscription
+    await program.methods
+      .removeConsumer(id)
+      .accounts({
+        owner: newOwner.publicKey,
+        subscription: newSubPda,
+        consumerProgram: fakeConsumer.publicKey,
+        consumerRegistration: consumerPda,
+      })
+      .signers([newOwner])
+      .rpc();
+    await program.methods
+      .cancelSubscription(id)
+      .accounts({ owner: newOwner.publicKey, subscription: newSubPda })
+      .signers([newOwner])
+      .rpc();
+
+    expect(await provider.connection.getAccountInfo(newSubPda)).to.be.null;
+  });
+
+  it("Charges base, per-word and compute fees and caps the callback compute limit", async () => {
+    const consumerPda = getConsumerPda(subscriptionId, mockConsumer.programId);
+    const request = (callbackComputeLimit: number, requestId: number) =>
+      mockConsumer.methods
+        .request(1, [...Buffer.alloc(32, 0x44)] as any, callbackComputeLimit, false, new anchor.BN(0), { userData: null, prefix: null })
+        .accounts({
+          requester: admin.publicKey,
+          vrfConfig: configPda,
+          subscription: subscriptionPda,
+          consumerRegistration: consumerPda,
+          thisProgram: mockConsumer.programId,
+          vrfRequest: getRequestPda(requestId),
+          callbackAccounts: null,
+          callbackLookupTable: null,
+          approvedMint: null,
+          vrfProgram: program.programId,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    const setPricing = async (baseFee: number, computeUnitPrice: number, maxCallbackComputeLimit: number) =>
+      applyConfigUpdate(program, {
+        newBaseFee: new anchor.BN(baseFee),
+        newComputeUnitPrice: new anchor.BN(computeUnitPrice),
+        newMaxCallbackComputeLimit: maxCallbackComputeLimit,
+      });
+
+    // 5,000 lamports per request, 0.05 lamports per callback compute unit
+    await setPricing(5_000, 50_000, 300_000);
+    const config = await program.account.coordinatorConfig.fetch(configPda);
+    expect(config.maxCallbackComputeLimit).to.equal(300_000);
+
+    try {
+      await request(300_001, await getNextRequestId());
+      expect.fail("Should have failed - callback compute limit above the maximum");
+    } catch (e: any) {
+      const errStr = e?.logs?.join(" ") ?? e.toString();
+      expect(errStr).to.contain("CallbackComputeLimitTooLarge");
+    }
+
+    const before = await program.account.subscription.fetch(subscriptionPda);
+    await request(200_001, await getNextRequestId());
+    const after = await program.account.subscription.fetch(subscriptionPda);
+    // base 5,000 + 1 word × fee_per_word + ⌈200,001 × 50,000 / 10^6⌉ = 10,001
+    expect(before.balance.sub(after.balance).toNumber()).to.equal(
+      5_000 + config.feePerWord.toNumber() + 10_001
+    );
+
+    try {
+      await setPricing(0, 0, 1_400_001);
+      expect.fail("Should have failed - maximum above a transaction's compute budget");
+    } catch (e: any) {
+      expect(e.toString()).to.contain("CallbackComputeLimitTooLarge");
+    }
+
+    await setPricing(0, 0, 1_000_000);
+  });
+
+  it("Enforces per-consumer limits and records usage", async () => {
+    const consumerPda = getConsumerPda(subscriptionId, mockConsumer.programId);
+    const request = async (numWords: number) =>
+      mockConsumer.methods
+        .request(numWords, [...Buffer.alloc(32, 0x55)] as any, 0, false, new anchor.BN(0), { userData: null, prefix: null })
+        .accounts({
+          requester: admin.publicKey,
+          vrfConfig: configPda,
+          subscription: subscriptionPda,
+          consumerRegistration: consumerPda,
+          thisProgram: mockConsumer.programId,
+          vrfRequest: getRequestPda(await getNextRequestId()),
+          callbackAccounts: null,
+          callbackLookupTable: null,
+          approvedMint: null,
+          vrfProgram: program.programId,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    const setLimits = (
+      maxFeePerEpoch: number,
+      maxRequestsPerWindow: number,
+      requestWindowSlots: number,
+      maxNumWords: number,
+      owner: Keypair = admin
+    ) =>
+      program.methods
+        .setConsumerLimits(
+          new anchor.BN(subscriptionId),
+          new anchor.BN(maxFeePerEpoch),
+          maxRequestsPerWindow,
+          new anchor.BN(requestWindowSlots),
+          maxNumWords
+        )
+        .accounts({
+          owner: owner.publicKey,
+          subscription: subscriptionPda,
+          consumerProgram: mockConsumer.programId,
+          consumerRegistration: consumerPda,
+        })
+        .signers(owner === admin ? [] : [owner])
+        .rpc();
+    const expectFailure = async (action: Promise<unknown>, error: string) => {
+      try {
+        await action;
+        expect.fail(`Should have failed with ${error}`);
+      } catch (e: any) {
+        const errStr = e?.logs?.join(" ") ?? e.toString();
+        expect(errStr).to.contain(error);
+      }
+    };
+
+    await expectFailure(setLimits(0, 0, 0, 1, testKeys.nonAdmin), "Unauthorized");
+    await expectFailure(setLimits(0, 1, 0, 0), "InvalidConsumerLimits");
+
+    // At most 2 words and 1 request per 10,000 slots
+    await setLimits(0, 1, 10_000, 2);
+    const before = await program.account.consumerRegistration.fetch(consumerPda);
+    await expectFailure(request(3), "NumWordsTooLarge");
+    await request(2);
+    await expectFailure(request(1), "ConsumerRateLimitExceeded");
+
+    const after = await program.account.consumerRegistration.fetch(consumerPda);
+    const config = await program.account.coordinatorConfig.fetch(configPda);
+    expect(after.maxNumWords).to.equal(2);
+    expect(after.maxRequestsPerWindow).to.equal(1);
+    expect(after.windowRequests).to.equal(1);
+    expect(after.totalRequests.sub(before.totalRequests).toNumber()).to.equal(1);
+    expect(after.totalFeesPaid.sub(before.totalFeesPaid).toNumber()).to.equal(
+      config.feePerWord.toNumber() * 2
+    );
+    expect(after.lastRequestSlot.toNumber()).to.be.greaterThan(0);
+
+    // Requests made earlier this epoch already used the whole allowance
+    await setLimits(config.feePerWord.toNumber() * 2, 0, 0, 0);
+    await expectFailure(request(1), "ConsumerFeeLimitExceeded");
+
+    await setLimits(0, 0, 0, 0);
+    await request(1);
+  });
+
+  it("Bills a subscription in an approved SPL mint", async () => {
+    const TOKEN_PROGRAM_ID = new PublicKey("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+    const ASSOCIATED_TOKEN_PROGRAM_ID = new PublicKey(
+      "ATokenGPvbd9kwyVRCVwF2pbmGt5TRAH2xXUcNwcaWWs"
+    );
+    const ata = (owner: PublicKey, mint: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [owner.toBuffer(), TOKEN_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+        ASSOCIATED_TOKEN_PROGRAM_ID
+      )[0];
+    const tokenAmount = async (account: PublicKey) =>
+      Number((await provider.connection.getTokenAccountBalance(account)).value.amount);
+
+    // Create a 6-decimal mint and mint 1,000,000 base units to the admin
+    const mint = Keypair.generate();
+    const adminAta = ata(admin.publicKey, mint.publicKey);
+    const initializeMint = Buffer.alloc(35);
+    initializeMint[0] = 20; // InitializeMint2
+    initializeMint[1] = 6;
+    admin.publicKey.toBuffer().copy(initializeMint, 2);
+    const mintTo = Buffer.alloc(9);
+    mintTo[0] = 7; // MintTo
+    mintTo.writeBigUInt64LE(BigInt(1_000_000), 1);
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: admin.publicKey,
+          newAccountPubkey: mint.publicKey,
+          lamports: await provider.connection.getMinimumBalanceForRentExemption(82),
+          space: 82,
+          programId: TOKEN_PROGRAM_ID,
+        }),
+        new anchor.web3.TransactionInstruction({
+          programId: TOKEN_PROGRAM_ID,
+          keys: [{ pubkey: mint.publicKey, isSigner: false, isWritable: true }],
+          data: initializeMint,
+        }),
+        new anchor.web3.TransactionInstruction({
+          programId: ASSOCIATED_TOKEN_PROGRAM_ID,
+          keys: [
+            { pubkey: admin.publicKey, isSigner: true, isWritable: true },
+            { pubkey: adminAta, isSigner: false, isWritable: true },
+            { pubkey: admin.publicKey, isSigner: false, isWritable: false },
+            { pubkey: mint.publicKey, isSigner: false, isWritable: false },
+            { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+            { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+          ],
+          data: Buffer.alloc(0),
+        }),
+        new anchor.web3.TransactionInstruction({
+          programId: TOKEN_PROGRAM_ID,
+          keys: [
+            { pubkey: mint.publicKey, isSigner: false, isWritable: true },
+            { pubkey: adminAta, isSigner: false, isWritable: true },
+            { pubkey: admin.publicKey, isSigner: true, isWritable: false },
+          ],
+          data: mintTo,
+        })
+      ),
+      [mint]
+    );
+
+    const [approvedMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("approved-mint"), mint.publicKey.toBuffer()],
+      program.programId
+    );
+    const approveAccounts = {
+      config: configPda,
+      mint: mint.publicKey,
+      approvedMint: approvedMintPda,
+      feeVault: feeVaultPda,
+      feeVaultTokenAccount: ata(feeVaultPda, mint.publicKey),
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    };
+
+    const nonAdmin = testKeys.nonAdmin;
+    try {
+      await program.methods
+        .approveMint(new anchor.BN(100))
+        .accounts({ admin: nonAdmin.publicKey, ...approveAccounts })
+        .signers([nonAdmin])
+        .rpc();
+      expect.fail("Should have failed - non-admin approving a mint");
+    } catch (e: any) {
+      expect(e.toString()).to.contain("Unauthorized");
+    }
+
+    await program.methods
+      .approveMint(new anchor.BN(100))
+      .accounts({ admin: admin.publicKey, ...approveAccounts })
+      .rpc();
+
+    const approved = await program.account.approvedMint.fetch(approvedMintPda);
+    expect(approved.tokenProgram.toBase58()).to.equal(TOKEN_PROGRAM_ID.toBase58());
+    expect(approved.feePerWord.toNumber()).to.equal(100);
+    expect(approved.enabled).to.equal(true);
+
+    const newSubId = await getNextSubscriptionId();
+    const newSubPda = getSubscriptionPda(newSubId);
+    const id = new anchor.BN(newSubId);
+    const vault = ata(newSubPda, mint.publicKey);
+
+    await program.methods
+      .createTokenSubscription()
+      .accounts({
+        owner: admin.publicKey,
+        config: configPda,
+        approvedMint: approvedMintPda,
+        mint: mint.publicKey,
+        subscription: newSubPda,
+        subscriptionVault: vault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const tokenAccounts = {
+      subscription: newSubPda,
+      mint: mint.publicKey,
+      subscriptionVault: vault,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+    await program.methods
+      .fundSubscriptionToken(id, new anchor.BN(500_000))
+      .accounts({ funder: admin.publicKey, funderTokenAccount: adminAta, ...tokenAccounts })
+      .rpc();
 
-    // Registration account should be closed
-    const regAccount = await provider.connection.getAccountInfo(consumerPda);
-    expect(regAccount).to.be.null;
+    let sub = await program.account.subscription.fetch(newSubPda);
+    expect(sub.billingMint.toBase58()).to.equal(mint.publicKey.toBase58());
+    expect(sub.balance.toNumber()).to.equal(500_000);
+    expect(await tokenAmount(vault)).to.equal(500_000);
+
+    // SOL instructions are rejected on a token-billed subscription
+    try {
+      await program.methods
+        .fundSubscription(id, new anchor.BN(1_000_000))
+        .accounts({
+          funder: admin.publicKey,
+          subscription: newSubPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      expect.fail("Should have failed - SOL funding of a token subscription");
+    } catch (e: any) {
+      expect(e.toString()).to.contain("BillingCurrencyMismatch");
+    }
+
+    await program.methods
+      .withdrawSubscriptionTokens(id, new anchor.BN(200_000))
+      .accounts({ owner: admin.publicKey, recipient: adminAta, ...tokenAccounts })
+      .rpc();
+
+    sub = await program.account.subscription.fetch(newSubPda);
+    expect(sub.balance.toNumber()).to.equal(300_000);
+    expect(await tokenAmount(vault)).to.equal(300_000);
+
+    await program.methods
+      .cancelTokenSubscription(id)
+      .accounts({ owner: admin.publicKey, recipient: adminAta, ...tokenAccounts })
+      .rpc();
+
+    expect(await provider.connection.getAccountInfo(newSubPda)).to.be.null;
+    expect(await provider.connection.getAccountInfo(vault)).to.be.null;
+    expect(await tokenAmount(adminAta)).to.equal(1_000_000);
+  });
+
+  it("Rejects Token-2022 mints with extensions that can seize or lock fees", async () => {
+    const TOKEN_2022_PROGRAM_ID = new PublicKey("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+    const ASSOCIATED_TOKEN_PROGRAM_ID = new PublicKey(
+      "ATokenGPvbd9kwyVRCVwF2pbmGt5TRAH2xXUcNwcaWWs"
+    );
+
+    // Extensions are initialized before InitializeMint2, in a mint account
+    // sized for them: 166 bytes (base mint, padding, account type) plus a
+    // 4-byte TLV header and the extension's data
+    const permanentDelegate = Buffer.alloc(33);
+    permanentDelegate[0] = 35; // InitializePermanentDelegate
+    admin.publicKey.toBuffer().copy(permanentDelegate, 1);
+    const extensions = [
+      { name: "PermanentDelegate", space: 166 + 4 + 32, data: permanentDelegate },
+      { name: "NonTransferable", space: 166 + 4, data: Buffer.from([32]) }, // InitializeNonTransferableMint
+    ];
+
+    for (const extension of extensions) {
+      const mint = Keypair.generate();
+      const initializeMint = Buffer.alloc(35);
+      initializeMint[0] = 20; // InitializeMint2
+      initializeMint[1] = 6;
+      admin.publicKey.toBuffer().copy(initializeMint, 2);
+      const mintKeys = [{ pubkey: mint.publicKey, isSigner: false, isWritable: true }];
+      await provider.sendAndConfirm(
+        new Transaction().add(
+          SystemProgram.createAccount({
+            fromPubkey: admin.publicKey,
+            newAccountPubkey: mint.publicKey,
+            lamports: await provider.connection.getMinimumBalanceForRentExemption(extension.space),
+            space: extension.space,
+            programId: TOKEN_2022_PROGRAM_ID,
+          }),
+          new anchor.web3.TransactionInstruction({
+            programId: TOKEN_2022_PROGRAM_ID,
+            keys: mintKeys,
+            data: extension.data,
+          }),
+          new anchor.web3.TransactionInstruction({
+            programId: TOKEN_2022_PROGRAM_ID,
+            keys: mintKeys,
+            data: initializeMint,
+          })
+        ),
+        [mint]
+      );
+
+      const [approvedMintPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("approved-mint"), mint.publicKey.toBuffer()],
+        program.programId
+      );
+      const [feeVaultTokenAccount] = PublicKey.findProgramAddressSync(
+        [feeVaultPda.toBuffer(), TOKEN_2022_PROGRAM_ID.toBuffer(), mint.publicKey.toBuffer()],
+        ASSOCIATED_TOKEN_PROGRAM_ID
+      );
+      try {
+        await program.methods
+          .approveMint(new anchor.BN(100))
+          .accounts({
+            admin: admin.publicKey,
+            config: configPda,
+            mint: mint.publicKey,
+            approvedMint: approvedMintPda,
+            feeVault: feeVaultPda,
+            feeVaultTokenAccount,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .rpc();
+        expect.fail(`Should have failed - ${extension.name} mint`);
+      } catch (e: any) {
+        const errStr = e?.logs?.join(" ") ?? e.toString();
+        expect(errStr).to.contain("UnsupportedMint");
+      }
+    }
   });
 
   // === UPDATE CONFIG ===
@@ -637,13 +2444,7 @@ describe("vrf-sol coordinator", () => {
     const newAuthority = testKeys.newAuthority;
     const newFee = new anchor.BN(20_000);
 
-    await program.methods
-      .updateConfig(newAuthority.publicKey, newFee, 20, null)
-      .accounts({
-        admin: admin.publicKey,
-        config: configPda,
-      })
-      .rpc();
+    await applyConfigUpdate(program, { newAuthority: newAuthority.publicKey, newFeePerWord: newFee, newMaxNumWords: 20 });
 
     const config = await program.account.coordinatorConfig.fetch(configPda);
     expect(config.authority.toBase58()).to.equal(newAuthority.publicKey.toBase58());
@@ -651,20 +2452,14 @@ describe("vrf-sol coordinator", () => {
     expect(config.maxNumWords).to.equal(20);
 
     // Revert for further tests
-    await program.methods
-      .updateConfig(authority.publicKey, feePerWord, maxNumWords, null)
-      .accounts({
-        admin: admin.publicKey,
-        config: configPda,
-      })
-      .rpc();
+    await applyConfigUpdate(program, { newAuthority: authority.publicKey, newFeePerWord: feePerWord, newMaxNumWords: maxNumWords });
   });
 
   it("Fails to update config with non-admin", async () => {
     const nonAdmin = testKeys.nonAdmin;
     try {
       await program.methods
-        .updateConfig(null, new anchor.BN(999), null, null)
+        .updateConfig(configChanges({ newFeePerWord: new anchor.BN(999) }))
         .accounts({
           admin: nonAdmin.publicKey,
           config: configPda,
@@ -678,32 +2473,329 @@ describe("vrf-sol coordinator", () => {
   });
 
   it("Fails to update config with zero-address authority", async () => {
+    try {
+      await applyConfigUpdate(program, { newAuthority: PublicKey.default });
+      expect.fail("Should have failed - zero address authority");
+    } catch (e: any) {
+      expect(e.toString()).to.contain("ZeroAddressNotAllowed");
+    }
+  });
+
+  it("Fails to update config with zero-address admin", async () => {
+    try {
+      await applyConfigUpdate(program, { newAdmin: PublicKey.default });
+      expect.fail("Should have failed - zero address admin");
+    } catch (e: any) {
+      expect(e.toString()).to.contain("ZeroAddressNotAllowed");
+    }
+  });
+
+  it("Fails to update config with an unsupported proof scheme", async () => {
+    try {
+      await applyConfigUpdate(program, { newProofScheme: 7 });
+      expect.fail("Should have failed - unsupported proof scheme");
+    } catch (e: any) {
+      expect(e.toString()).to.contain("InvalidProofScheme");
+    }
+  });
+
+  it("Switches the proof scheme to ECVRF and back", async () => {
+    await applyConfigUpdate(program, { newProofScheme: 1 });
+
+    let config = await program.account.coordinatorConfig.fetch(configPda);
+    expect(config.proofScheme).to.equal(1);
+
+    await applyConfigUpdate(program, { newProofScheme: 0 });
+
+    config = await program.account.coordinatorConfig.fetch(configPda);
+    expect(config.proofScheme).to.equal(0);
+  });
+  it("Fails to enable multi-oracle before an oracle set is registered", async () => {
+    try {
+      await applyConfigUpdate(program, { newProofScheme: 3 });
+      expect.fail("Should have failed - no oracle set");
+    } catch (e: any) {
+      expect(e.toString()).to.contain("InvalidOracleSet");
+    }
+  });
+
+  it("Fails to register an oracle set with a threshold above its size", async () => {
     try {
       await program.methods
-        .updateConfig(PublicKey.default, null, null, null)
+        .updateConfig(configChanges({ newOracleSet: { oracles: [Keypair.generate().publicKey], threshold: 2 } }))
         .accounts({
           admin: admin.publicKey,
           config: configPda,
         })
         .rpc();
-      expect.fail("Should have failed - zero address authority");
+      expect.fail("Should have failed - threshold too high");
     } catch (e: any) {
-      expect(e.toString()).to.contain("ZeroAddressNotAllowed");
+      expect(e.toString()).to.contain("InvalidOracleSet");
     }
   });
 
-  it("Fails to update config with zero-address admin", async () => {
+  it("Registers an oracle set", async () => {
+    const oracles = [
+      Keypair.generate().publicKey,
+      Keypair.generate().publicKey,
+      Keypair.generate().publicKey,
+    ];
+    await applyConfigUpdate(program, { newOracleSet: { oracles, threshold: 2 } });
+
+    const config = await program.account.coordinatorConfig.fetch(configPda);
+    expect(config.oracleCount).to.equal(3);
+    expect(config.oracleThreshold).to.equal(2);
+    expect(config.oracles[1].toBase58()).to.equal(oracles[1].toBase58());
+  });
+
+  it("Fails to set a zero request timeout", async () => {
+    try {
+      await applyConfigUpdate(program, { newRequestTimeoutSlots: new anchor.BN(0) });
+      expect.fail("Should have failed - zero timeout");
+    } catch (e: any) {
+      expect(e.toString()).to.contain("InvalidRequestTimeout");
+    }
+  });
+
+  it("Fails to set a protocol fee above 100%", async () => {
+    try {
+      await applyConfigUpdate(program, { newProtocolFeeBps: 10_001 });
+      expect.fail("Should have failed - protocol fee above 10000 bps");
+    } catch (e: any) {
+      expect(e.toString()).to.contain("InvalidProtocolFee");
+    }
+  });
+
+  it("Timelocks config updates and hands over the admin in two steps", async () => {
+    const [pendingPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pending-config-update")],
+      program.programId
+    );
+    // The delay cannot drop below the coordinator's minimum
+    try {
+      await applyConfigUpdate(program, { newConfigUpdateDelaySlots: new anchor.BN(0) });
+      expect.fail("Should have failed - delay below the minimum");
+    } catch (e: any) {
+      expect(e.toString()).to.contain("InvalidConfigUpdateDelay");
+    }
+
+    const delay = 20;
+    await applyConfigUpdate(program, { newConfigUpdateDelaySlots: new anchor.BN(delay) });
+    expect((await program.account.coordinatorConfig.fetch(configPda)).configUpdateDelaySlots.toNumber()).to.equal(delay);
+
+    // A staged change cannot be executed before its delay has passed, and
+    // that includes replacing the oracle set
+    const oraclesBefore = (await program.account.coordinatorConfig.fetch(configPda)).oracles;
+    const rogueOracle = Keypair.generate().publicKey;
+    await program.methods
+      .updateConfig(
+        configChanges({
+          newFeePerWord: new anchor.BN(999),
+          newOracleSet: { oracles: [rogueOracle], threshold: 1 },
+        })
+      )
+      .accounts({ admin: admin.publicKey, config: configPda })
+      .rpc();
+    const pending = await program.account.pendingConfigUpdate.fetch(pendingPda);
+    expect(pending.changes.newFeePerWord.toNumber()).to.equal(999);
+    expect(pending.changes.newOracleSet.oracles[0].toBase58()).to.equal(rogueOracle.toBase58());
+    expect(pending.executableSlot.toNumber() - pending.proposedSlot.toNumber()).to.equal(delay);
+    try {
+      await program.methods
+        .executeConfigUpdate()
+        .accounts({ admin: admin.publicKey, config: configPda })
+        .rpc();
+      expect.fail("Should have failed - still timelocked");
+    } catch (e: any) {
+      expect(e.toString()).to.contain("ConfigUpdateNotReady");
+    }
+
+    // Only one update can be pending; the admin can cancel it
+    try {
+      await program.methods
+        .updateConfig(configChanges({ newFeePerWord: new anchor.BN(1) }))
+        .accounts({ admin: admin.publicKey, config: configPda })
+        .rpc();
+      expect.fail("Should have failed - an update is already pending");
+    } catch (e: any) {
+      const errStr = e?.logs?.join(" ") ?? e.toString();
+      expect(errStr).to.contain("already in use");
+    }
+    await program.methods
+      .cancelConfigUpdate()
+      .accounts({ admin: admin.publicKey, config: configPda })
+      .rpc();
+    expect(await provider.connection.getAccountInfo(pendingPda)).to.be.null;
+    const afterCancel = await program.account.coordinatorConfig.fetch(configPda);
+    expect(afterCancel.feePerWord.toString()).to.equal(feePerWord.toString());
+    expect(afterCancel.oracles.map((o) => o.toBase58())).to.deep.equal(oraclesBefore.map((o) => o.toBase58()));
+
+    // A new admin is only proposed by the update and must accept
+    const newAdmin = Keypair.generate();
+    await fundAccount(newAdmin.publicKey, LAMPORTS_PER_SOL / 10);
+    await program.methods
+      .updateConfig(configChanges({ newAdmin: newAdmin.publicKey, newConfigUpdateDelaySlots: new anchor.BN(CONFIG_UPDATE_DELAY_SLOTS) }))
+      .accounts({ admin: admin.publicKey, config: configPda })
+      .rpc();
+    await waitForSlot((await program.account.pendingConfigUpdate.fetch(pendingPda)).executableSlot.toNumber());
+    await program.methods
+      .executeConfigUpdate()
+      .accounts({ admin: admin.publicKey, config: configPda })
+      .rpc();
+    let config = await program.account.coordinatorConfig.fetch(configPda);
+    expect(config.admin.toBase58()).to.equal(admin.publicKey.toBase58());
+    expect(config.pendingAdmin.toBase58()).to.equal(newAdmin.publicKey.toBase58());
+    expect(config.configUpdateDelaySlots.toNumber()).to.equal(CONFIG_UPDATE_DELAY_SLOTS);
+
+    try {
+      await program.methods
+        .acceptAdminTransfer()
+        .accounts({ newAdmin: testKeys.nonAdmin.publicKey, config: configPda })
+        .signers([testKeys.nonAdmin])
+        .rpc();
+      expect.fail("Should have failed - not the pending admin");
+    } catch (e: any) {
+      expect(e.toString()).to.contain("NotPendingAdmin");
+    }
+    await program.methods
+      .acceptAdminTransfer()
+      .accounts({ newAdmin: newAdmin.publicKey, config: configPda })
+      .signers([newAdmin])
+      .rpc();
+    config = await program.account.coordinatorConfig.fetch(configPda);
+    expect(config.admin.toBase58()).to.equal(newAdmin.publicKey.toBase58());
+    expect(config.pendingAdmin.toBase58()).to.equal(PublicKey.default.toBase58());
+
+    // Hand the admin role back for the tests below
+    await applyConfigUpdate(program, { newAdmin: admin.publicKey }, newAdmin);
+    await program.methods
+      .acceptAdminTransfer()
+      .accounts({ newAdmin: admin.publicKey, config: configPda })
+      .rpc();
+    config = await program.account.coordinatorConfig.fetch(configPda);
+    expect(config.admin.toBase58()).to.equal(admin.publicKey.toBase58());
+  });
+
+  // === PAUSE ===
+
+  it("Pauses requests and subscriptions while withdrawals keep working", async () => {
+    const pauser = Keypair.generate();
+    await applyConfigUpdate(program, { newPauser: pauser.publicKey });
+
+    try {
+      await program.methods
+        .pause()
+        .accounts({ signer: testKeys.nonAdmin.publicKey, config: configPda })
+        .signers([testKeys.nonAdmin])
+        .rpc();
+      expect.fail("Should have failed - not the pauser");
+    } catch (e: any) {
+      expect(e.toString()).to.contain("Unauthorized");
+    }
+
+    await program.methods
+      .pause()
+      .accounts({ signer: pauser.publicKey, config: configPda })
+      .signers([pauser])
+      .rpc();
+    let config = await program.account.coordinatorConfig.fetch(configPda);
+    expect(config.paused).to.equal(true);
+
+    const nextSubId = await getNextSubscriptionId();
     try {
       await program.methods
-        .updateConfig(null, null, null, PublicKey.default)
+        .createSubscription()
         .accounts({
-          admin: admin.publicKey,
+          owner: admin.publicKey,
           config: configPda,
+          subscription: getSubscriptionPda(nextSubId),
+          systemProgram: SystemProgram.programId,
         })
         .rpc();
-      expect.fail("Should have failed - zero address admin");
+      expect.fail("Should have failed - coordinator paused");
     } catch (e: any) {
-      expect(e.toString()).to.contain("ZeroAddressNotAllowed");
+      expect(e.toString()).to.contain("CoordinatorPaused");
+    }
+
+    // Funds can still move in and out of an existing subscription
+    const amount = new anchor.BN(100_000);
+    const before = (await program.account.subscription.fetch(subscriptionPda)).balance;
+    await program.methods
+      .fundSubscription(new anchor.BN(subscriptionId), amount)
+      .accounts({
+        funder: admin.publicKey,
+        subscription: subscriptionPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods
+      .withdrawSubscriptionFunds(new anchor.BN(subscriptionId), amount)
+      .accounts({
+        owner: admin.publicKey,
+        subscription: subscriptionPda,
+        recipient: admin.publicKey,
+      })
+      .rpc();
+    const after = (await program.account.subscription.fetch(subscriptionPda)).balance;
+    expect(after.toString()).to.equal(before.toString());
+
+    // Only the admin may unpause
+    try {
+      await program.methods
+        .unpause()
+        .accounts({ admin: pauser.publicKey, config: configPda })
+        .signers([pauser])
+        .rpc();
+      expect.fail("Should have failed - pauser cannot unpause");
+    } catch (e: any) {
+      expect(e.toString()).to.contain("Unauthorized");
+    }
+
+    await program.methods
+      .unpause()
+      .accounts({ admin: admin.publicKey, config: configPda })
+      .rpc();
+    config = await program.account.coordinatorConfig.fetch(configPda);
+    expect(config.paused).to.equal(false);
+    expect(config.pauser.toBase58()).to.equal(pauser.publicKey.toBase58());
+  });
+
+  // === MIGRATION ===
+
+  it("Leaves an up-to-date config unchanged when migrating", async () => {
+    const before = await provider.connection.getAccountInfo(configPda);
+
+    await program.methods
+      .migrateConfig()
+      .accounts({
+        admin: admin.publicKey,
+        config: configPda,
+        feeVault: feeVaultPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const after = await provider.connection.getAccountInfo(configPda);
+    expect(after!.data.length).to.equal(before!.data.length);
+    expect(after!.data.equals(before!.data)).to.be.true;
+  });
+
+  it("Fails to migrate config with non-admin", async () => {
+    const nonAdmin = testKeys.nonAdmin;
+    try {
+      await program.methods
+        .migrateConfig()
+        .accounts({
+          admin: nonAdmin.publicKey,
+          config: configPda,
+          feeVault: feeVaultPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([nonAdmin])
+        .rpc();
+      expect.fail("Should have failed - non-admin");
+    } catch (e: any) {
+      expect(e.toString()).to.contain("Unauthorized");
     }
   });
 });
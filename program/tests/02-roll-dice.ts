@@ -13,6 +13,7 @@ import {
 } from "@solana/web3.js";
 import fs from "fs";
 import * as testKeys from "./keys/load";
+import { applyConfigUpdate, CONFIG_UPDATE_DELAY_SLOTS } from "./utils/config";
 
 describe("roll-dice (consumer callback)", () => {
   const provider = anchor.AnchorProvider.env();
@@ -37,6 +38,11 @@ describe("roll-dice (consumer callback)", () => {
     vrfProgram.programId
   );
 
+  const [feeVaultPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("fee-vault")],
+    vrfProgram.programId
+  );
+
   const [gameConfigPda] = PublicKey.findProgramAddressSync(
     [Buffer.from("game-config")],
     diceProgram.programId
@@ -92,9 +98,10 @@ describe("roll-dice (consumer callback)", () => {
     return pda;
   }
 
+  // Request IDs are `subscription_id << 32 | req_count`.
   async function getNextRequestId(): Promise<number> {
-    const config = await vrfProgram.account.coordinatorConfig.fetch(configPda);
-    return config.requestCounter.toNumber();
+    const sub = await vrfProgram.account.subscription.fetch(subscriptionPda);
+    return subscriptionId * 2 ** 32 + sub.reqCount.toNumber();
   }
 
   async function fundAccount(
@@ -181,7 +188,7 @@ describe("roll-dice (consumer callback)", () => {
 
     try {
       await vrfProgram.methods
-        .fulfillRandomWords(reqId, [...randomness] as any)
+        .fulfillRandomWords(reqId, [...randomness] as any, null)
         .accounts({
           authority: authority.publicKey,
           config: configPda,
@@ -189,6 +196,15 @@ describe("roll-dice (consumer callback)", () => {
           requester: player.publicKey,
           consumerProgram: diceProgram.programId,
           instructionsSysvar: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          callbackAccounts: null,
+          subscription: subscriptionPda,
+          feeVault: feeVaultPda,
+          approvedMint: null,
+          feeMint: null,
+          subscriptionVault: null,
+          feeVaultTokenAccount: null,
+          tokenProgram: null,
+          randomnessResult: null,
         })
         .remainingAccounts([
           { pubkey: gameConfigPda, isWritable: false, isSigner: false },
@@ -214,23 +230,18 @@ describe("roll-dice (consumer callback)", () => {
     const existingConfig = await provider.connection.getAccountInfo(configPda);
     if (!existingConfig) {
       await vrfProgram.methods
-        .initialize(feePerWord, maxNumWords)
+        .initialize(feePerWord, maxNumWords, new anchor.BN(CONFIG_UPDATE_DELAY_SLOTS))
         .accounts({
           admin: player.publicKey,
           authority: authority.publicKey,
           config: configPda,
+          feeVault: feeVaultPda,
           systemProgram: SystemProgram.programId,
         })
         .rpc();
     } else {
       // Update authority for this run
-      await vrfProgram.methods
-        .updateConfig(authority.publicKey, feePerWord, maxNumWords, null)
-        .accounts({
-          admin: player.publicKey,
-          config: configPda,
-        })
-        .rpc();
+      await applyConfigUpdate(vrfProgram, { newAuthority: authority.publicKey, newFeePerWord: feePerWord, newMaxNumWords: maxNumWords });
     }
 
     // Create subscription
@@ -380,6 +391,56 @@ describe("roll-dice (consumer callback)", () => {
 
   // === ERROR CASES ===
 
+  it("Rejects a dice roll account that does not match the returned request ID", async () => {
+    // The dice roll is seeded by the request ID the coordinator returns,
+    // so an address derived from any other ID fails after the CPI.
+    const requestId = await getNextRequestId();
+    const consumerPda = getConsumerPda(subscriptionId, diceProgram.programId);
+
+    try {
+      await diceProgram.methods
+        .requestRoll([...Buffer.alloc(32, 0x99)] as any)
+        .accounts({
+          player: player.publicKey,
+          gameConfig: gameConfigPda,
+          vrfConfig: configPda,
+          subscription: subscriptionPda,
+          consumerRegistration: consumerPda,
+          vrfRequest: getRequestPda(requestId),
+          thisProgram: diceProgram.programId,
+          diceRoll: getDiceRollPda(player.publicKey, requestId + 1),
+          vrfProgram: vrfProgram.programId,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      expect.fail("Should have failed - dice roll PDA for another request");
+    } catch (e: any) {
+      const errStr = e?.logs?.join(" ") ?? e.toString();
+      expect(errStr).to.contain("InvalidDiceRoll");
+    }
+  });
+
+  it("Creates the dice roll even if its address was funded beforehand", async () => {
+    // Dice roll addresses are predictable, so anyone can send them lamports
+    // before the request; creation must still succeed.
+    const requestId = await getNextRequestId();
+    const diceRollPda = getDiceRollPda(player.publicKey, requestId);
+    const prefunded = LAMPORTS_PER_SOL / 100;
+    await fundAccount(diceRollPda, prefunded);
+
+    const seed = Buffer.alloc(32, 0x9a);
+    const { diceRollPda: settledPda } = await requestAndFulfillDiceRoll(seed);
+    expect(settledPda.toBase58()).to.equal(diceRollPda.toBase58());
+
+    const diceRoll = await diceProgram.account.diceRoll.fetch(diceRollPda);
+    expect(diceRoll.vrfRequestId.toNumber()).to.equal(requestId);
+    expect(diceRoll.result).to.be.gte(1).and.lte(6);
+
+    const info = await provider.connection.getAccountInfo(diceRollPda);
+    expect(info!.owner.toBase58()).to.equal(diceProgram.programId.toBase58());
+    expect(info!.lamports).to.be.at.least(prefunded);
+  });
+
   it("Fails to call fulfill_random_words from wrong coordinator", async () => {
     // The dice program verifies the coordinator_config signer matches
     // its stored coordinator_program. A random signer won't match.
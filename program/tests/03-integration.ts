@@ -10,6 +10,7 @@ import {
   LAMPORTS_PER_SOL,
   Transaction,
 } from "@solana/web3.js";
+import { applyConfigUpdate, CONFIG_UPDATE_DELAY_SLOTS } from "./utils/config";
 
 /**
  * Integration tests that verify end-to-end flow with a live backend.
@@ -43,6 +44,11 @@ describe("integration (live backend)", () => {
     vrfProgram.programId
   );
 
+  const [feeVaultPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("fee-vault")],
+    vrfProgram.programId
+  );
+
   const [gameConfigPda] = PublicKey.findProgramAddressSync(
     [Buffer.from("game-config")],
     diceProgram.programId
@@ -98,9 +104,10 @@ describe("integration (live backend)", () => {
     return pda;
   }
 
+  // Request IDs are `subscription_id << 32 | req_count`.
   async function getNextRequestId(): Promise<number> {
-    const config = await vrfProgram.account.coordinatorConfig.fetch(configPda);
-    return config.requestCounter.toNumber();
+    const sub = await vrfProgram.account.subscription.fetch(subscriptionPda);
+    return subscriptionId * 2 ** 32 + sub.reqCount.toNumber();
   }
 
   /**
@@ -135,23 +142,18 @@ describe("integration (live backend)", () => {
 
     if (!existingConfig) {
       await vrfProgram.methods
-        .initialize(feePerWord, maxNumWords)
+        .initialize(feePerWord, maxNumWords, new anchor.BN(CONFIG_UPDATE_DELAY_SLOTS))
         .accounts({
           admin: admin.publicKey,
           authority: AUTHORITY_PUBKEY,
           config: configPda,
+          feeVault: feeVaultPda,
           systemProgram: SystemProgram.programId,
         })
         .rpc();
     } else {
       // Update config to set live backend authority
-      await vrfProgram.methods
-        .updateConfig(AUTHORITY_PUBKEY, feePerWord, maxNumWords, null)
-        .accounts({
-          admin: admin.publicKey,
-          config: configPda,
-        })
-        .rpc();
+      await applyConfigUpdate(vrfProgram, { newAuthority: AUTHORITY_PUBKEY, newFeePerWord: feePerWord, newMaxNumWords: maxNumWords });
     }
 
     // Create subscription
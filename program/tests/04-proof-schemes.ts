@@ -0,0 +1,516 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { VrfSol } from "../target/types/vrf_sol";
+import { MockConsumer } from "../target/types/mock_consumer";
+import { expect } from "chai";
+import {
+  ComputeBudgetProgram,
+  Ed25519Program,
+  Keypair,
+  PublicKey,
+  SystemProgram,
+  LAMPORTS_PER_SOL,
+  Transaction,
+} from "@solana/web3.js";
+import { createHash } from "crypto";
+import fs from "fs";
+import nacl from "tweetnacl";
+import { applyConfigUpdate } from "./utils/config";
+import { ecvrfProve, vrfInput } from "./utils/ecvrf";
+
+/**
+ * Fulfillment under the verifiable proof schemes. Each test switches the
+ * coordinator to its scheme and fulfills store-only requests itself, so the
+ * output can be checked against one computed off-chain. A backend running
+ * another scheme cannot race these fulfillments.
+ */
+describe("proof schemes", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.vrfSol as Program<VrfSol>;
+  const mockConsumer = anchor.workspace.mockConsumer as Program<MockConsumer>;
+  const admin = provider.wallet as anchor.Wallet;
+
+  const authoritySecret = JSON.parse(
+    fs.readFileSync("../backend/vrf-signer.json", "utf-8")
+  );
+  const authority = Keypair.fromSecretKey(Uint8Array.from(authoritySecret));
+
+  const [configPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("coordinator-config")],
+    program.programId
+  );
+
+  const [feeVaultPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("fee-vault")],
+    program.programId
+  );
+
+  let subscriptionId: number;
+  let subscriptionPda: PublicKey;
+
+  // Restored once the tests are done.
+  let originalAuthority: PublicKey;
+  let originalProofScheme: number;
+
+  function getSubscriptionPda(subId: number | anchor.BN): PublicKey {
+    const id = new anchor.BN(subId);
+    const [pda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("subscription"), id.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    return pda;
+  }
+
+  function getConsumerPda(subId: number | anchor.BN, consumerProgram: PublicKey): PublicKey {
+    const id = new anchor.BN(subId);
+    const [pda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("consumer"),
+        id.toArrayLike(Buffer, "le", 8),
+        consumerProgram.toBuffer(),
+      ],
+      program.programId
+    );
+    return pda;
+  }
+
+  function getRequestPda(requestId: anchor.BN): PublicKey {
+    const [pda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vrf-request"), requestId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    return pda;
+  }
+
+  async function fundAccount(destination: PublicKey, lamports: number): Promise<void> {
+    const tx = new Transaction().add(
+      SystemProgram.transfer({
+        fromPubkey: admin.publicKey,
+        toPubkey: destination,
+        lamports,
+      })
+    );
+    await provider.sendAndConfirm(tx);
+  }
+
+  /**
+   * Make a store-only mock consumer request and wait until it may be
+   * fulfilled. Returns its ID, PDA, stored account and the VRF input the
+   * oracles prove.
+   */
+  async function makeStoredRequest(seedByte: number) {
+    const sub = await program.account.subscription.fetch(subscriptionPda);
+    const requestId = new anchor.BN(subscriptionId).shln(32).add(sub.reqCount);
+    const requestPda = getRequestPda(requestId);
+
+    await mockConsumer.methods
+      .request(1, [...Buffer.alloc(32, seedByte)] as any, 0, true, new anchor.BN(0), { userData: null, prefix: null })
+      .accounts({
+        requester: admin.publicKey,
+        vrfConfig: configPda,
+        subscription: subscriptionPda,
+        consumerRegistration: getConsumerPda(subscriptionId, mockConsumer.programId),
+        thisProgram: mockConsumer.programId,
+        vrfRequest: requestPda,
+        callbackAccounts: null,
+        callbackLookupTable: null,
+        approvedMint: null,
+        vrfProgram: program.programId,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const request = await program.account.randomnessRequest.fetch(requestPda);
+    const confirmedSlot = request.requestSlot.add(request.minConfirmationSlots).toNumber();
+    while ((await provider.connection.getSlot()) < confirmedSlot) {
+      await new Promise((r) => setTimeout(r, 400));
+    }
+
+    const alpha = vrfInput(Buffer.from(request.seed), request.requestSlot, requestId);
+    return { requestId, requestPda, request, alpha };
+  }
+
+  function fulfillAccounts(submitter: PublicKey, requestPda: PublicKey) {
+    return {
+      authority: submitter,
+      config: configPda,
+      request: requestPda,
+      requester: admin.publicKey,
+      consumerProgram: mockConsumer.programId,
+      instructionsSysvar: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+      callbackAccounts: null,
+      subscription: subscriptionPda,
+      feeVault: feeVaultPda,
+      approvedMint: null,
+      feeMint: null,
+      subscriptionVault: null,
+      feeVaultTokenAccount: null,
+      tokenProgram: null,
+      randomnessResult: null,
+    };
+  }
+
+  before(async () => {
+    await fundAccount(authority.publicKey, LAMPORTS_PER_SOL);
+
+    const config = await program.account.coordinatorConfig.fetch(configPda);
+    originalAuthority = config.authority;
+    originalProofScheme = config.proofScheme;
+    await applyConfigUpdate(program, { newAuthority: authority.publicKey });
+
+    subscriptionId = config.subscriptionCounter.toNumber();
+    subscriptionPda = getSubscriptionPda(subscriptionId);
+    await program.methods
+      .createSubscription()
+      .accounts({
+        owner: admin.publicKey,
+        config: configPda,
+        subscription: subscriptionPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods
+      .fundSubscription(new anchor.BN(subscriptionId), new anchor.BN(LAMPORTS_PER_SOL))
+      .accounts({
+        funder: admin.publicKey,
+        subscription: subscriptionPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods
+      .addConsumer(new anchor.BN(subscriptionId))
+      .accounts({
+        owner: admin.publicKey,
+        subscription: subscriptionPda,
+        consumerProgram: mockConsumer.programId,
+        consumerRegistration: getConsumerPda(subscriptionId, mockConsumer.programId),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  after(async () => {
+    await applyConfigUpdate(program, { newAuthority: originalAuthority, newProofScheme: originalProofScheme });
+  });
+
+  // === ECVRF ===
+
+  function fulfillEcvrf(requestId: anchor.BN, requestPda: PublicKey, proof: Buffer) {
+    return program.methods
+      .fulfillRandomWordsEcvrf(requestId, [...proof] as any, null)
+      .accounts(fulfillAccounts(authority.publicKey, requestPda))
+      .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+      .signers([authority])
+      .rpc();
+  }
+
+  it("Fulfills an ECVRF request with the output derived from the proof", async () => {
+    await applyConfigUpdate(program, { newProofScheme: 1 });
+
+    const { requestId, requestPda, alpha } = await makeStoredRequest(0x51);
+    const { proof, output } = ecvrfProve(authority.secretKey, alpha);
+    await fulfillEcvrf(requestId, requestPda, proof);
+
+    const stored = await program.account.randomnessRequest.fetch(requestPda);
+    expect(stored.status).to.equal(3); // Stored
+    expect(Buffer.from(stored.randomness).equals(output)).to.be.true;
+  });
+
+  it("Rejects a tampered ECVRF proof", async () => {
+    const { requestId, requestPda, alpha } = await makeStoredRequest(0x52);
+    const { proof } = ecvrfProve(authority.secretKey, alpha);
+
+    // Gamma of a valid proof over another input: still a curve point, but
+    // not the one the challenge commits to
+    const otherAlpha = Buffer.from(alpha);
+    otherAlpha[0] ^= 1;
+    const swappedGamma = Buffer.concat([
+      ecvrfProve(authority.secretKey, otherAlpha).proof.subarray(0, 32),
+      proof.subarray(32),
+    ]);
+    const flippedS = Buffer.from(proof);
+    flippedS[48] ^= 1;
+
+    for (const tampered of [swappedGamma, flippedS]) {
+      try {
+        await fulfillEcvrf(requestId, requestPda, tampered);
+        expect.fail("Should have failed - tampered proof");
+      } catch (e: any) {
+        const errStr = e?.logs?.join(" ") ?? e.toString();
+        expect(errStr).to.contain("InvalidVrfProof");
+      }
+    }
+  });
+
+  it("Rejects an ECVRF proof by a key other than the authority", async () => {
+    const { requestId, requestPda, alpha } = await makeStoredRequest(0x53);
+    const { proof } = ecvrfProve(Keypair.generate().secretKey, alpha);
+    try {
+      await fulfillEcvrf(requestId, requestPda, proof);
+      expect.fail("Should have failed - proof by another key");
+    } catch (e: any) {
+      const errStr = e?.logs?.join(" ") ?? e.toString();
+      expect(errStr).to.contain("InvalidVrfProof");
+    }
+  });
+
+  // === SIGNATURE HASH ===
+
+  // program_id || request_id (8 LE) || seed || request_slot (8 LE)
+  function signatureMessage(requestId: anchor.BN, seed: number[], requestSlot: anchor.BN): Buffer {
+    return Buffer.concat([
+      program.programId.toBuffer(),
+      requestId.toArrayLike(Buffer, "le", 8),
+      Buffer.from(seed),
+      requestSlot.toArrayLike(Buffer, "le", 8),
+    ]);
+  }
+
+  function fulfillSignatureHash(requestId: anchor.BN, requestPda: PublicKey, message: Buffer, randomness: Buffer) {
+    const signature = nacl.sign.detached(message, authority.secretKey);
+    return program.methods
+      .fulfillRandomWords(requestId, [...randomness] as any, null)
+      .accounts(fulfillAccounts(authority.publicKey, requestPda))
+      .preInstructions([
+        Ed25519Program.createInstructionWithPublicKey({
+          publicKey: authority.publicKey.toBytes(),
+          message,
+          signature,
+        }),
+      ])
+      .signers([authority])
+      .rpc();
+  }
+
+  it("Fulfills a signature-hash request with SHA256 of the oracle's signature", async () => {
+    await applyConfigUpdate(program, { newProofScheme: 2 });
+
+    const { requestId, requestPda, request } = await makeStoredRequest(0x71);
+    const message = signatureMessage(requestId, request.seed, request.requestSlot);
+    // Ed25519 signatures are deterministic, so anyone can recompute the output
+    const expected = createHash("sha256")
+      .update(nacl.sign.detached(message, authority.secretKey))
+      .digest();
+    await fulfillSignatureHash(requestId, requestPda, message, expected);
+
+    const stored = await program.account.randomnessRequest.fetch(requestPda);
+    expect(stored.status).to.equal(3); // Stored
+    expect(Buffer.from(stored.randomness).equals(expected)).to.be.true;
+  });
+
+  it("Rejects a signature-hash fulfillment over the wrong message or output", async () => {
+    const { requestId, requestPda, request } = await makeStoredRequest(0x72);
+    const message = signatureMessage(requestId, request.seed, request.requestSlot);
+
+    // Signed over another request slot
+    const wrongMessage = signatureMessage(requestId, request.seed, request.requestSlot.addn(1));
+    const wrongOutput = createHash("sha256")
+      .update(nacl.sign.detached(wrongMessage, authority.secretKey))
+      .digest();
+    try {
+      await fulfillSignatureHash(requestId, requestPda, wrongMessage, wrongOutput);
+      expect.fail("Should have failed - signature over the wrong message");
+    } catch (e: any) {
+      const errStr = e?.logs?.join(" ") ?? e.toString();
+      expect(errStr).to.contain("InvalidEd25519Message");
+    }
+
+    // The right signature, but an output the oracle chose
+    try {
+      await fulfillSignatureHash(requestId, requestPda, message, Buffer.alloc(32, 0x42));
+      expect.fail("Should have failed - output is not SHA256(signature)");
+    } catch (e: any) {
+      const errStr = e?.logs?.join(" ") ?? e.toString();
+      expect(errStr).to.contain("RandomnessMismatch");
+    }
+  });
+
+  // === MULTI-ORACLE ===
+
+  // Three registered oracles with a threshold of two
+  const oracles = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const MULTI_ORACLE_FALLBACK_SLOTS = 150;
+
+  /** Indices of a request's committee, in increasing order. */
+  function committee(requestId: anchor.BN): number[] {
+    const start = requestId.modn(oracles.length);
+    return [start, (start + 1) % oracles.length].sort((a, b) => a - b);
+  }
+
+  /** ECVRF proofs of the oracles at `indices` over `alpha`. */
+  function proveWith(indices: number[], alpha: Buffer) {
+    return indices.map((index) => ({
+      oracleIndex: index,
+      ...ecvrfProve(oracles[index].secretKey, alpha),
+    }));
+  }
+
+  function fulfillMulti(
+    requestId: anchor.BN,
+    requestPda: PublicKey,
+    proofs: { oracleIndex: number; proof: Buffer }[],
+    submitter: Keypair = oracles[0]
+  ) {
+    return program.methods
+      .fulfillRandomWordsMulti(
+        requestId,
+        proofs.map(({ oracleIndex, proof }) => ({ oracleIndex, proof: [...proof] })) as any,
+        null
+      )
+      .accounts(fulfillAccounts(submitter.publicKey, requestPda))
+      .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 600_000 })])
+      .signers([submitter])
+      .rpc();
+  }
+
+  it("Rejects an oracle set that lists an oracle twice", async () => {
+    try {
+      await applyConfigUpdate(program, {
+        newOracleSet: { oracles: [oracles[0].publicKey, oracles[0].publicKey], threshold: 2 },
+      });
+      expect.fail("Should have failed - duplicate oracle");
+    } catch (e: any) {
+      const errStr = e?.logs?.join(" ") ?? e.toString();
+      expect(errStr).to.contain("InvalidOracleSet");
+    }
+  });
+
+  it("Fulfills a multi-oracle request with proofs from its committee", async () => {
+    for (const oracle of oracles) {
+      await fundAccount(oracle.publicKey, LAMPORTS_PER_SOL);
+    }
+    await applyConfigUpdate(program, {
+      newOracleSet: { oracles: oracles.map((o) => o.publicKey), threshold: 2 },
+      newProofScheme: 3,
+    });
+
+    const { requestId, requestPda, alpha } = await makeStoredRequest(0x61);
+    const proofs = proveWith(committee(requestId), alpha);
+
+    // Any registered oracle may submit, including one outside the committee
+    const outside = [0, 1, 2].find((index) => !committee(requestId).includes(index))!;
+    await fulfillMulti(requestId, requestPda, proofs, oracles[outside]);
+
+    const stored = await program.account.randomnessRequest.fetch(requestPda);
+    expect(stored.status).to.equal(3); // Stored
+    const expected = createHash("sha256").update(proofs[0].output).update(proofs[1].output).digest();
+    expect(Buffer.from(stored.randomness).equals(expected)).to.be.true;
+  });
+
+  it("Rejects a multi-oracle fulfillment below or above the threshold", async () => {
+    const { requestId, requestPda, alpha } = await makeStoredRequest(0x62);
+    const members = committee(requestId);
+    try {
+      await fulfillMulti(requestId, requestPda, proveWith(members.slice(0, 1), alpha));
+      expect.fail("Should have failed - one proof for a threshold of two");
+    } catch (e: any) {
+      const errStr = e?.logs?.join(" ") ?? e.toString();
+      expect(errStr).to.contain("InsufficientOracleSignatures");
+    }
+
+    try {
+      await fulfillMulti(requestId, requestPda, proveWith([0, 1, 2], alpha));
+      expect.fail("Should have failed - three proofs for a threshold of two");
+    } catch (e: any) {
+      const errStr = e?.logs?.join(" ") ?? e.toString();
+      expect(errStr).to.contain("TooManyOracleProofs");
+    }
+  });
+
+  it("Rejects proofs from outside the committee before the fallback slot", async () => {
+    const { requestId, requestPda, alpha } = await makeStoredRequest(0x63);
+    const members = committee(requestId);
+    const outside = [0, 1, 2].find((index) => !members.includes(index))!;
+
+    // A registered oracle outside the committee cannot stand in yet
+    const substituted = [members[0], outside].sort((a, b) => a - b);
+    try {
+      await fulfillMulti(requestId, requestPda, proveWith(substituted, alpha));
+      expect.fail("Should have failed - oracle outside the committee");
+    } catch (e: any) {
+      const errStr = e?.logs?.join(" ") ?? e.toString();
+      expect(errStr).to.contain("OracleNotSelected");
+    }
+
+    // An index past the registered set
+    try {
+      await fulfillMulti(requestId, requestPda, [
+        ...proveWith(members.slice(0, 1), alpha),
+        { oracleIndex: 3, proof: ecvrfProve(Keypair.generate().secretKey, alpha).proof },
+      ]);
+      expect.fail("Should have failed - unregistered oracle index");
+    } catch (e: any) {
+      const errStr = e?.logs?.join(" ") ?? e.toString();
+      expect(errStr).to.contain("OracleNotSelected");
+    }
+
+    // A committee member's index with someone else's proof
+    try {
+      await fulfillMulti(requestId, requestPda, [
+        proveWith(members.slice(0, 1), alpha)[0],
+        { oracleIndex: members[1], proof: ecvrfProve(Keypair.generate().secretKey, alpha).proof },
+      ]);
+      expect.fail("Should have failed - proof by another key");
+    } catch (e: any) {
+      const errStr = e?.logs?.join(" ") ?? e.toString();
+      expect(errStr).to.contain("InvalidVrfProof");
+    }
+
+    // Unregistered keys cannot submit
+    const submitter = Keypair.generate();
+    await fundAccount(submitter.publicKey, LAMPORTS_PER_SOL / 10);
+    try {
+      await fulfillMulti(requestId, requestPda, proveWith(members, alpha), submitter);
+      expect.fail("Should have failed - unregistered submitter");
+    } catch (e: any) {
+      const errStr = e?.logs?.join(" ") ?? e.toString();
+      expect(errStr).to.contain("Unauthorized");
+    }
+  });
+
+  it("Rejects a duplicated or unordered oracle proof", async () => {
+    const { requestId, requestPda, alpha } = await makeStoredRequest(0x64);
+    const members = committee(requestId);
+    const first = proveWith(members.slice(0, 1), alpha)[0];
+    try {
+      await fulfillMulti(requestId, requestPda, [first, first]);
+      expect.fail("Should have failed - the same oracle twice");
+    } catch (e: any) {
+      const errStr = e?.logs?.join(" ") ?? e.toString();
+      expect(errStr).to.contain("DuplicateOracleSignature");
+    }
+
+    try {
+      await fulfillMulti(requestId, requestPda, proveWith([...members].reverse(), alpha));
+      expect.fail("Should have failed - proofs out of index order");
+    } catch (e: any) {
+      const errStr = e?.logs?.join(" ") ?? e.toString();
+      expect(errStr).to.contain("DuplicateOracleSignature");
+    }
+  });
+
+  it("Accepts any registered oracles once the committee's window has passed", async () => {
+    const { requestId, requestPda, request, alpha } = await makeStoredRequest(0x65);
+    const members = committee(requestId);
+    const outside = [0, 1, 2].find((index) => !members.includes(index))!;
+    const substituted = [members[0], outside].sort((a, b) => a - b);
+
+    const fallbackSlot = request.requestSlot
+      .add(request.minConfirmationSlots)
+      .addn(MULTI_ORACLE_FALLBACK_SLOTS)
+      .toNumber();
+    while ((await provider.connection.getSlot()) < fallbackSlot) {
+      await new Promise((r) => setTimeout(r, 400));
+    }
+
+    const proofs = proveWith(substituted, alpha);
+    await fulfillMulti(requestId, requestPda, proofs);
+
+    const stored = await program.account.randomnessRequest.fetch(requestPda);
+    expect(stored.status).to.equal(3); // Stored
+    const expected = createHash("sha256").update(proofs[0].output).update(proofs[1].output).digest();
+    expect(Buffer.from(stored.randomness).equals(expected)).to.be.true;
+  });
+});
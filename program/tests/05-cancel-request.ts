@@ -0,0 +1,238 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { VrfSol } from "../target/types/vrf_sol";
+import { MockConsumer } from "../target/types/mock_consumer";
+import { expect } from "chai";
+import {
+  Keypair,
+  PublicKey,
+  SystemProgram,
+  LAMPORTS_PER_SOL,
+} from "@solana/web3.js";
+import { applyConfigUpdate } from "./utils/config";
+import { nextDirectRequestId } from "./utils/nonce";
+
+/**
+ * Cancellation of expired requests. The oracle authority is rotated to a
+ * throwaway key for the duration, so a running backend cannot fulfill the
+ * requests before they expire.
+ */
+describe("cancel request", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.vrfSol as Program<VrfSol>;
+  const mockConsumer = anchor.workspace.mockConsumer as Program<MockConsumer>;
+  const admin = provider.wallet as anchor.Wallet;
+
+  const timeoutSlots = 2;
+
+  const [configPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("coordinator-config")],
+    program.programId
+  );
+
+  let subscriptionId: number;
+  let subscriptionPda: PublicKey;
+
+  // Restored once the tests are done.
+  let originalAuthority: PublicKey;
+  let originalTimeoutSlots: anchor.BN;
+
+  function getSubscriptionPda(subId: number | anchor.BN): PublicKey {
+    const id = new anchor.BN(subId);
+    const [pda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("subscription"), id.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    return pda;
+  }
+
+  function getConsumerPda(subId: number | anchor.BN, consumerProgram: PublicKey): PublicKey {
+    const id = new anchor.BN(subId);
+    const [pda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("consumer"),
+        id.toArrayLike(Buffer, "le", 8),
+        consumerProgram.toBuffer(),
+      ],
+      program.programId
+    );
+    return pda;
+  }
+
+  function getRequestPda(requestId: anchor.BN): PublicKey {
+    const [pda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vrf-request"), requestId.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    return pda;
+  }
+
+  async function waitForDeadline(requestPda: PublicKey): Promise<void> {
+    const request = await program.account.randomnessRequest.fetch(requestPda);
+    const deadline = request.requestSlot.addn(timeoutSlots).toNumber();
+    while ((await provider.connection.getSlot()) < deadline) {
+      await new Promise((r) => setTimeout(r, 400));
+    }
+  }
+
+  before(async () => {
+    const config = await program.account.coordinatorConfig.fetch(configPda);
+    originalAuthority = config.authority;
+    originalTimeoutSlots = config.requestTimeoutSlots;
+    await applyConfigUpdate(program, {
+      newAuthority: Keypair.generate().publicKey,
+      newRequestTimeoutSlots: new anchor.BN(timeoutSlots),
+    });
+
+    subscriptionId = config.subscriptionCounter.toNumber();
+    subscriptionPda = getSubscriptionPda(subscriptionId);
+    await program.methods
+      .createSubscription()
+      .accounts({
+        owner: admin.publicKey,
+        config: configPda,
+        subscription: subscriptionPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods
+      .fundSubscription(new anchor.BN(subscriptionId), new anchor.BN(LAMPORTS_PER_SOL))
+      .accounts({
+        funder: admin.publicKey,
+        subscription: subscriptionPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods
+      .addConsumer(new anchor.BN(subscriptionId))
+      .accounts({
+        owner: admin.publicKey,
+        subscription: subscriptionPda,
+        consumerProgram: mockConsumer.programId,
+        consumerRegistration: getConsumerPda(subscriptionId, mockConsumer.programId),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  after(async () => {
+    await applyConfigUpdate(program, {
+      newAuthority: originalAuthority,
+      newRequestTimeoutSlots: originalTimeoutSlots,
+    });
+  });
+
+  it("Re-credits the subscription balance when an expired request is cancelled", async () => {
+    const before = await program.account.subscription.fetch(subscriptionPda);
+    const requestId = new anchor.BN(subscriptionId).shln(32).add(before.reqCount);
+    const requestPda = getRequestPda(requestId);
+
+    await mockConsumer.methods
+      .request(2, [...Buffer.alloc(32, 0x81)] as any, 0, true, new anchor.BN(0), { userData: null, prefix: null })
+      .accounts({
+        requester: admin.publicKey,
+        vrfConfig: configPda,
+        subscription: subscriptionPda,
+        consumerRegistration: getConsumerPda(subscriptionId, mockConsumer.programId),
+        thisProgram: mockConsumer.programId,
+        vrfRequest: requestPda,
+        callbackAccounts: null,
+        callbackLookupTable: null,
+        approvedMint: null,
+        vrfProgram: program.programId,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const request = await program.account.randomnessRequest.fetch(requestPda);
+    const charged = await program.account.subscription.fetch(subscriptionPda);
+    expect(request.feePaid.gtn(0)).to.be.true;
+    expect(before.balance.sub(charged.balance).eq(request.feePaid)).to.be.true;
+    expect(charged.pendingFees.sub(before.pendingFees).eq(request.feePaid)).to.be.true;
+
+    // Migrating an up-to-date request changes nothing
+    const stored = await provider.connection.getAccountInfo(requestPda);
+    await program.methods
+      .migrateRequest(requestId)
+      .accounts({
+        admin: admin.publicKey,
+        config: configPda,
+        request: requestPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    const migrated = await provider.connection.getAccountInfo(requestPda);
+    expect(migrated!.data.equals(stored!.data)).to.be.true;
+
+    await waitForDeadline(requestPda);
+    await program.methods
+      .cancelRequest(requestId)
+      .accounts({
+        caller: admin.publicKey,
+        config: configPda,
+        request: requestPda,
+        subscription: subscriptionPda,
+        requester: admin.publicKey,
+      })
+      .rpc();
+
+    const after = await program.account.subscription.fetch(subscriptionPda);
+    expect(after.balance.eq(before.balance)).to.be.true;
+    expect(after.pendingFees.eq(before.pendingFees)).to.be.true;
+    expect(await provider.connection.getAccountInfo(requestPda)).to.be.null;
+  });
+
+  it("Refunds a cancelled direct request's fee from the request PDA", async () => {
+    // Direct requests record subscription ID 2^32 - 1 and take their IDs from the requester's nonce
+    const { requestId, noncePda } = await nextDirectRequestId(program, admin.publicKey);
+    const requestPda = getRequestPda(requestId);
+
+    await mockConsumer.methods
+      .requestDirect(2, [...Buffer.alloc(32, 0x82)] as any, 0, true, new anchor.BN(0), { userData: null, prefix: null })
+      .accounts({
+        requester: admin.publicKey,
+        vrfConfig: configPda,
+        requesterNonce: noncePda,
+        thisProgram: mockConsumer.programId,
+        vrfRequest: requestPda,
+        callbackAccounts: null,
+        callbackLookupTable: null,
+        vrfProgram: program.programId,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const request = await program.account.randomnessRequest.fetch(requestPda);
+    const fee = request.feePaid;
+    expect(fee.gtn(0)).to.be.true;
+    const requestInfo = await provider.connection.getAccountInfo(requestPda);
+    const rent = await provider.connection.getMinimumBalanceForRentExemption(requestInfo!.data.length);
+    expect(requestInfo!.lamports).to.equal(rent + fee.toNumber());
+
+    await waitForDeadline(requestPda);
+    const vaultLamportsBefore = await provider.connection.getBalance(feeVaultPda);
+    const requesterBefore = await provider.connection.getBalance(admin.publicKey);
+
+    await program.methods
+      .cancelRequest(requestId)
+      .accounts({
+        caller: admin.publicKey,
+        config: configPda,
+        request: requestPda,
+        subscription: null,
+        requester: admin.publicKey,
+      })
+      .rpc();
+
+    // The fee never reached the fee vault
+    expect(await provider.connection.getBalance(feeVaultPda)).to.equal(vaultLamportsBefore);
+
+    // The requester gets the fee and the request's rent back, less the
+    // cancel transaction's signature fee
+    const requesterGain = (await provider.connection.getBalance(admin.publicKey)) - requesterBefore;
+    expect(requesterGain).to.equal(fee.toNumber() + rent - 5000);
+    expect(await provider.connection.getAccountInfo(requestPda)).to.be.null;
+  });
+});
@@ -0,0 +1,70 @@
+import { Program } from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { VrfSol } from "../../target/types/vrf_sol";
+
+/**
+ * Config update delay the tests initialize the coordinator with: the
+ * coordinator's minimum, so staged changes apply after a few seconds.
+ */
+export const CONFIG_UPDATE_DELAY_SLOTS = 10;
+
+/**
+ * Build the `ConfigChanges` argument of `update_config`. Fields left out are
+ * `null`, so the coordinator leaves them unchanged.
+ */
+export function configChanges(changes: Record<string, unknown>): any {
+  return {
+    newAuthority: null,
+    newFeePerWord: null,
+    newMaxNumWords: null,
+    newAdmin: null,
+    newProofScheme: null,
+    newRequestTimeoutSlots: null,
+    newProtocolFeeBps: null,
+    newBaseFee: null,
+    newComputeUnitPrice: null,
+    newMaxCallbackComputeLimit: null,
+    newMinConfirmationSlots: null,
+    newPauser: null,
+    newConfigUpdateDelaySlots: null,
+    newOracleSet: null,
+    ...changes,
+  };
+}
+
+/**
+ * Stage `changes` with `update_config`, wait out the config update delay and
+ * apply them with `execute_config_update`. `admin` defaults to the provider
+ * wallet.
+ */
+export async function applyConfigUpdate(
+  program: Program<VrfSol>,
+  changes: Record<string, unknown>,
+  admin?: Keypair
+): Promise<void> {
+  const [configPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("coordinator-config")],
+    program.programId
+  );
+  const [pendingPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("pending-config-update")],
+    program.programId
+  );
+  const adminKey = admin ? admin.publicKey : program.provider.publicKey!;
+  const signers = admin ? [admin] : [];
+
+  await program.methods
+    .updateConfig(configChanges(changes))
+    .accounts({ admin: adminKey, config: configPda })
+    .signers(signers)
+    .rpc();
+  const pending = await program.account.pendingConfigUpdate.fetch(pendingPda);
+  while ((await program.provider.connection.getSlot()) < pending.executableSlot.toNumber()) {
+    await new Promise((r) => setTimeout(r, 400));
+  }
+  await program.methods
+    .executeConfigUpdate()
+    .accounts({ admin: adminKey, config: configPda })
+    .signers(signers)
+    .rpc();
+}
@@ -0,0 +1,155 @@
+import { BN } from "@coral-xyz/anchor";
+import { createHash } from "crypto";
+
+/**
+ * ECVRF-EDWARDS25519-SHA512-TAI prover (RFC 9381), mirroring
+ * `backend/src/ecvrf.rs`, so tests can build the proofs the coordinator
+ * verifies on-chain.
+ */
+
+const SUITE_STRING = 0x03;
+
+const P = new BN(2).pow(new BN(255)).subn(19);
+const L = new BN(2).pow(new BN(252)).add(new BN("27742317777372353535851937790883648493"));
+const field = BN.red(P);
+
+/** A field element in `field`'s reduction context. */
+type Fe = ReturnType<BN["toRed"]>;
+const fe = (n: number) => new BN(n).toRed(field);
+
+/** Extended coordinates (X, Y, Z, T) over the field. */
+type Point = [Fe, Fe, Fe, Fe];
+
+const ZERO = fe(0);
+const ONE = fe(1);
+const TWO = fe(2);
+const D_CONST = fe(121665).redNeg().redMul(fe(121666).redInvm());
+const SQRT_M1 = TWO.redPow(P.subn(1).divn(4));
+const IDENTITY: Point = [ZERO, ONE, ONE, ZERO];
+
+function add([x1, y1, z1, t1]: Point, [x2, y2, z2, t2]: Point): Point {
+  const a = y1.redSub(x1).redMul(y2.redSub(x2));
+  const b = y1.redAdd(x1).redMul(y2.redAdd(x2));
+  const c = TWO.redMul(D_CONST).redMul(t1).redMul(t2);
+  const d = TWO.redMul(z1).redMul(z2);
+  const e = b.redSub(a);
+  const f = d.redSub(c);
+  const g = d.redAdd(c);
+  const h = b.redAdd(a);
+  return [e.redMul(f), g.redMul(h), f.redMul(g), e.redMul(h)];
+}
+
+function multiply(point: Point, scalar: BN): Point {
+  let result = IDENTITY;
+  for (let i = scalar.bitLength() - 1; i >= 0; i--) {
+    result = add(result, result);
+    if (scalar.testn(i)) result = add(result, point);
+  }
+  return result;
+}
+
+function compress([x, y, z]: Point): Buffer {
+  const zInv = z.redInvm();
+  const ax = x.redMul(zInv).fromRed();
+  const bytes = y.redMul(zInv).fromRed().toArrayLike(Buffer, "le", 32);
+  bytes[31] |= (ax.isOdd() ? 1 : 0) << 7;
+  return bytes;
+}
+
+function decompress(bytes: Buffer): Point | null {
+  const sign = bytes[31] >> 7;
+  const yBytes = Buffer.from(bytes);
+  yBytes[31] &= 0x7f;
+  const y = new BN(yBytes, 10, "le").umod(P).toRed(field);
+  const u = y.redSqr().redSub(ONE);
+  const v = D_CONST.redMul(y.redSqr()).redAdd(ONE);
+  let x = u
+    .redMul(v.redPow(new BN(3)))
+    .redMul(u.redMul(v.redPow(new BN(7))).redPow(P.subn(5).divn(8)));
+  const vxx = v.redMul(x.redSqr());
+  if (!vxx.eq(u)) {
+    if (!vxx.eq(u.redNeg())) return null;
+    x = x.redMul(SQRT_M1);
+  }
+  const xInt = x.fromRed();
+  if (xInt.isZero() && sign === 1) return null;
+  if ((xInt.isOdd() ? 1 : 0) !== sign) x = x.redNeg();
+  return [x, y, ONE, x.redMul(y)];
+}
+
+const BASE: Point = decompress(
+  Buffer.from("5866666666666666666666666666666666666666666666666666666666666666", "hex")
+)!;
+const IDENTITY_BYTES = compress(IDENTITY);
+
+function sha512(...parts: Uint8Array[]): Buffer {
+  const hash = createHash("sha512");
+  for (const part of parts) hash.update(part);
+  return hash.digest();
+}
+
+/** Try-and-increment hash to curve, salted with the public key. */
+function encodeToCurve(publicKey: Buffer, alpha: Uint8Array): Point {
+  for (let ctr = 0; ctr < 256; ctr++) {
+    const hash = sha512(Buffer.from([SUITE_STRING, 0x01]), publicKey, alpha, Buffer.from([ctr, 0x00]));
+    const point = decompress(hash.subarray(0, 32));
+    if (point) {
+      const cleared = multiply(point, new BN(8));
+      if (!compress(cleared).equals(IDENTITY_BYTES)) return cleared;
+    }
+  }
+  throw new Error("try-and-increment exhausted 256 candidates");
+}
+
+function challenge(points: Buffer[]): Buffer {
+  return sha512(Buffer.from([SUITE_STRING, 0x02]), ...points, Buffer.from([0x00])).subarray(0, 16);
+}
+
+/**
+ * Build the VRF input of a request (must match `ecvrf::vrf_input`).
+ *
+ * `alpha = seed (32) || request_slot (8 LE) || request_id (8 LE)`
+ */
+export function vrfInput(seed: Uint8Array, requestSlot: BN, requestId: BN): Buffer {
+  return Buffer.concat([
+    Buffer.from(seed),
+    requestSlot.toArrayLike(Buffer, "le", 8),
+    requestId.toArrayLike(Buffer, "le", 8),
+  ]);
+}
+
+/**
+ * Prove `alpha` with an Ed25519 key. `secretKey` is a 32-byte seed or a
+ * 64-byte Solana secret key (seed followed by the public key).
+ *
+ * Returns the 80-byte proof `Gamma || c || s` and the 32-byte output the
+ * coordinator derives from it.
+ */
+export function ecvrfProve(
+  secretKey: Uint8Array,
+  alpha: Uint8Array
+): { proof: Buffer; output: Buffer } {
+  const hashedSk = sha512(secretKey.subarray(0, 32));
+  const scalarBytes = Buffer.from(hashedSk.subarray(0, 32));
+  scalarBytes[0] &= 248;
+  scalarBytes[31] &= 127;
+  scalarBytes[31] |= 64;
+  const x = new BN(scalarBytes, 10, "le");
+  const publicKey = compress(multiply(BASE, x));
+
+  const h = encodeToCurve(publicKey, alpha);
+  const hString = compress(h);
+  const gamma = multiply(h, x);
+
+  const k = new BN(sha512(hashedSk.subarray(32), hString), 10, "le").umod(L);
+  const c = challenge([publicKey, hString, compress(gamma), compress(multiply(BASE, k)), compress(multiply(h, k))]);
+  const s = k.add(new BN(c, 10, "le").mul(x)).umod(L);
+
+  const proof = Buffer.concat([compress(gamma), c, s.toArrayLike(Buffer, "le", 32)]);
+  const output = sha512(
+    Buffer.from([SUITE_STRING, 0x03]),
+    compress(multiply(gamma, new BN(8))),
+    Buffer.from([0x00])
+  ).subarray(0, 32);
+  return { proof, output: Buffer.from(output) };
+}
@@ -0,0 +1,35 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+import { VrfSol } from "../../target/types/vrf_sol";
+
+/**
+ * Create `requester`'s nonce if it does not exist yet and return the ID of
+ * their next direct-funded request, with the nonce PDA.
+ */
+export async function nextDirectRequestId(
+  program: Program<VrfSol>,
+  requester: PublicKey
+): Promise<{ requestId: anchor.BN; noncePda: PublicKey }> {
+  const [noncePda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("requester-nonce"), requester.toBuffer()],
+    program.programId
+  );
+  if ((await program.provider.connection.getAccountInfo(noncePda)) === null) {
+    const [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("coordinator-config")],
+      program.programId
+    );
+    await program.methods
+      .createRequesterNonce()
+      .accounts({
+        requester,
+        config: configPda,
+        requesterNonce: noncePda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+  const nonce = await program.account.requesterNonce.fetch(noncePda);
+  return { requestId: nonce.id.shln(32).add(nonce.reqCount), noncePda };
+}
@@ -304,8 +304,28 @@ async function main() {
 
     const maxNumWords = parseInt(maxNumWordsStr, 10);
 
+    const { delaySlotsStr } = await inquirer.prompt<{
+        delaySlotsStr: string;
+    }>([
+        {
+            type: "input",
+            name: "delaySlotsStr",
+            message: "Config update delay (slots, 10 to 6480000):",
+            default: "9000",
+            validate: (input: string) =>
+                /^\d+$/.test(input) &&
+                parseInt(input, 10) >= 10 &&
+                parseInt(input, 10) <= 6_480_000
+                    ? true
+                    : "Must be between 10 and 6480000 slots",
+        },
+    ]);
+
+    const configUpdateDelaySlots = new anchor.BN(delaySlotsStr);
+
     info("Fee per word", `${feePerWordStr} lamports (${lamportsToSol(feePerWord)} SOL)`);
     info("Max words", maxNumWordsStr);
+    info("Config update delay", `${delaySlotsStr} slots`);
 
     // ── Check existing config ────────────────────────────────────────────────
 
@@ -329,7 +349,6 @@ async function main() {
         info("Fee per word", `${config.feePerWord.toString()} lamports`);
         info("Max words", config.maxNumWords.toString());
         info("Subscriptions", config.subscriptionCounter.toString());
-        info("Requests", config.requestCounter.toString());
 
         console.log(
             chalk.yellow(
@@ -351,6 +370,7 @@ async function main() {
     info("Authority", authorityPubkey.toBase58());
     info("Fee per word", `${feePerWordStr} lamports`);
     info("Max words", maxNumWordsStr);
+    info("Config update delay", `${delaySlotsStr} slots`);
     info("Config PDA", configPda.toBase58());
     console.log();
     separator();
@@ -372,7 +392,7 @@ async function main() {
     console.log(chalk.dim("\n  Initializing coordinator..."));
 
     const sig = await program.methods
-        .initialize(feePerWord, maxNumWords)
+        .initialize(feePerWord, maxNumWords, configUpdateDelaySlots)
         .accountsPartial({
             admin: admin.publicKey,
             authority: authorityPubkey,
@@ -398,7 +418,6 @@ async function main() {
     info("Fee per word", `${config.feePerWord.toString()} lamports (${lamportsToSol(config.feePerWord)} SOL)`);
     info("Max words", config.maxNumWords.toString());
     info("Subscriptions", config.subscriptionCounter.toString());
-    info("Requests", config.requestCounter.toString());
     info("Config PDA", configPda.toBase58());
 
     separator();
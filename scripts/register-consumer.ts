@@ -285,7 +285,6 @@ async function main() {
     info("Fee per word", `${lamportsToSol(config.feePerWord)} SOL`);
     info("Max words", config.maxNumWords.toString());
     info("Subscriptions", config.subscriptionCounter.toString());
-    info("Requests", config.requestCounter.toString());
 
     // ── Step 5: Subscription ─────────────────────────────────────────────────
 
@@ -1,18 +1,29 @@
 import { PublicKey } from "@solana/web3.js";
 import BN from "bn.js";
-import { ACCOUNT_DISCRIMINATORS } from "./constants";
+import { ACCOUNT_DISCRIMINATORS, MAX_ORACLES } from "./constants";
 import {
   CoordinatorConfig,
   SubscriptionAccount,
+  FeeVaultAccount,
+  RequesterNonceAccount,
+  ApprovedMintAccount,
   ConsumerRegistrationAccount,
   RandomnessRequestAccount,
+  CallbackAccountsAccount,
+  CallbackAccountMeta,
+  RandomnessResultAccount,
+  ConfigChanges,
+  PendingConfigUpdateAccount,
+  RequestRandomWordsResult,
   RequestStatus,
+  ProofScheme,
+  CallbackVersion,
 } from "./types";
 
 /**
  * Deserialize a CoordinatorConfig account from raw buffer data.
  *
- * Layout (101 bytes total):
+ * Layout (471 bytes total):
  * ```
  * [0..8]    discriminator
  * [8..40]   admin (Pubkey)
@@ -22,12 +33,26 @@ import {
  * [84..92]  request_counter (u64 LE)
  * [92..100] subscription_counter (u64 LE)
  * [100]     bump (u8)
+ * [101]     proof_scheme (u8)
+ * [102]     oracle_count (u8)
+ * [103]     oracle_threshold (u8)
+ * [104..360] oracles ([Pubkey; 8])
+ * [360..368] request_timeout_slots (u64 LE)
+ * [368..370] protocol_fee_bps (u16 LE)
+ * [370..378] base_fee (u64 LE)
+ * [378..386] compute_unit_price (u64 LE)
+ * [386..390] max_callback_compute_limit (u32 LE)
+ * [390..398] min_confirmation_slots (u64 LE)
+ * [398..430] pauser (Pubkey)
+ * [430]     paused (bool)
+ * [431..439] config_update_delay_slots (u64 LE)
+ * [439..471] pending_admin (Pubkey)
  * ```
  */
 export function decodeCoordinatorConfig(data: Buffer): CoordinatorConfig {
-  if (data.length < 101) {
+  if (data.length < 368) {
     throw new Error(
-      `CoordinatorConfig data too short: expected 101 bytes, got ${data.length}`
+      `CoordinatorConfig data too short: expected 368 bytes, got ${data.length}`
     );
   }
 
@@ -36,6 +61,8 @@ export function decodeCoordinatorConfig(data: Buffer): CoordinatorConfig {
     throw new Error("Invalid CoordinatorConfig discriminator");
   }
 
+  const oracleCount = Math.min(data[102], MAX_ORACLES);
+
   return {
     admin: new PublicKey(data.subarray(8, 40)),
     authority: new PublicKey(data.subarray(40, 72)),
@@ -44,13 +71,29 @@ export function decodeCoordinatorConfig(data: Buffer): CoordinatorConfig {
     requestCounter: new BN(data.subarray(84, 92), "le"),
     subscriptionCounter: new BN(data.subarray(92, 100), "le"),
     bump: data[100],
+    proofScheme: data[101] as ProofScheme,
+    oracles: Array.from(
+      { length: oracleCount },
+      (_, i) => new PublicKey(data.subarray(104 + i * 32, 136 + i * 32))
+    ),
+    oracleThreshold: data[103],
+    requestTimeoutSlots: new BN(data.subarray(360, 368), "le"),
+    protocolFeeBps: data.length >= 370 ? data.readUInt16LE(368) : 0,
+    baseFee: data.length >= 378 ? new BN(data.subarray(370, 378), "le") : new BN(0),
+    computeUnitPrice: data.length >= 386 ? new BN(data.subarray(378, 386), "le") : new BN(0),
+    maxCallbackComputeLimit: data.length >= 390 ? data.readUInt32LE(386) : 0,
+    minConfirmationSlots: data.length >= 398 ? new BN(data.subarray(390, 398), "le") : new BN(0),
+    pauser: data.length >= 430 ? new PublicKey(data.subarray(398, 430)) : PublicKey.default,
+    paused: data.length >= 431 ? data[430] === 1 : false,
+    configUpdateDelaySlots: data.length >= 439 ? new BN(data.subarray(431, 439), "le") : new BN(0),
+    pendingAdmin: data.length >= 471 ? new PublicKey(data.subarray(439, 471)) : PublicKey.default,
   };
 }
 
 /**
  * Deserialize a Subscription account from raw buffer data.
  *
- * Layout (69 bytes total):
+ * Layout (141 bytes total):
  * ```
  * [0..8]   discriminator
  * [8..16]  id (u64 LE)
@@ -59,6 +102,9 @@ export function decodeCoordinatorConfig(data: Buffer): CoordinatorConfig {
  * [56..64] req_count (u64 LE)
  * [64..68] consumer_count (u32 LE)
  * [68]     bump (u8)
+ * [69..77] pending_fees (u64 LE)
+ * [77..109] pending_owner (Pubkey)
+ * [109..141] billing_mint (Pubkey)
  * ```
  */
 export function decodeSubscription(data: Buffer): SubscriptionAccount {
@@ -80,27 +126,147 @@ export function decodeSubscription(data: Buffer): SubscriptionAccount {
     reqCount: new BN(data.subarray(56, 64), "le"),
     consumerCount: data.readUInt32LE(64),
     bump: data[68],
+    pendingFees: data.length >= 77 ? new BN(data.subarray(69, 77), "le") : new BN(0),
+    pendingOwner: data.length >= 109 ? new PublicKey(data.subarray(77, 109)) : PublicKey.default,
+    billingMint: data.length >= 141 ? new PublicKey(data.subarray(109, 141)) : PublicKey.default,
   };
 }
 
 /**
- * Deserialize a ConsumerRegistration account from raw buffer data.
+ * Deserialize the FeeVault account from raw buffer data.
+ *
+ * Layout (25 bytes total):
+ * ```
+ * [0..8]   discriminator
+ * [8..16]  oracle_fees (u64 LE)
+ * [16..24] protocol_fees (u64 LE)
+ * [24]     bump (u8)
+ * ```
+ */
+export function decodeFeeVault(data: Buffer): FeeVaultAccount {
+  if (data.length < 25) {
+    throw new Error(
+      `FeeVault data too short: expected 25 bytes, got ${data.length}`
+    );
+  }
+
+  const disc = data.subarray(0, 8);
+  if (!disc.equals(ACCOUNT_DISCRIMINATORS.FeeVault)) {
+    throw new Error("Invalid FeeVault discriminator");
+  }
+
+  return {
+    oracleFees: new BN(data.subarray(8, 16), "le"),
+    protocolFees: new BN(data.subarray(16, 24), "le"),
+    bump: data[24],
+  };
+}
+
+/**
+ * Deserialize a RequesterNonce account from raw buffer data.
  *
  * Layout (57 bytes total):
  * ```
  * [0..8]   discriminator
- * [8..16]  subscription_id (u64 LE)
- * [16..48] program_id (Pubkey)
- * [48..56] nonce (u64 LE)
+ * [8..40]  requester (Pubkey)
+ * [40..48] id (u64 LE)
+ * [48..56] req_count (u64 LE)
  * [56]     bump (u8)
  * ```
  */
+export function decodeRequesterNonce(data: Buffer): RequesterNonceAccount {
+  if (data.length < 57) {
+    throw new Error(
+      `RequesterNonce data too short: expected 57 bytes, got ${data.length}`
+    );
+  }
+
+  const disc = data.subarray(0, 8);
+  if (!disc.equals(ACCOUNT_DISCRIMINATORS.RequesterNonce)) {
+    throw new Error("Invalid RequesterNonce discriminator");
+  }
+
+  return {
+    requester: new PublicKey(data.subarray(8, 40)),
+    id: new BN(data.subarray(40, 48), "le"),
+    reqCount: new BN(data.subarray(48, 56), "le"),
+    bump: data[56],
+  };
+}
+
+/**
+ * Deserialize an ApprovedMint account from raw buffer data.
+ *
+ * Layout (114 bytes total):
+ * ```
+ * [0..8]   discriminator
+ * [8..40]  mint (Pubkey)
+ * [40..72] token_program (Pubkey)
+ * [72..80] fee_per_word (u64 LE)
+ * [80]     enabled (bool)
+ * [81..89] oracle_fees (u64 LE)
+ * [89..97] protocol_fees (u64 LE)
+ * [97]     bump (u8)
+ * [98..106]  base_fee (u64 LE)
+ * [106..114] compute_unit_price (u64 LE)
+ * ```
+ */
+export function decodeApprovedMint(data: Buffer): ApprovedMintAccount {
+  if (data.length < 98) {
+    throw new Error(
+      `ApprovedMint data too short: expected 98 bytes, got ${data.length}`
+    );
+  }
+
+  const disc = data.subarray(0, 8);
+  if (!disc.equals(ACCOUNT_DISCRIMINATORS.ApprovedMint)) {
+    throw new Error("Invalid ApprovedMint discriminator");
+  }
+
+  return {
+    mint: new PublicKey(data.subarray(8, 40)),
+    tokenProgram: new PublicKey(data.subarray(40, 72)),
+    feePerWord: new BN(data.subarray(72, 80), "le"),
+    enabled: data[80] !== 0,
+    oracleFees: new BN(data.subarray(81, 89), "le"),
+    protocolFees: new BN(data.subarray(89, 97), "le"),
+    bump: data[97],
+    baseFee: data.length >= 106 ? new BN(data.subarray(98, 106), "le") : new BN(0),
+    computeUnitPrice: data.length >= 114 ? new BN(data.subarray(106, 114), "le") : new BN(0),
+  };
+}
+
+/**
+ * Deserialize a ConsumerRegistration account from raw buffer data.
+ *
+ * Layout (142 bytes total):
+ * ```
+ * [0..8]     discriminator
+ * [8..16]    subscription_id (u64 LE)
+ * [16..48]   program_id (Pubkey)
+ * [48..56]   nonce (u64 LE)
+ * [56]       bump (u8)
+ * [57..65]   max_fee_per_epoch (u64 LE)
+ * [65..69]   max_requests_per_window (u32 LE)
+ * [69..77]   request_window_slots (u64 LE)
+ * [77..81]   max_num_words (u32 LE)
+ * [81..89]   fee_epoch (u64 LE)
+ * [89..97]   epoch_fees (u64 LE)
+ * [97..105]  window_start_slot (u64 LE)
+ * [105..109] window_requests (u32 LE)
+ * [109..117] total_requests (u64 LE)
+ * [117..125] total_fees_paid (u64 LE)
+ * [125..133] last_request_slot (u64 LE)
+ * [133]      callback_prefix_len (u8)
+ * [134..142] callback_prefix ([u8; 8], first callback_prefix_len bytes used)
+ * ```
+ */
 export function decodeConsumerRegistration(
   data: Buffer
 ): ConsumerRegistrationAccount {
-  if (data.length < 57) {
+  if (data.length < 133) {
     throw new Error(
-      `ConsumerRegistration data too short: expected 57 bytes, got ${data.length}`
+      `ConsumerRegistration data too short: expected 133 bytes, got ${data.length}`
     );
   }
 
@@ -114,6 +280,22 @@ export function decodeConsumerRegistration(
     programId: new PublicKey(data.subarray(16, 48)),
     nonce: new BN(data.subarray(48, 56), "le"),
     bump: data[56],
+    maxFeePerEpoch: new BN(data.subarray(57, 65), "le"),
+    maxRequestsPerWindow: data.readUInt32LE(65),
+    requestWindowSlots: new BN(data.subarray(69, 77), "le"),
+    maxNumWords: data.readUInt32LE(77),
+    feeEpoch: new BN(data.subarray(81, 89), "le"),
+    epochFees: new BN(data.subarray(89, 97), "le"),
+    windowStartSlot: new BN(data.subarray(97, 105), "le"),
+    windowRequests: data.readUInt32LE(105),
+    totalRequests: new BN(data.subarray(109, 117), "le"),
+    totalFeesPaid: new BN(data.subarray(117, 125), "le"),
+    lastRequestSlot: new BN(data.subarray(125, 133), "le"),
+    // Absent on registrations created before callback prefixes.
+    callbackPrefix:
+      data.length >= 142
+        ? new Uint8Array(data.subarray(134, 134 + data[133]))
+        : new Uint8Array(0),
   };
 }
 
@@ -151,6 +333,10 @@ export function decodeRandomnessRequest(
     throw new Error("Invalid RandomnessRequest discriminator");
   }
 
+  const userData =
+    data.length >= 364 ? data.subarray(364, 364 + data.readUInt32LE(360)) : Buffer.alloc(0);
+  const prefixOffset = 364 + userData.length;
+
   return {
     requestId: new BN(data.subarray(8, 16), "le"),
     subscriptionId: new BN(data.subarray(16, 24), "le"),
@@ -164,5 +350,212 @@ export function decodeRandomnessRequest(
     randomness: new Uint8Array(data.subarray(137, 169)),
     fulfilledSlot: new BN(data.subarray(169, 177), "le"),
     bump: data[177],
+    // fee_paid, callback_attempts, store_only, callback_sidecar, fee_mint and
+    // min_confirmation_slots follow the callback account fields; absent on
+    // older requests.
+    feePaid: data.length >= 316 ? new BN(data.subarray(308, 316), "le") : new BN(0),
+    callbackAttempts: data.length >= 317 ? data[316] : 0,
+    storeOnly: data.length >= 318 ? data[317] !== 0 : false,
+    callbackSidecar: data.length >= 319 ? data[318] !== 0 : false,
+    feeMint: data.length >= 351 ? new PublicKey(data.subarray(319, 351)) : PublicKey.default,
+    minConfirmationSlots: data.length >= 359 ? new BN(data.subarray(351, 359), "le") : new BN(0),
+    // callback_version and user_data (u32 length + bytes), then the
+    // callback prefix (length + 8 bytes) after the user data.
+    callbackVersion: data.length >= 360 ? (data[359] as CallbackVersion) : CallbackVersion.V1,
+    userData: new Uint8Array(userData),
+    callbackPrefix:
+      data.length >= prefixOffset + 9
+        ? new Uint8Array(data.subarray(prefixOffset + 1, prefixOffset + 1 + data[prefixOffset]))
+        : new Uint8Array(0),
+  };
+}
+
+/**
+ * Deserialize a wallet request's RandomnessResult account from raw buffer data.
+ *
+ * Layout (133 bytes):
+ * ```
+ * [0..8]     discriminator
+ * [8..16]    request_id (u64 LE)
+ * [16..48]   requester (Pubkey)
+ * [48..52]   num_words (u32 LE)
+ * [52..84]   seed ([u8; 32])
+ * [84..92]   request_slot (u64 LE)
+ * [92..124]  randomness ([u8; 32])
+ * [124..132] fulfilled_slot (u64 LE)
+ * [132]      bump (u8)
+ * ```
+ */
+export function decodeRandomnessResult(data: Buffer): RandomnessResultAccount {
+  if (data.length < 133) {
+    throw new Error(
+      `RandomnessResult data too short: expected 133 bytes, got ${data.length}`
+    );
+  }
+
+  const disc = data.subarray(0, 8);
+  if (!disc.equals(ACCOUNT_DISCRIMINATORS.RandomnessResult)) {
+    throw new Error("Invalid RandomnessResult discriminator");
+  }
+
+  return {
+    requestId: new BN(data.subarray(8, 16), "le"),
+    requester: new PublicKey(data.subarray(16, 48)),
+    numWords: data.readUInt32LE(48),
+    seed: new Uint8Array(data.subarray(52, 84)),
+    requestSlot: new BN(data.subarray(84, 92), "le"),
+    randomness: new Uint8Array(data.subarray(92, 124)),
+    fulfilledSlot: new BN(data.subarray(124, 132), "le"),
+    bump: data[132],
+  };
+}
+
+/**
+ * Deserialize a CallbackAccounts sidecar account from raw buffer data.
+ *
+ * Layout (85 + 33 * n bytes):
+ * ```
+ * [0..8]    discriminator
+ * [8..16]   request_id (u64 LE)
+ * [16..48]  requester (Pubkey)
+ * [48..80]  lookup_table (Pubkey, default = none)
+ * [80]      bump (u8)
+ * [81..85]  accounts length (u32 LE)
+ * [85..]    accounts: pubkey (32) + is_writable (1) each
+ * ```
+ */
+export function decodeCallbackAccounts(data: Buffer): CallbackAccountsAccount {
+  if (data.length < 85) {
+    throw new Error(
+      `CallbackAccounts data too short: expected at least 85 bytes, got ${data.length}`
+    );
+  }
+
+  const disc = data.subarray(0, 8);
+  if (!disc.equals(ACCOUNT_DISCRIMINATORS.CallbackAccounts)) {
+    throw new Error("Invalid CallbackAccounts discriminator");
+  }
+
+  const count = data.readUInt32LE(81);
+  if (data.length < 85 + 33 * count) {
+    throw new Error(`CallbackAccounts data truncated: ${count} accounts`);
+  }
+  const accounts: CallbackAccountMeta[] = [];
+  for (let i = 0; i < count; i++) {
+    const offset = 85 + 33 * i;
+    accounts.push({
+      pubkey: new PublicKey(data.subarray(offset, offset + 32)),
+      isWritable: data[offset + 32] !== 0,
+    });
+  }
+
+  const lookupTable = new PublicKey(data.subarray(48, 80));
+  return {
+    requestId: new BN(data.subarray(8, 16), "le"),
+    requester: new PublicKey(data.subarray(16, 48)),
+    lookupTable: lookupTable.equals(PublicKey.default) ? null : lookupTable,
+    bump: data[80],
+    accounts,
+  };
+}
+
+/**
+ * Deserialize a PendingConfigUpdate account from raw buffer data.
+ *
+ * Layout (Borsh, so offsets after the discriminator depend on which changes
+ * are set; at most 455 bytes):
+ * ```
+ * [0..8]    discriminator
+ * changes   14 Options, each a 1-byte tag followed by the value if set:
+ *           new_authority (Pubkey), new_fee_per_word (u64), new_max_num_words (u32),
+ *           new_admin (Pubkey), new_proof_scheme (u8), new_request_timeout_slots (u64),
+ *           new_protocol_fee_bps (u16), new_base_fee (u64), new_compute_unit_price (u64),
+ *           new_max_callback_compute_limit (u32), new_min_confirmation_slots (u64),
+ *           new_pauser (Pubkey), new_config_update_delay_slots (u64),
+ *           new_oracle_set (u32 count, count Pubkeys, threshold u8)
+ * then      proposed_slot (u64 LE), executable_slot (u64 LE), bump (u8)
+ * ```
+ */
+export function decodePendingConfigUpdate(data: Buffer): PendingConfigUpdateAccount {
+  const disc = data.subarray(0, 8);
+  if (!disc.equals(ACCOUNT_DISCRIMINATORS.PendingConfigUpdate)) {
+    throw new Error("Invalid PendingConfigUpdate discriminator");
+  }
+
+  let offset = 8;
+  const option = <T>(size: number, read: (at: number) => T): T | null => {
+    if (offset >= data.length) {
+      throw new Error("PendingConfigUpdate data truncated");
+    }
+    const tag = data[offset];
+    offset += 1;
+    if (tag === 0) {
+      return null;
+    }
+    const value = read(offset);
+    offset += size;
+    return value;
+  };
+  const pubkey = (at: number) => new PublicKey(data.subarray(at, at + 32));
+  const u64 = (at: number) => new BN(data.subarray(at, at + 8), "le");
+
+  const changes: ConfigChanges = {
+    newAuthority: option(32, pubkey),
+    newFeePerWord: option(8, u64),
+    newMaxNumWords: option(4, (at) => data.readUInt32LE(at)),
+    newAdmin: option(32, pubkey),
+    newProofScheme: option(1, (at) => data[at] as ProofScheme),
+    newRequestTimeoutSlots: option(8, u64),
+    newProtocolFeeBps: option(2, (at) => data.readUInt16LE(at)),
+    newBaseFee: option(8, u64),
+    newComputeUnitPrice: option(8, u64),
+    newMaxCallbackComputeLimit: option(4, (at) => data.readUInt32LE(at)),
+    newMinConfirmationSlots: option(8, u64),
+    newPauser: option(32, pubkey),
+    newConfigUpdateDelaySlots: option(8, u64),
+    newOracleSet: null,
+  };
+  if (offset >= data.length) {
+    throw new Error("PendingConfigUpdate data truncated");
+  }
+  if (data[offset++] === 1) {
+    const count = data.readUInt32LE(offset);
+    const oracles = Array.from({ length: count }, (_, i) => pubkey(offset + 4 + 32 * i));
+    offset += 4 + 32 * count;
+    changes.newOracleSet = { oracles, threshold: data[offset] };
+    offset += 1;
+  }
+  if (data.length < offset + 17) {
+    throw new Error("PendingConfigUpdate data truncated");
+  }
+
+  return {
+    changes,
+    proposedSlot: u64(offset),
+    executableSlot: u64(offset + 8),
+    bump: data[offset + 16],
+  };
+}
+
+/**
+ * Deserialize the `RequestReceipt` that `request_random_words` sets as
+ * return data (e.g. `meta.returnData` of a simulated request).
+ *
+ * Layout (no discriminator):
+ * ```
+ * [0..8]    request_id (u64 LE)
+ * [8..40]   request (Pubkey)
+ * ```
+ */
+export function decodeRequestReceipt(data: Buffer): RequestRandomWordsResult {
+  if (data.length < 40) {
+    throw new Error(
+      `RequestReceipt data too short: expected 40 bytes, got ${data.length}`
+    );
+  }
+
+  return {
+    requestId: new BN(data.subarray(0, 8), "le"),
+    requestPda: new PublicKey(data.subarray(8, 40)),
   };
 }
@@ -12,12 +12,24 @@ import {
   getSubscriptionPda,
   getConsumerPda,
   getRequestPda,
+  getRequestId,
+  getFeeVaultPda,
+  getApprovedMintPda,
+  getAssociatedTokenAddress,
+  getPendingConfigUpdatePda,
+  getRandomnessResultPda,
+  getRequesterNoncePda,
 } from "./pda";
 import {
   decodeCoordinatorConfig,
   decodeSubscription,
+  decodeFeeVault,
+  decodeApprovedMint,
   decodeConsumerRegistration,
   decodeRandomnessRequest,
+  decodeRandomnessResult,
+  decodePendingConfigUpdate,
+  decodeRequesterNonce,
 } from "./accounts";
 import {
   createInitializeInstruction,
@@ -25,14 +37,35 @@ import {
   createFundSubscriptionInstruction,
   createAddConsumerInstruction,
   createRemoveConsumerInstruction,
+  createSetConsumerLimitsInstruction,
+  createWithdrawSubscriptionFundsInstruction,
+  createRequestSubscriptionOwnerTransferInstruction,
+  createAcceptSubscriptionOwnerTransferInstruction,
   createCancelSubscriptionInstruction,
+  createCreateTokenSubscriptionInstruction,
+  createFundSubscriptionTokenInstruction,
+  createWithdrawSubscriptionTokensInstruction,
+  createCancelTokenSubscriptionInstruction,
+  createCancelRequestInstruction,
+  createCloseCallbackAccountsInstruction,
+  createRequestWalletRandomnessInstruction,
+  createCloseRandomnessResultInstruction,
+  createWithdrawOracleFeesInstruction,
+  createCreateRequesterNonceInstruction,
 } from "./instructions";
-import { waitForFulfillment, addPriorityFee } from "./utils";
+import { waitForFulfillment, addPriorityFee, computeRequestFee } from "./utils";
 import {
   CoordinatorConfig,
   SubscriptionAccount,
+  FeeVaultAccount,
+  RequesterNonceAccount,
+  ApprovedMintAccount,
+  PendingConfigUpdateAccount,
   ConsumerRegistrationAccount,
+  ConsumerLimits,
   RandomnessRequestAccount,
+  RandomnessResultAccount,
+  RequestRandomWordsResult,
   CreateSubscriptionResult,
   WaitForFulfillmentOptions,
 } from "./types";
@@ -111,6 +144,46 @@ export class MoiraeVrf {
     return decodeSubscription(Buffer.from(accountInfo.data));
   }
 
+  /** Fetch and deserialize the fee vault. */
+  async getFeeVault(): Promise<FeeVaultAccount> {
+    const [feeVaultPda] = getFeeVaultPda(this.programId);
+    const accountInfo = await this.connection.getAccountInfo(feeVaultPda);
+    if (!accountInfo) {
+      throw new Error("Fee vault account not found");
+    }
+    return decodeFeeVault(Buffer.from(accountInfo.data));
+  }
+
+  /**
+   * Fetch and deserialize a requester's nonce, or `null` if they have not
+   * made a direct-funded request yet.
+   */
+  async getRequesterNonce(requester: PublicKey): Promise<RequesterNonceAccount | null> {
+    const [noncePda] = getRequesterNoncePda(requester, this.programId);
+    const accountInfo = await this.connection.getAccountInfo(noncePda);
+    return accountInfo ? decodeRequesterNonce(Buffer.from(accountInfo.data)) : null;
+  }
+
+  /** Fetch and deserialize the `ApprovedMint` account of a billing mint. */
+  async getApprovedMint(mint: PublicKey): Promise<ApprovedMintAccount> {
+    const [approvedMintPda] = getApprovedMintPda(mint, this.programId);
+    const accountInfo = await this.connection.getAccountInfo(approvedMintPda);
+    if (!accountInfo) {
+      throw new Error(`Mint ${mint.toBase58()} is not approved for billing`);
+    }
+    return decodeApprovedMint(Buffer.from(accountInfo.data));
+  }
+
+  /** Fetch the staged config update, or null if none is pending. */
+  async getPendingConfigUpdate(): Promise<PendingConfigUpdateAccount | null> {
+    const [pendingPda] = getPendingConfigUpdatePda(this.programId);
+    const accountInfo = await this.connection.getAccountInfo(pendingPda);
+    if (!accountInfo) {
+      return null;
+    }
+    return decodePendingConfigUpdate(Buffer.from(accountInfo.data));
+  }
+
   /** Fetch and deserialize a consumer registration account. */
   async getConsumerRegistration(
     subscriptionId: BN | number | bigint,
@@ -144,10 +217,66 @@ export class MoiraeVrf {
     return decodeRandomnessRequest(Buffer.from(accountInfo.data));
   }
 
-  /** Get the next request ID from the config counter. */
-  async getNextRequestId(): Promise<BN> {
+  /** Fetch and deserialize the `RandomnessResult` of a wallet request. */
+  async getRandomnessResult(
+    requestId: BN | number | bigint
+  ): Promise<RandomnessResultAccount> {
+    const [resultPda] = getRandomnessResultPda(requestId, this.programId);
+    const accountInfo = await this.connection.getAccountInfo(resultPda);
+    if (!accountInfo) {
+      throw new Error(
+        `Randomness result not found for request ${requestId.toString()}`
+      );
+    }
+    return decodeRandomnessResult(Buffer.from(accountInfo.data));
+  }
+
+  /** Get the ID the next request against `subscriptionId` will use. */
+  async getNextRequestId(subscriptionId: BN | number): Promise<BN> {
+    const subscription = await this.getSubscription(subscriptionId);
+    return getRequestId(subscriptionId, subscription.reqCount);
+  }
+
+  /**
+   * The ID `requester`'s next direct-funded request will be assigned. If they
+   * have no nonce yet, this is the ID it gets when created now.
+   */
+  async getNextDirectRequestId(requester: PublicKey): Promise<BN> {
+    const nonce = await this.getRequesterNonce(requester);
+    if (nonce) {
+      return getRequestId(nonce.id, nonce.reqCount);
+    }
     const config = await this.getConfig();
-    return config.requestCounter;
+    return getRequestId(config.subscriptionCounter, 0);
+  }
+
+  /**
+   * The fee a direct-funded request would pay, in lamports, at the
+   * coordinator's SOL prices.
+   */
+  async getDirectRequestFee(
+    numWords: number,
+    callbackComputeLimit: number
+  ): Promise<BN> {
+    return computeRequestFee(await this.getConfig(), numWords, callbackComputeLimit);
+  }
+
+  /**
+   * The fee a request against `subscriptionId` would be charged, in lamports
+   * or in the subscription's billing mint.
+   *
+   * @param callbackComputeLimit - The request's callback compute limit (0 for store-only requests).
+   */
+  async getRequestFee(
+    subscriptionId: BN | number,
+    numWords: number,
+    callbackComputeLimit: number
+  ): Promise<BN> {
+    const subscription = await this.getSubscription(subscriptionId);
+    const schedule = subscription.billingMint.equals(PublicKey.default)
+      ? await this.getConfig()
+      : await this.getApprovedMint(subscription.billingMint);
+    return computeRequestFee(schedule, numWords, callbackComputeLimit);
   }
 
   /** Get the next subscription ID from the config counter. */
@@ -261,6 +390,104 @@ export class MoiraeVrf {
     await sendAndConfirmTransaction(this.connection, tx, [owner]);
   }
 
+  /**
+   * Set a consumer's per-epoch fee, request rate and `num_words` limits.
+   * Usage is read back with `getConsumerRegistration`.
+   *
+   * @param owner - The subscription owner keypair.
+   * @param subscriptionId - The subscription ID.
+   * @param consumerProgramId - The registered consumer program.
+   * @param limits - The new limits; 0 disables a limit.
+   */
+  async setConsumerLimits(
+    owner: Keypair,
+    subscriptionId: BN | number,
+    consumerProgramId: PublicKey,
+    limits: ConsumerLimits
+  ): Promise<void> {
+    const ix = createSetConsumerLimitsInstruction(
+      owner.publicKey,
+      subscriptionId,
+      consumerProgramId,
+      limits,
+      this.programId
+    );
+
+    const tx = new Transaction().add(ix);
+    await sendAndConfirmTransaction(this.connection, tx, [owner]);
+  }
+
+  /**
+   * Withdraw part of a subscription's unspent balance. Rent and fees held for
+   * pending requests stay in the subscription.
+   *
+   * @param owner - The subscription owner keypair.
+   * @param subscriptionId - The subscription to withdraw from.
+   * @param amount - Amount in lamports to withdraw.
+   * @param recipient - Receives the lamports. Defaults to the owner.
+   */
+  async withdrawSubscriptionFunds(
+    owner: Keypair,
+    subscriptionId: BN | number,
+    amount: BN,
+    recipient: PublicKey = owner.publicKey
+  ): Promise<void> {
+    const ix = createWithdrawSubscriptionFundsInstruction(
+      owner.publicKey,
+      subscriptionId,
+      amount,
+      recipient,
+      this.programId
+    );
+
+    const tx = new Transaction().add(ix);
+    await sendAndConfirmTransaction(this.connection, tx, [owner]);
+  }
+
+  /**
+   * Propose a new owner for a subscription. Ownership changes only once the
+   * new owner calls {@link acceptSubscriptionOwnerTransfer}.
+   *
+   * @param owner - The current subscription owner keypair.
+   * @param subscriptionId - The subscription to transfer.
+   * @param newOwner - The proposed owner, or `PublicKey.default` to withdraw the proposal.
+   */
+  async requestSubscriptionOwnerTransfer(
+    owner: Keypair,
+    subscriptionId: BN | number,
+    newOwner: PublicKey
+  ): Promise<void> {
+    const ix = createRequestSubscriptionOwnerTransferInstruction(
+      owner.publicKey,
+      subscriptionId,
+      newOwner,
+      this.programId
+    );
+
+    const tx = new Transaction().add(ix);
+    await sendAndConfirmTransaction(this.connection, tx, [owner]);
+  }
+
+  /**
+   * Accept a pending subscription ownership transfer.
+   *
+   * @param newOwner - The pending owner keypair.
+   * @param subscriptionId - The subscription being transferred.
+   */
+  async acceptSubscriptionOwnerTransfer(
+    newOwner: Keypair,
+    subscriptionId: BN | number
+  ): Promise<void> {
+    const ix = createAcceptSubscriptionOwnerTransferInstruction(
+      newOwner.publicKey,
+      subscriptionId,
+      this.programId
+    );
+
+    const tx = new Transaction().add(ix);
+    await sendAndConfirmTransaction(this.connection, tx, [newOwner]);
+  }
+
   /**
    * Cancel a subscription and reclaim its balance.
    * Requires all consumers to be removed first.
@@ -282,6 +509,270 @@ export class MoiraeVrf {
     await sendAndConfirmTransaction(this.connection, tx, [owner]);
   }
 
+  /**
+   * Create a subscription billed in an admin-approved SPL mint. The token
+   * program is read from the mint's `ApprovedMint` account.
+   *
+   * @param payer - The keypair creating the subscription (becomes owner).
+   * @param mint - The billing mint.
+   * @returns The subscription ID and PDA address.
+   */
+  async createTokenSubscription(
+    payer: Keypair,
+    mint: PublicKey
+  ): Promise<CreateSubscriptionResult> {
+    const [config, approvedMint] = await Promise.all([
+      this.getConfig(),
+      this.getApprovedMint(mint),
+    ]);
+    const subscriptionId = config.subscriptionCounter;
+
+    const ix = createCreateTokenSubscriptionInstruction(
+      payer.publicKey,
+      subscriptionId,
+      mint,
+      approvedMint.tokenProgram,
+      this.programId
+    );
+
+    const tx = new Transaction().add(ix);
+    await sendAndConfirmTransaction(this.connection, tx, [payer]);
+
+    const [subscriptionPda] = getSubscriptionPda(subscriptionId, this.programId);
+    return { subscriptionId, subscriptionPda };
+  }
+
+  /**
+   * Fund a token-billed subscription from the payer's associated token account.
+   *
+   * @param payer - The keypair funding the subscription.
+   * @param subscriptionId - The subscription to fund.
+   * @param amount - Amount in the billing mint's base units.
+   */
+  async fundSubscriptionToken(
+    payer: Keypair,
+    subscriptionId: BN | number,
+    amount: BN
+  ): Promise<void> {
+    const { mint, tokenProgram } = await this.getTokenBilling(subscriptionId);
+    const ix = createFundSubscriptionTokenInstruction(
+      payer.publicKey,
+      subscriptionId,
+      mint,
+      getAssociatedTokenAddress(mint, payer.publicKey, tokenProgram),
+      amount,
+      tokenProgram,
+      this.programId
+    );
+
+    const tx = new Transaction().add(ix);
+    await sendAndConfirmTransaction(this.connection, tx, [payer]);
+  }
+
+  /**
+   * Withdraw part of a token-billed subscription's unspent balance. Tokens
+   * held for pending requests stay in the subscription vault.
+   *
+   * @param owner - The subscription owner keypair.
+   * @param subscriptionId - The subscription to withdraw from.
+   * @param amount - Amount in the billing mint's base units.
+   * @param recipient - Token account receiving the tokens. Defaults to the owner's associated token account.
+   */
+  async withdrawSubscriptionTokens(
+    owner: Keypair,
+    subscriptionId: BN | number,
+    amount: BN,
+    recipient?: PublicKey
+  ): Promise<void> {
+    const { mint, tokenProgram } = await this.getTokenBilling(subscriptionId);
+    const ix = createWithdrawSubscriptionTokensInstruction(
+      owner.publicKey,
+      subscriptionId,
+      mint,
+      recipient ?? getAssociatedTokenAddress(mint, owner.publicKey, tokenProgram),
+      amount,
+      tokenProgram,
+      this.programId
+    );
+
+    const tx = new Transaction().add(ix);
+    await sendAndConfirmTransaction(this.connection, tx, [owner]);
+  }
+
+  /**
+   * Cancel a token-billed subscription, returning its tokens and closing its
+   * vault. Requires all consumers to be removed first.
+   *
+   * @param owner - The subscription owner keypair.
+   * @param subscriptionId - The subscription to cancel.
+   * @param recipient - Token account receiving the tokens. Defaults to the owner's associated token account.
+   */
+  async cancelTokenSubscription(
+    owner: Keypair,
+    subscriptionId: BN | number,
+    recipient?: PublicKey
+  ): Promise<void> {
+    const { mint, tokenProgram } = await this.getTokenBilling(subscriptionId);
+    const ix = createCancelTokenSubscriptionInstruction(
+      owner.publicKey,
+      subscriptionId,
+      mint,
+      recipient ?? getAssociatedTokenAddress(mint, owner.publicKey, tokenProgram),
+      tokenProgram,
+      this.programId
+    );
+
+    const tx = new Transaction().add(ix);
+    await sendAndConfirmTransaction(this.connection, tx, [owner]);
+  }
+
+  /** Resolve the billing mint and its token program for a token-billed subscription. */
+  private async getTokenBilling(
+    subscriptionId: BN | number
+  ): Promise<{ mint: PublicKey; tokenProgram: PublicKey }> {
+    const subscription = await this.getSubscription(subscriptionId);
+    if (subscription.billingMint.equals(PublicKey.default)) {
+      throw new Error(
+        `Subscription ${subscriptionId.toString()} is billed in SOL`
+      );
+    }
+    const approvedMint = await this.getApprovedMint(subscription.billingMint);
+    return {
+      mint: subscription.billingMint,
+      tokenProgram: approvedMint.tokenProgram,
+    };
+  }
+
+  /**
+   * Cancel a request the oracle has not fulfilled within the coordinator's
+   * `requestTimeoutSlots`. The fee is re-credited to the subscription and the
+   * rent returned to the original requester.
+   *
+   * @param caller - The original requester or the subscription owner.
+   * @param requestId - The expired request.
+   */
+  async cancelRequest(
+    caller: Keypair,
+    requestId: BN | number
+  ): Promise<void> {
+    const request = await this.getRequest(requestId);
+    const ix = createCancelRequestInstruction(
+      caller.publicKey,
+      requestId,
+      request.subscriptionId,
+      request.requester,
+      this.programId
+    );
+
+    const tx = new Transaction().add(ix);
+    await sendAndConfirmTransaction(this.connection, tx, [caller]);
+  }
+
+  /**
+   * Close the callback accounts sidecar of a fulfilled, consumed or cancelled
+   * request. Rent goes back to the requester; anyone may pay the fee.
+   *
+   * @param payer - Fee payer.
+   * @param requestId - The closed request.
+   * @param requester - The request's original requester.
+   */
+  async closeCallbackAccounts(
+    payer: Keypair,
+    requestId: BN | number,
+    requester: PublicKey
+  ): Promise<void> {
+    const ix = createCloseCallbackAccountsInstruction(
+      requestId,
+      requester,
+      this.programId
+    );
+
+    const tx = new Transaction().add(ix);
+    await sendAndConfirmTransaction(this.connection, tx, [payer]);
+  }
+
+  /**
+   * Request randomness straight from a wallet, with no consumer program.
+   * The requester pays the direct request fee (`getDirectRequestFee(numWords, 0)`)
+   * plus rent for the request and result PDAs, and for their nonce on the
+   * first request. Once fulfilled, read the output with `getRandomnessResult`.
+   *
+   * @param requester - Signs and pays.
+   * @param numWords - Number of random words.
+   * @param seed - 32 bytes of caller entropy.
+   * @param minConfirmationSlots - Extra confirmation slots to wait (default: coordinator minimum).
+   */
+  async requestWalletRandomness(
+    requester: Keypair,
+    numWords: number,
+    seed: Uint8Array,
+    minConfirmationSlots: BN | number = 0
+  ): Promise<RequestRandomWordsResult> {
+    const tx = new Transaction();
+    if (!(await this.getRequesterNonce(requester.publicKey))) {
+      tx.add(createCreateRequesterNonceInstruction(requester.publicKey, this.programId));
+    }
+    const requestId = await this.getNextDirectRequestId(requester.publicKey);
+    const ix = createRequestWalletRandomnessInstruction(
+      requester.publicKey,
+      requestId,
+      numWords,
+      seed,
+      minConfirmationSlots,
+      this.programId
+    );
+
+    tx.add(ix);
+    await sendAndConfirmTransaction(this.connection, tx, [requester]);
+
+    const [requestPda] = getRequestPda(requestId, this.programId);
+    return { requestId, requestPda };
+  }
+
+  /**
+   * Close the `RandomnessResult` of a fulfilled or cancelled wallet request,
+   * returning its rent.
+   *
+   * @param requester - The wallet that made the request.
+   * @param requestId - The closed request.
+   */
+  async closeRandomnessResult(
+    requester: Keypair,
+    requestId: BN | number
+  ): Promise<void> {
+    const ix = createCloseRandomnessResultInstruction(
+      requester.publicKey,
+      requestId,
+      this.programId
+    );
+
+    const tx = new Transaction().add(ix);
+    await sendAndConfirmTransaction(this.connection, tx, [requester]);
+  }
+
+  /**
+   * Withdraw oracle earnings from the fee vault.
+   *
+   * @param authority - The oracle authority keypair (`config.authority`).
+   * @param recipient - Receives the lamports.
+   * @param amount - Lamports to withdraw, at most `getFeeVault().oracleFees`.
+   */
+  async withdrawOracleFees(
+    authority: Keypair,
+    recipient: PublicKey,
+    amount: BN | number
+  ): Promise<void> {
+    const ix = createWithdrawOracleFeesInstruction(
+      authority.publicKey,
+      recipient,
+      amount,
+      this.programId
+    );
+
+    const tx = new Transaction().add(ix);
+    await sendAndConfirmTransaction(this.connection, tx, [authority]);
+  }
+
   // ---------------------------------------------------------------------------
   // Request monitoring
   // ---------------------------------------------------------------------------
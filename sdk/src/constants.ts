@@ -5,6 +5,21 @@ export const VRF_PROGRAM_ID = new PublicKey(
   "A4pDDsKvtX2U3jyEURVSoH15Mx4JcgUiSqCKxqWE3N48"
 );
 
+/** SPL Token program. */
+export const TOKEN_PROGRAM_ID = new PublicKey(
+  "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+);
+
+/** SPL Token-2022 program. */
+export const TOKEN_2022_PROGRAM_ID = new PublicKey(
+  "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb"
+);
+
+/** SPL Associated Token Account program. */
+export const ASSOCIATED_TOKEN_PROGRAM_ID = new PublicKey(
+  "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+);
+
 // Pre-computed Anchor instruction discriminators from IDL.
 // sha256("global:<name>")[..8]
 export const DISCRIMINATORS = {
@@ -12,11 +27,44 @@ export const DISCRIMINATORS = {
   createSubscription: Buffer.from([206, 36, 102, 105, 21, 168, 239, 202]),
   fundSubscription: Buffer.from([167, 167, 19, 164, 121, 57, 237, 14]),
   cancelSubscription: Buffer.from([10, 118, 244, 87, 214, 118, 97, 15]),
+  withdrawSubscriptionFunds: Buffer.from([75, 31, 134, 155, 169, 245, 138, 78]),
+  requestSubscriptionOwnerTransfer: Buffer.from([158, 103, 243, 228, 193, 207, 231, 4]),
+  acceptSubscriptionOwnerTransfer: Buffer.from([248, 40, 53, 81, 145, 19, 44, 107]),
   addConsumer: Buffer.from([214, 196, 187, 148, 104, 86, 254, 106]),
   removeConsumer: Buffer.from([2, 200, 66, 138, 109, 184, 73, 135]),
+  setConsumerLimits: Buffer.from([199, 49, 168, 140, 116, 159, 55, 11]),
+  setConsumerCallback: Buffer.from([35, 153, 211, 216, 198, 222, 56, 94]),
   requestRandomWords: Buffer.from([197, 218, 104, 215, 7, 30, 16, 229]),
+  requestRandomWordsDirect: Buffer.from([245, 138, 42, 214, 18, 44, 124, 35]),
+  requestWalletRandomness: Buffer.from([149, 192, 19, 169, 251, 107, 201, 146]),
   fulfillRandomWords: Buffer.from([241, 31, 92, 116, 42, 230, 221, 188]),
   updateConfig: Buffer.from([29, 158, 252, 191, 10, 83, 219, 99]),
+  cancelRequest: Buffer.from([65, 196, 177, 247, 83, 151, 33, 130]),
+  retryCallback: Buffer.from([78, 138, 77, 248, 184, 181, 173, 194]),
+  consumeRandomness: Buffer.from([190, 217, 49, 162, 99, 26, 73, 234]),
+  consumeRandomnessSeed: Buffer.from([212, 143, 15, 151, 221, 143, 32, 9]),
+  closeCallbackAccounts: Buffer.from([53, 62, 55, 129, 227, 161, 229, 178]),
+  closeRandomnessResult: Buffer.from([33, 88, 196, 103, 223, 202, 254, 167]),
+  withdrawOracleFees: Buffer.from([163, 0, 240, 161, 227, 136, 143, 245]),
+  withdrawProtocolFees: Buffer.from([11, 68, 165, 98, 18, 208, 134, 73]),
+  approveMint: Buffer.from([143, 34, 125, 63, 7, 27, 138, 164]),
+  updateApprovedMint: Buffer.from([166, 35, 165, 187, 39, 162, 67, 28]),
+  createTokenSubscription: Buffer.from([215, 105, 163, 193, 9, 120, 2, 231]),
+  fundSubscriptionToken: Buffer.from([175, 45, 175, 60, 226, 242, 137, 52]),
+  withdrawSubscriptionTokens: Buffer.from([9, 190, 26, 109, 113, 231, 61, 220]),
+  cancelTokenSubscription: Buffer.from([127, 174, 109, 38, 161, 208, 211, 229]),
+  withdrawOracleTokenFees: Buffer.from([92, 191, 106, 29, 209, 131, 91, 94]),
+  withdrawProtocolTokenFees: Buffer.from([199, 218, 182, 65, 113, 201, 12, 63]),
+  pause: Buffer.from([211, 22, 221, 251, 74, 121, 193, 47]),
+  unpause: Buffer.from([169, 144, 4, 38, 10, 141, 188, 255]),
+  executeConfigUpdate: Buffer.from([132, 11, 62, 107, 12, 61, 38, 230]),
+  cancelConfigUpdate: Buffer.from([216, 180, 255, 207, 118, 146, 126, 89]),
+  acceptAdminTransfer: Buffer.from([89, 211, 96, 212, 233, 0, 251, 7]),
+  migrateConfig: Buffer.from([92, 131, 58, 105, 210, 154, 224, 193]),
+  migrateSubscription: Buffer.from([247, 8, 63, 1, 206, 114, 74, 211]),
+  migrateRegistration: Buffer.from([122, 59, 106, 247, 177, 226, 242, 237]),
+  migrateRequest: Buffer.from([19, 233, 76, 36, 18, 94, 141, 206]),
+  createRequesterNonce: Buffer.from([100, 9, 3, 61, 82, 208, 209, 136]),
 } as const;
 
 // Pre-computed Anchor account discriminators from IDL.
@@ -26,14 +74,77 @@ export const ACCOUNT_DISCRIMINATORS = {
   Subscription: Buffer.from([64, 7, 26, 135, 102, 132, 98, 33]),
   ConsumerRegistration: Buffer.from([214, 111, 64, 176, 202, 160, 126, 150]),
   RandomnessRequest: Buffer.from([244, 231, 228, 160, 148, 28, 17, 184]),
+  CallbackAccounts: Buffer.from([120, 209, 103, 64, 225, 67, 252, 65]),
+  RandomnessResult: Buffer.from([169, 208, 50, 154, 97, 106, 134, 7]),
+  FeeVault: Buffer.from([192, 178, 69, 232, 58, 149, 157, 132]),
+  ApprovedMint: Buffer.from([110, 224, 178, 194, 82, 202, 156, 181]),
+  PendingConfigUpdate: Buffer.from([164, 123, 113, 201, 221, 145, 32, 155]),
+  RequesterNonce: Buffer.from([33, 59, 160, 23, 104, 108, 21, 118]),
 } as const;
 
+/** Maximum number of registered oracles (multi-oracle scheme). */
+export const MAX_ORACLES = 8;
+
+/** Maximum callback accounts stored inline in a request. */
+export const MAX_CALLBACK_ACCOUNTS = 4;
+
+/** Maximum callback accounts in a `CallbackAccounts` sidecar. */
+export const MAX_SIDECAR_CALLBACK_ACCOUNTS = 32;
+
+/** Maximum `user_data` bytes a request can carry to its callback. */
+export const MAX_USER_DATA_LEN = 128;
+
+/** Maximum callback instruction prefix bytes (empty = Anchor `fulfill_random_words`). */
+export const MAX_CALLBACK_PREFIX_LEN = 8;
+
+/** Maximum `num_words` for a store-only request (return data limit). */
+export const MAX_STORE_ONLY_WORDS = 31;
+
+/** Low bits of a request ID holding the subscription's request index (`reqCount`). */
+export const REQUEST_INDEX_BITS = 32;
+
+/** Subscription ID recorded on direct-funded requests, which have no subscription. */
+export const DIRECT_SUBSCRIPTION_ID = 2 ** 32 - 1;
+
+/** Upper bound for `protocolFeeBps` (100%). */
+export const MAX_PROTOCOL_FEE_BPS = 10_000;
+
+/** Lower bound for `configUpdateDelaySlots`. */
+export const MIN_CONFIG_UPDATE_DELAY_SLOTS = 10;
+
+/** Upper bound for `configUpdateDelaySlots` (~30 days). */
+export const MAX_CONFIG_UPDATE_DELAY_SLOTS = 6_480_000;
+
+/** Recommended `configUpdateDelaySlots` (~1 hour); given to coordinators migrated from before the timelock. */
+export const DEFAULT_CONFIG_UPDATE_DELAY_SLOTS = 9_000;
+
+/** `computeUnitPrice` units per base unit of the billing currency (micro-lamports per lamport). */
+export const COMPUTE_UNIT_PRICE_SCALE = 1_000_000;
+
 /** Anchor account space: 8-byte discriminator + struct fields. */
-// CoordinatorConfig: admin(32) + authority(32) + fee_per_word(8) + max_num_words(4) + request_counter(8) + subscription_counter(8) + bump(1)
-export const COORDINATOR_CONFIG_SIZE = 8 + 32 + 32 + 8 + 4 + 8 + 8 + 1; // 101 bytes
-// Subscription: id(8) + owner(32) + balance(8) + req_count(8) + consumer_count(4) + bump(1)
-export const SUBSCRIPTION_SIZE = 8 + 8 + 32 + 8 + 8 + 4 + 1; // 69 bytes
-// ConsumerRegistration: subscription_id(8) + program_id(32) + nonce(8) + bump(1)
-export const CONSUMER_REGISTRATION_SIZE = 8 + 8 + 32 + 8 + 1; // 57 bytes
-// RandomnessRequest: request_id(8) + subscription_id(8) + consumer_program(32) + requester(32) + num_words(4) + seed(32) + request_slot(8) + callback_compute_limit(4) + status(1) + randomness(32) + fulfilled_slot(8) + bump(1)
-export const RANDOMNESS_REQUEST_SIZE = 8 + 8 + 8 + 32 + 32 + 4 + 32 + 8 + 4 + 1 + 32 + 8 + 1; // 178 bytes
+
+// CoordinatorConfig: admin(32) + authority(32) + fee_per_word(8) + max_num_words(4) + request_counter(8) + subscription_counter(8) + bump(1) + proof_scheme(1) + oracle_count(1) + oracle_threshold(1) + oracles(32 * 8) + request_timeout_slots(8) + protocol_fee_bps(2) + base_fee(8) + compute_unit_price(8) + max_callback_compute_limit(4) + min_confirmation_slots(8) + pauser(32) + paused(1) + config_update_delay_slots(8) + pending_admin(32)
+export const COORDINATOR_CONFIG_SIZE = 8 + 32 + 32 + 8 + 4 + 8 + 8 + 1 + 1 + 1 + 1 + 32 * MAX_ORACLES + 8 + 2 + 8 + 8 + 4 + 8 + 32 + 1 + 8 + 32; // 471 bytes
+// Subscription: id(8) + owner(32) + balance(8) + req_count(8) + consumer_count(4) + bump(1) + pending_fees(8) + pending_owner(32) + billing_mint(32)
+export const SUBSCRIPTION_SIZE = 8 + 8 + 32 + 8 + 8 + 4 + 1 + 8 + 32 + 32; // 141 bytes
+// ConsumerRegistration: subscription_id(8) + program_id(32) + nonce(8) + bump(1) + max_fee_per_epoch(8) + max_requests_per_window(4) + request_window_slots(8) + max_num_words(4) + fee_epoch(8) + epoch_fees(8) + window_start_slot(8) + window_requests(4) + total_requests(8) + total_fees_paid(8) + last_request_slot(8) + callback_prefix_len(1) + callback_prefix(8)
+export const CONSUMER_REGISTRATION_SIZE = 8 + 8 + 32 + 8 + 1 + 8 + 4 + 8 + 4 + 8 + 8 + 8 + 4 + 8 + 8 + 8 + 1 + 8; // 142 bytes
+// RandomnessRequest: request_id(8) + subscription_id(8) + consumer_program(32) + requester(32) + num_words(4) + seed(32) + request_slot(8) + callback_compute_limit(4) + status(1) + randomness(32) + fulfilled_slot(8) + bump(1) + callback_account_count(1) + callback_account_keys(32 * 4) + callback_writable_bitmap(1) + fee_paid(8) + callback_attempts(1) + store_only(1) + callback_sidecar(1) + fee_mint(32) + min_confirmation_slots(8) + callback_version(1) + user_data(4 + n) + callback_prefix_len(1) + callback_prefix(8)
+export const RANDOMNESS_REQUEST_SIZE = 8 + 8 + 8 + 32 + 32 + 4 + 32 + 8 + 4 + 1 + 32 + 8 + 1 + 1 + 32 * 4 + 1 + 8 + 1 + 1 + 1 + 32 + 8 + 1 + 4 + 1 + 8; // 373 bytes with no user data
+export function randomnessRequestSize(userDataLength: number): number {
+  return RANDOMNESS_REQUEST_SIZE + userDataLength;
+}
+// RandomnessResult: request_id(8) + requester(32) + num_words(4) + seed(32) + request_slot(8) + randomness(32) + fulfilled_slot(8) + bump(1)
+export const RANDOMNESS_RESULT_SIZE = 8 + 8 + 32 + 4 + 32 + 8 + 32 + 8 + 1; // 133 bytes
+// FeeVault: oracle_fees(8) + protocol_fees(8) + bump(1)
+export const FEE_VAULT_SIZE = 8 + 8 + 8 + 1; // 25 bytes
+// RequesterNonce: requester(32) + id(8) + req_count(8) + bump(1)
+export const REQUESTER_NONCE_SIZE = 8 + 32 + 8 + 8 + 1; // 57 bytes
+// ApprovedMint: mint(32) + token_program(32) + fee_per_word(8) + enabled(1) + oracle_fees(8) + protocol_fees(8) + bump(1) + base_fee(8) + compute_unit_price(8)
+export const APPROVED_MINT_SIZE = 8 + 32 + 32 + 8 + 1 + 8 + 8 + 1 + 8 + 8; // 114 bytes
+// PendingConfigUpdate: changes (14 Options, 430 max) + proposed_slot(8) + executable_slot(8) + bump(1)
+export const PENDING_CONFIG_UPDATE_SIZE = 8 + 430 + 8 + 8 + 1; // 455 bytes
+// CallbackAccounts: request_id(8) + requester(32) + lookup_table(32) + bump(1) + accounts(4 + 33 * n)
+export function callbackAccountsSize(count: number): number {
+  return 8 + 8 + 32 + 32 + 1 + 4 + 33 * count;
+}
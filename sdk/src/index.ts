@@ -1,12 +1,35 @@
 // Constants
 export {
   VRF_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
   DISCRIMINATORS,
   ACCOUNT_DISCRIMINATORS,
   COORDINATOR_CONFIG_SIZE,
+  MAX_ORACLES,
+  MAX_CALLBACK_ACCOUNTS,
+  MAX_SIDECAR_CALLBACK_ACCOUNTS,
+  MAX_STORE_ONLY_WORDS,
+  MAX_USER_DATA_LEN,
+  MAX_CALLBACK_PREFIX_LEN,
+  MAX_PROTOCOL_FEE_BPS,
+  MIN_CONFIG_UPDATE_DELAY_SLOTS,
+  MAX_CONFIG_UPDATE_DELAY_SLOTS,
+  DEFAULT_CONFIG_UPDATE_DELAY_SLOTS,
+  REQUEST_INDEX_BITS,
+  DIRECT_SUBSCRIPTION_ID,
+  COMPUTE_UNIT_PRICE_SCALE,
   SUBSCRIPTION_SIZE,
+  FEE_VAULT_SIZE,
+  REQUESTER_NONCE_SIZE,
+  APPROVED_MINT_SIZE,
+  PENDING_CONFIG_UPDATE_SIZE,
   CONSUMER_REGISTRATION_SIZE,
   RANDOMNESS_REQUEST_SIZE,
+  randomnessRequestSize,
+  RANDOMNESS_RESULT_SIZE,
+  callbackAccountsSize,
 } from "./constants";
 
 // PDA derivation
@@ -15,15 +38,38 @@ export {
   getSubscriptionPda,
   getConsumerPda,
   getRequestPda,
+  getRequestId,
+  getFeeVaultPda,
+  getRequesterNoncePda,
+  getRequesterNonceCounterPda,
+  getApprovedMintPda,
+  getPendingConfigUpdatePda,
+  getAssociatedTokenAddress,
+  getConsumerAuthorityPda,
+  getCallbackAccountsPda,
+  getRandomnessResultPda,
 } from "./pda";
 
 // Types
 export {
   RequestStatus,
+  ProofScheme,
+  CallbackVersion,
   type CoordinatorConfig,
   type SubscriptionAccount,
+  type FeeVaultAccount,
+  type RequesterNonceAccount,
+  type FeeSchedule,
+  type ApprovedMintAccount,
+  type ConfigChanges,
+  type OracleSet,
+  type PendingConfigUpdateAccount,
   type ConsumerRegistrationAccount,
+  type ConsumerLimits,
   type RandomnessRequestAccount,
+  type RandomnessResultAccount,
+  type CallbackAccountMeta,
+  type CallbackAccountsAccount,
   type CreateSubscriptionResult,
   type RequestRandomWordsResult,
   type WaitForFulfillmentOptions,
@@ -33,8 +79,15 @@ export {
 export {
   decodeCoordinatorConfig,
   decodeSubscription,
+  decodeFeeVault,
+  decodeRequesterNonce,
+  decodeApprovedMint,
   decodeConsumerRegistration,
   decodeRandomnessRequest,
+  decodeCallbackAccounts,
+  decodeRandomnessResult,
+  decodePendingConfigUpdate,
+  decodeRequestReceipt,
 } from "./accounts";
 
 // Low-level instruction builders
@@ -44,11 +97,40 @@ export {
   createFundSubscriptionInstruction,
   createAddConsumerInstruction,
   createRemoveConsumerInstruction,
+  createSetConsumerLimitsInstruction,
+  createWithdrawSubscriptionFundsInstruction,
+  createRequestSubscriptionOwnerTransferInstruction,
+  createAcceptSubscriptionOwnerTransferInstruction,
   createCancelSubscriptionInstruction,
+  createCancelRequestInstruction,
+  createRetryCallbackInstruction,
+  createCloseCallbackAccountsInstruction,
+  createCreateRequesterNonceInstruction,
+  createRequestWalletRandomnessInstruction,
+  createCloseRandomnessResultInstruction,
+  createWithdrawOracleFeesInstruction,
+  createWithdrawProtocolFeesInstruction,
+  createExecuteConfigUpdateInstruction,
+  createCancelConfigUpdateInstruction,
+  createAcceptAdminTransferInstruction,
+  createPauseInstruction,
+  createUnpauseInstruction,
+  createApproveMintInstruction,
+  createUpdateApprovedMintInstruction,
+  createCreateTokenSubscriptionInstruction,
+  createFundSubscriptionTokenInstruction,
+  createWithdrawSubscriptionTokensInstruction,
+  createCancelTokenSubscriptionInstruction,
+  createWithdrawOracleTokenFeesInstruction,
+  createWithdrawProtocolTokenFeesInstruction,
+  createMigrateConfigInstruction,
+  createMigrateSubscriptionInstruction,
+  createMigrateRegistrationInstruction,
+  createMigrateRequestInstruction,
 } from "./instructions";
 
 // Utilities
-export { waitForFulfillment, addPriorityFee } from "./utils";
+export { waitForFulfillment, addPriorityFee, computeRequestFee } from "./utils";
 
 // High-level client
 export { MoiraeVrf } from "./client";
@@ -1,31 +1,58 @@
 import {
+  type AccountMeta,
   PublicKey,
   SystemProgram,
   TransactionInstruction,
 } from "@solana/web3.js";
 import BN from "bn.js";
-import { DISCRIMINATORS, VRF_PROGRAM_ID } from "./constants";
-import { getConfigPda, getSubscriptionPda, getConsumerPda, getRequestPda } from "./pda";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  DEFAULT_CONFIG_UPDATE_DELAY_SLOTS,
+  DIRECT_SUBSCRIPTION_ID,
+  DISCRIMINATORS,
+  TOKEN_PROGRAM_ID,
+  VRF_PROGRAM_ID,
+} from "./constants";
+import {
+  getApprovedMintPda,
+  getAssociatedTokenAddress,
+  getConfigPda,
+  getSubscriptionPda,
+  getConsumerPda,
+  getRequestPda,
+  getCallbackAccountsPda,
+  getRandomnessResultPda,
+  getFeeVaultPda,
+  getPendingConfigUpdatePda,
+  getRequesterNoncePda,
+  getRequesterNonceCounterPda,
+} from "./pda";
+import { ConsumerLimits } from "./types";
 
 /**
- * Create an `initialize` instruction for the coordinator config.
+ * Create an `initialize` instruction for the coordinator config and fee vault.
+ * `configUpdateDelaySlots` must lie between `MIN_CONFIG_UPDATE_DELAY_SLOTS`
+ * and `MAX_CONFIG_UPDATE_DELAY_SLOTS`.
  *
- * Accounts: [admin (signer, writable), authority, config (writable), system_program]
+ * Accounts: [admin (signer, writable), authority, config (writable), fee_vault (writable), system_program]
  */
 export function createInitializeInstruction(
   admin: PublicKey,
   authority: PublicKey,
   feePerWord: BN,
   maxNumWords: number,
+  configUpdateDelaySlots: BN = new BN(DEFAULT_CONFIG_UPDATE_DELAY_SLOTS),
   programId: PublicKey = VRF_PROGRAM_ID
 ): TransactionInstruction {
   const [configPda] = getConfigPda(programId);
+  const [feeVaultPda] = getFeeVaultPda(programId);
 
-  // data: disc(8) + fee_per_word(8) + max_num_words(4)
-  const data = Buffer.alloc(8 + 8 + 4);
+  // data: disc(8) + fee_per_word(8) + max_num_words(4) + config_update_delay_slots(8)
+  const data = Buffer.alloc(8 + 8 + 4 + 8);
   DISCRIMINATORS.initialize.copy(data, 0);
   feePerWord.toArrayLike(Buffer, "le", 8).copy(data, 8);
   data.writeUInt32LE(maxNumWords, 16);
+  configUpdateDelaySlots.toArrayLike(Buffer, "le", 8).copy(data, 20);
 
   return new TransactionInstruction({
     programId,
@@ -33,6 +60,7 @@ export function createInitializeInstruction(
       { pubkey: admin, isSigner: true, isWritable: true },
       { pubkey: authority, isSigner: false, isWritable: false },
       { pubkey: configPda, isSigner: false, isWritable: true },
+      { pubkey: feeVaultPda, isSigner: false, isWritable: true },
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
     ],
     data,
@@ -162,6 +190,134 @@ export function createRemoveConsumerInstruction(
   });
 }
 
+/**
+ * Create a `set_consumer_limits` instruction.
+ *
+ * Accounts: [owner (signer), subscription, consumer_program, consumer_registration (writable)]
+ */
+export function createSetConsumerLimitsInstruction(
+  owner: PublicKey,
+  subscriptionId: BN | number,
+  consumerProgramId: PublicKey,
+  limits: ConsumerLimits,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  const [subscriptionPda] = getSubscriptionPda(subscriptionId, programId);
+  const [consumerPda] = getConsumerPda(subscriptionId, consumerProgramId, programId);
+  const id = new BN(subscriptionId.toString());
+
+  // data: disc(8) + subscription_id(8) + max_fee_per_epoch(8) + max_requests_per_window(4) + request_window_slots(8) + max_num_words(4)
+  const data = Buffer.alloc(8 + 8 + 8 + 4 + 8 + 4);
+  DISCRIMINATORS.setConsumerLimits.copy(data, 0);
+  id.toArrayLike(Buffer, "le", 8).copy(data, 8);
+  limits.maxFeePerEpoch.toArrayLike(Buffer, "le", 8).copy(data, 16);
+  data.writeUInt32LE(limits.maxRequestsPerWindow, 24);
+  limits.requestWindowSlots.toArrayLike(Buffer, "le", 8).copy(data, 28);
+  data.writeUInt32LE(limits.maxNumWords, 36);
+
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: owner, isSigner: true, isWritable: false },
+      { pubkey: subscriptionPda, isSigner: false, isWritable: false },
+      { pubkey: consumerProgramId, isSigner: false, isWritable: false },
+      { pubkey: consumerPda, isSigner: false, isWritable: true },
+    ],
+    data,
+  });
+}
+
+/**
+ * Create a `withdraw_subscription_funds` instruction.
+ *
+ * Accounts: [owner (signer), subscription (writable), recipient (writable)]
+ */
+export function createWithdrawSubscriptionFundsInstruction(
+  owner: PublicKey,
+  subscriptionId: BN | number,
+  amount: BN,
+  recipient: PublicKey = owner,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  const [subscriptionPda] = getSubscriptionPda(subscriptionId, programId);
+  const id = new BN(subscriptionId.toString());
+
+  // data: disc(8) + subscription_id(8) + amount(8)
+  const data = Buffer.alloc(8 + 8 + 8);
+  DISCRIMINATORS.withdrawSubscriptionFunds.copy(data, 0);
+  id.toArrayLike(Buffer, "le", 8).copy(data, 8);
+  amount.toArrayLike(Buffer, "le", 8).copy(data, 16);
+
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: owner, isSigner: true, isWritable: false },
+      { pubkey: subscriptionPda, isSigner: false, isWritable: true },
+      { pubkey: recipient, isSigner: false, isWritable: true },
+    ],
+    data,
+  });
+}
+
+/**
+ * Create a `request_subscription_owner_transfer` instruction. Pass
+ * `PublicKey.default` as `newOwner` to withdraw a pending proposal.
+ *
+ * Accounts: [owner (signer), subscription (writable)]
+ */
+export function createRequestSubscriptionOwnerTransferInstruction(
+  owner: PublicKey,
+  subscriptionId: BN | number,
+  newOwner: PublicKey,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  const [subscriptionPda] = getSubscriptionPda(subscriptionId, programId);
+  const id = new BN(subscriptionId.toString());
+
+  // data: disc(8) + subscription_id(8) + new_owner(32)
+  const data = Buffer.alloc(8 + 8 + 32);
+  DISCRIMINATORS.requestSubscriptionOwnerTransfer.copy(data, 0);
+  id.toArrayLike(Buffer, "le", 8).copy(data, 8);
+  newOwner.toBuffer().copy(data, 16);
+
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: owner, isSigner: true, isWritable: false },
+      { pubkey: subscriptionPda, isSigner: false, isWritable: true },
+    ],
+    data,
+  });
+}
+
+/**
+ * Create an `accept_subscription_owner_transfer` instruction.
+ *
+ * Accounts: [new_owner (signer), subscription (writable)]
+ */
+export function createAcceptSubscriptionOwnerTransferInstruction(
+  newOwner: PublicKey,
+  subscriptionId: BN | number,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  const [subscriptionPda] = getSubscriptionPda(subscriptionId, programId);
+  const id = new BN(subscriptionId.toString());
+
+  // data: disc(8) + subscription_id(8)
+  const data = Buffer.alloc(8 + 8);
+  DISCRIMINATORS.acceptSubscriptionOwnerTransfer.copy(data, 0);
+  id.toArrayLike(Buffer, "le", 8).copy(data, 8);
+
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: newOwner, isSigner: true, isWritable: false },
+      { pubkey: subscriptionPda, isSigner: false, isWritable: true },
+    ],
+    data,
+  });
+}
+
 /**
  * Create a `cancel_subscription` instruction.
  *
@@ -189,3 +345,903 @@ export function createCancelSubscriptionInstruction(
     data,
   });
 }
+
+/**
+ * Create a `cancel_request` instruction for an expired pending request.
+ *
+ * For direct-funded requests (`subscriptionId` is `DIRECT_SUBSCRIPTION_ID`)
+ * the subscription is omitted; the fee is refunded from the request PDA.
+ *
+ * Accounts: [caller (signer), config, request (writable), subscription (optional, writable), requester (writable)]
+ */
+export function createCancelRequestInstruction(
+  caller: PublicKey,
+  requestId: BN | number,
+  subscriptionId: BN | number,
+  requester: PublicKey,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  const [configPda] = getConfigPda(programId);
+  const [requestPda] = getRequestPda(requestId, programId);
+  const [subscriptionPda] = getSubscriptionPda(subscriptionId, programId);
+  const id = new BN(requestId.toString());
+  const direct = new BN(subscriptionId.toString()).eqn(DIRECT_SUBSCRIPTION_ID);
+
+  // data: disc(8) + request_id(8)
+  const data = Buffer.alloc(8 + 8);
+  DISCRIMINATORS.cancelRequest.copy(data, 0);
+  id.toArrayLike(Buffer, "le", 8).copy(data, 8);
+
+  // Anchor encodes an absent optional account as the program ID.
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: caller, isSigner: true, isWritable: false },
+      { pubkey: configPda, isSigner: false, isWritable: false },
+      { pubkey: requestPda, isSigner: false, isWritable: true },
+      direct
+        ? { pubkey: programId, isSigner: false, isWritable: false }
+        : { pubkey: subscriptionPda, isSigner: false, isWritable: true },
+      { pubkey: requester, isSigner: false, isWritable: true },
+    ],
+    data,
+  });
+}
+
+/**
+ * Create a `retry_callback` instruction for a request whose randomness is
+ * stored with `RequestStatus.CallbackFailed`.
+ *
+ * `callbackAccounts` must be exactly the stored callback accounts, in order;
+ * set `callbackSidecar` when the request keeps them in a sidecar.
+ *
+ * Accounts: [caller (signer), config, request (writable), requester (writable), consumer_program, callback_accounts (optional), ...callbackAccounts]
+ */
+export function createRetryCallbackInstruction(
+  caller: PublicKey,
+  requestId: BN | number,
+  requester: PublicKey,
+  consumerProgram: PublicKey,
+  callbackAccounts: AccountMeta[] = [],
+  callbackSidecar = false,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  const [configPda] = getConfigPda(programId);
+  const [requestPda] = getRequestPda(requestId, programId);
+  const id = new BN(requestId.toString());
+
+  // data: disc(8) + request_id(8) + record_failure(1, `None`)
+  const data = Buffer.alloc(8 + 8 + 1);
+  DISCRIMINATORS.retryCallback.copy(data, 0);
+  id.toArrayLike(Buffer, "le", 8).copy(data, 8);
+
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: caller, isSigner: true, isWritable: false },
+      { pubkey: configPda, isSigner: false, isWritable: false },
+      { pubkey: requestPda, isSigner: false, isWritable: true },
+      { pubkey: requester, isSigner: false, isWritable: true },
+      { pubkey: consumerProgram, isSigner: false, isWritable: false },
+      // Anchor encodes an absent optional account as the program ID.
+      {
+        pubkey: callbackSidecar
+          ? getCallbackAccountsPda(requestId, programId)[0]
+          : programId,
+        isSigner: false,
+        isWritable: false,
+      },
+      ...callbackAccounts,
+    ],
+    data,
+  });
+}
+
+/**
+ * Create a `close_callback_accounts` instruction for a request whose PDA has
+ * been closed. Permissionless; the rent goes back to the requester.
+ *
+ * Accounts: [callback_accounts (writable), request, requester (writable)]
+ */
+export function createCloseCallbackAccountsInstruction(
+  requestId: BN | number,
+  requester: PublicKey,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  const [sidecarPda] = getCallbackAccountsPda(requestId, programId);
+  const [requestPda] = getRequestPda(requestId, programId);
+  const id = new BN(requestId.toString());
+
+  // data: disc(8) + request_id(8)
+  const data = Buffer.alloc(8 + 8);
+  DISCRIMINATORS.closeCallbackAccounts.copy(data, 0);
+  id.toArrayLike(Buffer, "le", 8).copy(data, 8);
+
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: sidecarPda, isSigner: false, isWritable: true },
+      { pubkey: requestPda, isSigner: false, isWritable: false },
+      { pubkey: requester, isSigner: false, isWritable: true },
+    ],
+    data,
+  });
+}
+
+/**
+ * Create a `request_wallet_randomness` instruction: a direct-funded request
+ * with no consumer program, whose output is written to the request's
+ * `RandomnessResult`. `requestId` must be the requester's next direct
+ * request ID (`MoiraeVrf.getNextDirectRequestId`).
+ *
+ * Accounts: [requester (signer, writable), config, requester_nonce (writable), request (writable), randomness_result (writable), system_program]
+ */
+export function createRequestWalletRandomnessInstruction(
+  requester: PublicKey,
+  requestId: BN | number,
+  numWords: number,
+  seed: Uint8Array,
+  minConfirmationSlots: BN | number = 0,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  if (seed.length !== 32) {
+    throw new Error(`seed must be 32 bytes, got ${seed.length}`);
+  }
+  const [configPda] = getConfigPda(programId);
+  const [noncePda] = getRequesterNoncePda(requester, programId);
+  const [requestPda] = getRequestPda(requestId, programId);
+  const [resultPda] = getRandomnessResultPda(requestId, programId);
+
+  // data: disc(8) + num_words(4) + seed(32) + min_confirmation_slots(8)
+  const data = Buffer.alloc(8 + 4 + 32 + 8);
+  DISCRIMINATORS.requestWalletRandomness.copy(data, 0);
+  data.writeUInt32LE(numWords, 8);
+  Buffer.from(seed).copy(data, 12);
+  new BN(minConfirmationSlots.toString()).toArrayLike(Buffer, "le", 8).copy(data, 44);
+
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: requester, isSigner: true, isWritable: true },
+      { pubkey: configPda, isSigner: false, isWritable: false },
+      { pubkey: noncePda, isSigner: false, isWritable: true },
+      { pubkey: requestPda, isSigner: false, isWritable: true },
+      { pubkey: resultPda, isSigner: false, isWritable: true },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+    ],
+    data,
+  });
+}
+
+/**
+ * Create a `close_randomness_result` instruction for a wallet request whose
+ * PDA has been closed. Only the requester can sign it.
+ *
+ * Accounts: [requester (signer, writable), randomness_result (writable), request]
+ */
+export function createCloseRandomnessResultInstruction(
+  requester: PublicKey,
+  requestId: BN | number,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  const [resultPda] = getRandomnessResultPda(requestId, programId);
+  const [requestPda] = getRequestPda(requestId, programId);
+  const id = new BN(requestId.toString());
+
+  // data: disc(8) + request_id(8)
+  const data = Buffer.alloc(8 + 8);
+  DISCRIMINATORS.closeRandomnessResult.copy(data, 0);
+  id.toArrayLike(Buffer, "le", 8).copy(data, 8);
+
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: requester, isSigner: true, isWritable: true },
+      { pubkey: resultPda, isSigner: false, isWritable: true },
+      { pubkey: requestPda, isSigner: false, isWritable: false },
+    ],
+    data,
+  });
+}
+
+/**
+ * Create a `withdraw_oracle_fees` instruction moving oracle earnings from the
+ * fee vault to `recipient`.
+ *
+ * Accounts: [authority (signer), config, fee_vault (writable), recipient (writable)]
+ */
+export function createWithdrawOracleFeesInstruction(
+  authority: PublicKey,
+  recipient: PublicKey,
+  amount: BN | number,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  return createWithdrawFeesInstruction(
+    DISCRIMINATORS.withdrawOracleFees,
+    authority,
+    recipient,
+    amount,
+    programId
+  );
+}
+
+/**
+ * Create a `withdraw_protocol_fees` instruction moving protocol earnings from
+ * the fee vault to `recipient`.
+ *
+ * Accounts: [admin (signer), config, fee_vault (writable), recipient (writable)]
+ */
+export function createWithdrawProtocolFeesInstruction(
+  admin: PublicKey,
+  recipient: PublicKey,
+  amount: BN | number,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  return createWithdrawFeesInstruction(
+    DISCRIMINATORS.withdrawProtocolFees,
+    admin,
+    recipient,
+    amount,
+    programId
+  );
+}
+
+function createWithdrawFeesInstruction(
+  discriminator: Buffer,
+  signer: PublicKey,
+  recipient: PublicKey,
+  amount: BN | number,
+  programId: PublicKey
+): TransactionInstruction {
+  const [configPda] = getConfigPda(programId);
+  const [feeVaultPda] = getFeeVaultPda(programId);
+  const value = new BN(amount.toString());
+
+  // data: disc(8) + amount(8)
+  const data = Buffer.alloc(8 + 8);
+  discriminator.copy(data, 0);
+  value.toArrayLike(Buffer, "le", 8).copy(data, 8);
+
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: signer, isSigner: true, isWritable: false },
+      { pubkey: configPda, isSigner: false, isWritable: false },
+      { pubkey: feeVaultPda, isSigner: false, isWritable: true },
+      { pubkey: recipient, isSigner: false, isWritable: true },
+    ],
+    data,
+  });
+}
+
+/**
+ * Create an `execute_config_update` instruction applying the staged config
+ * update once its `executableSlot` is reached (admin-only). The pending
+ * update's rent is returned to `admin`.
+ *
+ * Accounts: [admin (signer, writable), config (writable), pending_config_update (writable)]
+ */
+export function createExecuteConfigUpdateInstruction(
+  admin: PublicKey,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  return createPendingConfigUpdateInstruction(
+    DISCRIMINATORS.executeConfigUpdate,
+    admin,
+    true,
+    programId
+  );
+}
+
+/**
+ * Create a `cancel_config_update` instruction discarding the staged config
+ * update (admin-only).
+ *
+ * Accounts: [admin (signer, writable), config, pending_config_update (writable)]
+ */
+export function createCancelConfigUpdateInstruction(
+  admin: PublicKey,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  return createPendingConfigUpdateInstruction(
+    DISCRIMINATORS.cancelConfigUpdate,
+    admin,
+    false,
+    programId
+  );
+}
+
+function createPendingConfigUpdateInstruction(
+  discriminator: Buffer,
+  admin: PublicKey,
+  configWritable: boolean,
+  programId: PublicKey
+): TransactionInstruction {
+  const [configPda] = getConfigPda(programId);
+  const [pendingPda] = getPendingConfigUpdatePda(programId);
+
+  // data: disc(8)
+  const data = Buffer.alloc(8);
+  discriminator.copy(data, 0);
+
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: admin, isSigner: true, isWritable: true },
+      { pubkey: configPda, isSigner: false, isWritable: configWritable },
+      { pubkey: pendingPda, isSigner: false, isWritable: true },
+    ],
+    data,
+  });
+}
+
+/**
+ * Create an `accept_admin_transfer` instruction. `newAdmin` must be the
+ * config's `pendingAdmin`.
+ *
+ * Accounts: [new_admin (signer), config (writable)]
+ */
+export function createAcceptAdminTransferInstruction(
+  newAdmin: PublicKey,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  const [configPda] = getConfigPda(programId);
+
+  // data: disc(8)
+  const data = Buffer.alloc(8);
+  DISCRIMINATORS.acceptAdminTransfer.copy(data, 0);
+
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: newAdmin, isSigner: true, isWritable: false },
+      { pubkey: configPda, isSigner: false, isWritable: true },
+    ],
+    data,
+  });
+}
+
+/**
+ * Create a `pause` instruction halting new subscriptions, requests,
+ * fulfillments and callback retries. `signer` must be the pauser or the admin.
+ *
+ * Accounts: [signer (signer), config (writable)]
+ */
+export function createPauseInstruction(
+  signer: PublicKey,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  const [configPda] = getConfigPda(programId);
+
+  // data: disc(8)
+  const data = Buffer.alloc(8);
+  DISCRIMINATORS.pause.copy(data, 0);
+
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: signer, isSigner: true, isWritable: false },
+      { pubkey: configPda, isSigner: false, isWritable: true },
+    ],
+    data,
+  });
+}
+
+/**
+ * Create an `unpause` instruction (admin-only).
+ *
+ * Accounts: [admin (signer), config (writable)]
+ */
+export function createUnpauseInstruction(
+  admin: PublicKey,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  const [configPda] = getConfigPda(programId);
+
+  // data: disc(8)
+  const data = Buffer.alloc(8);
+  DISCRIMINATORS.unpause.copy(data, 0);
+
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: admin, isSigner: true, isWritable: false },
+      { pubkey: configPda, isSigner: false, isWritable: true },
+    ],
+    data,
+  });
+}
+
+/**
+ * Create an `approve_mint` instruction approving `mint` for subscription
+ * billing and creating the fee vault's token account for it.
+ *
+ * Accounts: [admin (signer, writable), config, mint, approved_mint (writable), fee_vault, fee_vault_token_account (writable), token_program, associated_token_program, system_program]
+ */
+export function createApproveMintInstruction(
+  admin: PublicKey,
+  mint: PublicKey,
+  feePerWord: BN,
+  tokenProgramId: PublicKey = TOKEN_PROGRAM_ID,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  const [configPda] = getConfigPda(programId);
+  const [approvedMintPda] = getApprovedMintPda(mint, programId);
+  const [feeVaultPda] = getFeeVaultPda(programId);
+
+  // data: disc(8) + fee_per_word(8)
+  const data = Buffer.alloc(8 + 8);
+  DISCRIMINATORS.approveMint.copy(data, 0);
+  feePerWord.toArrayLike(Buffer, "le", 8).copy(data, 8);
+
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: admin, isSigner: true, isWritable: true },
+      { pubkey: configPda, isSigner: false, isWritable: false },
+      { pubkey: mint, isSigner: false, isWritable: false },
+      { pubkey: approvedMintPda, isSigner: false, isWritable: true },
+      { pubkey: feeVaultPda, isSigner: false, isWritable: false },
+      {
+        pubkey: getAssociatedTokenAddress(mint, feeVaultPda, tokenProgramId),
+        isSigner: false,
+        isWritable: true,
+      },
+      { pubkey: tokenProgramId, isSigner: false, isWritable: false },
+      { pubkey: ASSOCIATED_TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+    ],
+    data,
+  });
+}
+
+/**
+ * Create an `update_approved_mint` instruction. `null` leaves a field unchanged.
+ *
+ * Accounts: [admin (signer), config, approved_mint (writable)]
+ */
+export function createUpdateApprovedMintInstruction(
+  admin: PublicKey,
+  mint: PublicKey,
+  newFeePerWord: BN | null,
+  enabled: boolean | null,
+  newBaseFee: BN | null = null,
+  newComputeUnitPrice: BN | null = null,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  const [configPda] = getConfigPda(programId);
+  const [approvedMintPda] = getApprovedMintPda(mint, programId);
+  const optionU64 = (value: BN | null) =>
+    value === null
+      ? Buffer.from([0])
+      : Buffer.concat([Buffer.from([1]), value.toArrayLike(Buffer, "le", 8)]);
+
+  // data: disc(8) + Option<u64> + Option<bool> + Option<u64> + Option<u64>
+  const parts: Buffer[] = [DISCRIMINATORS.updateApprovedMint];
+  parts.push(optionU64(newFeePerWord));
+  parts.push(enabled === null ? Buffer.from([0]) : Buffer.from([1, enabled ? 1 : 0]));
+  parts.push(optionU64(newBaseFee));
+  parts.push(optionU64(newComputeUnitPrice));
+
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: admin, isSigner: true, isWritable: false },
+      { pubkey: configPda, isSigner: false, isWritable: false },
+      { pubkey: approvedMintPda, isSigner: false, isWritable: true },
+    ],
+    data: Buffer.concat(parts),
+  });
+}
+
+/**
+ * Create a `create_token_subscription` instruction for a subscription billed
+ * in an approved mint.
+ *
+ * Accounts: [owner (signer, writable), config (writable), approved_mint, mint, subscription (writable), subscription_vault (writable), token_program, associated_token_program, system_program]
+ */
+export function createCreateTokenSubscriptionInstruction(
+  owner: PublicKey,
+  subscriptionId: BN | number,
+  mint: PublicKey,
+  tokenProgramId: PublicKey = TOKEN_PROGRAM_ID,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  const [configPda] = getConfigPda(programId);
+  const [approvedMintPda] = getApprovedMintPda(mint, programId);
+  const [subscriptionPda] = getSubscriptionPda(subscriptionId, programId);
+
+  const data = Buffer.alloc(8);
+  DISCRIMINATORS.createTokenSubscription.copy(data, 0);
+
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: owner, isSigner: true, isWritable: true },
+      { pubkey: configPda, isSigner: false, isWritable: true },
+      { pubkey: approvedMintPda, isSigner: false, isWritable: false },
+      { pubkey: mint, isSigner: false, isWritable: false },
+      { pubkey: subscriptionPda, isSigner: false, isWritable: true },
+      {
+        pubkey: getAssociatedTokenAddress(mint, subscriptionPda, tokenProgramId),
+        isSigner: false,
+        isWritable: true,
+      },
+      { pubkey: tokenProgramId, isSigner: false, isWritable: false },
+      { pubkey: ASSOCIATED_TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+    ],
+    data,
+  });
+}
+
+/**
+ * Create a `fund_subscription_token` instruction paying `amount` from
+ * `funderTokenAccount` into a token-billed subscription.
+ *
+ * Accounts: [funder (signer), subscription (writable), mint, funder_token_account (writable), subscription_vault (writable), token_program]
+ */
+export function createFundSubscriptionTokenInstruction(
+  funder: PublicKey,
+  subscriptionId: BN | number,
+  mint: PublicKey,
+  funderTokenAccount: PublicKey,
+  amount: BN,
+  tokenProgramId: PublicKey = TOKEN_PROGRAM_ID,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  const [subscriptionPda] = getSubscriptionPda(subscriptionId, programId);
+  const id = new BN(subscriptionId.toString());
+
+  // data: disc(8) + subscription_id(8) + amount(8)
+  const data = Buffer.alloc(8 + 8 + 8);
+  DISCRIMINATORS.fundSubscriptionToken.copy(data, 0);
+  id.toArrayLike(Buffer, "le", 8).copy(data, 8);
+  amount.toArrayLike(Buffer, "le", 8).copy(data, 16);
+
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: funder, isSigner: true, isWritable: false },
+      { pubkey: subscriptionPda, isSigner: false, isWritable: true },
+      { pubkey: mint, isSigner: false, isWritable: false },
+      { pubkey: funderTokenAccount, isSigner: false, isWritable: true },
+      {
+        pubkey: getAssociatedTokenAddress(mint, subscriptionPda, tokenProgramId),
+        isSigner: false,
+        isWritable: true,
+      },
+      { pubkey: tokenProgramId, isSigner: false, isWritable: false },
+    ],
+    data,
+  });
+}
+
+/**
+ * Create a `withdraw_subscription_tokens` instruction.
+ *
+ * Accounts: [owner (signer), subscription (writable), mint, subscription_vault (writable), recipient (writable), token_program]
+ */
+export function createWithdrawSubscriptionTokensInstruction(
+  owner: PublicKey,
+  subscriptionId: BN | number,
+  mint: PublicKey,
+  recipient: PublicKey,
+  amount: BN,
+  tokenProgramId: PublicKey = TOKEN_PROGRAM_ID,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  const id = new BN(subscriptionId.toString());
+
+  // data: disc(8) + subscription_id(8) + amount(8)
+  const data = Buffer.alloc(8 + 8 + 8);
+  DISCRIMINATORS.withdrawSubscriptionTokens.copy(data, 0);
+  id.toArrayLike(Buffer, "le", 8).copy(data, 8);
+  amount.toArrayLike(Buffer, "le", 8).copy(data, 16);
+
+  return new TransactionInstruction({
+    programId,
+    keys: tokenSubscriptionKeys(owner, false, subscriptionId, mint, recipient, tokenProgramId, programId),
+    data,
+  });
+}
+
+/**
+ * Create a `cancel_token_subscription` instruction refunding every token in
+ * the subscription's vault to `recipient`.
+ *
+ * Accounts: [owner (signer, writable), subscription (writable), mint, subscription_vault (writable), recipient (writable), token_program]
+ */
+export function createCancelTokenSubscriptionInstruction(
+  owner: PublicKey,
+  subscriptionId: BN | number,
+  mint: PublicKey,
+  recipient: PublicKey,
+  tokenProgramId: PublicKey = TOKEN_PROGRAM_ID,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  const id = new BN(subscriptionId.toString());
+
+  // data: disc(8) + subscription_id(8)
+  const data = Buffer.alloc(8 + 8);
+  DISCRIMINATORS.cancelTokenSubscription.copy(data, 0);
+  id.toArrayLike(Buffer, "le", 8).copy(data, 8);
+
+  return new TransactionInstruction({
+    programId,
+    keys: tokenSubscriptionKeys(owner, true, subscriptionId, mint, recipient, tokenProgramId, programId),
+    data,
+  });
+}
+
+function tokenSubscriptionKeys(
+  owner: PublicKey,
+  ownerWritable: boolean,
+  subscriptionId: BN | number,
+  mint: PublicKey,
+  recipient: PublicKey,
+  tokenProgramId: PublicKey,
+  programId: PublicKey
+): AccountMeta[] {
+  const [subscriptionPda] = getSubscriptionPda(subscriptionId, programId);
+  return [
+    { pubkey: owner, isSigner: true, isWritable: ownerWritable },
+    { pubkey: subscriptionPda, isSigner: false, isWritable: true },
+    { pubkey: mint, isSigner: false, isWritable: false },
+    {
+      pubkey: getAssociatedTokenAddress(mint, subscriptionPda, tokenProgramId),
+      isSigner: false,
+      isWritable: true,
+    },
+    { pubkey: recipient, isSigner: false, isWritable: true },
+    { pubkey: tokenProgramId, isSigner: false, isWritable: false },
+  ];
+}
+
+/**
+ * Create a `withdraw_oracle_token_fees` instruction moving the oracle's
+ * earnings in `mint` to the `recipient` token account.
+ *
+ * Accounts: [authority (signer), config, approved_mint (writable), mint, fee_vault, fee_vault_token_account (writable), recipient (writable), token_program]
+ */
+export function createWithdrawOracleTokenFeesInstruction(
+  authority: PublicKey,
+  mint: PublicKey,
+  recipient: PublicKey,
+  amount: BN | number,
+  tokenProgramId: PublicKey = TOKEN_PROGRAM_ID,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  return createWithdrawTokenFeesInstruction(
+    DISCRIMINATORS.withdrawOracleTokenFees,
+    authority,
+    mint,
+    recipient,
+    amount,
+    tokenProgramId,
+    programId
+  );
+}
+
+/**
+ * Create a `withdraw_protocol_token_fees` instruction moving the protocol's
+ * earnings in `mint` to the `recipient` token account.
+ *
+ * Accounts: [admin (signer), config, approved_mint (writable), mint, fee_vault, fee_vault_token_account (writable), recipient (writable), token_program]
+ */
+export function createWithdrawProtocolTokenFeesInstruction(
+  admin: PublicKey,
+  mint: PublicKey,
+  recipient: PublicKey,
+  amount: BN | number,
+  tokenProgramId: PublicKey = TOKEN_PROGRAM_ID,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  return createWithdrawTokenFeesInstruction(
+    DISCRIMINATORS.withdrawProtocolTokenFees,
+    admin,
+    mint,
+    recipient,
+    amount,
+    tokenProgramId,
+    programId
+  );
+}
+
+function createWithdrawTokenFeesInstruction(
+  discriminator: Buffer,
+  signer: PublicKey,
+  mint: PublicKey,
+  recipient: PublicKey,
+  amount: BN | number,
+  tokenProgramId: PublicKey,
+  programId: PublicKey
+): TransactionInstruction {
+  const [configPda] = getConfigPda(programId);
+  const [approvedMintPda] = getApprovedMintPda(mint, programId);
+  const [feeVaultPda] = getFeeVaultPda(programId);
+  const value = new BN(amount.toString());
+
+  // data: disc(8) + amount(8)
+  const data = Buffer.alloc(8 + 8);
+  discriminator.copy(data, 0);
+  value.toArrayLike(Buffer, "le", 8).copy(data, 8);
+
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: signer, isSigner: true, isWritable: false },
+      { pubkey: configPda, isSigner: false, isWritable: false },
+      { pubkey: approvedMintPda, isSigner: false, isWritable: true },
+      { pubkey: mint, isSigner: false, isWritable: false },
+      { pubkey: feeVaultPda, isSigner: false, isWritable: false },
+      {
+        pubkey: getAssociatedTokenAddress(mint, feeVaultPda, tokenProgramId),
+        isSigner: false,
+        isWritable: true,
+      },
+      { pubkey: recipient, isSigner: false, isWritable: true },
+      { pubkey: tokenProgramId, isSigner: false, isWritable: false },
+    ],
+    data,
+  });
+}
+
+/**
+ * Create a `migrate_config` instruction growing a config written by an
+ * earlier program version to the current layout and creating the fee vault
+ * if missing (admin-only). A no-op on an up-to-date config.
+ *
+ * Accounts: [admin (signer, writable), config (writable), fee_vault (writable), system_program]
+ */
+export function createMigrateConfigInstruction(
+  admin: PublicKey,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  const [configPda] = getConfigPda(programId);
+  const [feeVaultPda] = getFeeVaultPda(programId);
+
+  // data: disc(8)
+  const data = Buffer.alloc(8);
+  DISCRIMINATORS.migrateConfig.copy(data, 0);
+
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: admin, isSigner: true, isWritable: true },
+      { pubkey: configPda, isSigner: false, isWritable: true },
+      { pubkey: feeVaultPda, isSigner: false, isWritable: true },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+    ],
+    data,
+  });
+}
+
+/**
+ * Create a `migrate_subscription` instruction growing a subscription
+ * written by an earlier program version to the current layout (admin-only).
+ *
+ * Accounts: [admin (signer, writable), config, subscription (writable), system_program]
+ */
+export function createMigrateSubscriptionInstruction(
+  admin: PublicKey,
+  subscriptionId: BN | number,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  const [configPda] = getConfigPda(programId);
+  const [subscriptionPda] = getSubscriptionPda(subscriptionId, programId);
+  const id = new BN(subscriptionId.toString());
+
+  // data: disc(8) + subscription_id(8)
+  const data = Buffer.alloc(8 + 8);
+  DISCRIMINATORS.migrateSubscription.copy(data, 0);
+  id.toArrayLike(Buffer, "le", 8).copy(data, 8);
+
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: admin, isSigner: true, isWritable: true },
+      { pubkey: configPda, isSigner: false, isWritable: false },
+      { pubkey: subscriptionPda, isSigner: false, isWritable: true },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+    ],
+    data,
+  });
+}
+
+/**
+ * Create a `migrate_registration` instruction growing a consumer
+ * registration written by an earlier program version to the current layout
+ * (admin-only).
+ *
+ * Accounts: [admin (signer, writable), config, consumer_program, consumer_registration (writable), system_program]
+ */
+export function createMigrateRegistrationInstruction(
+  admin: PublicKey,
+  subscriptionId: BN | number,
+  consumerProgramId: PublicKey,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  const [configPda] = getConfigPda(programId);
+  const [consumerPda] = getConsumerPda(subscriptionId, consumerProgramId, programId);
+  const id = new BN(subscriptionId.toString());
+
+  // data: disc(8) + subscription_id(8)
+  const data = Buffer.alloc(8 + 8);
+  DISCRIMINATORS.migrateRegistration.copy(data, 0);
+  id.toArrayLike(Buffer, "le", 8).copy(data, 8);
+
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: admin, isSigner: true, isWritable: true },
+      { pubkey: configPda, isSigner: false, isWritable: false },
+      { pubkey: consumerProgramId, isSigner: false, isWritable: false },
+      { pubkey: consumerPda, isSigner: false, isWritable: true },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+    ],
+    data,
+  });
+}
+
+/**
+ * Create a `migrate_request` instruction growing a request left open across
+ * a program upgrade to the current layout (admin-only).
+ *
+ * Accounts: [admin (signer, writable), config, request (writable), system_program]
+ */
+export function createMigrateRequestInstruction(
+  admin: PublicKey,
+  requestId: BN | number,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  const [configPda] = getConfigPda(programId);
+  const [requestPda] = getRequestPda(requestId, programId);
+  const id = new BN(requestId.toString());
+
+  // data: disc(8) + request_id(8)
+  const data = Buffer.alloc(8 + 8);
+  DISCRIMINATORS.migrateRequest.copy(data, 0);
+  id.toArrayLike(Buffer, "le", 8).copy(data, 8);
+
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: admin, isSigner: true, isWritable: true },
+      { pubkey: configPda, isSigner: false, isWritable: false },
+      { pubkey: requestPda, isSigner: false, isWritable: true },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+    ],
+    data,
+  });
+}
+
+/**
+ * Create a `create_requester_nonce` instruction, needed once before a
+ * requester's first direct-funded request.
+ *
+ * Accounts: [requester (signer, writable), config, nonce_counter (writable), requester_nonce (writable), system_program]
+ */
+export function createCreateRequesterNonceInstruction(
+  requester: PublicKey,
+  programId: PublicKey = VRF_PROGRAM_ID
+): TransactionInstruction {
+  const [configPda] = getConfigPda(programId);
+  const [counterPda] = getRequesterNonceCounterPda(programId);
+  const [noncePda] = getRequesterNoncePda(requester, programId);
+
+  const data = Buffer.alloc(8);
+  DISCRIMINATORS.createRequesterNonce.copy(data, 0);
+
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: requester, isSigner: true, isWritable: true },
+      { pubkey: configPda, isSigner: false, isWritable: false },
+      { pubkey: counterPda, isSigner: false, isWritable: true },
+      { pubkey: noncePda, isSigner: false, isWritable: true },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+    ],
+    data,
+  });
+}
@@ -1,6 +1,11 @@
 import { PublicKey } from "@solana/web3.js";
 import BN from "bn.js";
-import { VRF_PROGRAM_ID } from "./constants";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  REQUEST_INDEX_BITS,
+  TOKEN_PROGRAM_ID,
+  VRF_PROGRAM_ID,
+} from "./constants";
 
 /**
  * Derive the coordinator configuration PDA.
@@ -48,6 +53,20 @@ export function getConsumerPda(
   );
 }
 
+/**
+ * The ID of a subscription's `index`-th request:
+ * `subscription_id << 32 | index`. Pass the subscription's `reqCount` to get
+ * the ID its next request will use.
+ */
+export function getRequestId(
+  subscriptionId: BN | number | bigint,
+  index: BN | number | bigint
+): BN {
+  return new BN(subscriptionId.toString())
+    .shln(REQUEST_INDEX_BITS)
+    .or(new BN(index.toString()));
+}
+
 /**
  * Derive a randomness request PDA from its ID.
  * Seeds: `["vrf-request", request_id.to_le_bytes()]`
@@ -63,3 +82,131 @@ export function getRequestPda(
     programId
   );
 }
+
+/**
+ * Derive the callback accounts sidecar PDA of a request.
+ * Seeds: `["callback-accounts", request_id.to_le_bytes()]`
+ */
+export function getCallbackAccountsPda(
+  requestId: BN | number | bigint,
+  programId: PublicKey = VRF_PROGRAM_ID
+): [PublicKey, number] {
+  const id = new BN(requestId.toString());
+  const idBuffer = id.toArrayLike(Buffer, "le", 8);
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("callback-accounts"), idBuffer],
+    programId
+  );
+}
+
+/**
+ * Derive the `RandomnessResult` PDA of a wallet request.
+ * Seeds: `["randomness-result", request_id.to_le_bytes()]`
+ */
+export function getRandomnessResultPda(
+  requestId: BN | number | bigint,
+  programId: PublicKey = VRF_PROGRAM_ID
+): [PublicKey, number] {
+  const id = new BN(requestId.toString());
+  const idBuffer = id.toArrayLike(Buffer, "le", 8);
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("randomness-result"), idBuffer],
+    programId
+  );
+}
+
+/**
+ * Derive the fee vault PDA.
+ * Seeds: `["fee-vault"]`
+ */
+export function getFeeVaultPda(
+  programId: PublicKey = VRF_PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("fee-vault")],
+    programId
+  );
+}
+
+/**
+ * Derive a requester's nonce PDA, which numbers their direct-funded requests.
+ * Seeds: `["requester-nonce", requester]`
+ */
+export function getRequesterNoncePda(
+  requester: PublicKey,
+  programId: PublicKey = VRF_PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("requester-nonce"), requester.toBuffer()],
+    programId
+  );
+}
+
+/**
+ * Derive the singleton PDA that numbers requester nonces.
+ * Seeds: `["requester-nonce-counter"]`
+ */
+export function getRequesterNonceCounterPda(
+  programId: PublicKey = VRF_PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("requester-nonce-counter")],
+    programId
+  );
+}
+
+/**
+ * Derive the pending config update PDA.
+ * Seeds: `["pending-config-update"]`
+ */
+export function getPendingConfigUpdatePda(
+  programId: PublicKey = VRF_PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("pending-config-update")],
+    programId
+  );
+}
+
+/**
+ * Derive the approved mint PDA for a billing mint.
+ * Seeds: `["approved-mint", mint]`
+ */
+export function getApprovedMintPda(
+  mint: PublicKey,
+  programId: PublicKey = VRF_PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("approved-mint"), mint.toBuffer()],
+    programId
+  );
+}
+
+/**
+ * Derive the associated token account of `owner` for `mint`. Token-billed
+ * subscriptions and the fee vault hold their tokens in these.
+ */
+export function getAssociatedTokenAddress(
+  mint: PublicKey,
+  owner: PublicKey,
+  tokenProgramId: PublicKey = TOKEN_PROGRAM_ID
+): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [owner.toBuffer(), tokenProgramId.toBuffer(), mint.toBuffer()],
+    ASSOCIATED_TOKEN_PROGRAM_ID
+  )[0];
+}
+
+/**
+ * Derive a consumer program's authority PDA, which signs
+ * `request_random_words` and `consume_randomness`.
+ * Seeds: `["consumer-authority"]` under the consumer program.
+ */
+export function getConsumerAuthorityPda(
+  consumerProgramId: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("consumer-authority")],
+    consumerProgramId
+  );
+}
@@ -7,6 +7,30 @@ export enum RequestStatus {
   Pending = 0,
   /** Oracle has fulfilled and delivered callback. */
   Fulfilled = 1,
+  /** Randomness is stored but the consumer callback failed; retryable. */
+  CallbackFailed = 2,
+  /** Store-only request fulfilled; awaiting `consume_randomness`. */
+  Stored = 3,
+}
+
+/** Consumer callback ABI a request is delivered with. */
+export enum CallbackVersion {
+  /** `fulfill_random_words(request_id, random_words)`. */
+  V1 = 1,
+  /** `fulfill_random_words(request_id, random_words, user_data)`. */
+  V2 = 2,
+}
+
+/** How the coordinator verifies fulfillment proofs. */
+export enum ProofScheme {
+  /** Oracle-chosen output authenticated by an Ed25519 signature. */
+  Ed25519 = 0,
+  /** ECVRF-EDWARDS25519-SHA512-TAI proof (RFC 9381). */
+  Ecvrf = 1,
+  /** `SHA256(signature)` over a request-bound message. */
+  SignatureHash = 2,
+  /** Combined ECVRF outputs from the first M of N registered oracles. */
+  MultiOracle = 3,
 }
 
 /** Deserialized CoordinatorConfig account. */
@@ -19,12 +43,76 @@ export interface CoordinatorConfig {
   feePerWord: BN;
   /** Maximum number of random words a consumer may request at once. */
   maxNumWords: number;
-  /** Monotonically increasing counter for unique request PDA seeds. */
+  /** Legacy global request counter; no longer advanced. Request IDs come from `getRequestId`. */
   requestCounter: BN;
   /** Monotonically increasing counter for unique subscription PDA seeds. */
   subscriptionCounter: BN;
   /** PDA bump seed. */
   bump: number;
+  /** Fulfillment proof scheme. */
+  proofScheme: ProofScheme;
+  /** Registered oracle keys (multi-oracle scheme). */
+  oracles: PublicKey[];
+  /** Number of distinct registered oracles that prove each fulfillment. */
+  oracleThreshold: number;
+  /** Slots after `requestSlot` before a pending request may be cancelled. */
+  requestTimeoutSlots: BN;
+  /** Protocol share of each collected fee, in basis points. */
+  protocolFeeBps: number;
+  /** Flat fee in lamports charged per request. */
+  baseFee: BN;
+  /** Micro-lamports charged per unit of a request's `callbackComputeLimit`. */
+  computeUnitPrice: BN;
+  /** Largest `callbackComputeLimit` a request may ask for. */
+  maxCallbackComputeLimit: number;
+  /** Minimum slots every request waits after `requestSlot` before fulfillment. */
+  minConfirmationSlots: BN;
+  /** Key that may pause the coordinator; only the admin may unpause. */
+  pauser: PublicKey;
+  /** Whether new subscriptions, requests and fulfillments are halted. */
+  paused: boolean;
+  /** Slots a staged config update waits before it can be executed. */
+  configUpdateDelaySlots: BN;
+  /** Admin awaiting `accept_admin_transfer`, or the default pubkey if none. */
+  pendingAdmin: PublicKey;
+}
+
+/** Configuration changes staged by `update_config`; `null` leaves a field unchanged. */
+export interface ConfigChanges {
+  newAuthority: PublicKey | null;
+  newFeePerWord: BN | null;
+  newMaxNumWords: number | null;
+  /** Becomes `pendingAdmin` on execution; the new admin must accept. */
+  newAdmin: PublicKey | null;
+  newProofScheme: ProofScheme | null;
+  newRequestTimeoutSlots: BN | null;
+  newProtocolFeeBps: number | null;
+  newBaseFee: BN | null;
+  newComputeUnitPrice: BN | null;
+  newMaxCallbackComputeLimit: number | null;
+  newMinConfirmationSlots: BN | null;
+  newPauser: PublicKey | null;
+  newConfigUpdateDelaySlots: BN | null;
+  /** Replaces the multi-oracle set and threshold. */
+  newOracleSet: OracleSet | null;
+}
+
+/** Registered oracles and threshold of the multi-oracle scheme. */
+export interface OracleSet {
+  oracles: PublicKey[];
+  threshold: number;
+}
+
+/** Deserialized PendingConfigUpdate account. */
+export interface PendingConfigUpdateAccount {
+  /** The staged changes. */
+  changes: ConfigChanges;
+  /** Slot at which the update was staged. */
+  proposedSlot: BN;
+  /** First slot at which `execute_config_update` may apply it. */
+  executableSlot: BN;
+  /** PDA bump seed. */
+  bump: number;
 }
 
 /** Deserialized Subscription account. */
@@ -35,12 +123,62 @@ export interface SubscriptionAccount {
   owner: PublicKey;
   /** Current balance in lamports available for VRF fees. */
   balance: BN;
-  /** Total number of VRF requests made through this subscription. */
+  /** Total number of VRF requests made through this subscription; the index of its next request. */
   reqCount: BN;
   /** Number of consumer programs currently registered. */
   consumerCount: number;
   /** PDA bump seed. */
   bump: number;
+  /** Fees of pending requests, held until fulfillment or cancellation. */
+  pendingFees: BN;
+  /** Proposed new owner awaiting acceptance, or the default pubkey if none. */
+  pendingOwner: PublicKey;
+  /** SPL mint the subscription pays in, or the default pubkey for SOL. */
+  billingMint: PublicKey;
+}
+
+/** Deserialized FeeVault account. */
+export interface FeeVaultAccount {
+  /** Withdrawable oracle earnings in lamports. */
+  oracleFees: BN;
+  /** Withdrawable protocol earnings in lamports. */
+  protocolFees: BN;
+  /** PDA bump seed. */
+  bump: number;
+}
+
+/** Deserialized RequesterNonce account. */
+export interface RequesterNonceAccount {
+  /** The wallet whose direct-funded requests it counts. */
+  requester: PublicKey;
+  /** High bits of the requester's request IDs. */
+  id: BN;
+  /** Direct-funded requests made; the index of the next one. */
+  reqCount: BN;
+  /** PDA bump seed. */
+  bump: number;
+}
+
+/** Deserialized ApprovedMint account. */
+export interface ApprovedMintAccount {
+  /** The approved mint. */
+  mint: PublicKey;
+  /** Token program owning the mint (SPL Token or Token-2022). */
+  tokenProgram: PublicKey;
+  /** Fee per random word in the mint's base units. */
+  feePerWord: BN;
+  /** Whether new token subscriptions and requests are accepted. */
+  enabled: boolean;
+  /** Withdrawable oracle earnings in base units. */
+  oracleFees: BN;
+  /** Withdrawable protocol earnings in base units. */
+  protocolFees: BN;
+  /** PDA bump seed. */
+  bump: number;
+  /** Flat fee per request in base units. */
+  baseFee: BN;
+  /** Price per callback compute unit, in millionths of a base unit. */
+  computeUnitPrice: BN;
 }
 
 /** Deserialized ConsumerRegistration account. */
@@ -53,6 +191,39 @@ export interface ConsumerRegistrationAccount {
   nonce: BN;
   /** PDA bump seed. */
   bump: number;
+  /** Maximum fees per epoch in the subscription's billing units (0 = unlimited). */
+  maxFeePerEpoch: BN;
+  /** Maximum requests per `requestWindowSlots` (0 = unlimited). */
+  maxRequestsPerWindow: number;
+  /** Length of the rate-limit window in slots. */
+  requestWindowSlots: BN;
+  /** Maximum `num_words` per request (0 = only the coordinator's limit). */
+  maxNumWords: number;
+  /** Epoch `epochFees` is counted for. */
+  feeEpoch: BN;
+  /** Fees charged during `feeEpoch`. */
+  epochFees: BN;
+  /** First slot of the current rate-limit window. */
+  windowStartSlot: BN;
+  /** Requests made during the current rate-limit window. */
+  windowRequests: number;
+  /** Requests made since registration. */
+  totalRequests: BN;
+  /** Fees charged since registration; cancellation refunds are not subtracted. */
+  totalFeesPaid: BN;
+  /** Slot of the most recent request (0 if none). */
+  lastRequestSlot: BN;
+  /** Default callback instruction prefix (empty = Anchor `fulfill_random_words`). */
+  callbackPrefix: Uint8Array;
+}
+
+/** Per-consumer limits set with `set_consumer_limits`; 0 disables a limit. */
+export interface ConsumerLimits {
+  maxFeePerEpoch: BN;
+  maxRequestsPerWindow: number;
+  /** Required when `maxRequestsPerWindow` is set. */
+  requestWindowSlots: BN;
+  maxNumWords: number;
 }
 
 /** Deserialized RandomnessRequest account. */
@@ -81,6 +252,71 @@ export interface RandomnessRequestAccount {
   fulfilledSlot: BN;
   /** PDA bump seed. */
   bump: number;
+  /** Fee in lamports (or `feeMint` base units) deducted for this request (paid to the fee vault on fulfillment, re-credited on cancellation). */
+  feePaid: BN;
+  /** Recorded failed callback deliveries. */
+  callbackAttempts: number;
+  /** Store-only delivery: no callback, consumer pulls via `consume_randomness`. */
+  storeOnly: boolean;
+  /** Callback accounts are stored in a `CallbackAccounts` sidecar. */
+  callbackSidecar: boolean;
+  /** Mint `feePaid` is denominated in, or the default pubkey for lamports. */
+  feeMint: PublicKey;
+  /** Slots after `requestSlot` before the request can be fulfilled. */
+  minConfirmationSlots: BN;
+  /** Callback ABI; `V2` requests pass `userData` to the callback. */
+  callbackVersion: CallbackVersion;
+  /** Opaque consumer data stored with the request (empty for `V1`). */
+  userData: Uint8Array;
+  /** Callback instruction prefix (empty = Anchor `fulfill_random_words`). */
+  callbackPrefix: Uint8Array;
+}
+
+/** Prices used to compute a request's fee. */
+export interface FeeSchedule {
+  baseFee: BN;
+  feePerWord: BN;
+  computeUnitPrice: BN;
+}
+
+/** One callback account stored in a `CallbackAccounts` sidecar. */
+export interface CallbackAccountMeta {
+  pubkey: PublicKey;
+  isWritable: boolean;
+}
+
+/** Deserialized CallbackAccounts sidecar account. */
+export interface CallbackAccountsAccount {
+  /** The request these accounts belong to. */
+  requestId: BN;
+  /** Account that paid the rent (refunded on close). */
+  requester: PublicKey;
+  /** Address lookup table covering the accounts, or null. */
+  lookupTable: PublicKey | null;
+  /** PDA bump seed. */
+  bump: number;
+  /** Callback accounts in callback order. */
+  accounts: CallbackAccountMeta[];
+}
+
+/** Deserialized RandomnessResult account of a wallet request. */
+export interface RandomnessResultAccount {
+  /** The request this result belongs to. */
+  requestId: BN;
+  /** The wallet that made the request (refunded on close). */
+  requester: PublicKey;
+  /** Number of random words requested. */
+  numWords: number;
+  /** Caller-provided entropy. */
+  seed: Uint8Array;
+  /** Slot at which the request was created. */
+  requestSlot: BN;
+  /** Base VRF output; all zeros until fulfilled. */
+  randomness: Uint8Array;
+  /** Slot at which the request was fulfilled, or 0 while pending. */
+  fulfilledSlot: BN;
+  /** PDA bump seed. */
+  bump: number;
 }
 
 /** Result returned by subscription creation. */
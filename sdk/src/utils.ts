@@ -7,8 +7,9 @@ import {
 import BN from "bn.js";
 import { getRequestPda } from "./pda";
 import { decodeRandomnessRequest } from "./accounts";
-import { VRF_PROGRAM_ID } from "./constants";
+import { VRF_PROGRAM_ID, COMPUTE_UNIT_PRICE_SCALE } from "./constants";
 import {
+  FeeSchedule,
   RandomnessRequestAccount,
   RequestStatus,
   WaitForFulfillmentOptions,
@@ -84,3 +85,24 @@ export function addPriorityFee(
 
   return instructions;
 }
+
+/**
+ * Compute the fee the coordinator charges for a request, mirroring the
+ * on-chain pricing:
+ * `baseFee + feePerWord × numWords + ⌈computeUnitPrice × callbackComputeLimit / 10^6⌉`.
+ *
+ * Pass the `CoordinatorConfig` for SOL-billed subscriptions or the
+ * `ApprovedMintAccount` for token-billed ones. Store-only requests are
+ * charged no compute, so pass 0 for `callbackComputeLimit`.
+ */
+export function computeRequestFee(
+  schedule: FeeSchedule,
+  numWords: number,
+  callbackComputeLimit: number
+): BN {
+  const computeFee = schedule.computeUnitPrice
+    .muln(callbackComputeLimit)
+    .addn(COMPUTE_UNIT_PRICE_SCALE - 1)
+    .divn(COMPUTE_UNIT_PRICE_SCALE);
+  return schedule.baseFee.add(schedule.feePerWord.muln(numWords)).add(computeFee);
+}